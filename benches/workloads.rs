@@ -0,0 +1,16 @@
+use cesrox::bench::workloads;
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+
+fn bench_workloads(c: &mut Criterion) {
+    let mut group = c.benchmark_group("cesrox");
+    for workload in workloads().expect("standard workloads") {
+        group.throughput(Throughput::Bytes(workload.bytes()));
+        group.bench_function(workload.name(), |b| {
+            b.iter(|| workload.run().expect("workload runs"))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_workloads);
+criterion_main!(benches);
@@ -0,0 +1,248 @@
+use crate::{
+    derivation::self_addressing::SelfAddressing,
+    error::Error,
+    prefix::{Prefix, SelfAddressingPrefix},
+    said::{self, SaidPath},
+    serder::Serder,
+    version::SerializationKind,
+};
+use serde_json::{Map, Value};
+
+/// Labels of the sections of an ACDC that are either expanded into a block with a SAID of its
+/// own or compacted to that SAID: attributes, edges and rules.
+pub const SECTIONS: [&str; 3] = ["a", "e", "r"];
+
+/// Label of the SAID field, of the ACDC and of its blocks.
+const SAID_LABEL: &str = "d";
+
+/// An authentic chained data container (ACDC) with its SAIDs embedded, from which the variants
+/// of the ACDC are disclosed.
+///
+/// The SAID of an ACDC is the SAID of its most compact variant, in which every section is
+/// replaced by the SAID of its block. So the fully expanded, the compact and the partially
+/// disclosed variants all share the same SAID.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Acdc {
+    sad: Map<String, Value>,
+    kind: SerializationKind,
+}
+
+impl Acdc {
+    /// Embeds the SAIDs of `sad`, an ACDC serialized as `kind`: first those of the blocks nested
+    /// in its expanded sections, deepest first, then those of the sections, and last its own over
+    /// its compact variant. Every block with a `d` field in a section gets its SAID.
+    pub fn saidify(
+        sad: &Map<String, Value>,
+        code: &SelfAddressing,
+        kind: SerializationKind,
+    ) -> Result<Self, Error> {
+        let mut sad = sad.clone();
+        for label in SECTIONS {
+            if let Some(Value::Object(section)) = sad.get_mut(label) {
+                let paths = block_paths(section, &[]);
+                said::embed_saids(section, &paths, code, kind)?;
+            }
+        }
+        let said = said::compute_said(&compact(&sad, &[])?, &[SAID_LABEL], code, kind)?;
+        sad.insert(SAID_LABEL.into(), said.to_str().into());
+        Ok(Self { sad, kind })
+    }
+
+    /// Parses any variant of an ACDC, checking the SAIDs of its expanded sections and its own.
+    pub fn from_serder(serder: &Serder) -> Result<Self, Error> {
+        let acdc = Self {
+            sad: serder.ked().clone(),
+            kind: serder.kind(),
+        };
+        match acdc.verify()? {
+            true => Ok(acdc),
+            false => Err(Error::IncorrectDigest),
+        }
+    }
+
+    /// Whether the SAIDs of the expanded sections, and of the ACDC over its compact variant,
+    /// match their content.
+    pub fn verify(&self) -> Result<bool, Error> {
+        for label in SECTIONS {
+            if let Some(Value::Object(section)) = self.sad.get(label) {
+                let paths = block_paths(section, &[]);
+                if !said::verify_said_paths(section, &paths, self.kind)? {
+                    return Ok(false);
+                }
+            }
+        }
+        let said = self.said()?;
+        let compact = compact(&self.sad, &[])?;
+        Ok(said::compute_said(&compact, &[SAID_LABEL], &said.derivation, self.kind)? == said)
+    }
+
+    pub fn said(&self) -> Result<SelfAddressingPrefix, Error> {
+        said_of(&self.sad, "ACDC")
+    }
+
+    /// SAID of the section `label`, whether it is expanded or compact.
+    pub fn section_said(&self, label: &str) -> Result<SelfAddressingPrefix, Error> {
+        match self.sad.get(label) {
+            Some(Value::Object(section)) => said_of(section, label),
+            Some(Value::String(said)) => said.parse(),
+            _ => Err(Error::SemanticError(format!("Missing section {}", label))),
+        }
+    }
+
+    /// The ACDC with every section as it is, fully expanded if it was saidified so.
+    pub fn expanded(&self) -> Result<Serder, Error> {
+        Serder::from_ked(self.sad.clone(), self.kind)
+    }
+
+    /// The most compact variant, every section replaced by its SAID.
+    pub fn compact(&self) -> Result<Serder, Error> {
+        self.disclose(&[])
+    }
+
+    /// The partially disclosed variant which expands the sections `disclosed` only, e.g. the
+    /// attributes but not the rules. Compact sections cannot be expanded again.
+    pub fn disclose(&self, disclosed: &[&str]) -> Result<Serder, Error> {
+        if let Some(label) = disclosed
+            .iter()
+            .find(|label| !matches!(self.sad.get(**label), Some(Value::Object(_))))
+        {
+            return Err(Error::SemanticError(format!(
+                "Section {} is not expanded",
+                label
+            )));
+        }
+        Serder::from_ked(compact(&self.sad, disclosed)?, self.kind)
+    }
+}
+
+/// The SAID in the `d` field of `sad`, `what` naming the block in errors.
+fn said_of(sad: &Map<String, Value>, what: &str) -> Result<SelfAddressingPrefix, Error> {
+    sad.get(SAID_LABEL)
+        .and_then(Value::as_str)
+        .ok_or_else(|| Error::SemanticError(format!("Missing SAID of {}", what)))?
+        .parse()
+}
+
+/// Paths of the SAID fields of `block` and of the blocks nested in it, `prefix` leading to it.
+fn block_paths(block: &Map<String, Value>, prefix: &[&str]) -> Vec<SaidPath> {
+    let mut paths = vec![];
+    let labels = prefix.iter().copied().chain([SAID_LABEL]);
+    paths.extend(SaidPath::new(labels));
+    for (label, value) in block {
+        match value {
+            Value::Object(nested) if nested.contains_key(SAID_LABEL) => {
+                let prefix: Vec<&str> = prefix.iter().copied().chain([label.as_str()]).collect();
+                paths.extend(block_paths(nested, &prefix));
+            }
+            _ => {}
+        }
+    }
+    paths
+}
+
+/// `sad` with its sections replaced by their SAIDs, except the `disclosed` ones.
+fn compact(sad: &Map<String, Value>, disclosed: &[&str]) -> Result<Map<String, Value>, Error> {
+    let mut sad = sad.clone();
+    for label in SECTIONS.iter().filter(|label| !disclosed.contains(label)) {
+        if let Some(Value::Object(section)) = sad.get(*label) {
+            let said = said_of(section, label)?;
+            sad.insert((*label).into(), said.to_str().into());
+        }
+    }
+    Ok(sad)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn sad() -> Map<String, Value> {
+        match json!({
+            "v": "ACDC10JSON000000_",
+            "d": "",
+            "i": "EKYLUMmNPZeEs77Zvclf0bSN5IN-mLfLpx2ySb-HDlk4",
+            "ri": "EAAA",
+            "s": "EBdXt3gIXOf2BBWNHdSXCJnFJL5OuQPyM5K0neuniccM",
+            "a": {
+                "d": "",
+                "i": "EIzLqsNwcrsD1qhd4E8OB-UKPZGcWKpBYZLUCjmN6Shx",
+                "dt": "2021-01-01T00:00:00.000000+00:00",
+                "LEI": "254900OPPU84GM83MG36"
+            },
+            "e": {
+                "d": "",
+                "qvi": {
+                    "d": "",
+                    "n": "EAAB",
+                    "s": "EBdXt3gIXOf2BBWNHdSXCJnFJL5OuQPyM5K0neuniccM"
+                }
+            },
+            "r": {"d": "", "usage": {"l": "Use as you please"}}
+        }) {
+            Value::Object(sad) => sad,
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn variants() -> Result<(), Error> {
+        let code = SelfAddressing::Blake3_256;
+        let acdc = Acdc::saidify(&sad(), &code, SerializationKind::JSON)?;
+        assert!(acdc.verify()?);
+
+        // sections are saidified on their own, nested blocks first
+        let attributes = sad()["a"].as_object().unwrap().clone();
+        let (said, _) = said::saidify(&attributes, "d", &code, SerializationKind::JSON)?;
+        assert_eq!(acdc.section_said("a")?, said);
+        let expanded = acdc.expanded()?;
+        let edge = expanded.get("e").unwrap()["qvi"].as_object().unwrap();
+        assert!(said::verify_said(edge, "d", SerializationKind::JSON)?);
+
+        let compact = acdc.compact()?;
+        for label in SECTIONS {
+            assert_eq!(
+                compact.get(label),
+                Some(&Value::from(acdc.section_said(label)?.to_str()))
+            );
+        }
+        let partial = acdc.disclose(&["a"])?;
+        assert_eq!(partial.get("a"), expanded.get("a"));
+        assert_eq!(partial.get("r"), compact.get("r"));
+
+        // all variants share the SAID of the compact one, each sized on its own
+        for variant in [&expanded, &compact, &partial] {
+            assert_eq!(variant.said()?, acdc.said()?);
+            assert_eq!(variant.version().size, variant.raw().len());
+            let parsed = Acdc::from_serder(&Serder::from_raw(variant.raw())?)?;
+            assert_eq!(parsed.said()?, acdc.said()?);
+        }
+        assert!(compact.raw().len() < partial.raw().len());
+        assert!(partial.raw().len() < expanded.raw().len());
+
+        let compacted = Acdc::from_serder(&compact)?;
+        assert_eq!(compacted.section_said("e")?, acdc.section_said("e")?);
+        assert!(compacted.disclose(&["a"]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn tampered() -> Result<(), Error> {
+        let acdc = Acdc::saidify(&sad(), &SelfAddressing::Blake3_256, SerializationKind::JSON)?;
+        let mut ked = acdc.expanded()?.ked().clone();
+        ked["a"]["LEI"] = "5493001KJTIIGC8Y1R17".into();
+        let serder = Serder::from_ked(ked, SerializationKind::JSON)?;
+        assert!(Acdc::from_serder(&serder).is_err());
+
+        // a compact section standing in for other content
+        let mut ked = acdc.compact()?.ked().clone();
+        ked["r"] = SelfAddressing::Blake3_256.derive(b"rules").to_str().into();
+        let serder = Serder::from_ked(ked, SerializationKind::JSON)?;
+        assert!(Acdc::from_serder(&serder).is_err());
+
+        let mut sad = sad();
+        sad["a"].as_object_mut().unwrap().remove("d");
+        assert!(Acdc::saidify(&sad, &SelfAddressing::Blake3_256, SerializationKind::JSON).is_err());
+        Ok(())
+    }
+}
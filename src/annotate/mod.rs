@@ -0,0 +1,373 @@
+use crate::{
+    derivation::{
+        attached_signature_code::AttachedSignatureCode,
+        counter::{Counter, CounterCode},
+        genus::GenusVersion,
+        variable::VariableKind,
+    },
+    error::Error,
+    parser::{is_qb64_char, sniff},
+    prefix::{split_at_code_len, Number, Primitive, Siger},
+    version::{SerializationKind, VersionString},
+};
+use core::{fmt, str::FromStr};
+
+/// One entry of an annotated stream: a message, a count code or a primitive, with what it is.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Annotation {
+    /// Number of groups the entry is nested in.
+    pub depth: usize,
+    /// The entry as it is in the stream, binary messages in hex.
+    pub text: String,
+    /// What the entry is, e.g. `Ed25519Sha512 indexed signature`.
+    pub meaning: String,
+    /// Size of the entry in the stream, in bytes.
+    pub size: usize,
+    /// The decoded value when it is short enough to read, e.g. a count or an index.
+    pub value: Option<String>,
+}
+
+/// One line per entry, groups indented under their count code.
+impl fmt::Display for Annotation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let indent = "  ".repeat(self.depth);
+        write!(
+            f,
+            "{}{} # {}, {} bytes",
+            indent, self.text, self.meaning, self.size
+        )?;
+        match &self.value {
+            Some(value) => write!(f, ", {}", value),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Breaks a stream of messages and text domain (qb64) attachment groups down into its entries.
+pub fn annotations(stream: &[u8]) -> Result<Vec<Annotation>, Error> {
+    let mut annotator = Annotator::default();
+    let mut rest = stream;
+    while !rest.is_empty() {
+        if rest[0] == b'-' {
+            let len = rest.iter().take_while(|b| is_qb64_char(**b)).count();
+            let groups = std::str::from_utf8(&rest[..len]).expect("qb64 characters are ASCII");
+            let tail = annotator.groups(groups, 0)?;
+            if !tail.is_empty() {
+                return Err(Error::DeserializeError(format!(
+                    "Expected a count code, got {}",
+                    tail
+                )));
+            }
+            rest = &rest[len..];
+            continue;
+        }
+        let cold = sniff(rest)?;
+        if !cold.is_message() {
+            return Err(Error::DeserializeError(format!(
+                "Expected a message or a count code, got {:?}",
+                cold
+            )));
+        }
+        let vs = VersionString::find(rest)?;
+        let message = rest.get(..vs.size).ok_or_else(|| Error::ShortageError {
+            needed: vs.size - rest.len(),
+        })?;
+        annotator.annotations.push(Annotation {
+            depth: 0,
+            text: match (vs.kind, std::str::from_utf8(message)) {
+                (SerializationKind::JSON, Ok(text)) => text.into(),
+                _ => message.iter().map(|b| format!("{:02x}", b)).collect(),
+            },
+            meaning: format!("{} {:?} message", vs.protocol.to_str(), vs.kind),
+            size: vs.size,
+            value: Some(format!("version {}.{}", vs.version.major, vs.version.minor)),
+        });
+        rest = &rest[vs.size..];
+    }
+    Ok(annotator.annotations)
+}
+
+/// The [annotations] of a stream, one per line.
+pub fn annotate(stream: &[u8]) -> Result<String, Error> {
+    Ok(annotations(stream)?
+        .iter()
+        .map(|annotation| format!("{}\n", annotation))
+        .collect())
+}
+
+/// Members of a group the annotator expects, beyond primitives told apart by their code.
+#[derive(Clone, Copy)]
+pub(crate) enum Member {
+    Primitive,
+    /// Sequence numbers and ordinals, whose `0A` code is also the one of salts.
+    Number,
+    Indexed,
+    Group,
+}
+
+/// The members of one entry of the group counted by `code`, `None` for groups of arbitrary
+/// material.
+pub(crate) fn layout(code: CounterCode) -> Option<&'static [Member]> {
+    use Member::*;
+    match code {
+        CounterCode::ControllerIdxSigs | CounterCode::WitnessIdxSigs => Some(&[Indexed]),
+        CounterCode::NonTransReceiptCouples => Some(&[Primitive, Primitive]),
+        CounterCode::TransReceiptQuadruples => Some(&[Primitive, Number, Primitive, Indexed]),
+        CounterCode::FirstSeenReplayCouples => Some(&[Number, Primitive]),
+        CounterCode::TransIdxSigGroups => Some(&[Primitive, Number, Primitive, Group]),
+        CounterCode::TransLastIdxSigGroups => Some(&[Primitive, Group]),
+        CounterCode::SealSourceCouples => Some(&[Number, Primitive]),
+        CounterCode::SealSourceTriples => Some(&[Primitive, Number, Primitive]),
+        _ => None,
+    }
+}
+
+/// Whether the count of a CESR 1 group is in quadlets rather than in entries.
+pub(crate) fn counts_quadlets(code: CounterCode) -> bool {
+    matches!(
+        code,
+        CounterCode::PathedMaterialQuadlets
+            | CounterCode::AttachedMaterialQuadlets
+            | CounterCode::MessageDataMaterialQuadlets
+            | CounterCode::CombinedMaterialQuadlets
+            | CounterCode::MaterialQuadlets
+            | CounterCode::BigAttachedMaterialQuadlets
+            | CounterCode::BigMessageDataMaterialQuadlets
+            | CounterCode::BigCombinedMaterialQuadlets
+            | CounterCode::BigMaterialQuadlets
+    )
+}
+
+#[derive(Default)]
+struct Annotator {
+    annotations: Vec<Annotation>,
+    genus_version: GenusVersion,
+}
+
+impl Annotator {
+    fn push(&mut self, depth: usize, text: &str, meaning: String, value: Option<String>) {
+        self.annotations.push(Annotation {
+            depth,
+            text: text.into(),
+            meaning,
+            size: text.len(),
+            value,
+        });
+    }
+
+    /// Annotates the groups at the start of `s`, returning the rest of `s`.
+    fn groups<'a>(&mut self, mut s: &'a str, depth: usize) -> Result<&'a str, Error> {
+        while s.starts_with('-') {
+            s = self.group(s, depth)?;
+        }
+        Ok(s)
+    }
+
+    /// Annotates the group, or the genus version code, at the start of `s`.
+    fn group<'a>(&mut self, s: &'a str, depth: usize) -> Result<&'a str, Error> {
+        if let Some((declared, rest)) = GenusVersion::from_stream_in(s, &self.genus_version)? {
+            let version = format!("{}.{}", declared.version.major, declared.version.minor);
+            let meaning = format!("{:?} genus version", declared.genus);
+            self.push(depth, &s[..s.len() - rest.len()], meaning, Some(version));
+            self.genus_version = declared;
+            return Ok(rest);
+        }
+        let (counter, rest) = Counter::from_stream_in(s, &self.genus_version)?;
+        let meaning = format!("{:?} count code", counter.code);
+        let count = Some(format!("count {}", counter.count));
+        self.push(depth, &s[..s.len() - rest.len()], meaning, count);
+
+        let depth = depth + 1;
+        if !self.genus_version.counts_quadlets() && !counts_quadlets(counter.code) {
+            let mut rest = rest;
+            for _ in 0..counter.count {
+                rest = match layout(counter.code) {
+                    Some(layout) => self.members(rest, layout, depth)?,
+                    None => self.member(rest, Member::Primitive, depth)?,
+                };
+            }
+            return Ok(rest);
+        }
+        let len = counter.count as usize * 4;
+        if rest.len() < len {
+            return Err(Error::ShortageError {
+                needed: len - rest.len(),
+            });
+        }
+        let (mut material, rest) = rest.split_at(len);
+        while !material.is_empty() {
+            material = match layout(counter.code) {
+                Some(layout) => self.members(material, layout, depth)?,
+                None => self.member(material, Member::Primitive, depth)?,
+            };
+        }
+        Ok(rest)
+    }
+
+    fn members<'a>(
+        &mut self,
+        mut s: &'a str,
+        layout: &[Member],
+        depth: usize,
+    ) -> Result<&'a str, Error> {
+        for member in layout {
+            s = self.member(s, *member, depth)?;
+        }
+        Ok(s)
+    }
+
+    /// Annotates one member at the start of `s`, a nested group wherever a count code is met.
+    fn member<'a>(&mut self, s: &'a str, member: Member, depth: usize) -> Result<&'a str, Error> {
+        if s.starts_with('-') || matches!(member, Member::Group) {
+            return self.group(s, depth);
+        }
+        match member {
+            Member::Indexed => {
+                let (siger, rest) = split_at_code_len(s, AttachedSignatureCode::from_str(s)?)?;
+                let parsed = Siger::from_str(siger)?;
+                let meaning = format!("{:?} indexed signature", parsed.signature.derivation);
+                self.push(
+                    depth,
+                    siger,
+                    meaning,
+                    Some(format!("index {}", parsed.index())),
+                );
+                Ok(rest)
+            }
+            Member::Number => {
+                let (number, rest) = Number::from_stream(s)?;
+                let meaning = format!("{:?} number", number.code());
+                let value = Some(number.num().to_string());
+                self.push(depth, &s[..s.len() - rest.len()], meaning, value);
+                Ok(rest)
+            }
+            _ => {
+                let (primitive, rest) = Primitive::from_stream(s)?;
+                let (meaning, value) = describe(&primitive);
+                self.push(depth, &s[..s.len() - rest.len()], meaning, value);
+                Ok(rest)
+            }
+        }
+    }
+}
+
+/// What `primitive` is, with its value when it is short enough to read.
+fn describe(primitive: &Primitive) -> (String, Option<String>) {
+    match primitive {
+        Primitive::Key(key) => (format!("{:?} public key", key.derivation), None),
+        Primitive::Digest(digest) => (format!("{:?} digest", digest.derivation), None),
+        Primitive::Signature(signature) => (format!("{:?} signature", signature.derivation), None),
+        Primitive::Seed(_) => ("seed or salt".into(), None),
+        Primitive::Cipher(_) => ("cipher".into(), None),
+        Primitive::Number(number) => (
+            format!("{:?} number", number.code()),
+            Some(number.num().to_string()),
+        ),
+        Primitive::Datetime(dater) => ("datetime".into(), Some(dater.iso8601().into())),
+        Primitive::Tag(tagger) => ("tag".into(), Some(tagger.tag().into())),
+        Primitive::Variable(variable) => match variable.code().kind {
+            VariableKind::StrB64 => ("Base64 string".into(), variable.to_text()),
+            VariableKind::Bytes => ("bytes".into(), None),
+        },
+        Primitive::Counter(counter) => (
+            format!("{:?} count code", counter.code),
+            Some(format!("count {}", counter.count)),
+        ),
+        Primitive::Opaque(opaque) => (format!("unknown material {}", opaque.code), None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        attachments::{AttachmentsBuilder, EventSeal},
+        derivation::{self_addressing::SelfAddressing, self_signing::SelfSigning},
+        prefix::{AttachedSignaturePrefix, IdentifierPrefix, SadPath},
+        test_utils::signer,
+    };
+
+    const IXN: &str = r#"{"v":"KERI10JSON00002e_","t":"ixn","i":"EAAA"}"#;
+
+    fn attachments() -> AttachmentsBuilder {
+        let sig = AttachedSignaturePrefix::new(
+            SelfSigning::Ed25519Sha512,
+            signer(1, true).sign(b"event").signature,
+            2,
+        )
+        .unwrap();
+        let digest = SelfAddressing::Blake3_256.derive(b"event");
+        AttachmentsBuilder::new()
+            .controller_signature(sig.clone())
+            .seal_source_triple(EventSeal::new(
+                IdentifierPrefix::SelfAddressing(digest.clone()),
+                3,
+                digest,
+            ))
+            .pathed_material(
+                SadPath::from_path("-a").unwrap(),
+                AttachmentsBuilder::new().controller_signature(sig),
+            )
+    }
+
+    #[test]
+    fn annotate_stream() -> Result<(), Error> {
+        let attachments = attachments().build()?;
+        let stream = [IXN, &attachments].join("");
+        let annotations = annotations(stream.as_bytes())?;
+        let summary: Vec<_> = annotations
+            .iter()
+            .map(|a| (a.depth, a.meaning.as_str(), a.value.as_deref()))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (0, "KERI JSON message", Some("version 1.0")),
+                (0, "ControllerIdxSigs count code", Some("count 1")),
+                (1, "Ed25519Sha512 indexed signature", Some("index 2")),
+                (0, "SealSourceTriples count code", Some("count 1")),
+                (1, "Blake3_256 digest", None),
+                (1, "Huge number", Some("3")),
+                (1, "Blake3_256 digest", None),
+                (0, "PathedMaterialQuadlets count code", Some("count 25")),
+                (1, "Base64 string", Some("-a")),
+                (1, "ControllerIdxSigs count code", Some("count 1")),
+                (2, "Ed25519Sha512 indexed signature", Some("index 2")),
+            ]
+        );
+        // the entries make up the stream
+        let texts: String = annotations.iter().map(|a| a.text.as_str()).collect();
+        assert_eq!(texts, stream);
+
+        let text = annotate(stream.as_bytes())?;
+        assert_eq!(text.lines().count(), annotations.len());
+        assert!(text.lines().nth(2).unwrap().starts_with("  AC"));
+        assert!(text
+            .lines()
+            .nth(2)
+            .unwrap()
+            .ends_with(", 88 bytes, index 2"));
+        Ok(())
+    }
+
+    #[test]
+    fn genus_version() -> Result<(), Error> {
+        let v2 = GenusVersion::KERIACDC_2;
+        let stream = [v2.to_str(), attachments().build_in(&v2)?].join("");
+        let annotations = annotations(stream.as_bytes())?;
+        assert_eq!(annotations[0].meaning, "KERIACDC genus version");
+        assert_eq!(annotations[0].value.as_deref(), Some("2.0"));
+        assert_eq!(annotations[1].text, "-KAW");
+        assert_eq!(annotations[1].meaning, "ControllerIdxSigs count code");
+        assert_eq!(annotations[2].value.as_deref(), Some("index 2"));
+        Ok(())
+    }
+
+    #[test]
+    fn malformed() {
+        assert!(annotations(b"-AAB").is_err());
+        assert!(annotations(&IXN.as_bytes()[..20]).is_err());
+        assert!(annotations(b"ABCD").is_err());
+        assert_eq!(annotations(b"").unwrap(), vec![]);
+    }
+}
@@ -0,0 +1,234 @@
+use super::{
+    parse::{counts_quadlets, SUPPORTED_GROUPS},
+    Attachments,
+};
+use crate::{
+    codex::b64,
+    derivation::{
+        attached_signature_code::AttachedSignatureCode,
+        basic::Basic,
+        counter::{Counter, CounterCode},
+        genus::GenusVersion,
+        self_signing::SelfSigning,
+        split_at_len,
+    },
+    error::Error,
+    parser::ParseOptions,
+    prefix::{Cigar, PrefixRef},
+};
+
+/// Borrowed view of the attachment groups of a binary domain (qb2) stream. The indexed
+/// signatures and receipt couples, most of the material of most streams, are [PrefixRef]s into
+/// the stream instead of copies. The other groups are parsed into `other`.
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct AttachmentsRef<'a> {
+    /// `-A` indexed signatures of the controllers
+    pub controller_signatures: Vec<PrefixRef<'a, AttachedSignatureCode>>,
+    /// `-B` indexed signatures of the witnesses
+    pub witness_signatures: Vec<PrefixRef<'a, AttachedSignatureCode>>,
+    /// `-C` keys and signatures of nontransferable receiptors
+    pub receipt_couples: Vec<(PrefixRef<'a, Basic>, PrefixRef<'a, SelfSigning>)>,
+    /// The groups of the stream not viewed in place, with none of the above.
+    pub other: Attachments,
+}
+
+impl<'a> AttachmentsRef<'a> {
+    /// Parses the attachment groups of a binary domain (qb2) stream, all of `qb2` must be
+    /// attachment groups. Count codes are read from the stream Base64 encoded once, the material
+    /// of the viewed groups is left in place.
+    pub fn from_qb2(qb2: &'a [u8]) -> Result<Self, Error> {
+        let mut attachments = Self::default();
+        let text = b64::encode(qb2);
+        attachments.parse_groups(qb2, &text, &mut GenusVersion::default(), 0)?;
+        Ok(attachments)
+    }
+
+    /// Copies the views out of the stream.
+    pub fn to_owned(&self) -> Attachments {
+        Attachments {
+            controller_signatures: self
+                .controller_signatures
+                .iter()
+                .map(|s| s.to_owned())
+                .collect(),
+            witness_signatures: self
+                .witness_signatures
+                .iter()
+                .map(|s| s.to_owned())
+                .collect(),
+            receipt_couples: self
+                .receipt_couples
+                .iter()
+                .map(|(verfer, signature)| Cigar::new(verfer.to_owned(), signature.to_owned()))
+                .collect(),
+            ..self.other.clone()
+        }
+    }
+
+    /// Parses the groups of `qb2`, of which `text` is the text domain encoding. Offsets in `text`
+    /// are 4/3 of those in `qb2` as every primitive is quadlet aligned.
+    fn parse_groups(
+        &mut self,
+        qb2: &'a [u8],
+        text: &str,
+        genus_version: &mut GenusVersion,
+        depth: usize,
+    ) -> Result<(), Error> {
+        let bytes = |part: &str| (text.len() - part.len()) / 4 * 3;
+        let mut rest = text;
+        while !rest.is_empty() {
+            if let Some((declared, tail)) = GenusVersion::from_stream_in(rest, genus_version)? {
+                *genus_version = declared;
+                rest = tail;
+                continue;
+            }
+            let (counter, tail) = Counter::from_stream_in(rest, genus_version)?;
+            rest = match counter.code {
+                CounterCode::ControllerIdxSigs
+                | CounterCode::WitnessIdxSigs
+                | CounterCode::NonTransReceiptCouples => {
+                    let len = self.view_members(&counter, &qb2[bytes(tail)..], genus_version)?;
+                    &tail[len / 3 * 4..]
+                }
+                CounterCode::AttachedMaterialQuadlets
+                | CounterCode::BigAttachedMaterialQuadlets => {
+                    if depth >= ParseOptions::default().max_depth {
+                        return Err(Error::DeserializeError(
+                            "Attachment groups nested too deep".into(),
+                        ));
+                    }
+                    let (material, after) = split_at_len(tail, counter.count as usize * 4)?;
+                    // pipelined attachments are the attachments of the message all the same,
+                    // a switch within the group ends with it
+                    let nested = &qb2[bytes(tail)..bytes(after)];
+                    self.parse_groups(nested, material, &mut genus_version.clone(), depth + 1)?;
+                    after
+                }
+                code if counts_quadlets(code, genus_version) => {
+                    let (_, after) = split_at_len(tail, counter.count as usize * 4)?;
+                    let group = &rest[..rest.len() - after.len()];
+                    let (other, _) =
+                        Attachments::from_stream_in(group, &mut genus_version.clone())?;
+                    self.other.extend(other);
+                    after
+                }
+                code if SUPPORTED_GROUPS.contains(&code) => {
+                    let mut members = tail;
+                    for _ in 0..counter.count {
+                        (members, _) = self.other.parse_member(code, members, genus_version)?;
+                    }
+                    members
+                }
+                _ => {
+                    return Err(Error::DeserializeError(format!(
+                        "Unsupported attachment group: {}",
+                        &rest[..rest.len() - tail.len()]
+                    )))
+                }
+            };
+        }
+        Ok(())
+    }
+
+    /// Views the members of the signature or receipt couple group counted by `counter` at the
+    /// start of `qb2`, returning the number of bytes they take.
+    fn view_members(
+        &mut self,
+        counter: &Counter,
+        qb2: &'a [u8],
+        genus_version: &GenusVersion,
+    ) -> Result<usize, Error> {
+        let quadlets = genus_version.counts_quadlets();
+        let count = counter.count as usize;
+        let (mut rest, mut members) = (qb2, 0);
+        while if quadlets {
+            qb2.len() - rest.len() < count * 3
+        } else {
+            members < count
+        } {
+            rest = match counter.code {
+                CounterCode::NonTransReceiptCouples => {
+                    let (verfer, tail) = PrefixRef::<Basic>::from_qb2(rest)?;
+                    let (signature, tail) = PrefixRef::<SelfSigning>::from_qb2(tail)?;
+                    self.receipt_couples.push((verfer, signature));
+                    tail
+                }
+                code => {
+                    let (siger, tail) = PrefixRef::<AttachedSignatureCode>::from_qb2(rest)?;
+                    match code {
+                        CounterCode::ControllerIdxSigs => self.controller_signatures.push(siger),
+                        _ => self.witness_signatures.push(siger),
+                    }
+                    tail
+                }
+            };
+            members += 1;
+        }
+        let len = qb2.len() - rest.len();
+        if quadlets && len != count * 3 {
+            return Err(Error::DeserializeError(format!(
+                "Attachment group members overrun its {} quadlets",
+                count
+            )));
+        }
+        Ok(len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        attachments::AttachmentsBuilder,
+        derivation::self_addressing::SelfAddressing,
+        keys::PublicKey,
+        prefix::{AttachedSignaturePrefix, BasicPrefix},
+    };
+    use core::str::FromStr;
+
+    #[test]
+    fn views() -> Result<(), Error> {
+        let sig = |index| {
+            AttachedSignaturePrefix::new(SelfSigning::Ed25519Sha512, vec![index as u8; 64], index)
+        };
+        let key = BasicPrefix::new_unchecked(Basic::Ed25519NT, PublicKey::new(vec![4; 32]));
+        let cigar = Cigar::new(key, SelfSigning::Ed25519Sha512.derive(vec![5; 64]));
+        let builder = AttachmentsBuilder::new()
+            .controller_signatures([sig(0)?, sig(1)?])
+            .witness_signature(sig(2)?)
+            .receipt_couple(cigar.clone())
+            .seal_source_couple(1, SelfAddressing::Blake3_256.derive(b"delegating event"));
+        let qb2 = builder.build_qb2()?;
+
+        let view = AttachmentsRef::from_qb2(&qb2)?;
+        assert_eq!(view.controller_signatures.len(), 2);
+        assert_eq!(view.controller_signatures[1].raw, &[1; 64]);
+        // the signature is left in the stream
+        let raw = view.controller_signatures[1].raw.as_ptr() as usize;
+        assert!((qb2.as_ptr() as usize..qb2.as_ptr() as usize + qb2.len()).contains(&raw));
+        assert_eq!(
+            view.receipt_couples[0].1.raw,
+            &cigar.signature.signature[..]
+        );
+        assert_eq!(view.other.seal_source_couples.len(), 1);
+        assert_eq!(view.to_owned(), Attachments::from_str(&builder.build()?)?);
+        assert_eq!(Attachments::from_qb2(&qb2)?, view.to_owned());
+
+        // pipelined, and in the CESR 2 tables
+        let pipelined = builder.clone().pipelined().build_qb2()?;
+        let view = AttachmentsRef::from_qb2(&pipelined)?;
+        assert_eq!(view.to_owned(), Attachments::from_str(&builder.build()?)?);
+        let v2 = [
+            GenusVersion::KERIACDC_2.to_str(),
+            builder.build_in(&GenusVersion::KERIACDC_2)?,
+        ]
+        .join("");
+        let qb2 = b64::decode(&v2)?;
+        let view = AttachmentsRef::from_qb2(&qb2)?;
+        assert_eq!(view.controller_signatures.len(), 2);
+        assert_eq!(view.to_owned(), Attachments::from_str(&v2)?);
+
+        assert!(AttachmentsRef::from_qb2(&qb2[..qb2.len() - 3]).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,356 @@
+use super::{
+    Attachments, EventSeal, FirstSeenReplayCouple, SourceSeal, TransIdxSigGroup,
+    TransReceiptQuadruple,
+};
+use crate::{
+    codex::b64,
+    derivation::{
+        counter::{Counter, CounterCode},
+        genus::GenusVersion,
+    },
+    error::Error,
+    prefix::{AttachedSignaturePrefix, Cigar, Dater, Prefix, SadPath, SelfAddressingPrefix},
+};
+
+/// Assembles the attachment groups of a KERI event into a single text domain (qb64) stream,
+/// each group framed by its count code. Empty groups are left out.
+///
+/// Groups are written in the order KERIpy attaches them when cloning events: controller
+/// signatures (`-A`), witness signatures (`-B`), nontransferable receipt couples (`-C`),
+/// transferable receipt quadruples (`-D`), transferable indexed signature groups (`-F`),
+/// seal source couples (`-G`), seal source triples (`-I`) and first seen replay couples (`-E`),
+/// followed by pathed material (`-L`), one group per path. Pipelined attachments wrap all of them
+/// in one more group.
+#[derive(Debug, Default, Clone)]
+pub struct AttachmentsBuilder {
+    controller_signatures: Vec<AttachedSignaturePrefix>,
+    witness_signatures: Vec<AttachedSignaturePrefix>,
+    receipt_couples: Vec<Cigar>,
+    trans_receipt_quadruples: Vec<TransReceiptQuadruple>,
+    trans_idx_sig_groups: Vec<TransIdxSigGroup>,
+    seal_source_couples: Vec<SourceSeal>,
+    seal_source_triples: Vec<EventSeal>,
+    first_seen_replay_couples: Vec<FirstSeenReplayCouple>,
+    pathed_material: Vec<(SadPath, AttachmentsBuilder)>,
+    pipelined: bool,
+}
+
+impl AttachmentsBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an indexed signature of a controller, indexed into the keys of the event.
+    pub fn controller_signature(mut self, sig: impl Into<AttachedSignaturePrefix>) -> Self {
+        self.controller_signatures.push(sig.into());
+        self
+    }
+
+    pub fn controller_signatures<S>(self, sigs: impl IntoIterator<Item = S>) -> Self
+    where
+        S: Into<AttachedSignaturePrefix>,
+    {
+        sigs.into_iter()
+            .fold(self, |builder, sig| builder.controller_signature(sig))
+    }
+
+    /// Adds an indexed signature of a witness, indexed into the witness list of the event.
+    /// Witnesses have no prior next key list, dual index codes become current only codes.
+    pub fn witness_signature(mut self, sig: impl Into<AttachedSignaturePrefix>) -> Self {
+        let mut sig = sig.into();
+        sig.code = sig.code.to_current_only();
+        self.witness_signatures.push(sig);
+        self
+    }
+
+    pub fn witness_signatures<S>(self, sigs: impl IntoIterator<Item = S>) -> Self
+    where
+        S: Into<AttachedSignaturePrefix>,
+    {
+        sigs.into_iter()
+            .fold(self, |builder, sig| builder.witness_signature(sig))
+    }
+
+    /// Adds a receipt signature of a nontransferable identifier.
+    pub fn receipt_couple(mut self, cigar: Cigar) -> Self {
+        self.receipt_couples.push(cigar);
+        self
+    }
+
+    pub fn receipt_couples(self, cigars: impl IntoIterator<Item = Cigar>) -> Self {
+        cigars
+            .into_iter()
+            .fold(self, |builder, cigar| builder.receipt_couple(cigar))
+    }
+
+    /// Adds a receipt signature of a transferable identifier.
+    pub fn trans_receipt_quadruple(mut self, quadruple: TransReceiptQuadruple) -> Self {
+        self.trans_receipt_quadruples.push(quadruple);
+        self
+    }
+
+    /// Adds indexed signatures of a transferable identifier, e.g. the signer of a reply.
+    pub fn trans_idx_sig_group(mut self, group: TransIdxSigGroup) -> Self {
+        self.trans_idx_sig_groups.push(group);
+        self
+    }
+
+    /// Adds the sequence number and digest of the event anchoring this one, e.g. the
+    /// delegating event of a delegated event.
+    pub fn seal_source_couple(mut self, sn: u64, digest: SelfAddressingPrefix) -> Self {
+        self.seal_source_couples.push(SourceSeal::new(sn, digest));
+        self
+    }
+
+    /// Adds the seal of an anchoring event of another identifier.
+    pub fn seal_source_triple(mut self, seal: EventSeal) -> Self {
+        self.seal_source_triples.push(seal);
+        self
+    }
+
+    /// Adds the first seen ordinal of the event and the datetime it was first seen at.
+    pub fn first_seen_replay_couple(mut self, first_seen: u64, datetime: Dater) -> Self {
+        self.first_seen_replay_couples
+            .push(FirstSeenReplayCouple::new(first_seen, datetime));
+        self
+    }
+
+    /// Adds attachments over the content at `path` of the event, e.g. signatures over a nested
+    /// block.
+    pub fn pathed_material(mut self, path: SadPath, attachments: AttachmentsBuilder) -> Self {
+        self.pathed_material.push((path, attachments));
+        self
+    }
+
+    /// Wraps the groups in an attached material group (`-V`, `-0V` when big, `-C` under CESR 2)
+    /// counting their quadlets, so that parsers can take the attachments as a whole without
+    /// waiting for the next message.
+    pub fn pipelined(mut self) -> Self {
+        self.pipelined = true;
+        self
+    }
+
+    /// The attachment groups as a text domain (qb64) stream.
+    pub fn build(&self) -> Result<String, Error> {
+        self.build_in(&GenusVersion::default())
+    }
+
+    /// The attachment groups as a text domain (qb64) stream encoded with the count code table of
+    /// `genus_version`, which is not declared in the stream.
+    pub fn build_in(&self, genus_version: &GenusVersion) -> Result<String, Error> {
+        let mut groups = vec![];
+        let mut frame = |code, members: Vec<Result<String, Error>>| {
+            frame(&mut groups, genus_version, code, members)
+        };
+        frame(
+            CounterCode::ControllerIdxSigs,
+            self.controller_signatures
+                .iter()
+                .map(|s| Ok(s.to_str()))
+                .collect(),
+        )?;
+        frame(
+            CounterCode::WitnessIdxSigs,
+            self.witness_signatures
+                .iter()
+                .map(|s| Ok(s.to_str()))
+                .collect(),
+        )?;
+        frame(
+            CounterCode::NonTransReceiptCouples,
+            self.receipt_couples
+                .iter()
+                .map(|c| Ok(c.to_str()))
+                .collect(),
+        )?;
+        frame(
+            CounterCode::TransReceiptQuadruples,
+            self.trans_receipt_quadruples
+                .iter()
+                .map(|q| Ok(q.to_str()))
+                .collect(),
+        )?;
+        frame(
+            CounterCode::TransIdxSigGroups,
+            self.trans_idx_sig_groups
+                .iter()
+                .map(|g| g.to_str_in(genus_version))
+                .collect(),
+        )?;
+        frame(
+            CounterCode::SealSourceCouples,
+            self.seal_source_couples
+                .iter()
+                .map(|s| Ok(s.to_str()))
+                .collect(),
+        )?;
+        frame(
+            CounterCode::SealSourceTriples,
+            self.seal_source_triples
+                .iter()
+                .map(|s| Ok(s.to_str()))
+                .collect(),
+        )?;
+        frame(
+            CounterCode::FirstSeenReplayCouples,
+            self.first_seen_replay_couples
+                .iter()
+                .map(|c| Ok(c.to_str()))
+                .collect(),
+        )?;
+        for (path, attachments) in &self.pathed_material {
+            let material = [path.to_str(), attachments.build_in(genus_version)?].join("");
+            if !material.len().is_multiple_of(4) {
+                return Err(Error::SemanticError(format!(
+                    "Pathed material not aligned on 24 bit boundary: {}",
+                    material
+                )));
+            }
+            let counter = Counter {
+                code: CounterCode::PathedMaterialQuadlets,
+                count: (material.len() / 4) as u32,
+            };
+            groups.push(counter.to_str_in(genus_version)?);
+            groups.push(material);
+        }
+        let groups = groups.join("");
+        if !self.pipelined || groups.is_empty() {
+            return Ok(groups);
+        }
+        let count = (groups.len() / 4) as u32;
+        let code = match genus_version.version.major {
+            1 if count > CounterCode::AttachedMaterialQuadlets.max_count() => {
+                CounterCode::BigAttachedMaterialQuadlets
+            }
+            _ => CounterCode::AttachedMaterialQuadlets,
+        };
+        Ok([Counter { code, count }.to_str_in(genus_version)?, groups].join(""))
+    }
+
+    /// The attachment groups as a binary domain (qb2) stream.
+    pub fn build_qb2(&self) -> Result<Vec<u8>, Error> {
+        let qb64 = self.build()?;
+        if !qb64.len().is_multiple_of(4) {
+            return Err(Error::SemanticError(
+                "Attachments not aligned on 24 bit boundary".into(),
+            ));
+        }
+        b64::decode(qb64)
+    }
+}
+
+/// The builder writing out parsed attachments again.
+impl From<Attachments> for AttachmentsBuilder {
+    fn from(attachments: Attachments) -> Self {
+        let builder = Self {
+            controller_signatures: vec![],
+            witness_signatures: vec![],
+            receipt_couples: attachments.receipt_couples,
+            trans_receipt_quadruples: attachments.trans_receipt_quadruples,
+            trans_idx_sig_groups: attachments.trans_idx_sig_groups,
+            seal_source_couples: attachments.seal_source_couples,
+            seal_source_triples: attachments.seal_source_triples,
+            first_seen_replay_couples: attachments.first_seen_replay_couples,
+            pathed_material: attachments
+                .pathed_material
+                .into_iter()
+                .map(|(path, material)| (path, material.into()))
+                .collect(),
+            pipelined: false,
+        };
+        builder
+            .controller_signatures(attachments.controller_signatures)
+            .witness_signatures(attachments.witness_signatures)
+    }
+}
+
+/// Appends the count code and members of a group, unless the group is empty.
+fn frame(
+    groups: &mut Vec<String>,
+    genus_version: &GenusVersion,
+    code: CounterCode,
+    members: Vec<Result<String, Error>>,
+) -> Result<(), Error> {
+    if members.is_empty() {
+        return Ok(());
+    }
+    let members = members.into_iter().collect::<Result<Vec<_>, _>>()?;
+    let count = match genus_version.counts_quadlets() {
+        true => members.iter().map(|m| m.len() / 4).sum(),
+        false => members.len(),
+    };
+    let counter = Counter {
+        code,
+        count: count as u32,
+    };
+    groups.push(counter.to_str_in(genus_version)?);
+    groups.extend(members);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        derivation::{self_addressing::SelfAddressing, self_signing::SelfSigning},
+        test_utils::signer,
+    };
+
+    fn indexed(seed: u8, index: u16) -> AttachedSignaturePrefix {
+        let sig = signer(seed, true).sign(b"event");
+        AttachedSignaturePrefix::new(SelfSigning::Ed25519Sha512, sig.signature, index).unwrap()
+    }
+
+    #[test]
+    fn empty() -> Result<(), Error> {
+        assert_eq!(AttachmentsBuilder::new().build()?, "");
+        Ok(())
+    }
+
+    #[test]
+    fn groups() -> Result<(), Error> {
+        let sigs = [indexed(1, 0), indexed(2, 1)];
+        let wig = indexed(3, 0);
+        let witness = signer(4, false);
+        let cigar = Cigar::new(witness.basic_prefix(), witness.sign(b"event"));
+        let digest = SelfAddressing::Blake3_256.derive(b"delegating event");
+
+        let attachments = AttachmentsBuilder::new()
+            .first_seen_replay_couple(3, Dater::from_iso8601("2020-08-22T17:50:09.988921+00:00")?)
+            .seal_source_couple(5, digest.clone())
+            .receipt_couple(cigar.clone())
+            .witness_signature(wig.clone())
+            .controller_signatures(sigs.clone())
+            .build()?;
+
+        let expected = [
+            "-AAC".to_string(),
+            sigs[0].to_str(),
+            sigs[1].to_str(),
+            "-BAB".to_string(),
+            // under the current only code
+            ["B".to_string(), wig.to_str()[1..].to_string()].join(""),
+            "-CAB".to_string(),
+            cigar.to_str(),
+            "-GAB".to_string(),
+            // sequence numbers as keripy encodes them, pre-padded
+            "0AAAAAAAAAAAAAAAAAAAAAAF".to_string(),
+            digest.to_str(),
+            "-EAB".to_string(),
+            "0AAAAAAAAAAAAAAAAAAAAAAD".to_string(),
+            "1AAG2020-08-22T17c50c09d988921p00c00".to_string(),
+        ]
+        .join("");
+        assert_eq!(attachments, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn binary_domain() -> Result<(), Error> {
+        let builder = AttachmentsBuilder::new().controller_signature(indexed(1, 0));
+        let qb2 = builder.build_qb2()?;
+        assert_eq!(&qb2[..3], &[0xf8, 0x00, 0x01]);
+        assert_eq!(b64::encode(&qb2), builder.build()?);
+        Ok(())
+    }
+}
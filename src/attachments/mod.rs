@@ -0,0 +1,55 @@
+use crate::{
+    error::Error,
+    prefix::{siger::check_sigers, Cigar, SadPath, Siger},
+};
+
+pub mod borrowed;
+pub mod builder;
+pub mod parse;
+pub mod replay;
+pub mod seal;
+pub mod trans;
+
+pub use borrowed::AttachmentsRef;
+pub use builder::AttachmentsBuilder;
+pub use replay::FirstSeenReplayCouple;
+pub use seal::{DigestSeal, EventSeal, SourceSeal};
+pub use trans::{TransIdxSigGroup, TransReceiptQuadruple};
+
+/// The attachment groups of a KERI event, as parsed from an attachment stream.
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct Attachments {
+    /// `-A` indexed signatures of the controllers
+    pub controller_signatures: Vec<Siger>,
+    /// `-B` indexed signatures of the witnesses
+    pub witness_signatures: Vec<Siger>,
+    /// `-C` receipt signatures of nontransferable identifiers
+    pub receipt_couples: Vec<Cigar>,
+    /// `-D` receipt signatures of transferable identifiers
+    pub trans_receipt_quadruples: Vec<TransReceiptQuadruple>,
+    /// `-F` indexed signatures of transferable identifiers with their establishment events
+    pub trans_idx_sig_groups: Vec<TransIdxSigGroup>,
+    /// `-G` sequence numbers and digests of anchoring events
+    pub seal_source_couples: Vec<SourceSeal>,
+    /// `-I` prefixes, sequence numbers and digests of anchoring events of other identifiers
+    pub seal_source_triples: Vec<EventSeal>,
+    /// `-E` first seen ordinals and ISO-8601 datetimes
+    pub first_seen_replay_couples: Vec<FirstSeenReplayCouple>,
+    /// `-L` attachments over the content at a path of the event, e.g. signatures over a nested
+    /// block of an ACDC
+    pub pathed_material: Vec<(SadPath, Attachments)>,
+}
+
+impl Attachments {
+    /// The `-A` controller signatures sorted by index, their indices checked against the
+    /// `key_count` current keys of the signed event, see [check_sigers].
+    pub fn controller_sigers(&self, key_count: usize) -> Result<Vec<Siger>, Error> {
+        check_sigers(&self.controller_signatures, key_count)
+    }
+
+    /// The `-B` witness signatures sorted by index, their indices checked against the
+    /// `witness_count` witnesses of the signed event.
+    pub fn witness_sigers(&self, witness_count: usize) -> Result<Vec<Siger>, Error> {
+        check_sigers(&self.witness_signatures, witness_count)
+    }
+}
@@ -0,0 +1,582 @@
+use super::{
+    Attachments, AttachmentsRef, EventSeal, FirstSeenReplayCouple, SourceSeal, TransIdxSigGroup,
+    TransReceiptQuadruple,
+};
+use crate::{
+    derivation::{
+        attached_signature_code::AttachedSignatureCode,
+        b64_to_u64,
+        counter::{Counter, CounterCode},
+        genus::GenusVersion,
+        split_at_len,
+    },
+    error::Error,
+    parser::ParseOptions,
+    prefix::{split_at_code_len, Cigar, SadPath, Siger},
+};
+use core::str::FromStr;
+
+/// Attachment groups the parser understands.
+pub(super) const SUPPORTED_GROUPS: [CounterCode; 11] = [
+    CounterCode::ControllerIdxSigs,
+    CounterCode::WitnessIdxSigs,
+    CounterCode::NonTransReceiptCouples,
+    CounterCode::TransReceiptQuadruples,
+    CounterCode::TransIdxSigGroups,
+    CounterCode::SealSourceCouples,
+    CounterCode::SealSourceTriples,
+    CounterCode::FirstSeenReplayCouples,
+    CounterCode::PathedMaterialQuadlets,
+    CounterCode::AttachedMaterialQuadlets,
+    CounterCode::BigAttachedMaterialQuadlets,
+];
+
+/// Attachment groups of attachment groups: pathed material and pipelined attachments.
+const NESTING_GROUPS: [CounterCode; 3] = [
+    CounterCode::PathedMaterialQuadlets,
+    CounterCode::AttachedMaterialQuadlets,
+    CounterCode::BigAttachedMaterialQuadlets,
+];
+
+impl Attachments {
+    /// Parses attachment groups from the start of a text domain (qb64) stream, up to the end of
+    /// the stream or the first group that is not an attachment group, e.g. the next message.
+    /// Returns the attachments with the rest of `s`.
+    pub fn from_stream(s: &str) -> Result<(Self, &str), Error> {
+        Self::from_stream_in(s, &mut GenusVersion::default())
+    }
+
+    /// Parses attachment groups like [Attachments::from_stream], with `genus_version` the genus
+    /// version active at the start of `s`. It is switched by the genus version codes among the
+    /// groups, count codes are looked up in its table.
+    pub fn from_stream_in<'a>(
+        s: &'a str,
+        genus_version: &mut GenusVersion,
+    ) -> Result<(Self, &'a str), Error> {
+        Self::from_stream_with(s, genus_version, &ParseOptions::default())
+    }
+
+    /// Parses attachment groups like [Attachments::from_stream_in], as strictly as `options`
+    /// tell. Groups skipped by lenient options are left out of the attachments.
+    pub fn from_stream_with<'a>(
+        s: &'a str,
+        genus_version: &mut GenusVersion,
+        options: &ParseOptions,
+    ) -> Result<(Self, &'a str), Error> {
+        Self::parse_groups(s, genus_version, options, 0, &mut 0)
+    }
+
+    /// Parses attachment groups nested `depth` groups deep like
+    /// [Attachments::from_stream_with], counting the primitives parsed on from `primitive`.
+    /// Failures are located by their offset in `s` and the index of the failing primitive.
+    pub(crate) fn parse_groups<'a>(
+        s: &'a str,
+        genus_version: &mut GenusVersion,
+        options: &ParseOptions,
+        depth: usize,
+        primitive: &mut usize,
+    ) -> Result<(Self, &'a str), Error> {
+        let mut attachments = Self::default();
+        let mut rest = s;
+        while rest.starts_with('-') {
+            let at = |e: Error, part: &str, primitive: usize| e.at(offset_in(s, part), primitive);
+            let declared = GenusVersion::from_stream_in(rest, genus_version)
+                .map_err(|e| at(e, rest, *primitive))?;
+            if let Some((declared, tail)) = declared {
+                *genus_version = declared;
+                rest = tail;
+                *primitive += 1;
+                continue;
+            }
+            let (counter, tail) = match Counter::from_stream_in(rest, genus_version) {
+                Err(Error::UnknownCode { .. })
+                    if options.allow_unknown_codes && genus_version.counts_quadlets() =>
+                {
+                    rest = skip_unknown_group(rest).map_err(|e| at(e, rest, *primitive))?;
+                    *primitive += 1;
+                    continue;
+                }
+                parsed => parsed.map_err(|e| at(e, rest, *primitive))?,
+            };
+            if !SUPPORTED_GROUPS.contains(&counter.code) {
+                if !options.strict && counts_quadlets(counter.code, genus_version) {
+                    (_, rest) = split_at_len(tail, counter.count as usize * 4)
+                        .map_err(|e| at(e, tail, *primitive))?;
+                    *primitive += 1;
+                    continue;
+                }
+                let e = Error::DeserializeError(format!(
+                    "Unsupported attachment group: {}",
+                    &rest[..rest.len() - tail.len()]
+                ));
+                return Err(at(e, rest, *primitive));
+            }
+            *primitive += 1;
+            rest = tail;
+            if NESTING_GROUPS.contains(&counter.code) {
+                if depth >= options.max_depth {
+                    let e = Error::DeserializeError(format!(
+                        "Attachment groups nested deeper than {}",
+                        options.max_depth
+                    ));
+                    return Err(at(e, rest, *primitive));
+                }
+                let (material, tail) = split_at_len(rest, counter.count as usize * 4)
+                    .map_err(|e| at(e, rest, *primitive))?;
+                let (path, material) = match counter.code {
+                    CounterCode::PathedMaterialQuadlets => {
+                        let (path, material) = SadPath::from_stream(material)
+                            .map_err(|e| at(e, material, *primitive))?;
+                        *primitive += 1;
+                        (Some(path), material)
+                    }
+                    _ => (None, material),
+                };
+                // a switch within the group ends with it
+                let mut nested_genus_version = *genus_version;
+                let nested = Self::parse_groups(
+                    material,
+                    &mut nested_genus_version,
+                    options,
+                    depth + 1,
+                    primitive,
+                )
+                .map_err(|e| at(e, material, 0))?;
+                let nested = match nested {
+                    (nested, "") => nested,
+                    (_, unparsed) => {
+                        let e = Error::DeserializeError(format!(
+                            "Unexpected data after nested attachment groups: {}",
+                            unparsed
+                        ));
+                        return Err(at(e, unparsed, *primitive));
+                    }
+                };
+                match path {
+                    Some(path) => attachments.pathed_material.push((path, nested)),
+                    // pipelined attachments are the attachments of the message all the same
+                    None => attachments.extend(nested),
+                }
+                rest = tail;
+                continue;
+            }
+            if genus_version.counts_quadlets() {
+                let (mut members, tail) = split_at_len(rest, counter.count as usize * 4)
+                    .map_err(|e| at(e, rest, *primitive))?;
+                while !members.is_empty() {
+                    let (tail, primitives) = attachments
+                        .parse_member(counter.code, members, genus_version)
+                        .map_err(|e| at(e, members, *primitive))?;
+                    *primitive += primitives;
+                    members = tail;
+                }
+                rest = tail;
+            } else {
+                for _ in 0..counter.count {
+                    let (tail, primitives) = attachments
+                        .parse_member(counter.code, rest, genus_version)
+                        .map_err(|e| at(e, rest, *primitive))?;
+                    *primitive += primitives;
+                    rest = tail;
+                }
+            }
+        }
+        Ok((attachments, rest))
+    }
+
+    /// Parses attachment groups from a binary domain (qb2) stream, all of `qb2` must be
+    /// attachment groups.
+    pub fn from_qb2(qb2: &[u8]) -> Result<Self, Error> {
+        AttachmentsRef::from_qb2(qb2).map(|attachments| attachments.to_owned())
+    }
+
+    /// Appends the groups of `other`, e.g. of pipelined attachments.
+    pub(super) fn extend(&mut self, other: Self) {
+        self.controller_signatures
+            .extend(other.controller_signatures);
+        self.witness_signatures.extend(other.witness_signatures);
+        self.receipt_couples.extend(other.receipt_couples);
+        self.trans_receipt_quadruples
+            .extend(other.trans_receipt_quadruples);
+        self.trans_idx_sig_groups.extend(other.trans_idx_sig_groups);
+        self.seal_source_couples.extend(other.seal_source_couples);
+        self.seal_source_triples.extend(other.seal_source_triples);
+        self.first_seen_replay_couples
+            .extend(other.first_seen_replay_couples);
+        self.pathed_material.extend(other.pathed_material);
+    }
+
+    /// Parses one member of the group counted by `code` from the start of `s`, returning the
+    /// rest of `s` and the number of primitives of the member.
+    pub(super) fn parse_member<'a>(
+        &mut self,
+        code: CounterCode,
+        s: &'a str,
+        genus_version: &GenusVersion,
+    ) -> Result<(&'a str, usize), Error> {
+        let primitives = match code {
+            CounterCode::ControllerIdxSigs | CounterCode::WitnessIdxSigs => 1,
+            CounterCode::TransReceiptQuadruples => 4,
+            CounterCode::SealSourceTriples => 3,
+            _ => 2,
+        };
+        let rest = match code {
+            CounterCode::ControllerIdxSigs | CounterCode::WitnessIdxSigs => {
+                let (siger, rest) = split_at_code_len(s, AttachedSignatureCode::from_str(s)?)?;
+                let siger = Siger::from_str(siger)?;
+                match code {
+                    CounterCode::ControllerIdxSigs => self.controller_signatures.push(siger),
+                    _ => self.witness_signatures.push(siger),
+                }
+                rest
+            }
+            CounterCode::NonTransReceiptCouples => {
+                let (cigar, rest) = Cigar::from_stream(s)?;
+                self.receipt_couples.push(cigar);
+                rest
+            }
+            CounterCode::TransReceiptQuadruples => {
+                let (quadruple, rest) = TransReceiptQuadruple::from_stream(s)?;
+                self.trans_receipt_quadruples.push(quadruple);
+                rest
+            }
+            CounterCode::TransIdxSigGroups => {
+                let (group, rest) = TransIdxSigGroup::from_stream_in(s, genus_version)?;
+                // prefix, sequence number and digest, then the signatures with their count code
+                let primitives = 4 + group.signatures.len();
+                self.trans_idx_sig_groups.push(group);
+                return Ok((rest, primitives));
+            }
+            CounterCode::SealSourceCouples => {
+                let (seal, rest) = SourceSeal::from_stream(s)?;
+                self.seal_source_couples.push(seal);
+                rest
+            }
+            CounterCode::SealSourceTriples => {
+                let (seal, rest) = EventSeal::from_stream(s)?;
+                self.seal_source_triples.push(seal);
+                rest
+            }
+            CounterCode::FirstSeenReplayCouples => {
+                let (couple, rest) = FirstSeenReplayCouple::from_stream(s)?;
+                self.first_seen_replay_couples.push(couple);
+                rest
+            }
+            _ => unreachable!("group support checked before parsing members"),
+        };
+        Ok((rest, primitives))
+    }
+}
+
+/// Whether the groups of `code` are counted in quadlets under `genus_version`, so that they can
+/// be skipped without being understood.
+pub(super) fn counts_quadlets(code: CounterCode, genus_version: &GenusVersion) -> bool {
+    genus_version.counts_quadlets()
+        || matches!(
+            code,
+            CounterCode::PathedMaterialQuadlets
+                | CounterCode::AttachedMaterialQuadlets
+                | CounterCode::MessageDataMaterialQuadlets
+                | CounterCode::CombinedMaterialQuadlets
+                | CounterCode::MaterialQuadlets
+                | CounterCode::BigAttachedMaterialQuadlets
+                | CounterCode::BigMessageDataMaterialQuadlets
+                | CounterCode::BigCombinedMaterialQuadlets
+                | CounterCode::BigMaterialQuadlets
+        )
+}
+
+/// Skips the group of a CESR 2 count code missing from the code table at the start of `s`,
+/// returning the rest of `s`. The code is sized by its selector: `--` and `-_` start big codes
+/// with a 3 character hard part and a 5 character count, `-` small ones with 2 and 2.
+fn skip_unknown_group(s: &str) -> Result<&str, Error> {
+    let (hs, ss) = match s.get(..2) {
+        Some("--" | "-_") => (3, 5),
+        _ => (2, 2),
+    };
+    let (code, rest) = split_at_len(s, hs + ss)?;
+    let count = b64_to_u64(&code[hs..])? as usize;
+    let (_, rest) = split_at_len(rest, count * 4)?;
+    Ok(rest)
+}
+
+/// Byte offset of `part`, a slice of `s`, from the start of `s`.
+fn offset_in(s: &str, part: &str) -> usize {
+    part.as_ptr() as usize - s.as_ptr() as usize
+}
+
+/// Parses attachment groups, all of `s` must be attachment groups.
+impl FromStr for Attachments {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut primitive = 0;
+        let options = ParseOptions::default();
+        match Self::parse_groups(s, &mut GenusVersion::default(), &options, 0, &mut primitive)? {
+            (attachments, "") => Ok(attachments),
+            (_, rest) => Err(Error::DeserializeError(format!(
+                "Unexpected data after attachments: {}",
+                rest
+            ))
+            .at(offset_in(s, rest), primitive)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        attachments::AttachmentsBuilder,
+        derivation::{self_addressing::SelfAddressing, self_signing::SelfSigning, u64_to_b64},
+        prefix::{AttachedSignaturePrefix, Dater, IdentifierPrefix},
+        test_utils::signer,
+    };
+
+    fn dater() -> Dater {
+        Dater::from_iso8601("2020-08-22T17:50:09.988921+00:00").unwrap()
+    }
+
+    fn builder() -> AttachmentsBuilder {
+        let sigs = (0..2).map(|i| {
+            let sig = signer(i + 1, true).sign(b"event");
+            AttachedSignaturePrefix::new(SelfSigning::Ed25519Sha512, sig.signature, i as u16)
+                .unwrap()
+        });
+        let witness = signer(4, false);
+        AttachmentsBuilder::new()
+            .controller_signatures(sigs)
+            .receipt_couple(Cigar::new(witness.basic_prefix(), witness.sign(b"event")))
+            .seal_source_couple(1, SelfAddressing::Blake3_256.derive(b"delegating event"))
+            .seal_source_triple(EventSeal::new(
+                "EKYLUMmNPZeEs77Zvclf0bSN5IN-mLfLpx2ySb-HDlk4"
+                    .parse()
+                    .unwrap(),
+                2,
+                SelfAddressing::Blake3_256.derive(b"issuing event"),
+            ))
+            .first_seen_replay_couple(3, dater())
+    }
+
+    #[test]
+    fn pathed_material() -> Result<(), Error> {
+        let sig = signer(1, true).sign(b"block");
+        let nested = AttachmentsBuilder::new().controller_signature(AttachedSignaturePrefix::new(
+            SelfSigning::Ed25519Sha512,
+            sig.signature,
+            0,
+        )?);
+        let path = SadPath::from_path("-a")?;
+        let stream = builder()
+            .pathed_material(path.clone(), nested.clone())
+            .build()?;
+        let attachments = Attachments::from_str(&stream)?;
+        assert_eq!(
+            attachments.pathed_material,
+            vec![(path, Attachments::from_str(&nested.build()?)?)]
+        );
+        assert_eq!(attachments.controller_signatures.len(), 2);
+
+        // the count covers the path and the nested groups
+        let pathed = &stream[stream.find("-L").unwrap()..];
+        assert!(pathed.starts_with("-LAZ"));
+        assert!(Attachments::from_str(&pathed.replacen("-LAZ", "-LAY", 1)).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn pipelined() -> Result<(), Error> {
+        let stream = builder().build()?;
+        let pipelined = builder().pipelined().build()?;
+        assert_eq!(&pipelined[4..], stream);
+        assert_eq!(
+            &pipelined[..4],
+            format!("-V{}", u64_to_b64(stream.len() as u64 / 4, 2))
+        );
+        assert_eq!(
+            Attachments::from_str(&pipelined)?,
+            Attachments::from_str(&stream)?
+        );
+        assert_eq!(AttachmentsBuilder::new().pipelined().build()?, "");
+
+        // counts past the small code take the big one
+        let many = (0..125).fold(AttachmentsBuilder::new(), |builder, i| {
+            let witness = signer(i + 1, false);
+            builder.receipt_couple(Cigar::new(witness.basic_prefix(), witness.sign(b"event")))
+        });
+        let big = many.pipelined().build()?;
+        assert!(big.starts_with("-0V"));
+        assert_eq!(Attachments::from_str(&big)?.receipt_couples.len(), 125);
+        assert!(Attachments::from_str(&pipelined[..pipelined.len() - 4]).is_err());
+
+        let v2 = GenusVersion::KERIACDC_2;
+        let pipelined = builder().pipelined().build_in(&v2)?;
+        assert!(pipelined.starts_with("-C"));
+        let (attachments, _) = Attachments::from_stream_in(&pipelined, &mut v2.clone())?;
+        assert_eq!(attachments, Attachments::from_str(&stream)?);
+        Ok(())
+    }
+
+    #[test]
+    fn lenient() -> Result<(), Error> {
+        let stream = builder().build()?;
+        let lenient = ParseOptions::lenient();
+        let parse = |s: &str, genus_version: GenusVersion, options: &ParseOptions| {
+            Attachments::from_stream_with(s, &mut genus_version.clone(), options)
+                .map(|(attachments, rest)| (attachments, rest.len()))
+        };
+
+        // a known group counted in quadlets is skipped, one counted in members is not
+        let skippable = [stream.as_str(), "-WABAAAA"].join("");
+        assert!(Attachments::from_str(&skippable).is_err());
+        let (attachments, 0) = parse(&skippable, GenusVersion::default(), &lenient)? else {
+            panic!("unparsed attachments")
+        };
+        assert_eq!(attachments, Attachments::from_str(&stream)?);
+        let members = [stream.as_str(), "-HAB"].join("");
+        assert!(parse(&members, GenusVersion::default(), &lenient).is_err());
+
+        // under CESR 2 every group is counted in quadlets, unknown codes sized by their selector
+        let v2 = GenusVersion::KERIACDC_2;
+        let stream = builder().build_in(&v2)?;
+        for unknown in ["-aABAAAA", "--aAAAABAAAA"] {
+            let skippable = [unknown, stream.as_str()].join("");
+            let known_only = ParseOptions {
+                strict: false,
+                ..ParseOptions::strict()
+            };
+            assert!(parse(&skippable, v2, &known_only).is_err());
+            let (skipped, _) = parse(&skippable, v2, &lenient)?;
+            assert_eq!(skipped, parse(&stream, v2, &lenient)?.0);
+        }
+        assert!(parse("-aABAA", v2, &lenient).is_err());
+
+        // nesting bounded
+        let nested = AttachmentsBuilder::new().pathed_material(
+            SadPath::from_path("-a")?,
+            AttachmentsBuilder::new().pathed_material(SadPath::from_path("-b")?, builder()),
+        );
+        let nested = nested.build()?;
+        let shallow = ParseOptions::strict().max_depth(1);
+        assert!(parse(&nested, GenusVersion::default(), &ParseOptions::strict()).is_ok());
+        assert!(parse(&nested, GenusVersion::default(), &shallow).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn round_trip() -> Result<(), Error> {
+        let stream = builder().build()?;
+        let attachments = Attachments::from_str(&stream)?;
+        assert_eq!(attachments.controller_signatures.len(), 2);
+        assert_eq!(attachments.controller_signatures[1].index(), 1);
+        assert!(attachments.witness_signatures.is_empty());
+        assert!(attachments.receipt_couples[0].verify(b"event")?);
+        assert_eq!(attachments.seal_source_couples[0].sn, 1);
+        assert_eq!(attachments.seal_source_triples[0].sn, 2);
+        assert_eq!(
+            attachments.first_seen_replay_couples,
+            vec![FirstSeenReplayCouple::new(3, dater())]
+        );
+
+        assert_eq!(Attachments::from_qb2(&builder().build_qb2()?)?, attachments);
+        Ok(())
+    }
+
+    #[test]
+    fn transferable_groups() -> Result<(), Error> {
+        let digest = SelfAddressing::Blake3_256.derive(b"establishment event");
+        let prefix = IdentifierPrefix::SelfAddressing(digest.clone());
+        let sig = |seed, index| {
+            let sig = signer(seed, true).sign(b"event");
+            AttachedSignaturePrefix::new(SelfSigning::Ed25519Sha512, sig.signature, index).unwrap()
+        };
+        let quadruple = TransReceiptQuadruple::new(prefix.clone(), 0, digest.clone(), sig(1, 0));
+        let group = TransIdxSigGroup::new(prefix, 0, digest, [sig(1, 0), sig(2, 1)]);
+        let stream = AttachmentsBuilder::new()
+            .trans_idx_sig_group(group.clone())
+            .trans_receipt_quadruple(quadruple.clone())
+            .build()?;
+        assert!(stream.starts_with("-DAB"));
+
+        let attachments = Attachments::from_str(&stream)?;
+        assert_eq!(attachments.trans_receipt_quadruples, vec![quadruple]);
+        assert_eq!(attachments.trans_idx_sig_groups, vec![group]);
+        Ok(())
+    }
+
+    #[test]
+    fn stops_at_next_message() -> Result<(), Error> {
+        let stream = [builder().build()?.as_str(), r#"{"v":"KERI10JSON"}"#].join("");
+        let (attachments, rest) = Attachments::from_stream(&stream)?;
+        assert_eq!(attachments.controller_signatures.len(), 2);
+        assert_eq!(rest, r#"{"v":"KERI10JSON"}"#);
+        assert!(Attachments::from_str(&stream).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn malformed_groups() -> Result<(), Error> {
+        let stream = builder().build()?;
+        assert!(Attachments::from_str(&stream[..stream.len() - 1]).is_err());
+        // count larger than the group
+        assert!(Attachments::from_str(&stream.replacen("-AAC", "-AAD", 1)).is_err());
+        // unsupported group
+        assert!(Attachments::from_str("-WAA").is_err());
+        assert_eq!(Attachments::from_str("")?, Attachments::default());
+        Ok(())
+    }
+
+    #[test]
+    fn genus_version() -> Result<(), Error> {
+        let stream = builder().build()?;
+        let attachments = Attachments::from_str(&stream)?;
+        let declared = [GenusVersion::KERIACDC_1.to_str(), stream.clone()].join("");
+        assert_eq!(Attachments::from_str(&declared)?, attachments);
+
+        // the CESR 2 tables give the CESR 1 count codes other meanings
+        let mut genus_version = GenusVersion::default();
+        let switched = [GenusVersion::KERIACDC_2.to_str(), stream.clone()].join("");
+        assert!(Attachments::from_stream_in(&switched, &mut genus_version).is_err());
+        assert_eq!(genus_version, GenusVersion::KERIACDC_2);
+        assert!(Attachments::from_stream_in(&stream, &mut genus_version).is_err());
+
+        // and back
+        let back = GenusVersion::KERIACDC_1.to_str_in(&GenusVersion::KERIACDC_2);
+        let back = [back, stream].join("");
+        let (parsed, rest) = Attachments::from_stream_in(&back, &mut genus_version)?;
+        assert_eq!((parsed, rest), (attachments, ""));
+        assert_eq!(genus_version, GenusVersion::KERIACDC_1);
+
+        assert!(Attachments::from_str("--AAADAA").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn cesr_2() -> Result<(), Error> {
+        let v2 = GenusVersion::KERIACDC_2;
+        let digest = SelfAddressing::Blake3_256.derive(b"establishment event");
+        let sig = AttachedSignaturePrefix::new(
+            SelfSigning::Ed25519Sha512,
+            signer(1, true).sign(b"event").signature,
+            0,
+        )?;
+        let group = TransIdxSigGroup::new(
+            IdentifierPrefix::SelfAddressing(digest.clone()),
+            0,
+            digest,
+            [sig],
+        );
+        let builder = builder().trans_idx_sig_group(group);
+        let stream = builder.build_in(&v2)?;
+        // two signatures of 88 characters each
+        assert!(stream.starts_with("-KAs"));
+        let mut genus_version = v2;
+        let (attachments, rest) = Attachments::from_stream_in(&stream, &mut genus_version)?;
+        assert_eq!(rest, "");
+        assert_eq!(attachments, Attachments::from_str(&builder.build()?)?);
+
+        let declared = [v2.to_str(), stream].join("");
+        assert_eq!(Attachments::from_str(&declared)?, attachments);
+        Ok(())
+    }
+}
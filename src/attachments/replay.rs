@@ -0,0 +1,78 @@
+use crate::{
+    error::Error,
+    prefix::{Dater, Prefix, Seqner},
+};
+use core::str::FromStr;
+
+/// When an event was first seen (`-E` group member): its first seen ordinal, the position of the
+/// event in the first seen log of the replaying node, and the datetime it was first seen at.
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct FirstSeenReplayCouple {
+    pub first_seen: u64,
+    pub datetime: Dater,
+}
+
+impl FirstSeenReplayCouple {
+    pub fn new(first_seen: u64, datetime: Dater) -> Self {
+        Self {
+            first_seen,
+            datetime,
+        }
+    }
+
+    /// Text domain (qb64) representation of the couple.
+    pub fn to_str(&self) -> String {
+        [
+            Seqner::from(self.first_seen).to_str(),
+            self.datetime.to_str(),
+        ]
+        .join("")
+    }
+
+    /// Parses one couple from the start of `s`, returning it with the rest of `s`.
+    pub fn from_stream(s: &str) -> Result<(Self, &str), Error> {
+        let (first_seen, rest) = Seqner::from_stream(s)?;
+        let (datetime, rest) = Dater::from_stream(rest)?;
+        Ok((Self::new(first_seen.try_into()?, datetime), rest))
+    }
+}
+
+/// Parses a single couple, `s` must hold nothing else.
+impl FromStr for FirstSeenReplayCouple {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match Self::from_stream(s)? {
+            (couple, "") => Ok(couple),
+            (_, rest) => Err(Error::WrongLength {
+                expected: s.len() - rest.len(),
+                got: s.len(),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn couple() -> Result<(), Error> {
+        let dater = Dater::from_iso8601("2020-08-22T17:50:09.988921+00:00")?;
+        let couple = FirstSeenReplayCouple::new(1, dater);
+        let qb64 = couple.to_str();
+        assert_eq!(
+            qb64,
+            "0AAAAAAAAAAAAAAAAAAAAAAB1AAG2020-08-22T17c50c09d988921p00c00"
+        );
+        assert_eq!(FirstSeenReplayCouple::from_str(&qb64)?, couple);
+
+        let stream = [qb64.as_str(), "-AAB"].join("");
+        let (decoded, rest) = FirstSeenReplayCouple::from_stream(&stream)?;
+        assert_eq!(decoded, couple);
+        assert_eq!(rest, "-AAB");
+        assert!(FirstSeenReplayCouple::from_str(&stream).is_err());
+        assert!(FirstSeenReplayCouple::from_str(&qb64[..qb64.len() - 4]).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,201 @@
+use crate::{
+    derivation::self_addressing::SelfAddressing,
+    error::Error,
+    prefix::{split_at_code_len, IdentifierPrefix, Prefix, SelfAddressingPrefix, Seqner},
+    serder::Serder,
+};
+use core::str::FromStr;
+use serde_json::{json, Value};
+
+/// Parses a digest primitive from the start of `s`.
+fn said_from_stream(s: &str) -> Result<(SelfAddressingPrefix, &str), Error> {
+    let (said, rest) = split_at_code_len(s, SelfAddressing::from_str(s)?)?;
+    Ok((said.parse()?, rest))
+}
+
+/// Fails unless all of `s` was parsed.
+fn whole<T>(s: &str, (parsed, rest): (T, &str)) -> Result<T, Error> {
+    match rest {
+        "" => Ok(parsed),
+        rest => Err(Error::WrongLength {
+            expected: s.len() - rest.len(),
+            got: s.len(),
+        }),
+    }
+}
+
+/// Seal of an event of another identifier (`-I` group member): its prefix, sequence number
+/// and SAID, e.g. the delegating event of a delegated event.
+#[derive(Debug, PartialEq, Clone)]
+pub struct EventSeal {
+    pub prefix: IdentifierPrefix,
+    pub sn: u64,
+    pub said: SelfAddressingPrefix,
+}
+
+impl EventSeal {
+    pub fn new(prefix: IdentifierPrefix, sn: u64, said: SelfAddressingPrefix) -> Self {
+        Self { prefix, sn, said }
+    }
+
+    /// Seal of `event`, e.g. a delegated event for its delegator to anchor.
+    pub fn of(event: &Serder) -> Result<Self, Error> {
+        let prefix = event
+            .get("i")
+            .and_then(Value::as_str)
+            .ok_or_else(|| Error::SemanticError("Missing prefix field".into()))?;
+        Ok(Self::new(prefix.parse()?, event.sn()?, event.said()?))
+    }
+
+    /// Whether `event` anchors the seal in its `a` field.
+    pub fn anchored_in(&self, event: &Serder) -> bool {
+        let seal = Value::from(self.clone());
+        event
+            .get("a")
+            .and_then(Value::as_array)
+            .is_some_and(|anchors| anchors.contains(&seal))
+    }
+
+    /// Text domain (qb64) representation of the triple.
+    pub fn to_str(&self) -> String {
+        [
+            self.prefix.to_str(),
+            Seqner::from(self.sn).to_str(),
+            self.said.to_str(),
+        ]
+        .join("")
+    }
+
+    /// Parses one triple from the start of `s`, returning it with the rest of `s`.
+    pub fn from_stream(s: &str) -> Result<(Self, &str), Error> {
+        let (prefix, rest) = IdentifierPrefix::from_stream(s)?;
+        let (sn, rest) = Seqner::from_stream(rest)?;
+        let (said, rest) = said_from_stream(rest)?;
+        Ok((Self::new(prefix, sn.try_into()?, said), rest))
+    }
+}
+
+/// Parses a single triple, `s` must hold nothing else.
+impl FromStr for EventSeal {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        whole(s, Self::from_stream(s)?)
+    }
+}
+
+/// The seal as anchored in the `a` field of an event, the sequence number as a hex string.
+impl From<EventSeal> for Value {
+    fn from(seal: EventSeal) -> Self {
+        json!({
+            "i": seal.prefix.to_str(),
+            "s": format!("{:x}", seal.sn),
+            "d": seal.said.to_str(),
+        })
+    }
+}
+
+/// Seal of the event anchoring this one in the log of its source (`-G` group member): the
+/// sequence number and SAID of the anchoring event, e.g. the delegating event attached to a
+/// delegated event.
+#[derive(Debug, PartialEq, Clone)]
+pub struct SourceSeal {
+    pub sn: u64,
+    pub said: SelfAddressingPrefix,
+}
+
+impl SourceSeal {
+    pub fn new(sn: u64, said: SelfAddressingPrefix) -> Self {
+        Self { sn, said }
+    }
+
+    /// Text domain (qb64) representation of the couple.
+    pub fn to_str(&self) -> String {
+        [Seqner::from(self.sn).to_str(), self.said.to_str()].join("")
+    }
+
+    /// Parses one couple from the start of `s`, returning it with the rest of `s`.
+    pub fn from_stream(s: &str) -> Result<(Self, &str), Error> {
+        let (sn, rest) = Seqner::from_stream(s)?;
+        let (said, rest) = said_from_stream(rest)?;
+        Ok((Self::new(sn.try_into()?, said), rest))
+    }
+}
+
+/// Parses a single couple, `s` must hold nothing else.
+impl FromStr for SourceSeal {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        whole(s, Self::from_stream(s)?)
+    }
+}
+
+/// Seal of arbitrary data by its digest. It has no group of its own, it is encoded as the bare
+/// digest.
+#[derive(Debug, PartialEq, Clone)]
+pub struct DigestSeal {
+    pub said: SelfAddressingPrefix,
+}
+
+impl DigestSeal {
+    pub fn new(said: SelfAddressingPrefix) -> Self {
+        Self { said }
+    }
+
+    /// Text domain (qb64) representation of the digest.
+    pub fn to_str(&self) -> String {
+        self.said.to_str()
+    }
+
+    /// Parses the digest from the start of `s`, returning the seal with the rest of `s`.
+    pub fn from_stream(s: &str) -> Result<(Self, &str), Error> {
+        let (said, rest) = said_from_stream(s)?;
+        Ok((Self::new(said), rest))
+    }
+}
+
+/// Parses a single digest, `s` must hold nothing else.
+impl FromStr for DigestSeal {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        whole(s, Self::from_stream(s)?)
+    }
+}
+
+impl From<SelfAddressingPrefix> for DigestSeal {
+    fn from(said: SelfAddressingPrefix) -> Self {
+        Self::new(said)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seals() -> Result<(), Error> {
+        let said = SelfAddressing::Blake3_256.derive(b"anchoring event");
+        let prefix: IdentifierPrefix = "EKYLUMmNPZeEs77Zvclf0bSN5IN-mLfLpx2ySb-HDlk4".parse()?;
+
+        let seal = EventSeal::new(prefix.clone(), 16, said.clone());
+        let qb64 = seal.to_str();
+        assert_eq!(&qb64[44..68], "0AAAAAAAAAAAAAAAAAAAAAAQ");
+        assert_eq!(qb64.len(), 44 + 24 + 44);
+        assert_eq!(EventSeal::from_str(&qb64)?, seal);
+        assert!(EventSeal::from_str(&qb64[..qb64.len() - 4]).is_err());
+
+        let seal = SourceSeal::new(16, said.clone());
+        let stream = [seal.to_str(), prefix.to_str()].join("");
+        let (decoded, rest) = SourceSeal::from_stream(&stream)?;
+        assert_eq!(decoded, seal);
+        assert_eq!(rest, prefix.to_str());
+        assert!(SourceSeal::from_str(&stream).is_err());
+
+        let seal = DigestSeal::from(said.clone());
+        assert_eq!(seal.to_str(), said.to_str());
+        assert_eq!(DigestSeal::from_str(&said.to_str())?, seal);
+        Ok(())
+    }
+}
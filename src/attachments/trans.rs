@@ -0,0 +1,254 @@
+use crate::{
+    derivation::{
+        attached_signature_code::AttachedSignatureCode,
+        counter::{Counter, CounterCode},
+        genus::GenusVersion,
+        self_addressing::SelfAddressing,
+        split_at_len,
+    },
+    error::Error,
+    prefix::{split_at_code_len, IdentifierPrefix, Prefix, SelfAddressingPrefix, Seqner, Siger},
+};
+use core::str::FromStr;
+
+/// Parses the prefix, sequence number and digest locating an establishment event of a
+/// transferable identifier from the start of `s`.
+fn event_from_stream(
+    s: &str,
+) -> Result<(IdentifierPrefix, u64, SelfAddressingPrefix, &str), Error> {
+    let (prefix, rest) = IdentifierPrefix::from_stream(s)?;
+    let (sn, rest) = Seqner::from_stream(rest)?;
+    let (digest, rest) = split_at_code_len(rest, SelfAddressing::from_str(rest)?)?;
+    Ok((prefix, sn.try_into()?, digest.parse()?, rest))
+}
+
+/// A receipt signature of a transferable identifier (`-D` group member): the prefix, sequence
+/// number and digest of the establishment event holding the signing key, and the signature
+/// indexed into the keys of that event.
+#[derive(Debug, PartialEq, Clone)]
+pub struct TransReceiptQuadruple {
+    pub prefix: IdentifierPrefix,
+    pub sn: u64,
+    pub digest: SelfAddressingPrefix,
+    pub signature: Siger,
+}
+
+impl TransReceiptQuadruple {
+    pub fn new(
+        prefix: IdentifierPrefix,
+        sn: u64,
+        digest: SelfAddressingPrefix,
+        signature: impl Into<Siger>,
+    ) -> Self {
+        Self {
+            prefix,
+            sn,
+            digest,
+            signature: signature.into(),
+        }
+    }
+
+    /// Text domain (qb64) representation of the quadruple.
+    pub fn to_str(&self) -> String {
+        [
+            self.prefix.to_str(),
+            Seqner::from(self.sn).to_str(),
+            self.digest.to_str(),
+            self.signature.to_str(),
+        ]
+        .join("")
+    }
+
+    /// Parses one quadruple from the start of `s`, returning it with the rest of `s`.
+    pub fn from_stream(s: &str) -> Result<(Self, &str), Error> {
+        let (prefix, sn, digest, rest) = event_from_stream(s)?;
+        let (signature, rest) = split_at_code_len(rest, AttachedSignatureCode::from_str(rest)?)?;
+        Ok((
+            Self::new(prefix, sn, digest, Siger::from_str(signature)?),
+            rest,
+        ))
+    }
+}
+
+/// Parses a single quadruple, `s` must hold nothing else.
+impl FromStr for TransReceiptQuadruple {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match Self::from_stream(s)? {
+            (quadruple, "") => Ok(quadruple),
+            (_, rest) => Err(Error::WrongLength {
+                expected: s.len() - rest.len(),
+                got: s.len(),
+            }),
+        }
+    }
+}
+
+/// Indexed signatures of a transferable identifier (`-F` group member): the prefix, sequence
+/// number and digest of the establishment event holding the signing keys, followed by the
+/// signatures as a controller signatures (`-A`) group.
+#[derive(Debug, PartialEq, Clone)]
+pub struct TransIdxSigGroup {
+    pub prefix: IdentifierPrefix,
+    pub sn: u64,
+    pub digest: SelfAddressingPrefix,
+    pub signatures: Vec<Siger>,
+}
+
+impl TransIdxSigGroup {
+    pub fn new<S: Into<Siger>>(
+        prefix: IdentifierPrefix,
+        sn: u64,
+        digest: SelfAddressingPrefix,
+        signatures: impl IntoIterator<Item = S>,
+    ) -> Self {
+        Self {
+            prefix,
+            sn,
+            digest,
+            signatures: signatures.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// Text domain (qb64) representation of the group.
+    pub fn to_str(&self) -> Result<String, Error> {
+        self.to_str_in(&GenusVersion::default())
+    }
+
+    /// Text domain (qb64) representation of the group, the signatures counted as in the tables
+    /// of `genus_version`.
+    pub fn to_str_in(&self, genus_version: &GenusVersion) -> Result<String, Error> {
+        let signatures: String = self.signatures.iter().map(Siger::to_str).collect();
+        let count = match genus_version.counts_quadlets() {
+            true => signatures.len() / 4,
+            false => self.signatures.len(),
+        };
+        let counter = Counter {
+            code: CounterCode::ControllerIdxSigs,
+            count: count as u32,
+        };
+        Ok([
+            self.prefix.to_str(),
+            Seqner::from(self.sn).to_str(),
+            self.digest.to_str(),
+            counter.to_str_in(genus_version)?,
+            signatures,
+        ]
+        .join(""))
+    }
+
+    /// Parses one group from the start of `s`, returning it with the rest of `s`.
+    pub fn from_stream(s: &str) -> Result<(Self, &str), Error> {
+        Self::from_stream_in(s, &GenusVersion::default())
+    }
+
+    /// Parses one group encoded with the tables of `genus_version` from the start of `s`,
+    /// returning it with the rest of `s`.
+    pub fn from_stream_in<'a>(
+        s: &'a str,
+        genus_version: &GenusVersion,
+    ) -> Result<(Self, &'a str), Error> {
+        let (prefix, sn, digest, rest) = event_from_stream(s)?;
+        let (counter, mut rest) = Counter::from_stream_in(rest, genus_version)?;
+        if counter.code != CounterCode::ControllerIdxSigs {
+            return Err(Error::SemanticError(format!(
+                "Expected indexed signatures, got count code {}",
+                counter.code.to_str()
+            )));
+        }
+        let mut signatures = vec![];
+        let mut parse_signature = |rest: &'a str| -> Result<&'a str, Error> {
+            let (signature, tail) =
+                split_at_code_len(rest, AttachedSignatureCode::from_str(rest)?)?;
+            signatures.push(Siger::from_str(signature)?);
+            Ok(tail)
+        };
+        if genus_version.counts_quadlets() {
+            let (mut group, tail) = split_at_len(rest, counter.count as usize * 4)?;
+            while !group.is_empty() {
+                group = parse_signature(group)?;
+            }
+            rest = tail;
+        } else {
+            for _ in 0..counter.count {
+                rest = parse_signature(rest)?;
+            }
+        }
+        Ok((
+            Self {
+                prefix,
+                sn,
+                digest,
+                signatures,
+            },
+            rest,
+        ))
+    }
+}
+
+/// Parses a single group, `s` must hold nothing else.
+impl FromStr for TransIdxSigGroup {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match Self::from_stream(s)? {
+            (group, "") => Ok(group),
+            (_, rest) => Err(Error::WrongLength {
+                expected: s.len() - rest.len(),
+                got: s.len(),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        derivation::self_signing::SelfSigning, prefix::AttachedSignaturePrefix, test_utils::signer,
+    };
+
+    fn indexed(seed: u8, index: u16) -> AttachedSignaturePrefix {
+        AttachedSignaturePrefix::new(
+            SelfSigning::Ed25519Sha512,
+            signer(seed, true).sign(b"event").signature,
+            index,
+        )
+        .unwrap()
+    }
+
+    fn event() -> (IdentifierPrefix, SelfAddressingPrefix) {
+        let digest = SelfAddressing::Blake3_256.derive(b"establishment event");
+        (IdentifierPrefix::SelfAddressing(digest.clone()), digest)
+    }
+
+    #[test]
+    fn quadruple() -> Result<(), Error> {
+        let (prefix, digest) = event();
+        let quadruple = TransReceiptQuadruple::new(prefix, 2, digest, indexed(1, 0));
+        let qb64 = quadruple.to_str();
+        assert_eq!(qb64.len(), 44 + 24 + 44 + 88);
+        assert_eq!(TransReceiptQuadruple::from_str(&qb64)?, quadruple);
+
+        assert!(TransReceiptQuadruple::from_str(&qb64[..qb64.len() - 1]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn group() -> Result<(), Error> {
+        let (prefix, digest) = event();
+        let group = TransIdxSigGroup::new(prefix, 0, digest, [indexed(1, 0), indexed(2, 1)]);
+        let qb64 = group.to_str()?;
+        assert_eq!(&qb64[112..116], "-AAC");
+        let stream = [qb64.as_str(), "-AAB"].join("");
+        let (decoded, rest) = TransIdxSigGroup::from_stream(&stream)?;
+        assert_eq!(decoded, group);
+        assert_eq!(rest, "-AAB");
+
+        // signatures must be framed as controller signatures
+        assert!(TransIdxSigGroup::from_str(&qb64.replacen("-AAC", "-BAC", 1)).is_err());
+        assert!(TransIdxSigGroup::from_str(&qb64.replacen("-AAC", "-AAD", 1)).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,205 @@
+use crate::{
+    attachments::{Attachments, AttachmentsBuilder},
+    derivation::{self_addressing::SelfAddressing, self_signing::SelfSigning},
+    error::Error,
+    keys::{PrivateKey, Signer},
+    prefix::{AttachedSignaturePrefix, BasicPrefix, IdentifierPrefix, Prefix, SelfSigningPrefix},
+};
+use core::str::FromStr;
+
+/// A representative workload, set up once and then run over and over by a benchmark harness.
+pub trait Workload {
+    /// Name of the workload in benchmark reports.
+    fn name(&self) -> String;
+
+    /// Number of bytes one run processes, for throughput reports.
+    fn bytes(&self) -> u64;
+
+    /// Runs the workload once.
+    fn run(&self) -> Result<(), Error>;
+}
+
+/// Digesting a payload into a self-addressing prefix.
+pub struct Digesting {
+    pub code: SelfAddressing,
+    pub payload: Vec<u8>,
+}
+
+impl Digesting {
+    pub fn new(code: SelfAddressing, len: usize) -> Self {
+        Self {
+            code,
+            payload: (0..len).map(|i| i as u8).collect(),
+        }
+    }
+}
+
+impl Workload for Digesting {
+    fn name(&self) -> String {
+        format!("digest/{:?}/{}", self.code, self.payload.len())
+    }
+
+    fn bytes(&self) -> u64 {
+        self.payload.len() as u64
+    }
+
+    fn run(&self) -> Result<(), Error> {
+        self.code.derive(&self.payload);
+        Ok(())
+    }
+}
+
+/// Parsing identifier prefixes from the text domain and encoding them again.
+pub struct PrefixRoundTrip {
+    pub prefixes: Vec<String>,
+}
+
+impl PrefixRoundTrip {
+    /// Round trips of `count` prefixes, basic and self-addressing in turns.
+    pub fn new(count: usize) -> Self {
+        let prefixes = (0..count)
+            .map(|i| match i % 2 {
+                0 => signer(i as u8).basic_prefix().to_str(),
+                _ => SelfAddressing::Blake3_256.derive(&[i as u8]).to_str(),
+            })
+            .collect();
+        Self { prefixes }
+    }
+}
+
+impl Workload for PrefixRoundTrip {
+    fn name(&self) -> String {
+        format!("prefix_round_trip/{}", self.prefixes.len())
+    }
+
+    fn bytes(&self) -> u64 {
+        self.prefixes.iter().map(|p| p.len() as u64).sum()
+    }
+
+    fn run(&self) -> Result<(), Error> {
+        for qb64 in &self.prefixes {
+            if IdentifierPrefix::from_str(qb64)?.to_str() != *qb64 {
+                return Err(Error::SemanticError(format!("Round trip changed {}", qb64)));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Parsing the attachments of an event signed by several controllers and receipted by witnesses.
+pub struct AttachmentParsing {
+    pub stream: String,
+}
+
+impl AttachmentParsing {
+    /// Attachments of `signatures` controller signatures and as many witness receipts.
+    pub fn new(signatures: u16) -> Result<Self, Error> {
+        let sigs = (0..signatures)
+            .map(|i| {
+                let sig = signer(i as u8).sign(b"event");
+                AttachedSignaturePrefix::new(SelfSigning::Ed25519Sha512, sig.signature, i)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let stream = AttachmentsBuilder::new()
+            .controller_signatures(sigs.clone())
+            .witness_signatures(sigs)
+            .seal_source_couple(1, SelfAddressing::Blake3_256.derive(b"delegating event"))
+            .build()?;
+        Ok(Self { stream })
+    }
+}
+
+impl Workload for AttachmentParsing {
+    fn name(&self) -> String {
+        format!("attachment_parsing/{}", self.stream.len())
+    }
+
+    fn bytes(&self) -> u64 {
+        self.stream.len() as u64
+    }
+
+    fn run(&self) -> Result<(), Error> {
+        Attachments::from_str(&self.stream).map(|_| ())
+    }
+}
+
+/// Verifying a signature over a payload.
+pub struct SignatureVerification {
+    pub key: BasicPrefix,
+    pub payload: Vec<u8>,
+    pub signature: SelfSigningPrefix,
+}
+
+impl SignatureVerification {
+    /// Verification of an Ed25519 signature over `len` bytes.
+    pub fn ed25519(len: usize) -> Self {
+        let signer = signer(1);
+        let payload: Vec<u8> = (0..len).map(|i| i as u8).collect();
+        Self {
+            key: signer.basic_prefix(),
+            signature: signer.sign(&payload),
+            payload,
+        }
+    }
+}
+
+impl Workload for SignatureVerification {
+    fn name(&self) -> String {
+        format!(
+            "verify/{}/{}",
+            self.signature.derivation_code(),
+            self.payload.len()
+        )
+    }
+
+    fn bytes(&self) -> u64 {
+        self.payload.len() as u64
+    }
+
+    fn run(&self) -> Result<(), Error> {
+        match self.key.verify(&self.payload, &self.signature)? {
+            true => Ok(()),
+            false => Err(Error::SignatureVerificationError),
+        }
+    }
+}
+
+/// The standard workloads, the same on every machine so reports can be compared.
+pub fn workloads() -> Result<Vec<Box<dyn Workload>>, Error> {
+    let mut workloads: Vec<Box<dyn Workload>> = vec![];
+    for code in [
+        SelfAddressing::Blake3_256,
+        SelfAddressing::SHA2_256,
+        SelfAddressing::Blake2B256,
+    ] {
+        workloads.push(Box::new(Digesting::new(code, 1024)));
+    }
+    workloads.push(Box::new(PrefixRoundTrip::new(100)));
+    workloads.push(Box::new(AttachmentParsing::new(10)?));
+    workloads.push(Box::new(SignatureVerification::ed25519(1024)));
+    Ok(workloads)
+}
+
+/// A deterministic Ed25519 signer, so workloads are the same from run to run.
+fn signer(seed: u8) -> Signer {
+    Signer::new(
+        SelfSigning::Ed25519Sha512,
+        PrivateKey::new(vec![seed; 32]),
+        true,
+    )
+    .expect("32 byte Ed25519 seed")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn workloads_run() -> Result<(), Error> {
+        for workload in workloads()? {
+            workload.run()?;
+            assert!(workload.bytes() > 0, "{}", workload.name());
+        }
+        Ok(())
+    }
+}
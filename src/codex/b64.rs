@@ -0,0 +1,85 @@
+//! The Base64 engine behind every text domain conversion: the `base64` crate, or the SIMD
+//! accelerated `base64-simd` crate with the `base64-simd` feature. Both use the URL-safe alphabet
+//! and never write padding, CESR aligns primitives itself.
+
+use crate::error::Error;
+
+/// URL-safe Base64 encoding of `bytes`, without padding.
+#[cfg(not(feature = "base64-simd"))]
+pub(crate) fn encode(bytes: impl AsRef<[u8]>) -> String {
+    base64::encode_config(bytes, base64::URL_SAFE_NO_PAD)
+}
+
+/// URL-safe Base64 decoding of `b64`.
+#[cfg(not(feature = "base64-simd"))]
+pub(crate) fn decode(b64: impl AsRef<[u8]>) -> Result<Vec<u8>, Error> {
+    validate(b64.as_ref())?;
+    Ok(base64::decode_config(b64, base64::URL_SAFE)?)
+}
+
+/// URL-safe Base64 encoding of `bytes`, without padding.
+#[cfg(feature = "base64-simd")]
+pub(crate) fn encode(bytes: impl AsRef<[u8]>) -> String {
+    base64_simd::URL_SAFE_NO_PAD.encode_to_string(bytes)
+}
+
+/// URL-safe Base64 decoding of `b64`.
+#[cfg(feature = "base64-simd")]
+pub(crate) fn decode(b64: impl AsRef<[u8]>) -> Result<Vec<u8>, Error> {
+    let b64 = b64.as_ref();
+    validate(b64)?;
+    base64_simd::URL_SAFE_NO_PAD
+        .decode_to_vec(b64)
+        .map_err(|_| Error::InvalidPadding)
+}
+
+/// Checks every character of `b64` is of the URL-safe alphabet, whichever the engine, failing on
+/// the first one that is not with what it is: standard Base64, padding or anything else.
+pub(crate) fn validate(b64: &[u8]) -> Result<(), Error> {
+    match b64.iter().position(|c| !is_b64(*c)) {
+        None => Ok(()),
+        Some(offset) => Err(match b64[offset] {
+            c @ (b'+' | b'/') => Error::StandardBase64 {
+                offset,
+                character: c as char,
+            },
+            b'=' => Error::Base64Padding { offset },
+            _ => Error::InvalidBase64 { offset },
+        }),
+    }
+}
+
+fn is_b64(c: u8) -> bool {
+    c.is_ascii_alphanumeric() || c == b'-' || c == b'_'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn engine() -> Result<(), Error> {
+        assert_eq!(encode([0xfb, 0xff]), "-_8");
+        assert_eq!(decode("-_8")?, [0xfb, 0xff]);
+        assert_eq!(decode("AAAA")?, [0, 0, 0]);
+        assert!(matches!(
+            decode("AA.A"),
+            Err(Error::InvalidBase64 { offset: 2 })
+        ));
+        // bits beyond the last byte must be zero
+        assert!(matches!(decode("AB"), Err(Error::InvalidPadding)));
+        // the standard alphabet and padding are named, whichever the engine
+        assert!(matches!(
+            decode("AA+/"),
+            Err(Error::StandardBase64 {
+                offset: 2,
+                character: '+'
+            })
+        ));
+        assert!(matches!(
+            decode("AA=="),
+            Err(Error::Base64Padding { offset: 2 })
+        ));
+        Ok(())
+    }
+}
@@ -0,0 +1,214 @@
+use super::{counter, matter, sizage, Sizage, COUNTER_2};
+use crate::derivation::{
+    attached_signature_code::Indexer, counter::CounterCode, CodeTable, DerivationCode,
+};
+
+/// The code tables a code can come from.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum Table {
+    /// The master code table, see [super::MATTER].
+    Matter,
+    /// The indexed code table of indexed signatures.
+    Indexer,
+    /// The CESR 1 count code table, see [super::COUNTER].
+    Counter,
+    /// The CESR 2 count code table, see [COUNTER_2].
+    Counter2,
+}
+
+/// What the material under a code is.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum Category {
+    /// Private keys and the seeds they are derived from.
+    Seed,
+    PublicKey,
+    Digest,
+    Signature,
+    IndexedSignature,
+    /// Salts and nonces, `0A` being a huge number as well.
+    Salt,
+    /// Seeds and salts sealed to a public key.
+    Cipher,
+    Number,
+    Tag,
+    Datetime,
+    /// Base64 strings and bytes of variable size.
+    Variable,
+    Counter,
+    GenusVersion,
+}
+
+/// A code of one of the code tables, with its name, sizes and what it is.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct CodeEntry {
+    /// The name of the code, that of keripy for the master code table.
+    pub name: String,
+    /// The hard part of the code.
+    pub code: &'static str,
+    pub sizage: Sizage,
+    pub category: Category,
+    pub table: Table,
+}
+
+/// Names and categories of the codes of [super::MATTER], in the same order.
+#[rustfmt::skip]
+const MATTER_NAMES: &[(&str, &str, Category)] = &[
+    ("A", "Ed25519_Seed", Category::Seed),
+    ("B", "Ed25519N", Category::PublicKey),
+    ("C", "X25519", Category::PublicKey),
+    ("D", "Ed25519", Category::PublicKey),
+    ("E", "Blake3_256", Category::Digest),
+    ("F", "Blake2b_256", Category::Digest),
+    ("G", "Blake2s_256", Category::Digest),
+    ("H", "SHA3_256", Category::Digest),
+    ("I", "SHA2_256", Category::Digest),
+    ("J", "ECDSA_256k1_Seed", Category::Seed),
+    ("K", "Ed448_Seed", Category::Seed),
+    ("L", "X448", Category::PublicKey),
+    ("M", "Short", Category::Number),
+    ("N", "Big", Category::Number),
+    ("O", "X25519_Private", Category::Seed),
+    ("P", "X25519_Cipher_Seed", Category::Cipher),
+    ("Q", "ECDSA_256r1_Seed", Category::Seed),
+    ("R", "Tall", Category::Number),
+    ("S", "Large", Category::Number),
+    ("T", "Great", Category::Number),
+    ("U", "Vast", Category::Number),
+    ("X", "Tag3", Category::Tag),
+    ("Y", "Tag7", Category::Tag),
+    ("Z", "Tag11", Category::Tag),
+    ("a", "Salt_256", Category::Salt),
+    ("0A", "Salt_128", Category::Salt),
+    ("0B", "Ed25519_Sig", Category::Signature),
+    ("0C", "ECDSA_256k1_Sig", Category::Signature),
+    ("0D", "Blake3_512", Category::Digest),
+    ("0E", "SHA3_512", Category::Digest),
+    ("0F", "Blake2b_512", Category::Digest),
+    ("0G", "SHA2_512", Category::Digest),
+    ("0H", "Long", Category::Number),
+    ("0I", "ECDSA_256r1_Sig", Category::Signature),
+    ("0J", "Tag1", Category::Tag),
+    ("0K", "Tag2", Category::Tag),
+    ("0L", "Tag5", Category::Tag),
+    ("0M", "Tag6", Category::Tag),
+    ("0N", "Tag9", Category::Tag),
+    ("0O", "Tag10", Category::Tag),
+    ("1AAA", "ECDSA_256k1N", Category::PublicKey),
+    ("1AAB", "ECDSA_256k1", Category::PublicKey),
+    ("1AAC", "Ed448N", Category::PublicKey),
+    ("1AAD", "Ed448", Category::PublicKey),
+    ("1AAE", "Ed448_Sig", Category::Signature),
+    ("1AAF", "Tag4", Category::Tag),
+    ("1AAG", "DateTime", Category::Datetime),
+    ("1AAH", "X25519_Cipher_Salt", Category::Cipher),
+    ("1AAI", "ECDSA_256r1N", Category::PublicKey),
+    ("1AAJ", "ECDSA_256r1", Category::PublicKey),
+    ("1AAN", "Tag8", Category::Tag),
+    ("1ABA", "ML_DSA_44N", Category::PublicKey),
+    ("1ABB", "ML_DSA_44", Category::PublicKey),
+    ("1ABC", "ML_DSA_65N", Category::PublicKey),
+    ("1ABD", "ML_DSA_65", Category::PublicKey),
+    ("1ABE", "ML_DSA_87N", Category::PublicKey),
+    ("1ABF", "ML_DSA_87", Category::PublicKey),
+    ("1ABG", "ML_DSA_44_Sig", Category::Signature),
+    ("1ABH", "ML_DSA_65_Sig", Category::Signature),
+    ("1ABI", "ML_DSA_87_Sig", Category::Signature),
+    ("4A", "StrB64_L0", Category::Variable),
+    ("5A", "StrB64_L1", Category::Variable),
+    ("6A", "StrB64_L2", Category::Variable),
+    ("7AAA", "StrB64_Big_L0", Category::Variable),
+    ("8AAA", "StrB64_Big_L1", Category::Variable),
+    ("9AAA", "StrB64_Big_L2", Category::Variable),
+    ("4B", "Bytes_L0", Category::Variable),
+    ("5B", "Bytes_L1", Category::Variable),
+    ("6B", "Bytes_L2", Category::Variable),
+    ("7AAB", "Bytes_Big_L0", Category::Variable),
+    ("8AAB", "Bytes_Big_L1", Category::Variable),
+    ("9AAB", "Bytes_Big_L2", Category::Variable),
+];
+
+/// Every code of the master, indexed and both count code tables, in table order, for tools
+/// that list codes rather than parse with them. Codes shared by the count code tables are
+/// listed once for each.
+pub fn all_codes() -> impl Iterator<Item = CodeEntry> {
+    let matter = MATTER_NAMES.iter().map(|(code, name, category)| CodeEntry {
+        name: (*name).into(),
+        code,
+        sizage: matter(code),
+        category: *category,
+        table: Table::Matter,
+    });
+    let indexer = Indexer::CODES.iter().map(|(code, indexer)| CodeEntry {
+        name: format!("{:?}", indexer),
+        code,
+        sizage: Sizage {
+            hs: indexer.hard_size(),
+            ss: indexer.soft_size(),
+            fs: Some(
+                indexer.hard_size()
+                    + indexer.soft_size()
+                    + indexer.signature().derivative_b64_len(),
+            ),
+            ls: 0,
+        },
+        category: Category::IndexedSignature,
+        table: Table::Indexer,
+    });
+    let counters = |codes: &'static [(&'static str, CounterCode)], table| {
+        codes.iter().map(move |(code, counter_code)| CodeEntry {
+            name: format!("{:?}", counter_code),
+            code,
+            sizage: match table {
+                Table::Counter2 => sizage(COUNTER_2, code).expect("CESR 2 count code"),
+                _ => counter(code),
+            },
+            category: match counter_code {
+                CounterCode::KERIACDCGenusVersion => Category::GenusVersion,
+                _ => Category::Counter,
+            },
+            table,
+        })
+    };
+    matter
+        .chain(indexer)
+        .chain(counters(CounterCode::CODES, Table::Counter))
+        .chain(counters(CounterCode::CESR_2_CODES, Table::Counter2))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codex::{COUNTER, MATTER};
+
+    #[test]
+    fn entries() {
+        let entries: Vec<CodeEntry> = all_codes().collect();
+        let codes = |table| -> Vec<&str> {
+            entries
+                .iter()
+                .filter(|entry| entry.table == table)
+                .map(|entry| entry.code)
+                .collect()
+        };
+        let matter: Vec<&str> = MATTER.iter().map(|(code, _)| *code).collect();
+        assert_eq!(codes(Table::Matter), matter);
+        let counter: Vec<&str> = COUNTER.iter().map(|(code, _)| *code).collect();
+        assert_eq!(codes(Table::Counter), counter);
+        assert_eq!(codes(Table::Indexer).len(), Indexer::CODES.len());
+        assert_eq!(codes(Table::Counter2).len(), COUNTER_2.len());
+
+        let ed25519 = &entries[3];
+        assert_eq!(
+            (ed25519.name.as_str(), ed25519.code, ed25519.category),
+            ("Ed25519", "D", Category::PublicKey)
+        );
+        let siger = entries
+            .iter()
+            .find(|entry| entry.table == Table::Indexer && entry.code == "0A")
+            .unwrap();
+        assert_eq!(siger.sizage, Sizage::fixed(2, 2, 156));
+        assert!(entries
+            .iter()
+            .any(|entry| entry.code == "-_AAA" && entry.category == Category::GenusVersion));
+    }
+}
@@ -0,0 +1,443 @@
+use crate::error::Error;
+
+pub(crate) mod b64;
+mod entries;
+
+pub use entries::{all_codes, Category, CodeEntry, Table};
+
+/// Sizes of a code and of the primitives under it, in Base64 characters except for `ls`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct Sizage {
+    /// Hard size, the fixed part of the code.
+    pub hs: usize,
+    /// Soft size, the part of the code carrying a size, count or label.
+    pub ss: usize,
+    /// Full size of the primitive including its code, `None` for variable size codes.
+    pub fs: Option<usize>,
+    /// Lead size, the number of zero bytes prepended to the raw material.
+    pub ls: usize,
+}
+
+impl Sizage {
+    const fn fixed(hs: usize, ss: usize, fs: usize) -> Self {
+        Self {
+            hs,
+            ss,
+            fs: Some(fs),
+            ls: 0,
+        }
+    }
+
+    const fn lead(self, ls: usize) -> Self {
+        Self { ls, ..self }
+    }
+
+    const fn variable(hs: usize, ss: usize, ls: usize) -> Self {
+        Self {
+            hs,
+            ss,
+            fs: None,
+            ls,
+        }
+    }
+
+    /// Code size, the hard and the soft part together.
+    pub fn cs(&self) -> usize {
+        self.hs + self.ss
+    }
+
+    /// Number of characters after the code of a fixed size primitive, 0 for variable size codes.
+    pub fn derivative_b64_len(&self) -> usize {
+        self.fs.map_or(0, |fs| fs - self.cs())
+    }
+}
+
+/// The [master code table], each code with its sizes.
+///
+/// The `1AB` codes of the ML-DSA keys and signatures are provisional, they are not in the
+/// published table yet.
+///
+/// [master code table]: https://weboftrust.github.io/ietf-cesr/draft-ssmith-cesr.html#name-master-code-table
+#[rustfmt::skip]
+pub const MATTER: &[(&str, Sizage)] = &[
+    ("A", Sizage::fixed(1, 0, 44)),     // Ed25519 seed
+    ("B", Sizage::fixed(1, 0, 44)),     // Ed25519 non-transferable public key
+    ("C", Sizage::fixed(1, 0, 44)),     // X25519 public key
+    ("D", Sizage::fixed(1, 0, 44)),     // Ed25519 public key
+    ("E", Sizage::fixed(1, 0, 44)),     // Blake3-256 digest
+    ("F", Sizage::fixed(1, 0, 44)),     // Blake2b-256 digest
+    ("G", Sizage::fixed(1, 0, 44)),     // Blake2s-256 digest
+    ("H", Sizage::fixed(1, 0, 44)),     // SHA3-256 digest
+    ("I", Sizage::fixed(1, 0, 44)),     // SHA2-256 digest
+    ("J", Sizage::fixed(1, 0, 44)),     // ECDSA secp256k1 seed
+    ("K", Sizage::fixed(1, 0, 76)),     // Ed448 seed
+    ("L", Sizage::fixed(1, 0, 76)),     // X448 public key
+    ("M", Sizage::fixed(1, 0, 4)),      // short number, 2 bytes
+    ("N", Sizage::fixed(1, 0, 12)),     // big number, 8 bytes
+    ("O", Sizage::fixed(1, 0, 44)),     // X25519 private key
+    ("P", Sizage::fixed(1, 0, 124)),    // X25519 sealed seed
+    ("Q", Sizage::fixed(1, 0, 44)),     // ECDSA secp256r1 seed
+    ("R", Sizage::fixed(1, 0, 8)),      // tall number, 5 bytes
+    ("S", Sizage::fixed(1, 0, 16)),     // large number, 11 bytes
+    ("T", Sizage::fixed(1, 0, 20)),     // great number, 14 bytes
+    ("U", Sizage::fixed(1, 0, 24)),     // vast number, 17 bytes
+    ("X", Sizage::fixed(1, 3, 4)),      // tag of 3 characters
+    ("Y", Sizage::fixed(1, 7, 8)),      // tag of 7 characters
+    ("Z", Sizage::fixed(1, 11, 12)),    // tag of 11 characters
+    ("a", Sizage::fixed(1, 0, 44)),     // 256 bit salt or nonce
+    ("0A", Sizage::fixed(2, 0, 24)),    // 128 bit salt, or huge number of 16 bytes
+    ("0B", Sizage::fixed(2, 0, 88)),    // Ed25519 signature
+    ("0C", Sizage::fixed(2, 0, 88)),    // ECDSA secp256k1 signature
+    ("0D", Sizage::fixed(2, 0, 88)),    // Blake3-512 digest
+    ("0E", Sizage::fixed(2, 0, 88)),    // SHA3-512 digest
+    ("0F", Sizage::fixed(2, 0, 88)),    // Blake2b-512 digest
+    ("0G", Sizage::fixed(2, 0, 88)),    // SHA2-512 digest
+    ("0H", Sizage::fixed(2, 0, 8)),     // long number, 4 bytes
+    ("0I", Sizage::fixed(2, 0, 88)),    // ECDSA secp256r1 signature
+    ("0J", Sizage::fixed(2, 2, 4)),     // tag of 1 character
+    ("0K", Sizage::fixed(2, 2, 4)),     // tag of 2 characters
+    ("0L", Sizage::fixed(2, 6, 8)),     // tag of 5 characters
+    ("0M", Sizage::fixed(2, 6, 8)),     // tag of 6 characters
+    ("0N", Sizage::fixed(2, 10, 12)),   // tag of 9 characters
+    ("0O", Sizage::fixed(2, 10, 12)),   // tag of 10 characters
+    ("1AAA", Sizage::fixed(4, 0, 48)),  // ECDSA secp256k1 non-transferable public key
+    ("1AAB", Sizage::fixed(4, 0, 48)),  // ECDSA secp256k1 public key
+    ("1AAC", Sizage::fixed(4, 0, 80)),  // Ed448 non-transferable public key
+    ("1AAD", Sizage::fixed(4, 0, 80)),  // Ed448 public key
+    ("1AAE", Sizage::fixed(4, 0, 156)), // Ed448 signature
+    ("1AAF", Sizage::fixed(4, 4, 8)),   // tag of 4 characters
+    ("1AAG", Sizage::fixed(4, 0, 36)),  // ISO 8601 datetime
+    ("1AAH", Sizage::fixed(4, 0, 100)), // X25519 sealed salt
+    ("1AAI", Sizage::fixed(4, 0, 48)),  // ECDSA secp256r1 non-transferable public key
+    ("1AAJ", Sizage::fixed(4, 0, 48)),  // ECDSA secp256r1 public key
+    ("1AAN", Sizage::fixed(4, 8, 12)),  // tag of 8 characters
+    ("1ABA", Sizage::fixed(4, 0, 1756).lead(2)), // ML-DSA-44 non-transferable public key
+    ("1ABB", Sizage::fixed(4, 0, 1756).lead(2)), // ML-DSA-44 public key
+    ("1ABC", Sizage::fixed(4, 0, 2608).lead(1)), // ML-DSA-65 non-transferable public key
+    ("1ABD", Sizage::fixed(4, 0, 2608).lead(1)), // ML-DSA-65 public key
+    ("1ABE", Sizage::fixed(4, 0, 3460)), // ML-DSA-87 non-transferable public key
+    ("1ABF", Sizage::fixed(4, 0, 3460)), // ML-DSA-87 public key
+    ("1ABG", Sizage::fixed(4, 0, 3232).lead(1)), // ML-DSA-44 signature
+    ("1ABH", Sizage::fixed(4, 0, 4416)), // ML-DSA-65 signature
+    ("1ABI", Sizage::fixed(4, 0, 6176).lead(2)), // ML-DSA-87 signature
+    ("4A", Sizage::variable(2, 2, 0)),  // Base64 string
+    ("5A", Sizage::variable(2, 2, 1)),
+    ("6A", Sizage::variable(2, 2, 2)),
+    ("7AAA", Sizage::variable(4, 4, 0)), // big Base64 string
+    ("8AAA", Sizage::variable(4, 4, 1)),
+    ("9AAA", Sizage::variable(4, 4, 2)),
+    ("4B", Sizage::variable(2, 2, 0)),  // bytes
+    ("5B", Sizage::variable(2, 2, 1)),
+    ("6B", Sizage::variable(2, 2, 2)),
+    ("7AAB", Sizage::variable(4, 4, 0)), // big bytes
+    ("8AAB", Sizage::variable(4, 4, 1)),
+    ("9AAB", Sizage::variable(4, 4, 2)),
+];
+
+/// The [count code table], each code with its sizes.
+///
+/// [count code table]: https://weboftrust.github.io/ietf-cesr/draft-ssmith-cesr.html#name-count-code-tables
+#[rustfmt::skip]
+pub const COUNTER: &[(&str, Sizage)] = &[
+    ("-A", Sizage::fixed(2, 2, 4)),
+    ("-B", Sizage::fixed(2, 2, 4)),
+    ("-C", Sizage::fixed(2, 2, 4)),
+    ("-D", Sizage::fixed(2, 2, 4)),
+    ("-E", Sizage::fixed(2, 2, 4)),
+    ("-F", Sizage::fixed(2, 2, 4)),
+    ("-G", Sizage::fixed(2, 2, 4)),
+    ("-H", Sizage::fixed(2, 2, 4)),
+    ("-I", Sizage::fixed(2, 2, 4)),
+    ("-J", Sizage::fixed(2, 2, 4)),
+    ("-K", Sizage::fixed(2, 2, 4)),
+    ("-L", Sizage::fixed(2, 2, 4)),
+    ("-U", Sizage::fixed(2, 2, 4)),
+    ("-V", Sizage::fixed(2, 2, 4)),
+    ("-W", Sizage::fixed(2, 2, 4)),
+    ("-X", Sizage::fixed(2, 2, 4)),
+    ("-Y", Sizage::fixed(2, 2, 4)),
+    ("-Z", Sizage::fixed(2, 2, 4)),
+    ("-0V", Sizage::fixed(3, 5, 8)),
+    ("-0W", Sizage::fixed(3, 5, 8)),
+    ("-0X", Sizage::fixed(3, 5, 8)),
+    ("-0Y", Sizage::fixed(3, 5, 8)),
+    ("-0Z", Sizage::fixed(3, 5, 8)),
+    ("--AAA", Sizage::fixed(5, 3, 8)),
+];
+
+/// The count code table of CESR 2, each code with its sizes. Every code but the genus version
+/// code comes in a small and a big size, the big one starting with `--`.
+#[rustfmt::skip]
+pub const COUNTER_2: &[(&str, Sizage)] = &[
+    ("-A", Sizage::fixed(2, 2, 4)),
+    ("-B", Sizage::fixed(2, 2, 4)),
+    ("-C", Sizage::fixed(2, 2, 4)),
+    ("-D", Sizage::fixed(2, 2, 4)),
+    ("-E", Sizage::fixed(2, 2, 4)),
+    ("-F", Sizage::fixed(2, 2, 4)),
+    ("-G", Sizage::fixed(2, 2, 4)),
+    ("-H", Sizage::fixed(2, 2, 4)),
+    ("-I", Sizage::fixed(2, 2, 4)),
+    ("-J", Sizage::fixed(2, 2, 4)),
+    ("-K", Sizage::fixed(2, 2, 4)),
+    ("-L", Sizage::fixed(2, 2, 4)),
+    ("-M", Sizage::fixed(2, 2, 4)),
+    ("-N", Sizage::fixed(2, 2, 4)),
+    ("-O", Sizage::fixed(2, 2, 4)),
+    ("-P", Sizage::fixed(2, 2, 4)),
+    ("-Q", Sizage::fixed(2, 2, 4)),
+    ("-R", Sizage::fixed(2, 2, 4)),
+    ("-S", Sizage::fixed(2, 2, 4)),
+    ("-T", Sizage::fixed(2, 2, 4)),
+    ("-U", Sizage::fixed(2, 2, 4)),
+    ("-V", Sizage::fixed(2, 2, 4)),
+    ("-W", Sizage::fixed(2, 2, 4)),
+    ("-X", Sizage::fixed(2, 2, 4)),
+    ("-Y", Sizage::fixed(2, 2, 4)),
+    ("-Z", Sizage::fixed(2, 2, 4)),
+    ("--A", Sizage::fixed(3, 5, 8)),
+    ("--B", Sizage::fixed(3, 5, 8)),
+    ("--C", Sizage::fixed(3, 5, 8)),
+    ("--D", Sizage::fixed(3, 5, 8)),
+    ("--E", Sizage::fixed(3, 5, 8)),
+    ("--F", Sizage::fixed(3, 5, 8)),
+    ("--G", Sizage::fixed(3, 5, 8)),
+    ("--H", Sizage::fixed(3, 5, 8)),
+    ("--I", Sizage::fixed(3, 5, 8)),
+    ("--J", Sizage::fixed(3, 5, 8)),
+    ("--K", Sizage::fixed(3, 5, 8)),
+    ("--L", Sizage::fixed(3, 5, 8)),
+    ("--M", Sizage::fixed(3, 5, 8)),
+    ("--N", Sizage::fixed(3, 5, 8)),
+    ("--O", Sizage::fixed(3, 5, 8)),
+    ("--P", Sizage::fixed(3, 5, 8)),
+    ("--Q", Sizage::fixed(3, 5, 8)),
+    ("--R", Sizage::fixed(3, 5, 8)),
+    ("--S", Sizage::fixed(3, 5, 8)),
+    ("--T", Sizage::fixed(3, 5, 8)),
+    ("--U", Sizage::fixed(3, 5, 8)),
+    ("--V", Sizage::fixed(3, 5, 8)),
+    ("--W", Sizage::fixed(3, 5, 8)),
+    ("--X", Sizage::fixed(3, 5, 8)),
+    ("--Y", Sizage::fixed(3, 5, 8)),
+    ("--Z", Sizage::fixed(3, 5, 8)),
+    ("-_AAA", Sizage::fixed(5, 3, 8)),
+];
+
+/// The sizes of `code` in `table`, the code must match a whole entry.
+pub fn sizage(table: &[(&str, Sizage)], code: &str) -> Result<Sizage, Error> {
+    table
+        .iter()
+        .find(|(c, _)| *c == code)
+        .map(|(_, sizage)| *sizage)
+        .ok_or_else(|| Error::UnknownCode { code: code.into() })
+}
+
+/// The sizes of a code of the master code table.
+///
+/// # Panics
+///
+/// If `code` is not in the table, use [sizage] for codes from untrusted input.
+pub fn matter(code: &str) -> Sizage {
+    sizage(MATTER, code).expect("code in the master code table")
+}
+
+/// The sizes of a code of the CESR 1 or CESR 2 count code table, the tables agree on the sizes
+/// of the codes they share.
+///
+/// # Panics
+///
+/// If `code` is in neither table, use [sizage] for codes from untrusted input.
+pub fn counter(code: &str) -> Sizage {
+    sizage(COUNTER, code)
+        .or_else(|_| sizage(COUNTER_2, code))
+        .expect("code in a count code table")
+}
+
+/// Number of pad characters of `raw` with `ls` lead bytes, the characters the code takes over so
+/// that the primitive aligns on a 24 bit boundary.
+pub fn pad_size(ls: usize, raw: &[u8]) -> usize {
+    (3 - (ls + raw.len()) % 3) % 3
+}
+
+/// Text domain encoding of `raw` under `code`.
+///
+/// The material is prepended with `ls` lead bytes and with as many zero pad bytes as it needs to
+/// align on a 24 bit boundary. The Base64 characters of the pad bytes are then dropped, their place
+/// is taken by the code, whose length must therefore be the pad size modulo 4.
+pub fn encode(code: &str, ls: usize, raw: &[u8]) -> String {
+    let ps = pad_size(ls, raw);
+    let padded = [vec![0; ps + ls], raw.to_vec()].concat();
+    let b64 = b64::encode(padded);
+    [code, &b64[ps..]].concat()
+}
+
+/// Raw material of the Base64 characters `b64` following a code, the inverse of [encode]. The pad
+/// size is what fills `b64` up to whole quadlets, for aligned primitives the code size modulo 4.
+/// Pad bits and lead bytes must be zero.
+pub fn decode(b64: &str, ls: usize) -> Result<Vec<u8>, Error> {
+    let ps = (4 - b64.len() % 4) % 4;
+    let padded = ["A".repeat(ps), b64.to_string()].concat();
+    let bytes = b64::decode(padded).map_err(|e| match e {
+        Error::InvalidBase64 { offset } => Error::InvalidBase64 {
+            offset: offset - ps,
+        },
+        Error::StandardBase64 { offset, character } => Error::StandardBase64 {
+            offset: offset - ps,
+            character,
+        },
+        Error::Base64Padding { offset } => Error::Base64Padding {
+            offset: offset - ps,
+        },
+        e => e,
+    })?;
+    match bytes.get(..ps + ls) {
+        Some(lead) if lead.iter().all(|b| *b == 0) => Ok(bytes[ps + ls..].to_vec()),
+        _ => Err(Error::InvalidPadding),
+    }
+}
+
+/// Checks `qb64` is text domain CESR as far as its characters tell: URL-safe Base64 only, no
+/// padding, in whole quadlets. Codes and sizes are left to parsing.
+pub fn validate_qb64(qb64: &str) -> Result<(), Error> {
+    b64::validate(qb64.as_bytes())?;
+    match qb64.len() % 4 {
+        0 => Ok(()),
+        rem => Err(Error::WrongLength {
+            expected: qb64.len() + 4 - rem,
+            got: qb64.len(),
+        }),
+    }
+}
+
+/// Whether `qb64` passes [validate_qb64], to reject input before parsing it.
+pub fn is_valid_qb64(qb64: &str) -> bool {
+    validate_qb64(qb64).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        derivation::{
+            basic::Basic, counter::CounterCode, self_addressing::SelfAddressing,
+            self_signing::SelfSigning, CodeTable,
+        },
+        prefix::BasicPrefix,
+    };
+    use core::str::FromStr;
+
+    #[test]
+    fn tables() {
+        for table in [MATTER, COUNTER] {
+            for (i, (code, sizage)) in table.iter().enumerate() {
+                assert_eq!(code.len(), sizage.hs, "{}", code);
+                assert!(sizage.ls < 3, "{}", code);
+                // codes are told apart by their start
+                for (other, _) in &table[i + 1..] {
+                    assert!(!other.starts_with(code) && !code.starts_with(other));
+                }
+                // primitives are aligned on quadlets
+                let len = sizage.fs.unwrap_or(sizage.cs());
+                assert!(len.is_multiple_of(4), "{}", code);
+            }
+        }
+    }
+
+    #[test]
+    fn encoding() -> Result<(), Error> {
+        // taken from KERIPY: tests/core/test_coring.py
+        assert_eq!(
+            encode("0A", 0, b"0123456789abcdef"),
+            "0AAwMTIzNDU2Nzg5YWJjZGVm"
+        );
+        assert_eq!(encode("M", 0, &[0, 5]), "MAAF");
+        assert_eq!(decode("AwMTIzNDU2Nzg5YWJjZGVm", 0)?, b"0123456789abcdef");
+
+        for (code, sizage) in MATTER.iter().filter(|(_, sizage)| sizage.ss == 0) {
+            let Some(fs) = sizage.fs else { continue };
+            let raw = vec![7; sizage.derivative_b64_len() * 3 / 4 - sizage.ls];
+            let qb64 = encode(code, sizage.ls, &raw);
+            assert_eq!(qb64.len(), fs, "{}", code);
+            assert_eq!(decode(&qb64[sizage.cs()..], sizage.ls)?, raw);
+        }
+
+        // pad bits and lead bytes must be zero
+        assert!(matches!(decode("wMTIzN", 0), Err(Error::InvalidPadding)));
+        assert!(matches!(decode("AQEB", 1), Err(Error::InvalidPadding)));
+        assert!(matches!(
+            decode("AAE*AA", 0),
+            Err(Error::InvalidBase64 { offset: 3 })
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn derivation_codes() {
+        for (code, _) in Basic::CODES {
+            assert!(sizage(MATTER, code).is_ok(), "{}", code);
+        }
+        for (code, _) in SelfSigning::CODES {
+            assert!(sizage(MATTER, code).is_ok(), "{}", code);
+        }
+        for (code, _) in SelfAddressing::CODES {
+            assert!(sizage(MATTER, code).is_ok(), "{}", code);
+        }
+        for (code, _) in CounterCode::CODES {
+            assert!(sizage(COUNTER, code).is_ok(), "{}", code);
+        }
+        for (code, _) in CounterCode::CESR_2_CODES {
+            assert!(sizage(COUNTER_2, code).is_ok(), "{}", code);
+        }
+        assert!(matches!(
+            sizage(MATTER, "1AA"),
+            Err(Error::UnknownCode { .. })
+        ));
+    }
+
+    #[test]
+    fn guardrails() {
+        let qb64 = "DKxy2sgzfplyr-tgwIxS19f2OchFHtLwPWD3v4oYimBx";
+        assert!(is_valid_qb64(qb64));
+        assert!(is_valid_qb64(""));
+        assert!(matches!(
+            validate_qb64(&qb64.replace('-', "+")),
+            Err(Error::StandardBase64 {
+                offset: 13,
+                character: '+'
+            })
+        ));
+        assert!(matches!(
+            validate_qb64("MAA="),
+            Err(Error::Base64Padding { offset: 3 })
+        ));
+        assert!(matches!(
+            validate_qb64("MAAB*"),
+            Err(Error::InvalidBase64 { offset: 4 })
+        ));
+        assert!(matches!(
+            validate_qb64("MAABA"),
+            Err(Error::WrongLength {
+                expected: 8,
+                got: 5
+            })
+        ));
+
+        // parsing names them too, in codes as in material
+        assert!(matches!(
+            BasicPrefix::from_str(&qb64.replace('-', "/")),
+            Err(Error::StandardBase64 {
+                offset: 12,
+                character: '/'
+            })
+        ));
+        assert!(matches!(
+            BasicPrefix::from_str("+Kxy2sgzfplyr-tgwIxS19f2OchFHtLwPWD3v4oYimBx"),
+            Err(Error::StandardBase64 { offset: 0, .. })
+        ));
+        assert!(matches!(
+            decode("AA==", 0),
+            Err(Error::Base64Padding { offset: 2 })
+        ));
+    }
+}
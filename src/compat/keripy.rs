@@ -0,0 +1,89 @@
+use crate::{
+    error::Error,
+    prefix::{verify_next_keys, BasicPrefix, SelfAddressingPrefix},
+    serder::Serder,
+    threshold::SignatureThreshold,
+};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+pub use crate::prefix::{legacy_next_digest, verify_legacy_next};
+
+/// Whether the keys and threshold revealed by `rotation` are those committed to by the `n` field
+/// of the `prior` establishment event, in either form keripy has written it.
+///
+/// Early keripy events commit with a single XOR digest, see [legacy_next_digest], to the next
+/// keys and to the threshold the rotation reveals as `kt`, and have no `nt` field. Later ones
+/// list a digest per key, satisfied under the prior `nt`.
+pub fn verify_next_commitment(prior: &Serder, rotation: &Serder) -> Result<bool, Error> {
+    let keys: Vec<BasicPrefix> = field(rotation, "k")?;
+    match prior.get("n") {
+        Some(Value::String(digest)) => Ok(verify_legacy_next(
+            &field(rotation, "kt")?,
+            &keys,
+            &digest.parse()?,
+        )),
+        Some(_) => {
+            let digests: Vec<SelfAddressingPrefix> = field(prior, "n")?;
+            let threshold: SignatureThreshold = field(prior, "nt")?;
+            Ok(verify_next_keys(&keys, &digests, &threshold))
+        }
+        None => Err(Error::SemanticError("Missing next keys field".into())),
+    }
+}
+
+fn field<T: DeserializeOwned>(serder: &Serder, label: &str) -> Result<T, Error> {
+    let value = serder
+        .get(label)
+        .ok_or_else(|| Error::SemanticError(format!("Missing {} field", label)))?;
+    Ok(T::deserialize(value)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        derivation::self_addressing::SelfAddressing,
+        message::{InceptionBuilder, RotationBuilder},
+        prefix::{next_digests, IdentifierPrefix, Prefix},
+        test_utils::signer,
+    };
+
+    fn keys(seeds: &[u8]) -> Vec<BasicPrefix> {
+        seeds
+            .iter()
+            .map(|seed| signer(*seed, true).basic_prefix())
+            .collect()
+    }
+
+    #[test]
+    fn next_commitment() -> Result<(), Error> {
+        let code = SelfAddressing::Blake3_256;
+        let icp = InceptionBuilder::new()
+            .keys(keys(&[1]))
+            .next_keys(next_digests(&keys(&[2, 3]), code))
+            .next_threshold("2".parse()?)
+            .build()?;
+        let prefix: IdentifierPrefix = icp.serder.get("i").unwrap().as_str().unwrap().parse()?;
+        let verify = |prior: &Serder, seeds: &[u8], threshold: &str| {
+            let rotation = RotationBuilder::new(prefix.clone(), 1, icp.said()?)
+                .keys(keys(seeds))
+                .threshold(threshold.parse()?)
+                .build()?;
+            verify_next_commitment(prior, &rotation.serder)
+        };
+        assert!(verify(&icp.serder, &[2, 3], "2")?);
+        assert!(!verify(&icp.serder, &[2], "1")?);
+
+        // an early keripy inception, one digest of the next threshold and keys
+        let mut ked = icp.serder.ked().clone();
+        ked.remove("nt");
+        let digest = legacy_next_digest(&"2".parse()?, &keys(&[2, 3]), code);
+        ked.insert("n".into(), digest.to_str().into());
+        let legacy = Serder::from_ked(ked, icp.serder.kind())?;
+        assert!(verify(&legacy, &[2, 3], "2")?);
+        assert!(!verify(&legacy, &[2, 3], "1")?);
+        assert!(!verify(&legacy, &[2], "1")?);
+        Ok(())
+    }
+}
@@ -0,0 +1,2 @@
+/// Deviations of keripy releases from the specification, reproduced to read what they wrote.
+pub mod keripy;
@@ -1,47 +1,353 @@
-use super::{self_signing::SelfSigning, DerivationCode};
-use crate::error::Error;
-use base64::{decode_config, encode_config};
+use super::{
+    b64_to_u64, self_signing::SelfSigning, split_at_len, u64_to_b64, CodeTable, DerivationCode,
+};
+use crate::{codex::b64, error::Error};
 use core::str::FromStr;
 
+/// Indexed signature codes from the CESR [indexed code table].
+///
+/// The index tells which key of the current key list made the signature. Dual index codes also
+/// carry the ondex, the index of the key digest in the prior next key list, which may differ from
+/// the index after a rotation reshuffles the keys. Current only (`Crt`) codes have no ondex.
+/// Big codes have room for larger indices and for an ondex different from the index.
+///
+/// [indexed code table]: https://weboftrust.github.io/ietf-cesr/draft-ssmith-cesr.html#name-indexed-code-table
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum Indexer {
+    Ed25519Sig,
+    Ed25519CrtSig,
+    ECDSAsecp256k1Sig,
+    ECDSAsecp256k1CrtSig,
+    Ed448Sig,
+    Ed448CrtSig,
+    Ed25519BigSig,
+    Ed25519BigCrtSig,
+    ECDSAsecp256k1BigSig,
+    ECDSAsecp256k1BigCrtSig,
+    Ed448BigSig,
+    Ed448BigCrtSig,
+    ECDSAsecp256r1Sig,
+    ECDSAsecp256r1CrtSig,
+    ECDSAsecp256r1BigSig,
+    ECDSAsecp256r1BigCrtSig,
+}
+
+impl Indexer {
+    /// Number of characters of the fixed part of the code.
+    pub fn hard_size(&self) -> usize {
+        match self {
+            Self::Ed25519Sig
+            | Self::Ed25519CrtSig
+            | Self::ECDSAsecp256k1Sig
+            | Self::ECDSAsecp256k1CrtSig
+            | Self::ECDSAsecp256r1Sig
+            | Self::ECDSAsecp256r1CrtSig => 1,
+            _ => 2,
+        }
+    }
+
+    /// Number of characters of the index and the ondex together.
+    pub fn soft_size(&self) -> usize {
+        match self {
+            Self::Ed25519Sig
+            | Self::Ed25519CrtSig
+            | Self::ECDSAsecp256k1Sig
+            | Self::ECDSAsecp256k1CrtSig
+            | Self::ECDSAsecp256r1Sig
+            | Self::ECDSAsecp256r1CrtSig => 1,
+            Self::Ed448Sig | Self::Ed448CrtSig => 2,
+            Self::Ed25519BigSig
+            | Self::Ed25519BigCrtSig
+            | Self::ECDSAsecp256k1BigSig
+            | Self::ECDSAsecp256k1BigCrtSig
+            | Self::ECDSAsecp256r1BigSig
+            | Self::ECDSAsecp256r1BigCrtSig => 4,
+            Self::Ed448BigSig | Self::Ed448BigCrtSig => 6,
+        }
+    }
+
+    /// Number of characters of the ondex, the last part of the soft part of the code.
+    pub fn other_size(&self) -> usize {
+        match self {
+            Self::Ed25519Sig
+            | Self::Ed25519CrtSig
+            | Self::ECDSAsecp256k1Sig
+            | Self::ECDSAsecp256k1CrtSig
+            | Self::ECDSAsecp256r1Sig
+            | Self::ECDSAsecp256r1CrtSig => 0,
+            Self::Ed448Sig | Self::Ed448CrtSig => 1,
+            Self::Ed25519BigSig
+            | Self::Ed25519BigCrtSig
+            | Self::ECDSAsecp256k1BigSig
+            | Self::ECDSAsecp256k1BigCrtSig
+            | Self::ECDSAsecp256r1BigSig
+            | Self::ECDSAsecp256r1BigCrtSig => 2,
+            Self::Ed448BigSig | Self::Ed448BigCrtSig => 3,
+        }
+    }
+
+    /// Whether the code carries the current index only, without an ondex.
+    pub fn is_current_only(&self) -> bool {
+        matches!(
+            self,
+            Self::Ed25519CrtSig
+                | Self::ECDSAsecp256k1CrtSig
+                | Self::Ed448CrtSig
+                | Self::Ed25519BigCrtSig
+                | Self::ECDSAsecp256k1BigCrtSig
+                | Self::Ed448BigCrtSig
+                | Self::ECDSAsecp256r1CrtSig
+                | Self::ECDSAsecp256r1BigCrtSig
+        )
+    }
+
+    /// The signature scheme of the indexed signature.
+    pub fn signature(&self) -> SelfSigning {
+        match self {
+            Self::Ed25519Sig
+            | Self::Ed25519CrtSig
+            | Self::Ed25519BigSig
+            | Self::Ed25519BigCrtSig => SelfSigning::Ed25519Sha512,
+            Self::ECDSAsecp256k1Sig
+            | Self::ECDSAsecp256k1CrtSig
+            | Self::ECDSAsecp256k1BigSig
+            | Self::ECDSAsecp256k1BigCrtSig => SelfSigning::ECDSAsecp256k1Sha256,
+            Self::Ed448Sig | Self::Ed448CrtSig | Self::Ed448BigSig | Self::Ed448BigCrtSig => {
+                SelfSigning::Ed448
+            }
+            Self::ECDSAsecp256r1Sig
+            | Self::ECDSAsecp256r1CrtSig
+            | Self::ECDSAsecp256r1BigSig
+            | Self::ECDSAsecp256r1BigCrtSig => SelfSigning::ECDSAsecp256r1Sha256,
+        }
+    }
+
+    /// Largest index that fits in the code.
+    pub fn max_index(&self) -> u64 {
+        (1 << (6 * (self.soft_size() - self.other_size()))) - 1
+    }
+
+    /// Largest ondex that fits in the code. Small dual index codes have no room for an ondex, it
+    /// is implied to be equal to the index.
+    pub fn max_ondex(&self) -> u64 {
+        (1 << (6 * self.other_size())) - 1
+    }
+
+    /// The fixed part of the code.
+    pub fn to_str(&self) -> String {
+        match self {
+            Self::Ed25519Sig => "A",
+            Self::Ed25519CrtSig => "B",
+            Self::ECDSAsecp256k1Sig => "C",
+            Self::ECDSAsecp256k1CrtSig => "D",
+            Self::Ed448Sig => "0A",
+            Self::Ed448CrtSig => "0B",
+            Self::Ed25519BigSig => "2A",
+            Self::Ed25519BigCrtSig => "2B",
+            Self::ECDSAsecp256k1BigSig => "2C",
+            Self::ECDSAsecp256k1BigCrtSig => "2D",
+            Self::Ed448BigSig => "3A",
+            Self::Ed448BigCrtSig => "3B",
+            Self::ECDSAsecp256r1Sig => "E",
+            Self::ECDSAsecp256r1CrtSig => "F",
+            Self::ECDSAsecp256r1BigSig => "2E",
+            Self::ECDSAsecp256r1BigCrtSig => "2F",
+        }
+        .into()
+    }
+}
+
+impl CodeTable for Indexer {
+    const CODES: &'static [(&'static str, Self)] = &[
+        ("A", Self::Ed25519Sig),
+        ("B", Self::Ed25519CrtSig),
+        ("C", Self::ECDSAsecp256k1Sig),
+        ("D", Self::ECDSAsecp256k1CrtSig),
+        ("E", Self::ECDSAsecp256r1Sig),
+        ("F", Self::ECDSAsecp256r1CrtSig),
+        ("0A", Self::Ed448Sig),
+        ("0B", Self::Ed448CrtSig),
+        ("2A", Self::Ed25519BigSig),
+        ("2B", Self::Ed25519BigCrtSig),
+        ("2C", Self::ECDSAsecp256k1BigSig),
+        ("2D", Self::ECDSAsecp256k1BigCrtSig),
+        ("2E", Self::ECDSAsecp256r1BigSig),
+        ("2F", Self::ECDSAsecp256r1BigCrtSig),
+        ("3A", Self::Ed448BigSig),
+        ("3B", Self::Ed448BigCrtSig),
+    ];
+}
+
+/// Parses the fixed part of an indexed signature code from the start of `s`.
+impl FromStr for Indexer {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::lookup(s)
+    }
+}
+
 /// Attached Signature Derivation Codes
 ///
 /// A self signing prefix derivation outputs a signature as its derivative (2.3.5)
+/// An attached signature additionally carries the index of the signing key in the key list.
 #[derive(Debug, PartialEq, Clone, Copy)]
 pub struct AttachedSignatureCode {
     pub index: u16,
-    pub code: SelfSigning,
+    /// Index of the key digest in the prior next key list, `None` for current only codes.
+    pub ondex: Option<u16>,
+    pub code: Indexer,
 }
 
 impl AttachedSignatureCode {
-    pub fn new(code: SelfSigning, index: u16) -> Self {
-        Self { index, code }
+    /// Dual index code where the ondex equals the index, switching to the big code when the index
+    /// does not fit in the small one. Fails when it does not fit in the big one either, or for
+    /// ML-DSA signatures which have no indexed codes yet.
+    pub fn new(code: SelfSigning, index: u16) -> Result<Self, Error> {
+        Self::dual(code, index, index)
+    }
+
+    /// Dual index code of the key at `index` of the current key list whose digest is at `ondex`
+    /// of the prior next key list, e.g. in a rotation that reorders the keys. The small code is
+    /// chosen when both fit in it, the big one otherwise.
+    pub fn dual(code: SelfSigning, index: u16, ondex: u16) -> Result<Self, Error> {
+        let (small, big) = indexers(code, false).ok_or_else(|| {
+            Error::SemanticError(format!("No indexed signature code for {:?}", code))
+        })?;
+        Self::with_indexer(small, index, Some(ondex))
+            .or_else(|_| Self::with_indexer(big, index, Some(ondex)))
+    }
+
+    /// Current only code of the key at `index`, for signatures with no prior next key list to
+    /// index into, e.g. those of witnesses. The small code is chosen when the index fits in it.
+    pub fn current_only(code: SelfSigning, index: u16) -> Result<Self, Error> {
+        let (small, big) = indexers(code, true).ok_or_else(|| {
+            Error::SemanticError(format!("No indexed signature code for {:?}", code))
+        })?;
+        Self::with_indexer(small, index, None).or_else(|_| Self::with_indexer(big, index, None))
+    }
+
+    /// The current only code of the same scheme and index, dropping the ondex.
+    pub fn to_current_only(self) -> Self {
+        Self::current_only(self.code.signature(), self.index).unwrap_or(self)
+    }
+
+    /// Index of the key in the current key list.
+    pub fn index(&self) -> u16 {
+        self.index
+    }
+
+    /// Index of the key digest in the prior next key list, `None` for current only codes.
+    pub fn ondex(&self) -> Option<u16> {
+        self.ondex
+    }
+
+    /// Code with an explicitly chosen indexer, validating that the index and ondex fit in it.
+    pub fn with_indexer(code: Indexer, index: u16, ondex: Option<u16>) -> Result<Self, Error> {
+        if index as u64 > code.max_index() {
+            return Err(Error::SemanticError(format!(
+                "Index {} too big for attachment code {}",
+                index,
+                code.to_str()
+            )));
+        }
+        match ondex {
+            None if !code.is_current_only() => Err(Error::SemanticError(format!(
+                "Missing ondex for dual index attachment code {}",
+                code.to_str()
+            ))),
+            Some(_) if code.is_current_only() => Err(Error::SemanticError(format!(
+                "Unexpected ondex for current only attachment code {}",
+                code.to_str()
+            ))),
+            Some(o) if code.other_size() == 0 && o != index => Err(Error::SemanticError(format!(
+                "Ondex {} must equal index {} for attachment code {}",
+                o,
+                index,
+                code.to_str()
+            ))),
+            Some(o) if code.other_size() > 0 && o as u64 > code.max_ondex() => {
+                Err(Error::SemanticError(format!(
+                    "Ondex {} too big for attachment code {}",
+                    o,
+                    code.to_str()
+                )))
+            }
+            _ => Ok(Self { index, ondex, code }),
+        }
+    }
+
+    /// The small code of the same scheme when the index and ondex fit in it, the code as it is
+    /// otherwise.
+    pub fn normalized(&self) -> Self {
+        let small = match self.code {
+            Indexer::Ed25519BigSig => Indexer::Ed25519Sig,
+            Indexer::Ed25519BigCrtSig => Indexer::Ed25519CrtSig,
+            Indexer::ECDSAsecp256k1BigSig => Indexer::ECDSAsecp256k1Sig,
+            Indexer::ECDSAsecp256k1BigCrtSig => Indexer::ECDSAsecp256k1CrtSig,
+            Indexer::Ed448BigSig => Indexer::Ed448Sig,
+            Indexer::Ed448BigCrtSig => Indexer::Ed448CrtSig,
+            Indexer::ECDSAsecp256r1BigSig => Indexer::ECDSAsecp256r1Sig,
+            Indexer::ECDSAsecp256r1BigCrtSig => Indexer::ECDSAsecp256r1CrtSig,
+            _ => return *self,
+        };
+        Self::with_indexer(small, self.index, self.ondex).unwrap_or(*self)
     }
 }
 
+/// The small and big indexers of `code`, of the current only or the dual index family. None for
+/// ML-DSA.
+fn indexers(code: SelfSigning, current_only: bool) -> Option<(Indexer, Indexer)> {
+    Some(match (code, current_only) {
+        (SelfSigning::Ed25519Sha512, false) => (Indexer::Ed25519Sig, Indexer::Ed25519BigSig),
+        (SelfSigning::Ed25519Sha512, true) => (Indexer::Ed25519CrtSig, Indexer::Ed25519BigCrtSig),
+        (SelfSigning::ECDSAsecp256k1Sha256, false) => {
+            (Indexer::ECDSAsecp256k1Sig, Indexer::ECDSAsecp256k1BigSig)
+        }
+        (SelfSigning::ECDSAsecp256k1Sha256, true) => (
+            Indexer::ECDSAsecp256k1CrtSig,
+            Indexer::ECDSAsecp256k1BigCrtSig,
+        ),
+        (SelfSigning::Ed448, false) => (Indexer::Ed448Sig, Indexer::Ed448BigSig),
+        (SelfSigning::Ed448, true) => (Indexer::Ed448CrtSig, Indexer::Ed448BigCrtSig),
+        (SelfSigning::ECDSAsecp256r1Sha256, false) => {
+            (Indexer::ECDSAsecp256r1Sig, Indexer::ECDSAsecp256r1BigSig)
+        }
+        (SelfSigning::ECDSAsecp256r1Sha256, true) => (
+            Indexer::ECDSAsecp256r1CrtSig,
+            Indexer::ECDSAsecp256r1BigCrtSig,
+        ),
+        (SelfSigning::MLDSA44 | SelfSigning::MLDSA65 | SelfSigning::MLDSA87, _) => return None,
+    })
+}
+
 impl DerivationCode for AttachedSignatureCode {
     fn code_len(&self) -> usize {
-        match self.code {
-            SelfSigning::Ed25519Sha512 | SelfSigning::ECDSAsecp256k1Sha256 => 2,
-            SelfSigning::Ed448 => 4,
-        }
+        self.code.hard_size() + self.code.soft_size()
     }
 
     fn derivative_b64_len(&self) -> usize {
-        match self.code {
-            SelfSigning::Ed25519Sha512 | SelfSigning::ECDSAsecp256k1Sha256 => 86,
-            SelfSigning::Ed448 => 152,
-        }
+        self.code.signature().derivative_b64_len()
+    }
+
+    fn hard_size(&self) -> usize {
+        self.code.hard_size()
+    }
+
+    fn soft_size(&self) -> usize {
+        self.code.soft_size()
     }
 
-    // TODO, this will only work with indices up to 63
     fn to_str(&self) -> String {
+        let ondex_size = self.code.other_size();
+        let ondex = match (self.ondex, ondex_size) {
+            (Some(o), size) if size > 0 => u64_to_b64(o as u64, size),
+            _ => u64_to_b64(0, ondex_size),
+        };
         [
-            match self.code {
-                SelfSigning::Ed25519Sha512 => "A",
-                SelfSigning::ECDSAsecp256k1Sha256 => "B",
-                SelfSigning::Ed448 => "0AA",
-            },
-            &num_to_b64(self.index),
+            self.code.to_str(),
+            u64_to_b64(self.index as u64, self.code.soft_size() - ondex_size),
+            ondex,
         ]
         .join("")
     }
@@ -51,39 +357,39 @@ impl FromStr for AttachedSignatureCode {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match &s[..1] {
-            "A" => Ok(Self::new(
-                SelfSigning::Ed25519Sha512,
-                b64_to_num(&s.as_bytes()[1..2])?,
-            )),
-            "B" => Ok(Self::new(
-                SelfSigning::ECDSAsecp256k1Sha256,
-                b64_to_num(&s.as_bytes()[1..2])?,
-            )),
-            "0" => match &s[1..3] {
-                "AA" => Ok(Self::new(
-                    SelfSigning::Ed448,
-                    b64_to_num(&s.as_bytes()[3..4])?,
-                )),
-                _ => Err(Error::DeserializeError("Unknows signature code".into())),
-            },
-            _ => Err(Error::DeserializeError("Unknown attachment code".into())),
-        }
+        let code = Indexer::from_str(s)?;
+        let (hs, ss, os) = (code.hard_size(), code.soft_size(), code.other_size());
+        let soft = &split_at_len(s, hs + ss)?.0[hs..];
+        let (index, ondex) = split_at_len(soft, ss - os)?;
+        let index = b64_to_u64(index)?;
+        let index = u16::try_from(index)
+            .map_err(|_| Error::DeserializeError(format!("Attachment index too big: {}", index)))?;
+        let ondex = b64_to_u64(ondex)?;
+        let ondex = match (code.is_current_only(), os) {
+            (true, _) if ondex != 0 => {
+                return Err(Error::DeserializeError(format!(
+                    "Unexpected ondex for current only attachment code: {}",
+                    s
+                )))
+            }
+            (true, _) => None,
+            (false, 0) => Some(index),
+            (false, _) => Some(u16::try_from(ondex).map_err(|_| {
+                Error::DeserializeError(format!("Attachment ondex too big: {}", ondex))
+            })?),
+        };
+        Ok(Self { index, ondex, code })
     }
 }
 
 // returns the u16 from the lowest 2 bytes of the b64 string
 // currently only works for strings 4 chars or less
 pub fn b64_to_num(b64: &[u8]) -> Result<u16, Error> {
-    let slice = decode_config(
-        match b64.len() {
-            1 => [r"AAA".as_bytes(), b64].concat(),
-            2 => [r"AA".as_bytes(), b64].concat(),
-            _ => b64.to_owned(),
-        },
-        base64::URL_SAFE,
-    )
-    .map_err(|e| Error::Base64DecodingError { source: e })?;
+    let slice = b64::decode(match b64.len() {
+        1 => [r"AAA".as_bytes(), b64].concat(),
+        2 => [r"AA".as_bytes(), b64].concat(),
+        _ => b64.to_owned(),
+    })?;
     let len = slice.len();
 
     Ok(u16::from_be_bytes(match len {
@@ -95,11 +401,9 @@ pub fn b64_to_num(b64: &[u8]) -> Result<u16, Error> {
 
 pub fn num_to_b64(num: u16) -> String {
     match num {
-        n if n < 63 => {
-            encode_config([num.to_be_bytes()[1] << 2], base64::URL_SAFE_NO_PAD)[..1].to_string()
-        }
-        n if n < 4095 => encode_config(num.to_be_bytes(), base64::URL_SAFE_NO_PAD)[..2].to_string(),
-        _ => encode_config(num.to_be_bytes(), base64::URL_SAFE_NO_PAD),
+        n if n < 63 => b64::encode([num.to_be_bytes()[1] << 2])[..1].to_string(),
+        n if n < 4095 => b64::encode(num.to_be_bytes())[..2].to_string(),
+        _ => b64::encode(num.to_be_bytes()),
     }
 }
 
@@ -113,3 +417,164 @@ fn num_to_b64_test() {
     assert_eq!("AE", num_to_b64(64));
     assert_eq!("EAA", num_to_b64(4096));
 }
+
+#[cfg(test)]
+mod indexer_tests {
+    use super::*;
+
+    #[test]
+    fn test_to_str() -> Result<(), Error> {
+        assert_eq!(
+            AttachedSignatureCode::new(SelfSigning::Ed25519Sha512, 1)?.to_str(),
+            "AB"
+        );
+        assert_eq!(
+            AttachedSignatureCode::new(SelfSigning::ECDSAsecp256k1Sha256, 2)?.to_str(),
+            "CC"
+        );
+        assert_eq!(
+            AttachedSignatureCode::new(SelfSigning::Ed448, 3)?.to_str(),
+            "0ADD"
+        );
+        assert_eq!(
+            AttachedSignatureCode::new(SelfSigning::ECDSAsecp256r1Sha256, 4)?.to_str(),
+            "EE"
+        );
+        // index too big for the small codes
+        assert_eq!(
+            AttachedSignatureCode::new(SelfSigning::Ed25519Sha512, 64)?.to_str(),
+            "2ABABA"
+        );
+        assert_eq!(
+            AttachedSignatureCode::new(SelfSigning::Ed448, 64)?.to_str(),
+            "3AABAABA"
+        );
+        // index too big for the big codes, not truncated to its low sextets
+        assert_eq!(
+            AttachedSignatureCode::new(SelfSigning::Ed25519Sha512, 4095)?.to_str(),
+            "2A____"
+        );
+        assert!(AttachedSignatureCode::new(SelfSigning::Ed25519Sha512, 5000).is_err());
+        assert!(AttachedSignatureCode::new(SelfSigning::MLDSA44, 0).is_err());
+
+        let crt = AttachedSignatureCode::with_indexer(Indexer::Ed25519CrtSig, 5, None)?;
+        assert_eq!(crt.to_str(), "BF");
+        let crt = AttachedSignatureCode::with_indexer(Indexer::Ed448CrtSig, 5, None)?;
+        assert_eq!(crt.to_str(), "0BFA");
+        let dual = AttachedSignatureCode::with_indexer(Indexer::Ed25519BigSig, 1, Some(2))?;
+        assert_eq!(dual.to_str(), "2AABAC");
+        assert_eq!(dual.prefix_b64_len(), 92);
+
+        assert!(AttachedSignatureCode::with_indexer(Indexer::Ed25519Sig, 64, Some(64)).is_err());
+        assert!(AttachedSignatureCode::with_indexer(Indexer::Ed25519Sig, 1, Some(2)).is_err());
+        assert!(AttachedSignatureCode::with_indexer(Indexer::Ed25519Sig, 1, None).is_err());
+        assert!(AttachedSignatureCode::with_indexer(Indexer::Ed25519CrtSig, 1, Some(1)).is_err());
+        assert!(AttachedSignatureCode::with_indexer(Indexer::Ed448Sig, 1, Some(64)).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_str() -> Result<(), Error> {
+        let code = AttachedSignatureCode::from_str("AB")?;
+        assert_eq!(
+            code,
+            AttachedSignatureCode::new(SelfSigning::Ed25519Sha512, 1)?
+        );
+
+        let code = AttachedSignatureCode::from_str("BF")?;
+        assert_eq!(
+            (code.code, code.index, code.ondex),
+            (Indexer::Ed25519CrtSig, 5, None)
+        );
+
+        let code = AttachedSignatureCode::from_str("2CABAC")?;
+        assert_eq!(
+            (code.code, code.index, code.ondex),
+            (Indexer::ECDSAsecp256k1BigSig, 1, Some(2))
+        );
+
+        let code = AttachedSignatureCode::from_str("2FABAA")?;
+        assert_eq!(
+            (code.code, code.index, code.ondex),
+            (Indexer::ECDSAsecp256r1BigCrtSig, 1, None)
+        );
+
+        let code = AttachedSignatureCode::from_str("3BAABAAA")?;
+        assert_eq!(
+            (code.code, code.index, code.ondex),
+            (Indexer::Ed448BigCrtSig, 1, None)
+        );
+
+        assert!(AttachedSignatureCode::from_str("").is_err());
+        assert!(AttachedSignatureCode::from_str("A").is_err());
+        assert!(AttachedSignatureCode::from_str("2A").is_err());
+        assert!(AttachedSignatureCode::from_str("1AAA").is_err());
+        assert!(AttachedSignatureCode::from_str("0C").is_err());
+        // current only codes have no ondex
+        assert!(AttachedSignatureCode::from_str("0BFB").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn dual() -> Result<(), Error> {
+        for (code, index, ondex, indexer) in [
+            (SelfSigning::Ed25519Sha512, 3, 3, Indexer::Ed25519Sig),
+            (SelfSigning::Ed25519Sha512, 3, 1, Indexer::Ed25519BigSig),
+            (SelfSigning::Ed25519Sha512, 64, 64, Indexer::Ed25519BigSig),
+            (SelfSigning::Ed448, 3, 1, Indexer::Ed448Sig),
+            (SelfSigning::Ed448, 3, 64, Indexer::Ed448BigSig),
+            (
+                SelfSigning::ECDSAsecp256r1Sha256,
+                0,
+                2,
+                Indexer::ECDSAsecp256r1BigSig,
+            ),
+        ] {
+            let dual = AttachedSignatureCode::dual(code, index, ondex)?;
+            assert_eq!(
+                (dual.code, dual.index(), dual.ondex()),
+                (indexer, index, Some(ondex))
+            );
+            assert_eq!(AttachedSignatureCode::from_str(&dual.to_str())?, dual);
+        }
+        assert_eq!(
+            AttachedSignatureCode::dual(SelfSigning::Ed25519Sha512, 5, 5)?,
+            AttachedSignatureCode::new(SelfSigning::Ed25519Sha512, 5)?
+        );
+        // past the big code, or a scheme without indexed codes
+        assert!(AttachedSignatureCode::dual(SelfSigning::Ed25519Sha512, 0, 4096).is_err());
+        assert!(AttachedSignatureCode::dual(SelfSigning::MLDSA44, 0, 0).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn current_only() -> Result<(), Error> {
+        for (code, index, indexer) in [
+            (SelfSigning::Ed25519Sha512, 3, Indexer::Ed25519CrtSig),
+            (SelfSigning::Ed25519Sha512, 64, Indexer::Ed25519BigCrtSig),
+            (
+                SelfSigning::ECDSAsecp256k1Sha256,
+                1,
+                Indexer::ECDSAsecp256k1CrtSig,
+            ),
+            (SelfSigning::Ed448, 63, Indexer::Ed448CrtSig),
+            (
+                SelfSigning::ECDSAsecp256r1Sha256,
+                70,
+                Indexer::ECDSAsecp256r1BigCrtSig,
+            ),
+        ] {
+            let crt = AttachedSignatureCode::current_only(code, index)?;
+            assert_eq!((crt.code, crt.index(), crt.ondex()), (indexer, index, None));
+            assert_eq!(AttachedSignatureCode::from_str(&crt.to_str())?, crt);
+            assert_eq!(
+                AttachedSignatureCode::new(code, index)?.to_current_only(),
+                crt
+            );
+        }
+        let big = AttachedSignatureCode::dual(SelfSigning::Ed25519Sha512, 2, 9)?;
+        assert_eq!(big.to_current_only().to_str(), "BC");
+        assert!(AttachedSignatureCode::current_only(SelfSigning::Ed25519Sha512, 4096).is_err());
+        Ok(())
+    }
+}
@@ -1,5 +1,5 @@
-use super::DerivationCode;
-use crate::{error::Error, keys::PublicKey, prefix::BasicPrefix};
+use super::{CodeTable, DerivationCode};
+use crate::{codex, error::Error, keys::PublicKey, prefix::BasicPrefix};
 use core::str::FromStr;
 use serde::{Deserialize, Serialize};
 
@@ -19,70 +19,111 @@ pub enum Basic {
     Ed448,
     X25519,
     X448,
+    ECDSAsecp256r1NT,
+    ECDSAsecp256r1,
+    /// ML-DSA (Dilithium) public keys, the codes are provisional until registered in the master
+    /// code table.
+    MLDSA44NT,
+    MLDSA44,
+    MLDSA65NT,
+    MLDSA65,
+    MLDSA87NT,
+    MLDSA87,
 }
 
 impl Basic {
     pub fn derive(&self, public_key: PublicKey) -> BasicPrefix {
-        BasicPrefix::new(*self, public_key)
+        BasicPrefix::new_unchecked(*self, public_key)
+    }
+
+    /// Whether keys under this code may be rotated, the encryption keys are never transferable.
+    pub fn is_transferable(&self) -> bool {
+        !matches!(
+            self,
+            Self::ECDSAsecp256k1NT
+                | Self::Ed25519NT
+                | Self::Ed448NT
+                | Self::X25519
+                | Self::X448
+                | Self::ECDSAsecp256r1NT
+                | Self::MLDSA44NT
+                | Self::MLDSA65NT
+                | Self::MLDSA87NT
+        )
+    }
+
+    /// The code of the same key type for a transferable or a non-transferable identifier, `None`
+    /// for the encryption keys which have no transferable code.
+    pub fn with_transferability(&self, transferable: bool) -> Option<Self> {
+        let (non_transferable, transferable_code) = match self {
+            Self::ECDSAsecp256k1NT | Self::ECDSAsecp256k1 => {
+                (Self::ECDSAsecp256k1NT, Self::ECDSAsecp256k1)
+            }
+            Self::Ed25519NT | Self::Ed25519 => (Self::Ed25519NT, Self::Ed25519),
+            Self::Ed448NT | Self::Ed448 => (Self::Ed448NT, Self::Ed448),
+            Self::ECDSAsecp256r1NT | Self::ECDSAsecp256r1 => {
+                (Self::ECDSAsecp256r1NT, Self::ECDSAsecp256r1)
+            }
+            Self::MLDSA44NT | Self::MLDSA44 => (Self::MLDSA44NT, Self::MLDSA44),
+            Self::MLDSA65NT | Self::MLDSA65 => (Self::MLDSA65NT, Self::MLDSA65),
+            Self::MLDSA87NT | Self::MLDSA87 => (Self::MLDSA87NT, Self::MLDSA87),
+            Self::X25519 | Self::X448 => return None,
+        };
+        Some(if transferable {
+            transferable_code
+        } else {
+            non_transferable
+        })
     }
 }
 
 impl DerivationCode for Basic {
     fn code_len(&self) -> usize {
-        match self {
-            Self::Ed25519NT | Self::Ed25519 | Self::X25519 | Self::X448 => 1,
-            Self::ECDSAsecp256k1NT | Self::ECDSAsecp256k1 | Self::Ed448NT | Self::Ed448 => 4,
-        }
+        codex::matter(self.code()).cs()
     }
 
     fn derivative_b64_len(&self) -> usize {
-        match self {
-            Self::Ed25519NT | Self::Ed25519 | Self::X25519 => 43,
-            Self::X448 => 75,
-            Self::ECDSAsecp256k1NT | Self::ECDSAsecp256k1 => 47,
-            Self::Ed448NT | Self::Ed448 => 76,
-        }
+        codex::matter(self.code()).derivative_b64_len()
+    }
+
+    fn lead_size(&self) -> usize {
+        codex::matter(self.code()).ls
     }
 
     fn to_str(&self) -> String {
-        match self {
-            Self::Ed25519NT => "B",
-            Self::X25519 => "C",
-            Self::Ed25519 => "D",
-            Self::X448 => "L",
-            Self::ECDSAsecp256k1NT => "1AAA",
-            Self::ECDSAsecp256k1 => "1AAB",
-            Self::Ed448NT => "1AAC",
-            Self::Ed448 => "1AAD",
-        }
-        .into()
+        self.code().into()
     }
 }
 
 /// Maps basic identifier data type strings to entries in the [master code table].
 ///
 /// [master code table]: https://weboftrust.github.io/ietf-cesr/draft-ssmith-cesr.html#name-master-code-table
+impl CodeTable for Basic {
+    const CODES: &'static [(&'static str, Self)] = &[
+        ("B", Self::Ed25519NT),
+        ("C", Self::X25519),
+        ("D", Self::Ed25519),
+        ("L", Self::X448),
+        ("1AAA", Self::ECDSAsecp256k1NT),
+        ("1AAB", Self::ECDSAsecp256k1),
+        ("1AAC", Self::Ed448NT),
+        ("1AAD", Self::Ed448),
+        ("1AAI", Self::ECDSAsecp256r1NT),
+        ("1AAJ", Self::ECDSAsecp256r1),
+        ("1ABA", Self::MLDSA44NT),
+        ("1ABB", Self::MLDSA44),
+        ("1ABC", Self::MLDSA65NT),
+        ("1ABD", Self::MLDSA65),
+        ("1ABE", Self::MLDSA87NT),
+        ("1ABF", Self::MLDSA87),
+    ];
+}
+
 impl FromStr for Basic {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s
-            .get(..1)
-            .ok_or_else(|| Error::DeserializeError("Empty prefix".into()))?
-        {
-            "B" => Ok(Self::Ed25519NT),
-            "C" => Ok(Self::X25519),
-            "D" => Ok(Self::Ed25519),
-            "L" => Ok(Self::X448),
-            "1" => match &s[1..4] {
-                "AAA" => Ok(Self::ECDSAsecp256k1NT),
-                "AAB" => Ok(Self::ECDSAsecp256k1),
-                "AAC" => Ok(Self::Ed448NT),
-                "AAD" => Ok(Self::Ed448),
-                _ => Err(Error::DeserializeError("Unknown signature code".into())),
-            },
-            _ => Err(Error::DeserializeError("Unknown prefix code".into())),
-        }
+        Self::lookup(s)
     }
 }
 
@@ -90,9 +131,8 @@ impl FromStr for Basic {
 mod basic_tests {
     use crate::derivation::basic::Basic;
     use crate::derivation::basic::FromStr;
-    use crate::derivation::DerivationCode;
-    use crate::error::Error;
-    use crate::error::Error::DeserializeError;
+    use crate::derivation::{CodeTable, DerivationCode};
+
     use crate::keys::PublicKey;
 
     #[test]
@@ -106,6 +146,8 @@ mod basic_tests {
         assert_eq!(Basic::ECDSAsecp256k1.code_len(), 4);
         assert_eq!(Basic::Ed448NT.code_len(), 4);
         assert_eq!(Basic::Ed448.code_len(), 4);
+        assert_eq!(Basic::ECDSAsecp256r1NT.code_len(), 4);
+        assert_eq!(Basic::ECDSAsecp256r1.code_len(), 4);
     }
 
     #[test]
@@ -116,11 +158,14 @@ mod basic_tests {
 
         assert_eq!(Basic::X448.derivative_b64_len(), 75);
 
-        assert_eq!(Basic::ECDSAsecp256k1NT.derivative_b64_len(), 47);
-        assert_eq!(Basic::ECDSAsecp256k1.derivative_b64_len(), 47);
+        assert_eq!(Basic::ECDSAsecp256k1NT.derivative_b64_len(), 44);
+        assert_eq!(Basic::ECDSAsecp256k1.derivative_b64_len(), 44);
 
         assert_eq!(Basic::Ed448NT.derivative_b64_len(), 76);
         assert_eq!(Basic::Ed448.derivative_b64_len(), 76);
+
+        assert_eq!(Basic::ECDSAsecp256r1NT.derivative_b64_len(), 44);
+        assert_eq!(Basic::ECDSAsecp256r1.derivative_b64_len(), 44);
     }
 
     #[test]
@@ -133,19 +178,55 @@ mod basic_tests {
         assert_eq!(Basic::ECDSAsecp256k1.to_str(), "1AAB");
         assert_eq!(Basic::Ed448NT.to_str(), "1AAC");
         assert_eq!(Basic::Ed448.to_str(), "1AAD");
+        assert_eq!(Basic::ECDSAsecp256r1NT.to_str(), "1AAI");
+        assert_eq!(Basic::ECDSAsecp256r1.to_str(), "1AAJ");
     }
 
     #[test]
     fn test_from_str() {
-        assert_eq!(Basic::from_str(&"B").unwrap(), Basic::Ed25519NT);
-        assert_eq!(Basic::from_str(&"C").unwrap(), Basic::X25519);
-        assert_eq!(Basic::from_str(&"D").unwrap(), Basic::Ed25519);
-        assert_eq!(Basic::from_str(&"L").unwrap(), Basic::X448);
-
-        assert_eq!(Basic::from_str(&"1AAA").unwrap(), Basic::ECDSAsecp256k1NT);
-        assert_eq!(Basic::from_str(&"1AAB").unwrap(), Basic::ECDSAsecp256k1);
-        assert_eq!(Basic::from_str(&"1AAC").unwrap(), Basic::Ed448NT);
-        assert_eq!(Basic::from_str(&"1AAD").unwrap(), Basic::Ed448);
+        assert_eq!(Basic::from_str("B").unwrap(), Basic::Ed25519NT);
+        assert_eq!(Basic::from_str("C").unwrap(), Basic::X25519);
+        assert_eq!(Basic::from_str("D").unwrap(), Basic::Ed25519);
+        assert_eq!(Basic::from_str("L").unwrap(), Basic::X448);
+
+        assert_eq!(Basic::from_str("1AAA").unwrap(), Basic::ECDSAsecp256k1NT);
+        assert_eq!(Basic::from_str("1AAB").unwrap(), Basic::ECDSAsecp256k1);
+        assert_eq!(Basic::from_str("1AAC").unwrap(), Basic::Ed448NT);
+        assert_eq!(Basic::from_str("1AAD").unwrap(), Basic::Ed448);
+        assert_eq!(Basic::from_str("1AAI").unwrap(), Basic::ECDSAsecp256r1NT);
+        assert_eq!(Basic::from_str("1AAJ").unwrap(), Basic::ECDSAsecp256r1);
+        assert_eq!(Basic::from_str("1ABA").unwrap(), Basic::MLDSA44NT);
+        assert_eq!(Basic::from_str("1ABF").unwrap(), Basic::MLDSA87);
+    }
+
+    #[test]
+    fn transferability() {
+        for (code, basic) in Basic::CODES {
+            assert_eq!(
+                Basic::from_str(code).unwrap().is_transferable(),
+                basic.is_transferable()
+            );
+            match basic.with_transferability(true) {
+                Some(transferable) => {
+                    assert!(transferable.is_transferable());
+                    let non_transferable = basic.with_transferability(false).unwrap();
+                    assert!(!non_transferable.is_transferable());
+                    assert!(*basic == transferable || *basic == non_transferable);
+                }
+                None => assert!(!basic.is_transferable()),
+            }
+        }
+        assert!(Basic::Ed25519.is_transferable());
+        assert!(!Basic::Ed25519NT.is_transferable());
+        assert_eq!(
+            Basic::Ed25519.with_transferability(false),
+            Some(Basic::Ed25519NT)
+        );
+        assert_eq!(
+            Basic::ECDSAsecp256k1NT.with_transferability(true),
+            Some(Basic::ECDSAsecp256k1)
+        );
+        assert_eq!(Basic::X25519.with_transferability(false), None);
     }
 
     #[test]
@@ -176,5 +257,29 @@ mod basic_tests {
 
         let der = Basic::X448.derive(PublicKey::new([0; 56].to_vec()));
         assert_eq!(der.to_str(), ["L".to_string(), "A".repeat(75)].join(""));
+
+        let der = Basic::ECDSAsecp256r1NT.derive(PublicKey::new([0; 33].to_vec()));
+        assert_eq!(der.to_str(), ["1AAI".to_string(), "A".repeat(44)].join(""));
+
+        let der = Basic::ECDSAsecp256r1.derive(PublicKey::new([0; 33].to_vec()));
+        assert_eq!(der.to_str(), ["1AAJ".to_string(), "A".repeat(44)].join(""));
+
+        let der = Basic::MLDSA44.derive(PublicKey::new([0; 1312].to_vec()));
+        assert_eq!(
+            der.to_str(),
+            ["1ABB".to_string(), "A".repeat(1752)].join("")
+        );
+
+        let der = Basic::MLDSA65NT.derive(PublicKey::new([0; 1952].to_vec()));
+        assert_eq!(
+            der.to_str(),
+            ["1ABC".to_string(), "A".repeat(2604)].join("")
+        );
+
+        let der = Basic::MLDSA87.derive(PublicKey::new([0; 2592].to_vec()));
+        assert_eq!(
+            der.to_str(),
+            ["1ABF".to_string(), "A".repeat(3456)].join("")
+        );
     }
 }
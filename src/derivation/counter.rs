@@ -0,0 +1,485 @@
+use super::{
+    ascii, b64_to_u64, genus::GenusVersion, lookup, split_at_len, u64_to_b64, CodeTable,
+    DerivationCode,
+};
+use crate::{
+    codex::{self, b64},
+    error::Error,
+};
+use core::str::FromStr;
+
+/// Count codes from the CESR [count code table].<br>
+/// A count code frames a group of primitives, or of other groups, in an attachment stream.
+/// The count tells how many members, or how many quadlets (4 Base64 characters), the group has.
+///
+/// Variants name groups rather than codes: the CESR 1 and CESR 2 tables spell the groups they
+/// share differently, e.g. controller signatures are `-A` in the first and `-K` in the second,
+/// and CESR 2 counts every group in quadlets. See [CounterCode::code_in].
+///
+/// [count code table]: https://weboftrust.github.io/ietf-cesr/draft-ssmith-cesr.html#name-count-code-tables
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum CounterCode {
+    /// `-A` indexed signatures of the controllers
+    ControllerIdxSigs,
+    /// `-B` indexed signatures of the witnesses
+    WitnessIdxSigs,
+    /// `-C` couples of non-transferable prefix and signature
+    NonTransReceiptCouples,
+    /// `-D` quadruples of transferable prefix, sequence number, event digest and signature
+    TransReceiptQuadruples,
+    /// `-E` couples of first seen sequence number and datetime
+    FirstSeenReplayCouples,
+    /// `-F` groups of transferable prefix, sequence number, event digest and indexed signatures
+    TransIdxSigGroups,
+    /// `-G` couples of sequence number and digest of a delegating or issuing event
+    SealSourceCouples,
+    /// `-H` groups of transferable prefix and indexed signatures of its last establishment event
+    TransLastIdxSigGroups,
+    /// `-I` triples of prefix, sequence number and digest of an anchoring event
+    SealSourceTriples,
+    /// `-J` groups of a path and the signatures over the content at that path
+    SadPathSig,
+    /// `-K` groups of a root path and path signature groups
+    SadPathSigGroup,
+    /// `-L` quadlets of pathed material
+    PathedMaterialQuadlets,
+    /// `-U` groups of message data
+    MessageDataGroups,
+    /// `-V` quadlets of attached material
+    AttachedMaterialQuadlets,
+    /// `-W` quadlets of message data
+    MessageDataMaterialQuadlets,
+    /// `-X` quadlets of message data and attached material
+    CombinedMaterialQuadlets,
+    /// `-Y` groups of generic material
+    MaterialGroups,
+    /// `-Z` quadlets of generic material
+    MaterialQuadlets,
+    /// `-0V` quadlets of attached material, big count
+    BigAttachedMaterialQuadlets,
+    /// `-0W` quadlets of message data, big count
+    BigMessageDataMaterialQuadlets,
+    /// `-0X` quadlets of message data and attached material, big count
+    BigCombinedMaterialQuadlets,
+    /// `-0Y` groups of generic material, big count
+    BigMaterialGroups,
+    /// `-0Z` quadlets of generic material, big count
+    BigMaterialQuadlets,
+    /// `--AAA` version of the KERI/ACDC code tables the rest of the stream is encoded with, see
+    /// [GenusVersion]
+    KERIACDCGenusVersion,
+    /// `-A` groups of generic material, CESR 2
+    GenericGroup,
+    /// `-B` quadlets of a message body with its attachments, CESR 2
+    BodyWithAttachmentGroup,
+    /// `-D` quadlets of a datagram segment, CESR 2
+    DatagramSegmentGroup,
+    /// `-E` quadlets of an encrypt sender sign receiver wrapper, CESR 2
+    ESSRWrapperGroup,
+    /// `-F` quadlets of a message body with fixed fields, CESR 2
+    FixBodyGroup,
+    /// `-G` quadlets of a message body with labeled fields, CESR 2
+    MapBodyGroup,
+    /// `-H` quadlets of a message body in a non-CESR serialization, CESR 2
+    NonNativeBodyGroup,
+    /// `-I` quadlets of a map of labeled material, CESR 2
+    GenericMapGroup,
+    /// `-J` quadlets of a list of material, CESR 2
+    GenericListGroup,
+    /// `-Q` quadlets of digest seals, CESR 2
+    DigestSealSingles,
+    /// `-R` quadlets of merkle tree root digest seals, CESR 2
+    MerkleRootSealSingles,
+    /// `-U` quadlets of prefixes of the last establishment event of a delegator or issuer, CESR 2
+    SealSourceLastSingles,
+    /// `-V` quadlets of backer prefix and digest of its registrar, CESR 2
+    BackerRegistrarSealCouples,
+    /// `-W` quadlets of type and digest of a typed seal, CESR 2
+    TypedDigestSealCouples,
+    /// `-Z` quadlets of an encrypt sender sign receiver payload, CESR 2
+    ESSRPayloadGroup,
+}
+
+impl CounterCode {
+    /// The CESR 2 count code table. Each code is spelled small and big, the big one with a five
+    /// character count.
+    #[rustfmt::skip]
+    pub const CESR_2_CODES: &'static [(&'static str, Self)] = &[
+        ("-A", Self::GenericGroup),
+        ("-B", Self::BodyWithAttachmentGroup),
+        ("-C", Self::AttachedMaterialQuadlets),
+        ("-D", Self::DatagramSegmentGroup),
+        ("-E", Self::ESSRWrapperGroup),
+        ("-F", Self::FixBodyGroup),
+        ("-G", Self::MapBodyGroup),
+        ("-H", Self::NonNativeBodyGroup),
+        ("-I", Self::GenericMapGroup),
+        ("-J", Self::GenericListGroup),
+        ("-K", Self::ControllerIdxSigs),
+        ("-L", Self::WitnessIdxSigs),
+        ("-M", Self::NonTransReceiptCouples),
+        ("-N", Self::TransReceiptQuadruples),
+        ("-O", Self::FirstSeenReplayCouples),
+        ("-P", Self::PathedMaterialQuadlets),
+        ("-Q", Self::DigestSealSingles),
+        ("-R", Self::MerkleRootSealSingles),
+        ("-S", Self::SealSourceTriples),
+        ("-T", Self::SealSourceCouples),
+        ("-U", Self::SealSourceLastSingles),
+        ("-V", Self::BackerRegistrarSealCouples),
+        ("-W", Self::TypedDigestSealCouples),
+        ("-X", Self::TransIdxSigGroups),
+        ("-Y", Self::TransLastIdxSigGroups),
+        ("-Z", Self::ESSRPayloadGroup),
+        ("--A", Self::GenericGroup),
+        ("--B", Self::BodyWithAttachmentGroup),
+        ("--C", Self::AttachedMaterialQuadlets),
+        ("--D", Self::DatagramSegmentGroup),
+        ("--E", Self::ESSRWrapperGroup),
+        ("--F", Self::FixBodyGroup),
+        ("--G", Self::MapBodyGroup),
+        ("--H", Self::NonNativeBodyGroup),
+        ("--I", Self::GenericMapGroup),
+        ("--J", Self::GenericListGroup),
+        ("--K", Self::ControllerIdxSigs),
+        ("--L", Self::WitnessIdxSigs),
+        ("--M", Self::NonTransReceiptCouples),
+        ("--N", Self::TransReceiptQuadruples),
+        ("--O", Self::FirstSeenReplayCouples),
+        ("--P", Self::PathedMaterialQuadlets),
+        ("--Q", Self::DigestSealSingles),
+        ("--R", Self::MerkleRootSealSingles),
+        ("--S", Self::SealSourceTriples),
+        ("--T", Self::SealSourceCouples),
+        ("--U", Self::SealSourceLastSingles),
+        ("--V", Self::BackerRegistrarSealCouples),
+        ("--W", Self::TypedDigestSealCouples),
+        ("--X", Self::TransIdxSigGroups),
+        ("--Y", Self::TransLastIdxSigGroups),
+        ("--Z", Self::ESSRPayloadGroup),
+        ("-_AAA", Self::KERIACDCGenusVersion),
+    ];
+
+    /// The count code table of `genus_version`.
+    pub fn table(genus_version: &GenusVersion) -> &'static [(&'static str, Self)] {
+        match genus_version.version.major {
+            1 => Self::CODES,
+            _ => Self::CESR_2_CODES,
+        }
+    }
+
+    /// The fixed part of the code in the table of `genus_version`, the small one when there are
+    /// two, `None` when the group is not in that table.
+    pub fn code_in(&self, genus_version: &GenusVersion) -> Option<&'static str> {
+        Self::table(genus_version)
+            .iter()
+            .find(|(_, code)| code == self)
+            .map(|(code, _)| *code)
+    }
+
+    /// Number of characters of the fixed part of the code.
+    pub fn hard_size(&self) -> usize {
+        codex::counter(self.code()).hs
+    }
+
+    /// Number of characters of the count.
+    pub fn soft_size(&self) -> usize {
+        codex::counter(self.code()).ss
+    }
+
+    /// Largest count that fits in the soft part of the code.
+    pub fn max_count(&self) -> u32 {
+        ((1u64 << (6 * self.soft_size())) - 1) as u32
+    }
+
+    /// The fixed part of the code.
+    pub fn to_str(&self) -> String {
+        self.code().into()
+    }
+}
+
+/// The CESR 1 count code table.
+impl CodeTable for CounterCode {
+    const CODES: &'static [(&'static str, Self)] = &[
+        ("-A", Self::ControllerIdxSigs),
+        ("-B", Self::WitnessIdxSigs),
+        ("-C", Self::NonTransReceiptCouples),
+        ("-D", Self::TransReceiptQuadruples),
+        ("-E", Self::FirstSeenReplayCouples),
+        ("-F", Self::TransIdxSigGroups),
+        ("-G", Self::SealSourceCouples),
+        ("-H", Self::TransLastIdxSigGroups),
+        ("-I", Self::SealSourceTriples),
+        ("-J", Self::SadPathSig),
+        ("-K", Self::SadPathSigGroup),
+        ("-L", Self::PathedMaterialQuadlets),
+        ("-U", Self::MessageDataGroups),
+        ("-V", Self::AttachedMaterialQuadlets),
+        ("-W", Self::MessageDataMaterialQuadlets),
+        ("-X", Self::CombinedMaterialQuadlets),
+        ("-Y", Self::MaterialGroups),
+        ("-Z", Self::MaterialQuadlets),
+        ("-0V", Self::BigAttachedMaterialQuadlets),
+        ("-0W", Self::BigMessageDataMaterialQuadlets),
+        ("-0X", Self::BigCombinedMaterialQuadlets),
+        ("-0Y", Self::BigMaterialGroups),
+        ("-0Z", Self::BigMaterialQuadlets),
+        ("--AAA", Self::KERIACDCGenusVersion),
+    ];
+
+    /// The code in the CESR 1 table, or in the CESR 2 one for groups only there.
+    fn code(&self) -> &'static str {
+        self.code_in(&GenusVersion::KERIACDC_1)
+            .or_else(|| self.code_in(&GenusVersion::KERIACDC_2))
+            .expect("every count code in a table")
+    }
+}
+
+/// Parses the fixed part of a count code from the start of `s`.
+impl FromStr for CounterCode {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::lookup(s)
+    }
+}
+
+/// A count code together with its count, e.g. `-AAB` for one indexed controller signature.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct Counter {
+    pub code: CounterCode,
+    pub count: u32,
+}
+
+impl Counter {
+    pub fn new(code: CounterCode, count: u32) -> Result<Self, Error> {
+        if count > code.max_count() {
+            return Err(Error::SemanticError(format!(
+                "Count {} too big for count code {}",
+                count,
+                code.to_str()
+            )));
+        }
+        Ok(Self { code, count })
+    }
+
+    /// Binary domain (qb2) representation of the counter.
+    pub fn to_qb2(&self) -> Vec<u8> {
+        b64::decode(self.to_str()).expect("counters are Base64 aligned on 24 bit boundary")
+    }
+
+    /// Text domain (qb64) representation of the counter as bytes.
+    pub fn to_qb64b(&self) -> Vec<u8> {
+        self.to_str().into_bytes()
+    }
+
+    /// Parses a counter from the start of a text domain (qb64) stream held in a byte buffer.
+    pub fn from_qb64b(qb64b: &[u8]) -> Result<Self, Error> {
+        // no count code is longer than eight characters, e.g. `-0VAAAAA`
+        Self::from_str(ascii(&qb64b[..qb64b.len().min(8)]))
+    }
+
+    /// Parses a counter from the start of a binary domain (qb2) stream.
+    pub fn from_qb2(qb2: &[u8]) -> Result<Self, Error> {
+        // six bytes hold the longest count codes, e.g. genus version codes
+        let head = b64::encode(&qb2[..qb2.len().min(6)]);
+        let code = CounterCode::from_str(&head)?;
+        let size = Counter { code, count: 0 }.prefix_b2_len();
+        let counter = qb2.get(..size).ok_or_else(|| Error::ShortageError {
+            needed: size - qb2.len(),
+        })?;
+        Self::from_str(&b64::encode(counter))
+    }
+}
+
+impl Counter {
+    /// Text domain (qb64) representation of the counter in the table of `genus_version`, with
+    /// the big code of the group when the count does not fit the small one. Fails when the group
+    /// is not in the table or the count fits neither code.
+    pub fn to_str_in(&self, genus_version: &GenusVersion) -> Result<String, Error> {
+        CounterCode::table(genus_version)
+            .iter()
+            .filter(|(_, code)| *code == self.code)
+            .map(|(code, _)| (*code, codex::counter(code).ss))
+            .find(|(_, ss)| (self.count as u64) < 1 << (6 * ss))
+            .map(|(code, ss)| [code.into(), u64_to_b64(self.count as u64, ss)].join(""))
+            .ok_or_else(|| {
+                Error::SemanticError(format!(
+                    "No count code for {} {:?} in the tables of genus version {}",
+                    self.count,
+                    self.code,
+                    genus_version.to_str()
+                ))
+            })
+    }
+
+    /// Parses a counter from the start of a text domain (qb64) stream encoded with the table of
+    /// `genus_version`, returning it with the rest of `s`.
+    pub fn from_stream_in<'a>(
+        s: &'a str,
+        genus_version: &GenusVersion,
+    ) -> Result<(Self, &'a str), Error> {
+        let (code, group) = lookup(CounterCode::table(genus_version), s)?;
+        let sizage = codex::counter(code);
+        let (counter, rest) = split_at_len(s, sizage.cs())?;
+        let count = b64_to_u64(&counter[sizage.hs..])? as u32;
+        Ok((
+            Self {
+                code: *group,
+                count,
+            },
+            rest,
+        ))
+    }
+}
+
+impl DerivationCode for Counter {
+    fn code_len(&self) -> usize {
+        self.code.hard_size() + self.code.soft_size()
+    }
+
+    fn derivative_b64_len(&self) -> usize {
+        0
+    }
+
+    fn hard_size(&self) -> usize {
+        self.code.hard_size()
+    }
+
+    fn soft_size(&self) -> usize {
+        self.code.soft_size()
+    }
+
+    fn to_str(&self) -> String {
+        [
+            self.code.to_str(),
+            u64_to_b64(self.count as u64, self.code.soft_size()),
+        ]
+        .join("")
+    }
+}
+
+/// Parses a counter from the start of a text domain (qb64) stream.
+impl FromStr for Counter {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let code = CounterCode::from_str(s)?;
+        let count = &split_at_len(s, code.hard_size() + code.soft_size())?.0[code.hard_size()..];
+        Ok(Self {
+            code,
+            count: b64_to_u64(count)? as u32,
+        })
+    }
+}
+
+#[cfg(test)]
+mod counter_tests {
+    use super::*;
+
+    #[test]
+    fn test_to_str() -> Result<(), Error> {
+        let counter = Counter::new(CounterCode::ControllerIdxSigs, 1)?;
+        assert_eq!(counter.to_str(), "-AAB");
+        assert_eq!(counter.prefix_b64_len(), 4);
+
+        let counter = Counter::new(CounterCode::NonTransReceiptCouples, 4095)?;
+        assert_eq!(counter.to_str(), "-C__");
+
+        let counter = Counter::new(CounterCode::BigAttachedMaterialQuadlets, 1)?;
+        assert_eq!(counter.to_str(), "-0VAAAAB");
+        assert_eq!(counter.prefix_b64_len(), 8);
+
+        assert!(Counter::new(CounterCode::WitnessIdxSigs, 4096).is_err());
+        assert!(Counter::new(CounterCode::BigMaterialGroups, 4096).is_ok());
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_str() -> Result<(), Error> {
+        assert_eq!(
+            Counter::from_str("-AAB")?,
+            Counter::new(CounterCode::ControllerIdxSigs, 1)?
+        );
+        assert_eq!(
+            Counter::from_str("-FBAAAAA")?,
+            Counter::new(CounterCode::TransIdxSigGroups, 64)?
+        );
+        assert_eq!(
+            Counter::from_str("-0VAAABA")?,
+            Counter::new(CounterCode::BigAttachedMaterialQuadlets, 64)?
+        );
+
+        assert!(Counter::from_str("").is_err());
+        assert!(Counter::from_str("AAAB").is_err());
+        assert!(Counter::from_str("-AA").is_err());
+        assert!(Counter::from_str("-0").is_err());
+        assert!(Counter::from_str("-0AAAAAA").is_err());
+        assert!(Counter::from_str("-MAA").is_err());
+        assert!(Counter::from_str("-A=A").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_qb2() -> Result<(), Error> {
+        let counter = Counter::new(CounterCode::ControllerIdxSigs, 1)?;
+        assert_eq!(counter.to_qb2(), vec![0xf8, 0x00, 0x01]);
+        assert_eq!(Counter::from_qb2(&[0xf8, 0x00, 0x01, 0xff])?, counter);
+
+        let counter = Counter::new(CounterCode::BigAttachedMaterialQuadlets, 1000)?;
+        let qb2 = counter.to_qb2();
+        assert_eq!(qb2.len(), 6);
+        assert_eq!(Counter::from_qb2(&qb2)?, counter);
+        assert!(Counter::from_qb2(&qb2[..3]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_qb64b() -> Result<(), Error> {
+        let counter = Counter::new(CounterCode::ControllerIdxSigs, 1)?;
+        assert_eq!(counter.to_qb64b(), b"-AAB");
+        // the rest of the stream need not be text
+        assert_eq!(Counter::from_qb64b(b"-AAB\xa2\x01")?, counter);
+
+        let counter = Counter::new(CounterCode::BigAttachedMaterialQuadlets, 1000)?;
+        assert_eq!(Counter::from_qb64b(&counter.to_qb64b())?, counter);
+        assert!(Counter::from_qb64b(b"-0VAA").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_tables() -> Result<(), Error> {
+        let (v1, v2) = (GenusVersion::KERIACDC_1, GenusVersion::KERIACDC_2);
+        let counter = Counter::new(CounterCode::ControllerIdxSigs, 1)?;
+        assert_eq!(counter.to_str_in(&v1)?, "-AAB");
+        assert_eq!(counter.to_str_in(&v2)?, "-KAB");
+        assert_eq!(Counter::from_stream_in("-KAB", &v2)?, (counter, ""));
+        // `-A` means something else in each table
+        assert_eq!(
+            Counter::from_stream_in("-AAB", &v2)?.0,
+            Counter::new(CounterCode::GenericGroup, 1)?
+        );
+
+        // big codes for counts too big for the small ones
+        let big = Counter {
+            code: CounterCode::WitnessIdxSigs,
+            count: 4096,
+        };
+        assert_eq!(big.to_str_in(&v2)?, "--LAABAA");
+        assert_eq!(Counter::from_stream_in("--LAABAA-AAB", &v2)?, (big, "-AAB"));
+        assert!(big.to_str_in(&v1).is_err());
+
+        // groups only in one of the tables
+        let counter = Counter::new(CounterCode::AttachedMaterialQuadlets, 2)?;
+        assert_eq!(counter.to_str_in(&v2)?, "-CAC");
+        assert!(Counter::new(CounterCode::SadPathSig, 1)?
+            .to_str_in(&v2)
+            .is_err());
+        assert!(Counter::new(CounterCode::DigestSealSingles, 1)?
+            .to_str_in(&v1)
+            .is_err());
+        assert_eq!(CounterCode::DigestSealSingles.to_str(), "-Q");
+        assert!(Counter::from_stream_in("-0VAAAAB", &v2).is_err());
+        Ok(())
+    }
+}
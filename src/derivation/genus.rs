@@ -0,0 +1,183 @@
+use super::{b64_to_u64, counter::CounterCode, split_at_len, u64_to_b64};
+use crate::{codex::b64, error::Error, version::Version};
+use core::str::FromStr;
+
+/// Length of a genus version code: selector, genus and version, e.g. `--AAABAA`.
+pub const GENUS_VERSION_LEN: usize = 8;
+
+/// Families of code tables, each versioned on its own.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum Genus {
+    /// `AAA` the code tables of KERI and ACDC
+    KERIACDC,
+}
+
+impl Genus {
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            Self::KERIACDC => "AAA",
+        }
+    }
+}
+
+impl FromStr for Genus {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "AAA" => Ok(Self::KERIACDC),
+            _ => Err(Error::DeserializeError(format!("Unknown genus: {}", s))),
+        }
+    }
+}
+
+/// A genus version code, e.g. `--AAABAA`, which declares the genus and version of the code tables
+/// the rest of a stream is encoded with. Streams that declare none use the KERI/ACDC 1.0 tables.
+///
+/// The code is spelled `--AAA` in the CESR 1 count code table and `-_AAA` in the CESR 2 one,
+/// where `--` starts the big count codes.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct GenusVersion {
+    pub genus: Genus,
+    pub version: Version,
+}
+
+impl GenusVersion {
+    pub const KERIACDC_1: Self = Self {
+        genus: Genus::KERIACDC,
+        version: Version { major: 1, minor: 0 },
+    };
+    pub const KERIACDC_2: Self = Self {
+        genus: Genus::KERIACDC,
+        version: Version { major: 2, minor: 0 },
+    };
+
+    pub fn new(genus: Genus, version: Version) -> Result<Self, Error> {
+        if !(1..=2).contains(&version.major) || version.minor >= 64 * 64 {
+            return Err(Error::DeserializeError(format!(
+                "Unsupported genus version: {}.{}",
+                version.major, version.minor
+            )));
+        }
+        Ok(Self { genus, version })
+    }
+
+    /// Whether the group of `code` is in the count code table of this version.
+    pub fn supports(&self, code: CounterCode) -> bool {
+        code.code_in(self).is_some()
+    }
+
+    /// Whether groups are counted in quadlets rather than in members, as in the CESR 2 tables.
+    pub fn counts_quadlets(&self) -> bool {
+        self.version.major >= 2
+    }
+
+    /// The genus version code, spelled for the tables of the version it declares.
+    pub fn to_str(&self) -> String {
+        self.to_str_in(self)
+    }
+
+    /// The genus version code, spelled for the tables of `active`, e.g. to switch a stream back
+    /// from CESR 2 to CESR 1 tables.
+    pub fn to_str_in(&self, active: &Self) -> String {
+        let selector = match active.version.major {
+            1 => "--",
+            _ => "-_",
+        };
+        [
+            selector,
+            self.genus.to_str(),
+            &u64_to_b64(self.version.major as u64, 1),
+            &u64_to_b64(self.version.minor as u64, 2),
+        ]
+        .join("")
+    }
+
+    /// Parses the genus version code at the start of `s` when there is one, spelled for the
+    /// tables of `active`. Returns it with the rest of `s`.
+    pub fn from_stream_in<'a>(s: &'a str, active: &Self) -> Result<Option<(Self, &'a str)>, Error> {
+        let declares = match active.version.major {
+            1 => s.starts_with("--") || s.starts_with("-_"),
+            _ => s.starts_with("-_"),
+        };
+        if !declares {
+            return Ok(None);
+        }
+        let (code, rest) = split_at_len(s, GENUS_VERSION_LEN)?;
+        // the code is sliced by bytes below, multi-byte characters must not get that far
+        b64::validate(code.as_bytes())?;
+        let version = Version::new(
+            b64_to_u64(&code[5..6])? as u8,
+            b64_to_u64(&code[6..])? as u16,
+        );
+        Ok(Some((
+            Self::new(Genus::from_str(&code[2..5])?, version)?,
+            rest,
+        )))
+    }
+}
+
+impl Default for GenusVersion {
+    fn default() -> Self {
+        Self::KERIACDC_1
+    }
+}
+
+/// Parses a genus version code in either spelling, `s` must hold nothing else.
+impl FromStr for GenusVersion {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match Self::from_stream_in(s, &Self::KERIACDC_1)? {
+            Some((genus_version, "")) => Ok(genus_version),
+            _ => Err(Error::DeserializeError(format!(
+                "Invalid genus version code: {}",
+                s
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::attachments::Attachments;
+
+    #[test]
+    fn genus_version() -> Result<(), Error> {
+        assert_eq!(GenusVersion::KERIACDC_1.to_str(), "--AAABAA");
+        assert_eq!(GenusVersion::KERIACDC_2.to_str(), "-_AAACAA");
+        let back = GenusVersion::KERIACDC_1.to_str_in(&GenusVersion::KERIACDC_2);
+        assert_eq!(back, "-_AAABAA");
+        assert_eq!(
+            GenusVersion::from_str("--AAABAA")?,
+            GenusVersion::KERIACDC_1
+        );
+        assert_eq!(
+            GenusVersion::from_str("-_AAACAA")?,
+            GenusVersion::KERIACDC_2
+        );
+        let minor = GenusVersion::new(Genus::KERIACDC, Version::new(1, 65))?;
+        assert_eq!(GenusVersion::from_str(&minor.to_str())?, minor);
+
+        // `--` starts big count codes in the CESR 2 tables
+        let v2 = GenusVersion::KERIACDC_2;
+        assert_eq!(GenusVersion::from_stream_in("--AAABAA", &v2)?, None);
+        assert_eq!(
+            GenusVersion::from_stream_in("-_AAABAA-AAB", &v2)?,
+            Some((GenusVersion::KERIACDC_1, "-AAB"))
+        );
+        assert_eq!(GenusVersion::from_stream_in("-AAB", &v2)?, None);
+
+        for code in ["--AAADAA", "--AAAAAA", "--ABABAA", "--AAAB", "-_AAAB=A"] {
+            assert!(GenusVersion::from_str(code).is_err(), "{}", code);
+        }
+
+        // eight bytes ending on a character boundary, with a multi-byte character inside
+        let stream = "--78néfCAda2DéYedk632XqNLéc14ItCk3nF6";
+        assert!(GenusVersion::from_str(stream).is_err());
+        assert!(Attachments::from_str(stream).is_err());
+        assert!(Attachments::from_stream(stream).is_err());
+        Ok(())
+    }
+}
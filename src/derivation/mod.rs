@@ -1,7 +1,18 @@
+use crate::{
+    codex::{b64, Sizage},
+    error::Error,
+};
+use core::mem::discriminant;
+
 pub mod attached_signature_code;
 pub mod basic;
+pub mod counter;
+pub mod genus;
+pub mod number;
 pub mod self_addressing;
 pub mod self_signing;
+pub mod tag;
+pub mod variable;
 
 /// Derivation codes are the type system of CESR supported data types. Each code corresponds to one and exactly one
 /// entry in the CESR [master code table].<br>
@@ -12,8 +23,246 @@ pub mod self_signing;
 pub trait DerivationCode {
     fn code_len(&self) -> usize;
     fn derivative_b64_len(&self) -> usize;
+    /// Number of zero bytes prepended to the derivative to align it on a 24 bit boundary.
+    fn lead_size(&self) -> usize {
+        0
+    }
+    /// Length in bytes of the raw derivative, without its lead bytes.
+    fn derivative_len(&self) -> usize {
+        self.derivative_b64_len() * 3 / 4 - self.lead_size()
+    }
     fn prefix_b64_len(&self) -> usize {
         self.code_len() + self.derivative_b64_len()
     }
+    /// Length of the prefix in the binary domain (qb2), three bytes for every four Base64 characters.
+    fn prefix_b2_len(&self) -> usize {
+        self.prefix_b64_len() * 3 / 4
+    }
     fn to_str(&self) -> String;
+    /// Hard size, the characters of the code fixed by the code table.
+    fn hard_size(&self) -> usize {
+        self.code_len()
+    }
+    /// Soft size, the characters of the code carrying a size, count, index or label.
+    fn soft_size(&self) -> usize {
+        0
+    }
+    /// Number of lead bytes, the `ls` of the code table, see [DerivationCode::lead_size].
+    fn lead_bytes(&self) -> usize {
+        self.lead_size()
+    }
+    /// Full size of the primitive in Base64 characters, `None` for variable size codes whose
+    /// size is in their soft part.
+    fn full_size(&self) -> Option<usize> {
+        Some(self.prefix_b64_len())
+    }
+    /// The sizes of the code as the code table lists them.
+    fn sizage(&self) -> Sizage {
+        Sizage {
+            hs: self.hard_size(),
+            ss: self.soft_size(),
+            fs: self.full_size(),
+            ls: self.lead_bytes(),
+        }
+    }
+}
+
+/// Table of the text domain codes of one kind of primitive, e.g. the basic derivation codes.
+///
+/// Lookups only compare bytes and never slice into the input, so short, malformed or non-ASCII
+/// input fails with an error instead of a panic.
+pub trait CodeTable: Clone + 'static {
+    const CODES: &'static [(&'static str, Self)];
+
+    /// Looks up the code at the start of `s`.
+    fn lookup(s: &str) -> Result<Self, Error> {
+        lookup(Self::CODES, s).map(|(_, value)| value.clone())
+    }
+
+    /// The code of `self`, variants carrying data match their entry whatever the data.
+    fn code(&self) -> &'static str {
+        Self::CODES
+            .iter()
+            .find(|(_, value)| discriminant(value) == discriminant(self))
+            .map(|(code, _)| *code)
+            .expect("every variant in the code table")
+    }
+}
+
+/// Finds the entry of `table` whose code starts `s`. Fails with [Error::ShortageError] when `s`
+/// is cut off in the middle of a code and with [Error::UnknownCode] otherwise.
+pub fn lookup<'t, T>(table: &'t [(&'t str, T)], s: &str) -> Result<&'t (&'t str, T), Error> {
+    let mut needed: Option<usize> = None;
+    for entry in table {
+        let code = entry.0;
+        if s.as_bytes().starts_with(code.as_bytes()) {
+            return Ok(entry);
+        }
+        if code.as_bytes().starts_with(s.as_bytes()) {
+            let n = code.len() - s.len();
+            needed = Some(needed.map_or(n, |m| m.min(n)));
+        }
+    }
+    match needed {
+        Some(needed) => Err(Error::ShortageError { needed }),
+        None => {
+            let longest = table.iter().map(|(code, _)| code.len()).max().unwrap_or(0);
+            let code: String = s.chars().take(longest).collect();
+            // standard Base64 and padding are named rather than taken for unknown codes
+            match b64::validate(code.as_bytes()) {
+                Err(e @ (Error::StandardBase64 { .. } | Error::Base64Padding { .. })) => Err(e),
+                _ => Err(Error::UnknownCode { code }),
+            }
+        }
+    }
+}
+
+/// Splits the first `len` characters off `s`, failing instead of panicking when `s` is too short
+/// or `len` falls inside a multi-byte character.
+pub(crate) fn split_at_len(s: &str, len: usize) -> Result<(&str, &str), Error> {
+    if s.len() < len {
+        return Err(Error::ShortageError {
+            needed: len - s.len(),
+        });
+    }
+    match (s.get(..len), s.get(len..)) {
+        (Some(head), Some(tail)) => Ok((head, tail)),
+        _ => Err(Error::InvalidBase64 {
+            offset: s.bytes().position(|b| !b.is_ascii()).unwrap_or(0),
+        }),
+    }
+}
+
+/// The leading ASCII characters of `b`, the only ones a text domain (qb64) stream may hold.
+/// Checking for ASCII is all the validation UTF-8 text needs.
+pub(crate) fn ascii(b: &[u8]) -> &str {
+    let len = b.iter().position(|b| !b.is_ascii()).unwrap_or(b.len());
+    core::str::from_utf8(&b[..len]).expect("ASCII is UTF-8")
+}
+
+/// The URL-safe Base64 alphabet, indexed by sextet value.
+const B64_ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// Encodes `num` as exactly `len` Base64 characters, most significant sextet first.
+/// Sextets above `len` are dropped so callers must check `num` fits in `len` characters.
+pub fn u64_to_b64(num: u64, len: usize) -> String {
+    (0..len)
+        .rev()
+        .map(|i| {
+            let sextet = num.checked_shr(6 * i as u32).unwrap_or(0) & 0x3f;
+            B64_ALPHABET[sextet as usize] as char
+        })
+        .collect()
+}
+
+/// Decodes Base64 characters into an integer, most significant sextet first.
+pub fn b64_to_u64(b64: &str) -> Result<u64, Error> {
+    if b64.len() > 10 {
+        return Err(Error::SemanticError(format!(
+            "Base64 number too big for u64: {}",
+            b64
+        )));
+    }
+    b64.bytes().enumerate().try_fold(0u64, |acc, (i, c)| {
+        let sextet = B64_ALPHABET
+            .iter()
+            .position(|a| *a == c)
+            .ok_or(Error::InvalidBase64 { offset: i })?;
+        Ok((acc << 6) | sextet as u64)
+    })
+}
+
+#[test]
+fn code_lookup() {
+    const TABLE: &[(&str, u8)] = &[("A", 1), ("0B", 2), ("1AAC", 3)];
+    assert_eq!(lookup(TABLE, "AXYZ").unwrap(), &("A", 1));
+    assert_eq!(lookup(TABLE, "0B").unwrap(), &("0B", 2));
+    assert_eq!(lookup(TABLE, "1AACXYZ").unwrap(), &("1AAC", 3));
+    assert!(matches!(
+        lookup(TABLE, ""),
+        Err(Error::ShortageError { needed: 1 })
+    ));
+    assert!(matches!(
+        lookup(TABLE, "1A"),
+        Err(Error::ShortageError { needed: 2 })
+    ));
+    assert!(matches!(
+        lookup(TABLE, "1ABCXYZ"),
+        Err(Error::UnknownCode { code }) if code == "1ABC"
+    ));
+    // multi-byte characters are compared, never sliced into
+    assert!(matches!(
+        lookup(TABLE, "0é"),
+        Err(Error::UnknownCode { .. })
+    ));
+    assert!(matches!(lookup(TABLE, "é"), Err(Error::UnknownCode { .. })));
+
+    assert_eq!(split_at_len("ABCD", 2).unwrap(), ("AB", "CD"));
+    assert!(matches!(
+        split_at_len("AB", 4),
+        Err(Error::ShortageError { needed: 2 })
+    ));
+    assert!(matches!(
+        split_at_len("Aé", 2),
+        Err(Error::InvalidBase64 { offset: 1 })
+    ));
+}
+
+#[test]
+fn b64_number_conversions() {
+    assert_eq!(u64_to_b64(0, 2), "AA");
+    assert_eq!(u64_to_b64(1, 2), "AB");
+    assert_eq!(u64_to_b64(63, 1), "_");
+    assert_eq!(u64_to_b64(64, 2), "BA");
+    assert_eq!(u64_to_b64(4095, 2), "__");
+    assert_eq!(u64_to_b64(4096, 5), "AABAA");
+
+    assert_eq!(b64_to_u64("AB").unwrap(), 1);
+    assert_eq!(b64_to_u64("BA").unwrap(), 64);
+    assert_eq!(b64_to_u64("__").unwrap(), 4095);
+    assert_eq!(b64_to_u64("").unwrap(), 0);
+    assert!(b64_to_u64("A=").is_err());
+    assert!(b64_to_u64("AAAAAAAAAAA").is_err());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codex;
+    use attached_signature_code::AttachedSignatureCode;
+    use counter::{Counter, CounterCode};
+    use variable::{VariableCode, VariableKind};
+
+    #[test]
+    fn sizes() -> Result<(), Error> {
+        fn table<C: CodeTable + DerivationCode>() {
+            for (code, value) in C::CODES {
+                assert_eq!(value.sizage(), codex::matter(code), "{}", code);
+            }
+        }
+        table::<basic::Basic>();
+        table::<self_signing::SelfSigning>();
+        table::<self_addressing::SelfAddressing>();
+        table::<number::NumberCode>();
+
+        for len in 1..=tag::TagCode::MAX_LEN {
+            let tag = tag::TagCode::new(len)?;
+            assert_eq!(tag.sizage(), codex::matter(&tag.to_str()), "{}", len);
+        }
+        for len in [0, 1, 2, 3 * 4096] {
+            let code = VariableCode::new(VariableKind::Bytes, len)?;
+            let hard = &code.to_str()[..code.hard_size()];
+            assert_eq!(code.sizage(), codex::matter(hard), "{}", hard);
+        }
+        for (code, value) in CounterCode::CODES {
+            let counter = Counter::new(*value, 0)?;
+            assert_eq!(counter.sizage(), codex::counter(code), "{}", code);
+        }
+        let siger = AttachedSignatureCode::new(self_signing::SelfSigning::Ed448, 1)?;
+        assert_eq!(
+            (siger.hard_size(), siger.soft_size(), siger.full_size()),
+            (2, 2, Some(156))
+        );
+        Ok(())
+    }
 }
@@ -0,0 +1,137 @@
+use super::{CodeTable, DerivationCode};
+use crate::error::Error;
+use core::str::FromStr;
+
+/// Number codes from the CESR [master code table], fixed size codes for unsigned integers of
+/// increasing width, e.g. for sequence numbers and first seen ordinals.
+///
+/// [master code table]: https://weboftrust.github.io/ietf-cesr/draft-ssmith-cesr.html#name-master-code-table
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, PartialOrd, Ord)]
+pub enum NumberCode {
+    /// `M` 2 bytes
+    Short,
+    /// `0H` 4 bytes
+    Long,
+    /// `R` 5 bytes
+    Tall,
+    /// `N` 8 bytes
+    Big,
+    /// `S` 11 bytes
+    Large,
+    /// `T` 14 bytes
+    Great,
+    /// `0A` 16 bytes
+    Huge,
+    /// `U` 17 bytes
+    Vast,
+}
+
+impl NumberCode {
+    /// All codes, smallest first.
+    pub const ALL: [NumberCode; 8] = [
+        Self::Short,
+        Self::Long,
+        Self::Tall,
+        Self::Big,
+        Self::Large,
+        Self::Great,
+        Self::Huge,
+        Self::Vast,
+    ];
+
+    /// Number of bytes of the number.
+    pub fn raw_len(&self) -> usize {
+        match self {
+            Self::Short => 2,
+            Self::Long => 4,
+            Self::Tall => 5,
+            Self::Big => 8,
+            Self::Large => 11,
+            Self::Great => 14,
+            Self::Huge => 16,
+            Self::Vast => 17,
+        }
+    }
+
+    /// Smallest code that fits `num`.
+    pub fn for_num(num: u128) -> Self {
+        let len = 16 - num.leading_zeros() as usize / 8;
+        *Self::ALL
+            .iter()
+            .find(|code| code.raw_len() >= len)
+            .expect("the huge code fits every u128")
+    }
+}
+
+impl DerivationCode for NumberCode {
+    fn code_len(&self) -> usize {
+        match self {
+            Self::Long | Self::Huge => 2,
+            _ => 1,
+        }
+    }
+
+    fn derivative_b64_len(&self) -> usize {
+        (self.raw_len() * 4).div_ceil(3)
+    }
+
+    fn to_str(&self) -> String {
+        match self {
+            Self::Short => "M",
+            Self::Long => "0H",
+            Self::Tall => "R",
+            Self::Big => "N",
+            Self::Large => "S",
+            Self::Great => "T",
+            Self::Huge => "0A",
+            Self::Vast => "U",
+        }
+        .into()
+    }
+}
+
+impl CodeTable for NumberCode {
+    const CODES: &'static [(&'static str, Self)] = &[
+        ("M", Self::Short),
+        ("0H", Self::Long),
+        ("R", Self::Tall),
+        ("N", Self::Big),
+        ("S", Self::Large),
+        ("T", Self::Great),
+        ("0A", Self::Huge),
+        ("U", Self::Vast),
+    ];
+}
+
+impl FromStr for NumberCode {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::lookup(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn codes() -> Result<(), Error> {
+        for code in NumberCode::ALL {
+            assert!(code.prefix_b64_len().is_multiple_of(4));
+            assert_eq!(NumberCode::from_str(&code.to_str())?, code);
+        }
+        assert_eq!(NumberCode::Short.prefix_b64_len(), 4);
+        assert_eq!(NumberCode::Huge.prefix_b64_len(), 24);
+
+        assert_eq!(NumberCode::for_num(0), NumberCode::Short);
+        assert_eq!(NumberCode::for_num(0xffff), NumberCode::Short);
+        assert_eq!(NumberCode::for_num(0x1_0000), NumberCode::Long);
+        assert_eq!(NumberCode::for_num(u64::MAX as u128), NumberCode::Big);
+        assert_eq!(NumberCode::for_num(u128::MAX), NumberCode::Huge);
+
+        assert!(NumberCode::from_str("0B").is_err());
+        assert!(NumberCode::from_str("").is_err());
+        Ok(())
+    }
+}
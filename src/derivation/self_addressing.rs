@@ -1,7 +1,9 @@
-use super::DerivationCode;
-use crate::{error::Error, prefix::SelfAddressingPrefix};
-use blake2::{Blake2b, Digest, VarBlake2b, VarBlake2s};
-use blake3;
+use super::{CodeTable, DerivationCode};
+use crate::{codex, error::Error, prefix::SelfAddressingPrefix};
+use blake2::{
+    digest::{Update, VariableOutput},
+    Digest, VarBlake2b, VarBlake2s,
+};
 use core::str::FromStr;
 use sha2::{Sha256, Sha512};
 use sha3::{Sha3_256, Sha3_512};
@@ -14,12 +16,13 @@ use sha3::{Sha3_256, Sha3_512};
 /// Self Addressing Derivations
 ///
 /// Self-addressing is a digest/hash of some inception data (2.3.2)
-///   Delegated Self-addressing uses the "dip" event data for the inception data (2.3.4)
-#[derive(Debug, PartialEq, Clone, Hash)]
+///   Delegated Self-addressing uses the "dip" event data for the inception data (2.3.4), see
+///   [InceptionBuilder::delegator](crate::message::InceptionBuilder::delegator)
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
 pub enum SelfAddressing {
     Blake3_256,
-    Blake2B256(Vec<u8>),
-    Blake2S256(Vec<u8>),
+    Blake2B256,
+    Blake2S256,
     SHA3_256,
     SHA2_256,
     Blake3_512,
@@ -30,176 +33,412 @@ pub enum SelfAddressing {
 
 impl SelfAddressing {
     pub fn digest(&self, data: &[u8]) -> Vec<u8> {
-        match self {
-            Self::Blake3_256 => blake3_256_digest(data),
-            Self::Blake2B256(key) => blake2b_256_digest(data, key),
-            Self::Blake2S256(key) => blake2s_256_digest(data, key),
-            Self::SHA3_256 => sha3_256_digest(data),
-            Self::SHA2_256 => sha2_256_digest(data),
-            Self::Blake3_512 => blake3_512_digest(data),
-            Self::SHA3_512 => sha3_512_digest(data),
-            Self::Blake2B512 => blake2b_512_digest(data),
-            Self::SHA2_512 => sha2_512_digest(data),
-        }
+        let mut hasher = self.hasher();
+        hasher.update(data);
+        hasher.finalize()
+    }
+
+    /// Incremental hasher for digesting data in chunks.
+    pub fn hasher(&self) -> Hasher {
+        Hasher::new(*self, &[])
     }
 
     pub fn derive(&self, data: &[u8]) -> SelfAddressingPrefix {
-        SelfAddressingPrefix::new(self.to_owned(), self.digest(data))
+        SelfAddressingPrefix::new_unchecked(*self, self.digest(data))
     }
-}
 
-impl DerivationCode for SelfAddressing {
-    fn code_len(&self) -> usize {
+    /// Security strength in bits, the collision resistance of the digest: half its size.
+    pub fn strength(&self) -> usize {
         match self {
-            Self::Blake3_256
-            | Self::Blake2B256(_)
-            | Self::Blake2S256(_)
-            | Self::SHA3_256
-            | Self::SHA2_256 => 1,
-            Self::Blake3_512 | Self::SHA3_512 | Self::Blake2B512 | Self::SHA2_512 => 2,
+            Self::Blake3_512 | Self::SHA3_512 | Self::Blake2B512 | Self::SHA2_512 => 256,
+            _ => 128,
         }
     }
 
-    fn derivative_b64_len(&self) -> usize {
-        match self {
-            Self::Blake3_256
-            | Self::Blake2B256(_)
-            | Self::Blake2S256(_)
-            | Self::SHA3_256
-            | Self::SHA2_256 => 43,
-            Self::Blake3_512 | Self::SHA3_512 | Self::Blake2B512 | Self::SHA2_512 => 86,
+    /// Digests every payload, spread over the rayon thread pool. The prefixes are in the order of
+    /// the payloads.
+    #[cfg(feature = "rayon")]
+    pub fn par_derive<P: AsRef<[u8]> + Sync>(&self, payloads: &[P]) -> Vec<SelfAddressingPrefix> {
+        use rayon::prelude::*;
+        payloads
+            .par_iter()
+            .map(|payload| self.derive(payload.as_ref()))
+            .collect()
+    }
+}
+
+/// Blake2 digests keyed with a secret, so that only holders of the key can recompute them. The key
+/// is not part of the code, keyed and plain digests share their derivation codes: a parsed `F`,
+/// `G` or `0F` prefix does not tell whether it is keyed, and is verified with the key by
+/// [SelfAddressingPrefix::verify_keyed].
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct KeyedSelfAddressing {
+    code: SelfAddressing,
+}
+
+impl KeyedSelfAddressing {
+    /// Keyed digests under `code`, which must be one of the Blake2 codes.
+    pub fn new(code: SelfAddressing) -> Result<Self, Error> {
+        match code {
+            SelfAddressing::Blake2B256
+            | SelfAddressing::Blake2S256
+            | SelfAddressing::Blake2B512 => Ok(Self { code }),
+            _ => Err(Error::SemanticError(format!(
+                "Not a keyed digest: {}",
+                code.code()
+            ))),
         }
     }
 
-    fn to_str(&self) -> String {
-        match self {
-            Self::Blake3_256 => "E",
-            Self::Blake2B256(_) => "F",
-            Self::Blake2S256(_) => "G",
-            Self::SHA3_256 => "H",
-            Self::SHA2_256 => "I",
-            Self::Blake3_512 => "0D",
-            Self::SHA3_512 => "0E",
-            Self::Blake2B512 => "0F",
-            Self::SHA2_512 => "0G",
+    pub fn code(&self) -> SelfAddressing {
+        self.code
+    }
+
+    /// Digest of `data` keyed with `key`, of at most 32 bytes for Blake2s and 64 bytes for Blake2b.
+    pub fn digest(&self, key: &[u8], data: &[u8]) -> Result<Vec<u8>, Error> {
+        let mut hasher = self.hasher(key)?;
+        hasher.update(data);
+        Ok(hasher.finalize())
+    }
+
+    /// Incremental hasher for digesting data in chunks, keyed with `key`.
+    pub fn hasher(&self, key: &[u8]) -> Result<Hasher, Error> {
+        let max = match self.code {
+            SelfAddressing::Blake2S256 => 32,
+            _ => 64,
+        };
+        if key.len() > max {
+            return Err(Error::SemanticError(format!(
+                "Key of {} bytes too long for {}",
+                key.len(),
+                self.code.code()
+            )));
         }
-        .into()
+        Ok(Hasher::new(self.code, key))
+    }
+
+    pub fn derive(&self, key: &[u8], data: &[u8]) -> Result<SelfAddressingPrefix, Error> {
+        Ok(SelfAddressingPrefix::new_unchecked(
+            self.code,
+            self.digest(key, data)?,
+        ))
     }
 }
 
-impl FromStr for SelfAddressing {
-    type Err = Error;
+/// The digest algorithms a validator accepts: the approved codes, of at least a minimum strength.
+/// Compliance deployments list their approved algorithms so that any other, e.g. SHA2-256, is
+/// rejected by [SelfAddressingPrefix::verify_with_policy].
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct DigestPolicy {
+    approved: Vec<SelfAddressing>,
+    min_strength: usize,
+}
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s
-            .get(..1)
-            .ok_or_else(|| Error::DeserializeError("Empty prefix".into()))?
-        {
-            "E" => Ok(Self::Blake3_256),
-            "F" => Ok(Self::Blake2B256(vec![])),
-            "G" => Ok(Self::Blake2S256(vec![])),
-            "H" => Ok(Self::SHA3_256),
-            "I" => Ok(Self::SHA2_256),
-            "0" => match &s[1..2] {
-                "D" => Ok(Self::Blake3_512),
-                "E" => Ok(Self::SHA3_512),
-                "F" => Ok(Self::Blake2B512),
-                "G" => Ok(Self::SHA2_512),
-                _ => Err(Error::DeserializeError("Unknown hash code".into())),
-            },
-            _ => Err(Error::DeserializeError(
-                "Unknown hash algorithm code".into(),
-            )),
+impl DigestPolicy {
+    pub fn new(approved: impl IntoIterator<Item = SelfAddressing>) -> Self {
+        Self {
+            approved: approved.into_iter().collect(),
+            min_strength: 0,
+        }
+    }
+
+    /// Rejects the approved codes weaker than `bits`, see [SelfAddressing::strength].
+    pub fn min_strength(self, bits: usize) -> Self {
+        Self {
+            min_strength: bits,
+            ..self
         }
     }
-}
 
-fn blake3_256_digest(input: &[u8]) -> Vec<u8> {
-    blake3::hash(input).as_bytes().to_vec()
+    pub fn allows(&self, code: SelfAddressing) -> bool {
+        self.approved.contains(&code) && code.strength() >= self.min_strength
+    }
+
+    /// Fails when `code` is not allowed.
+    pub fn check(&self, code: SelfAddressing) -> Result<(), Error> {
+        match self.allows(code) {
+            true => Ok(()),
+            false => Err(Error::SemanticError(format!(
+                "Digest algorithm {:?} not allowed by policy",
+                code
+            ))),
+        }
+    }
 }
 
-fn blake2s_256_digest(input: &[u8], key: &[u8]) -> Vec<u8> {
-    use blake2::digest::{Update, VariableOutput};
-    let mut hasher = VarBlake2s::new_keyed(key, 256);
-    hasher.update(input);
-    hasher.finalize_boxed().to_vec()
+/// Every digest algorithm, of any strength.
+impl Default for DigestPolicy {
+    fn default() -> Self {
+        Self::new(SelfAddressing::CODES.iter().map(|(_, code)| *code))
+    }
 }
 
-// TODO it seems that blake2b is always defined as outputting 512 bits?
-// TODO updated -> is this the one?
-fn blake2b_256_digest(input: &[u8], key: &[u8]) -> Vec<u8> {
-    use blake2::digest::{Update, VariableOutput};
-    let mut hasher = VarBlake2b::new_keyed(key, 256);
-    hasher.update(input);
-    hasher.finalize_boxed().to_vec()
+impl DerivationCode for SelfAddressing {
+    fn code_len(&self) -> usize {
+        codex::matter(self.code()).cs()
+    }
+
+    fn derivative_b64_len(&self) -> usize {
+        codex::matter(self.code()).derivative_b64_len()
+    }
+
+    fn to_str(&self) -> String {
+        self.code().into()
+    }
 }
 
-fn blake3_512_digest(input: &[u8]) -> Vec<u8> {
-    let mut out = [0u8; 64];
-    let mut h = blake3::Hasher::new();
-    h.update(input);
-    h.finalize_xof().fill(&mut out);
-    out.to_vec()
+/// Maps digest data type strings to entries in the [master code table].
+///
+/// [master code table]: https://weboftrust.github.io/ietf-cesr/draft-ssmith-cesr.html#name-master-code-table
+impl CodeTable for SelfAddressing {
+    const CODES: &'static [(&'static str, Self)] = &[
+        ("E", Self::Blake3_256),
+        ("F", Self::Blake2B256),
+        ("G", Self::Blake2S256),
+        ("H", Self::SHA3_256),
+        ("I", Self::SHA2_256),
+        ("0D", Self::Blake3_512),
+        ("0E", Self::SHA3_512),
+        ("0F", Self::Blake2B512),
+        ("0G", Self::SHA2_512),
+    ];
 }
 
-fn blake2b_512_digest(input: &[u8]) -> Vec<u8> {
-    let mut hasher = Blake2b::new();
-    hasher.update(input);
-    hasher.finalize().to_vec()
+impl FromStr for SelfAddressing {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::lookup(s)
+    }
 }
 
-fn sha3_256_digest(input: &[u8]) -> Vec<u8> {
-    let mut h = Sha3_256::new();
-    h.update(input);
-    h.finalize().to_vec()
+/// Incremental digest of data fed in chunks, created by [`SelfAddressing::hasher`], so large
+/// payloads need not be held in memory at once.
+pub struct Hasher {
+    code: SelfAddressing,
+    state: HasherState,
 }
 
-fn sha2_256_digest(input: &[u8]) -> Vec<u8> {
-    let mut h = Sha256::new();
-    h.update(input);
-    h.finalize().to_vec()
+enum HasherState {
+    Blake3(Box<blake3::Hasher>),
+    Blake2B(VarBlake2b),
+    Blake2S(VarBlake2s),
+    SHA3_256(Sha3_256),
+    SHA2_256(Sha256),
+    SHA3_512(Sha3_512),
+    SHA2_512(Sha512),
 }
 
-fn sha3_512_digest(input: &[u8]) -> Vec<u8> {
-    let mut h = Sha3_512::new();
-    h.update(input);
-    h.finalize().to_vec()
+impl Hasher {
+    /// `key` is only used by the Blake2 codes and must fit them, an empty key gives the plain
+    /// digest.
+    fn new(code: SelfAddressing, key: &[u8]) -> Self {
+        let state = match code {
+            SelfAddressing::Blake3_256 | SelfAddressing::Blake3_512 => {
+                HasherState::Blake3(Box::new(blake3::Hasher::new()))
+            }
+            // output sizes are in bytes
+            SelfAddressing::Blake2B256 => HasherState::Blake2B(VarBlake2b::new_keyed(key, 32)),
+            SelfAddressing::Blake2S256 => HasherState::Blake2S(VarBlake2s::new_keyed(key, 32)),
+            SelfAddressing::Blake2B512 => HasherState::Blake2B(VarBlake2b::new_keyed(key, 64)),
+            SelfAddressing::SHA3_256 => HasherState::SHA3_256(Sha3_256::new()),
+            SelfAddressing::SHA2_256 => HasherState::SHA2_256(Sha256::new()),
+            SelfAddressing::SHA3_512 => HasherState::SHA3_512(Sha3_512::new()),
+            SelfAddressing::SHA2_512 => HasherState::SHA2_512(Sha512::new()),
+        };
+        Self { code, state }
+    }
+
+    /// Feeds the next chunk of data.
+    pub fn update(&mut self, data: &[u8]) -> &mut Self {
+        match &mut self.state {
+            HasherState::Blake3(h) => {
+                h.update(data);
+            }
+            HasherState::Blake2B(h) => Update::update(h, data),
+            HasherState::Blake2S(h) => Update::update(h, data),
+            HasherState::SHA3_256(h) => Update::update(h, data),
+            HasherState::SHA2_256(h) => Update::update(h, data),
+            HasherState::SHA3_512(h) => Update::update(h, data),
+            HasherState::SHA2_512(h) => Update::update(h, data),
+        };
+        self
+    }
+
+    /// Digest of all the data fed so far.
+    pub fn finalize(self) -> Vec<u8> {
+        match self.state {
+            HasherState::Blake3(h) => match self.code {
+                SelfAddressing::Blake3_512 => {
+                    let mut out = [0u8; 64];
+                    h.finalize_xof().fill(&mut out);
+                    out.to_vec()
+                }
+                _ => h.finalize().as_bytes().to_vec(),
+            },
+            HasherState::Blake2B(h) => h.finalize_boxed().to_vec(),
+            HasherState::Blake2S(h) => h.finalize_boxed().to_vec(),
+            HasherState::SHA3_256(h) => h.finalize().to_vec(),
+            HasherState::SHA2_256(h) => h.finalize().to_vec(),
+            HasherState::SHA3_512(h) => h.finalize().to_vec(),
+            HasherState::SHA2_512(h) => h.finalize().to_vec(),
+        }
+    }
+
+    /// Self-addressing prefix of all the data fed so far.
+    pub fn finalize_prefix(self) -> SelfAddressingPrefix {
+        let code = self.code;
+        SelfAddressingPrefix::new_unchecked(code, self.finalize())
+    }
 }
 
-fn sha2_512_digest(input: &[u8]) -> Vec<u8> {
-    let mut h = Sha512::new();
-    h.update(input);
-    h.finalize().to_vec()
+/// Lets readers be digested with [`std::io::copy`].
+impl std::io::Write for Hasher {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod self_addressing_tests {
-    use crate::derivation::self_addressing::SelfAddressing;
+    use crate::derivation::self_addressing::{KeyedSelfAddressing, SelfAddressing};
+    use crate::error::Error;
     use crate::prefix::Prefix;
-    use base64::URL_SAFE;
-    use std::str;
 
     #[test]
     fn test_self_addressing() {
         let der = SelfAddressing::Blake3_256.derive(b"abcdefghijklmnopqrstuvwxyz0123456789");
-        assert_eq!(der.to_str(), "EsLkveIFUPvt38xhtgYYJRCCpAGO7WjjHVR37Pawv67E");
+        assert_eq!(der.to_str(), "ELC5L3iBVD77d_MYbYGGCUQgqQBju1o4x1Ud-z2sL-ux");
 
         let der = SelfAddressing::Blake3_512.derive(b"abcdefghijklmnopqrstuvwxyz0123456789");
-        assert_eq!(der.to_str(), "0DsLkveIFUPvt38xhtgYYJRCCpAGO7WjjHVR37Pawv67GaNK7TEsvL8TnpL3E8EF7fR4KFgQaeMYFMM0mFNWmP-g");
+        assert_eq!(der.to_str(), "0DCwuS94gVQ--3fzGG2BhglEIKkAY7taOMdVHfs9rC_rsZo0rtMSy8vxOekvcTwQXt9HgoWBBp4xgUwzSYU1aY_6");
 
         let der = SelfAddressing::SHA2_256.derive(b"abcdefghijklmnopqrstuvwxyz0123456789");
-        assert_eq!(der.to_str(), "IAR_CmU450lEUFUD4emkJKz8iqGdn9yg95-7ts4l77fY");
+        assert_eq!(der.to_str(), "IAEfwplOOdJRFBVA-HppCSs_IqhnZ_coPefu7bOJe-32");
 
         let der = SelfAddressing::SHA2_512.derive(b"abcdefghijklmnopqrstuvwxyz0123456789");
-        assert_eq!(der.to_str(), "0GpZtJIWoOOiBEO3LGS9rlHUGzOtCKhqT7k2N43S-c04mYCewx5SWbO0VJOI0CZWE2K-cVSNQ5O-dtp-6wGDlHDA");
+        assert_eq!(der.to_str(), "0GClm0khag46IEQ7csZL2uUdQbM60IqGpPuTY3jdL5zTiZgJ7DHlJZs7RUk4jQJlYTYr5xVI1Dk7522n7rAYOUcM");
 
         let der = SelfAddressing::Blake2B512.derive(b"abcdefghijklmnopqrstuvwxyz0123456789");
-        assert_eq!(der.to_str(), "0FefJm_XA2SP8c_tg8TZDS0tnho1iFCO15rIXDksOBFqkEpPFOu9r8hoEjBaFP_ewvUnZoOegECZ1_qEF5h9bcJQ");
+        assert_eq!(der.to_str(), "0FB58mb9cDZI_xz-2DxNkNLS2eGjWIUI7XmshcOSw4EWqQSk8U672vyGgSMFoU_97C9Sdmg56AQJnX-oQXmH1twl");
+
+        let der = SelfAddressing::Blake2B256.derive(b"abcdefghijklmnopqrstuvwxyz0123456789");
+        assert_eq!(der.to_str(), "FF36lp3jxrfn3eq3Qq2Ig6hsf7ueOrhu1cLFe5fJk9dQ");
+
+        let der = SelfAddressing::Blake2S256.derive(b"abcdefghijklmnopqrstuvwxyz0123456789");
+        assert_eq!(der.to_str(), "GIp3rPTTrL9iPRBqMiCxN0l8qpXkzT5Y1TxRzb-Mh0BZ");
 
         let der = SelfAddressing::SHA3_256.derive(b"abcdefghijklmnopqrstuvwxyz0123456789");
-        assert_eq!(der.to_str(), "HAV1P0Jucuk5IyFE_LxP955z5dh52NpDhXmEnrqDJ8cU");
+        assert_eq!(der.to_str(), "HAFdT9CbnLpOSMhRPy8T_eec-XYedjaQ4V5hJ66gyfHF");
 
         let der = SelfAddressing::SHA3_512.derive(b"abcdefghijklmnopqrstuvwxyz0123456789");
-        assert_eq!(der.to_str(), "0E59Emwi3GR06eDd87T1qgIq6of-KgJMIUsw2RtV0i3YSUDN4paOZtnqvOYEKt8MdX16f83bZnB-gcKby8aOIQcA");
+        assert_eq!(der.to_str(), "0EDn0SbCLcZHTp4N3ztPWqAirqh_4qAkwhSzDZG1XSLdhJQM3ilo5m2eq85gQq3wx1fXp_zdtmcH6BwpvLxo4hBw");
+    }
+
+    #[test]
+    fn keyed() -> Result<(), Error> {
+        // keripy digests with hashlib, e.g. blake2b(ser, digest_size=32, key=key)
+        let data = b"abcdefghijklmnopqrstuvwxyz0123456789";
+        let key = b"0123456789abcdef";
+        for (code, qb64) in [
+            (
+                SelfAddressing::Blake2B256,
+                "FP0COAmknK4M6b7USDIldjzGXPEldRCoW4vAK5z5NO0i",
+            ),
+            (
+                SelfAddressing::Blake2S256,
+                "GDltJ-bRb7t88Z7CGEKAv2a_PQp2DdVqP-ku71eR9v2U",
+            ),
+            (
+                SelfAddressing::Blake2B512,
+                "0FB4tlRlmKhGHzSc-kVWzCOUIXhYLpoxHmTF-E2bzCKPFpfEi80kLHnMz6MO3ddq3VOkbhCGlu5-x1uwkNgeiUHS",
+            ),
+        ] {
+            let keyed = KeyedSelfAddressing::new(code)?;
+            assert_eq!(keyed.derive(key, data)?.to_str(), qb64);
+            // an empty key gives the plain digest
+            assert_eq!(keyed.derive(b"", data)?, code.derive(data));
+        }
+
+        assert!(KeyedSelfAddressing::new(SelfAddressing::Blake3_256).is_err());
+        let keyed = KeyedSelfAddressing::new(SelfAddressing::Blake2S256)?;
+        assert!(keyed.digest(&[0; 33], data).is_err());
+        assert!(KeyedSelfAddressing::new(SelfAddressing::Blake2B256)?
+            .digest(&[0; 64], data)
+            .is_ok());
+        Ok(())
+    }
+
+    #[test]
+    fn blake2_vectors() -> Result<(), Error> {
+        let hex = |s: &str| -> Vec<u8> {
+            (0..s.len())
+                .step_by(2)
+                .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+                .collect()
+        };
+        // RFC 7693 appendix B, and the keyed known answers of the BLAKE2 reference code
+        assert_eq!(
+            SelfAddressing::Blake2S256.digest(b"abc"),
+            hex("508c5e8c327c14e2e1a72ba34eeb452f37458b209ed63a294d999b4c86675982")
+        );
+        let key: Vec<u8> = (0..64).collect();
+        let blake2s = KeyedSelfAddressing::new(SelfAddressing::Blake2S256)?;
+        assert_eq!(
+            blake2s.digest(&key[..32], b"")?,
+            hex("48a8997da407876b3d79c0d92325ad3b89cbb754d86ab71aee047ad345fd2c49")
+        );
+        let data: Vec<u8> = (0..255).collect();
+        assert_eq!(
+            blake2s.digest(&key[..32], &data)?,
+            hex("3fb735061abc519dfe979e54c1ee5bfad0a9d858b3315bad34bde999efd724dd")
+        );
+        assert_eq!(
+            KeyedSelfAddressing::new(SelfAddressing::Blake2B512)?.digest(&key, b"")?,
+            hex("10ebb67700b1868efb4417987acf4690ae9d972fb7a590c2f02871799aaa4786b5e996e8f0f4eb981fc214b005f42d2ff4233499391653df7aefcbc13fc51568")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_hasher() {
+        let data = b"abcdefghijklmnopqrstuvwxyz0123456789".repeat(1000);
+        for code in [
+            SelfAddressing::Blake3_256,
+            SelfAddressing::Blake2B256,
+            SelfAddressing::Blake2S256,
+            SelfAddressing::SHA3_256,
+            SelfAddressing::SHA2_256,
+            SelfAddressing::Blake3_512,
+            SelfAddressing::SHA3_512,
+            SelfAddressing::Blake2B512,
+            SelfAddressing::SHA2_512,
+        ] {
+            let mut hasher = code.hasher();
+            for chunk in data.chunks(1000) {
+                hasher.update(chunk);
+            }
+            assert_eq!(hasher.finalize_prefix(), code.derive(&data));
+        }
+
+        let mut hasher = SelfAddressing::Blake3_256.hasher();
+        std::io::copy(&mut &data[..], &mut hasher).unwrap();
+        assert_eq!(hasher.finalize(), SelfAddressing::Blake3_256.digest(&data));
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn par_derive() {
+        let payloads: Vec<Vec<u8>> = (0..100u8).map(|i| vec![i; 100]).collect();
+        let prefixes = SelfAddressing::Blake3_256.par_derive(&payloads);
+        assert_eq!(prefixes.len(), 100);
+        for (prefix, payload) in prefixes.iter().zip(&payloads) {
+            assert_eq!(*prefix, SelfAddressing::Blake3_256.derive(payload));
+        }
     }
 }
@@ -1,5 +1,5 @@
-use super::DerivationCode;
-use crate::{error::Error, prefix::SelfSigningPrefix};
+use super::{CodeTable, DerivationCode};
+use crate::{codex, error::Error, prefix::SelfSigningPrefix};
 use core::str::FromStr;
 
 /// Self Signing Derivations
@@ -13,68 +13,58 @@ pub enum SelfSigning {
     Ed25519Sha512,
     ECDSAsecp256k1Sha256,
     Ed448,
+    ECDSAsecp256r1Sha256,
+    /// ML-DSA (Dilithium) signatures, the codes are provisional until registered in the master
+    /// code table.
+    MLDSA44,
+    MLDSA65,
+    MLDSA87,
 }
 
 impl SelfSigning {
     pub fn derive(&self, sig: Vec<u8>) -> SelfSigningPrefix {
-        SelfSigningPrefix::new(*self, sig)
+        SelfSigningPrefix::new_unchecked(*self, sig)
     }
 }
 
 impl DerivationCode for SelfSigning {
     fn code_len(&self) -> usize {
-        match self {
-            Self::Ed25519Sha512 | Self::ECDSAsecp256k1Sha256 => 2,
-            Self::Ed448 => 4,
-        }
+        codex::matter(self.code()).cs()
     }
 
     fn derivative_b64_len(&self) -> usize {
-        match self {
-            Self::Ed25519Sha512 | Self::ECDSAsecp256k1Sha256 => 86,
-            Self::Ed448 => 152,
-        }
+        codex::matter(self.code()).derivative_b64_len()
+    }
+
+    fn lead_size(&self) -> usize {
+        codex::matter(self.code()).ls
     }
 
     fn to_str(&self) -> String {
-        match self {
-            Self::Ed25519Sha512 => "0B",
-            Self::ECDSAsecp256k1Sha256 => "0C",
-            Self::Ed448 => "1AAE",
-        }
-        .into()
+        self.code().into()
     }
 }
 
 /// Maps self signing identifier data type strings to entries in the [master code table].
 ///
 /// [master code table]: https://weboftrust.github.io/ietf-cesr/draft-ssmith-cesr.html#name-master-code-table
+impl CodeTable for SelfSigning {
+    const CODES: &'static [(&'static str, Self)] = &[
+        ("0B", Self::Ed25519Sha512),
+        ("0C", Self::ECDSAsecp256k1Sha256),
+        ("0I", Self::ECDSAsecp256r1Sha256),
+        ("1AAE", Self::Ed448),
+        ("1ABG", Self::MLDSA44),
+        ("1ABH", Self::MLDSA65),
+        ("1ABI", Self::MLDSA87),
+    ];
+}
+
 impl FromStr for SelfSigning {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s
-            .get(..1)
-            .ok_or_else(|| Error::DeserializeError("Empty prefix".into()))?
-        {
-            "0" => match &s[1..2] {
-                "B" => Ok(Self::Ed25519Sha512),
-                "C" => Ok(Self::ECDSAsecp256k1Sha256),
-                _ => Err(Error::DeserializeError(
-                    "Unknown signature type code".into(),
-                )),
-            },
-            "1" => match &s[1..4] {
-                "AAE" => Ok(Self::Ed448),
-                _ => Err(Error::DeserializeError(
-                    "Unknown signature type code".into(),
-                )),
-            },
-            _ => Err(Error::DeserializeError(format!(
-                "Unknown master code: {}",
-                s
-            ))),
-        }
+        Self::lookup(s)
     }
 }
 
@@ -93,5 +83,26 @@ mod self_signing_tests {
 
         let der = SelfSigning::Ed448.derive(vec![0; 114]);
         assert_eq!(der.to_str(), ["1AAE".to_string(), "A".repeat(152)].join(""));
+
+        let der = SelfSigning::ECDSAsecp256r1Sha256.derive(vec![0; 64]);
+        assert_eq!(der.to_str(), ["0I".to_string(), "A".repeat(86)].join(""));
+
+        let der = SelfSigning::MLDSA44.derive(vec![0; 2420]);
+        assert_eq!(
+            der.to_str(),
+            ["1ABG".to_string(), "A".repeat(3228)].join("")
+        );
+
+        let der = SelfSigning::MLDSA65.derive(vec![0; 3309]);
+        assert_eq!(
+            der.to_str(),
+            ["1ABH".to_string(), "A".repeat(4412)].join("")
+        );
+
+        let der = SelfSigning::MLDSA87.derive(vec![0; 4627]);
+        assert_eq!(
+            der.to_str(),
+            ["1ABI".to_string(), "A".repeat(6172)].join("")
+        );
     }
 }
@@ -0,0 +1,116 @@
+use super::{lookup, DerivationCode};
+use crate::error::Error;
+use core::str::FromStr;
+
+/// Tag codes from the CESR [master code table], for labels of 1 to 11 Base64 characters.<br>
+/// The label is the soft part of the code, so a tag primitive has no derivative. Labels of 1, 5
+/// and 9 characters are prepadded with one `_` to align the primitive on a 24 bit boundary.
+///
+/// [master code table]: https://weboftrust.github.io/ietf-cesr/draft-ssmith-cesr.html#name-master-code-table
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct TagCode {
+    /// Number of characters of the label, 1 to 11.
+    len: usize,
+}
+
+impl TagCode {
+    pub const MAX_LEN: usize = 11;
+
+    /// Code of labels of `len` characters.
+    pub fn new(len: usize) -> Result<Self, Error> {
+        if !(1..=Self::MAX_LEN).contains(&len) {
+            return Err(Error::SemanticError(format!(
+                "Tags must have 1 to {} characters, got {}",
+                Self::MAX_LEN,
+                len
+            )));
+        }
+        Ok(Self { len })
+    }
+
+    /// Number of characters of the label.
+    pub fn tag_len(&self) -> usize {
+        self.len
+    }
+
+    /// Number of `_` prepad characters before the label.
+    pub fn pad_len(&self) -> usize {
+        usize::from(self.len % 4 == 1)
+    }
+}
+
+impl DerivationCode for TagCode {
+    /// Length of the whole primitive, the hard code, the padding and the label.
+    fn code_len(&self) -> usize {
+        self.to_str().len() + self.pad_len() + self.len
+    }
+
+    fn derivative_b64_len(&self) -> usize {
+        0
+    }
+
+    fn hard_size(&self) -> usize {
+        self.to_str().len()
+    }
+
+    /// The padding and the label.
+    fn soft_size(&self) -> usize {
+        self.pad_len() + self.len
+    }
+
+    /// The hard part of the code, without the label.
+    fn to_str(&self) -> String {
+        match self.len {
+            1 => "0J",
+            2 => "0K",
+            3 => "X",
+            4 => "1AAF",
+            5 => "0L",
+            6 => "0M",
+            7 => "Y",
+            8 => "1AAN",
+            9 => "0N",
+            10 => "0O",
+            _ => "Z",
+        }
+        .into()
+    }
+}
+
+/// Parses the hard part of a tag code from the start of `s`.
+impl FromStr for TagCode {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        const LENS: &[(&str, usize)] = &[
+            ("0J", 1),
+            ("0K", 2),
+            ("X", 3),
+            ("1AAF", 4),
+            ("0L", 5),
+            ("0M", 6),
+            ("Y", 7),
+            ("1AAN", 8),
+            ("0N", 9),
+            ("0O", 10),
+            ("Z", 11),
+        ];
+        Self::new(lookup(LENS, s)?.1)
+    }
+}
+
+#[test]
+fn tag_codes() -> Result<(), Error> {
+    for len in 1..=TagCode::MAX_LEN {
+        let code = TagCode::new(len)?;
+        assert!(code.prefix_b64_len().is_multiple_of(4));
+        assert_eq!(TagCode::from_str(&code.to_str())?, code);
+    }
+    assert_eq!(TagCode::new(1)?.prefix_b64_len(), 4);
+    assert_eq!(TagCode::new(8)?.prefix_b64_len(), 12);
+    assert!(TagCode::new(0).is_err());
+    assert!(TagCode::new(12).is_err());
+    assert!(TagCode::from_str("0P").is_err());
+    assert!(TagCode::from_str("1AAG").is_err());
+    Ok(())
+}
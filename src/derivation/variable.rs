@@ -0,0 +1,197 @@
+use super::{b64_to_u64, u64_to_b64, DerivationCode};
+use crate::error::Error;
+use core::str::FromStr;
+
+/// Largest size, in quadlets, of the small variable size codes.
+const MAX_SMALL_SIZE: u32 = (1 << 12) - 1;
+
+/// Largest size, in quadlets, of the big variable size codes.
+const MAX_BIG_SIZE: u32 = (1 << 24) - 1;
+
+/// Kinds of material carried under variable size codes.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum VariableKind {
+    /// `4A`, `5A`, `6A` and the big `7AAA`, `8AAA`, `9AAA` codes: a Base64 string
+    StrB64,
+    /// `4B`, `5B`, `6B` and the big `7AAB`, `8AAB`, `9AAB` codes: opaque bytes
+    Bytes,
+}
+
+/// Variable size codes from the CESR [master code table].<br>
+/// Unlike the fixed size codes the code carries the size of the material, in quadlets (4 Base64
+/// characters), as its soft part. The material is prepended with zero lead bytes to align it on a
+/// 24 bit boundary, the number of lead bytes is told by the first character of the code.
+///
+/// [master code table]: https://weboftrust.github.io/ietf-cesr/draft-ssmith-cesr.html#name-master-code-table
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct VariableCode {
+    pub kind: VariableKind,
+    /// Number of zero lead bytes, 0 to 2.
+    pub lead: usize,
+    /// Size of the lead bytes and the material together, in quadlets.
+    pub size: u32,
+}
+
+impl VariableCode {
+    /// Code for `len` bytes of material, the smallest code the size fits in.
+    pub fn new(kind: VariableKind, len: usize) -> Result<Self, Error> {
+        let lead = (3 - len % 3) % 3;
+        let size = (len + lead) / 3;
+        if size > MAX_BIG_SIZE as usize {
+            return Err(Error::SemanticError(format!(
+                "Material of {} bytes too big for a variable size code",
+                len
+            )));
+        }
+        Ok(Self {
+            kind,
+            lead,
+            size: size as u32,
+        })
+    }
+
+    /// Whether the code is one of the big codes, with room for sizes of 4096 quadlets or more.
+    pub fn is_big(&self) -> bool {
+        self.size > MAX_SMALL_SIZE
+    }
+
+    /// Length of the material, without the lead bytes.
+    pub fn raw_len(&self) -> usize {
+        self.size as usize * 3 - self.lead
+    }
+}
+
+impl DerivationCode for VariableCode {
+    fn code_len(&self) -> usize {
+        self.hard_size() + self.soft_size()
+    }
+
+    fn derivative_b64_len(&self) -> usize {
+        self.size as usize * 4
+    }
+
+    fn lead_size(&self) -> usize {
+        self.lead
+    }
+
+    fn hard_size(&self) -> usize {
+        if self.is_big() {
+            4
+        } else {
+            2
+        }
+    }
+
+    /// Number of characters of the size.
+    fn soft_size(&self) -> usize {
+        self.hard_size()
+    }
+
+    fn full_size(&self) -> Option<usize> {
+        None
+    }
+
+    fn to_str(&self) -> String {
+        let kind = match self.kind {
+            VariableKind::StrB64 => "A",
+            VariableKind::Bytes => "B",
+        };
+        let hard = if self.is_big() {
+            [(b'7' + self.lead as u8) as char, 'A', 'A']
+                .iter()
+                .collect()
+        } else {
+            ((b'4' + self.lead as u8) as char).to_string()
+        };
+        [
+            hard,
+            kind.to_string(),
+            u64_to_b64(self.size as u64, self.soft_size()),
+        ]
+        .join("")
+    }
+}
+
+/// Parses a variable size code, with its size, from the start of `s`.
+impl FromStr for VariableCode {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let unknown = || Error::UnknownCode { code: s.into() };
+        let (lead, big) = match s.get(..1).ok_or_else(unknown)? {
+            "4" => (0, false),
+            "5" => (1, false),
+            "6" => (2, false),
+            "7" => (0, true),
+            "8" => (1, true),
+            "9" => (2, true),
+            _ => return Err(unknown()),
+        };
+        let (kind, soft) = match (big, s.get(1..2), s.get(1..4)) {
+            (false, Some("A"), _) => (VariableKind::StrB64, 2..4),
+            (false, Some("B"), _) => (VariableKind::Bytes, 2..4),
+            (true, _, Some("AAA")) => (VariableKind::StrB64, 4..8),
+            (true, _, Some("AAB")) => (VariableKind::Bytes, 4..8),
+            _ => return Err(unknown()),
+        };
+        let size = b64_to_u64(s.get(soft).ok_or_else(unknown)?)? as u32;
+        let code = Self { kind, lead, size };
+        if code.is_big() != big {
+            return Err(Error::DeserializeError(format!(
+                "Size does not match variable size code: {}",
+                s
+            )));
+        }
+        Ok(code)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn codes() -> Result<(), Error> {
+        let code = VariableCode::new(VariableKind::Bytes, 6)?;
+        assert_eq!((code.lead, code.size), (0, 2));
+        assert_eq!(code.to_str(), "4BAC");
+        assert_eq!(code.prefix_b64_len(), 12);
+
+        let code = VariableCode::new(VariableKind::Bytes, 5)?;
+        assert_eq!(code.to_str(), "5BAC");
+        assert_eq!(code.raw_len(), 5);
+        let code = VariableCode::new(VariableKind::StrB64, 4)?;
+        assert_eq!(code.to_str(), "6AAC");
+        assert_eq!(code.lead_size(), 2);
+
+        let code = VariableCode::new(VariableKind::Bytes, 3 * 4096)?;
+        assert!(code.is_big());
+        assert_eq!(code.to_str(), "7AABABAA");
+        assert_eq!(code.code_len(), 8);
+        let code = VariableCode::new(VariableKind::StrB64, 3 * 4096 + 1)?;
+        assert_eq!(code.to_str(), "9AAAABAB");
+        assert!(VariableCode::new(VariableKind::Bytes, 3 << 24).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn parse() -> Result<(), Error> {
+        for code in ["4BAC", "5AAC", "6BAA", "7AABABAA", "9AAAABAB"] {
+            assert_eq!(VariableCode::from_str(code)?.to_str(), code);
+        }
+        assert_eq!(
+            VariableCode::from_str("5BAC")?,
+            VariableCode {
+                kind: VariableKind::Bytes,
+                lead: 1,
+                size: 2
+            }
+        );
+        // big code for a size the small code fits
+        assert!(VariableCode::from_str("7AABAAAC").is_err());
+        for code in ["", "4", "4C", "4BA", "7AAC", "1AAA", "7AABAAB"] {
+            assert!(VariableCode::from_str(code).is_err(), "{}", code);
+        }
+        Ok(())
+    }
+}
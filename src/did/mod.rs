@@ -0,0 +1,222 @@
+use crate::{
+    error::Error,
+    prefix::{IdentifierPrefix, Prefix},
+};
+use core::{fmt, str::FromStr};
+
+/// A decentralized identifier naming a KERI AID, of the [did:keri] or [did:webs] method.
+///
+/// [did:keri]: https://weboftrust.github.io/did-keri/
+/// [did:webs]: https://trustoverip.github.io/tswg-did-method-webs-specification/
+#[derive(Debug, PartialEq, Clone)]
+pub enum Did {
+    /// `did:keri:<aid>`, resolved from the key event log of the AID.
+    Keri(IdentifierPrefix),
+    /// `did:webs:<host>[%3A<port>][:<path>]*:<aid>`, resolved from the web host, its path
+    /// segments kept decoded.
+    Webs {
+        host: String,
+        port: Option<u16>,
+        path: Vec<String>,
+        aid: IdentifierPrefix,
+    },
+}
+
+impl Did {
+    pub fn keri(aid: IdentifierPrefix) -> Self {
+        Self::Keri(aid)
+    }
+
+    /// The did:webs of `aid` at `host`, under the path of `segments`.
+    pub fn webs(host: &str, port: Option<u16>, segments: &[&str], aid: IdentifierPrefix) -> Self {
+        Self::Webs {
+            host: host.into(),
+            port,
+            path: segments.iter().map(|segment| segment.to_string()).collect(),
+            aid,
+        }
+    }
+
+    /// The AID the DID names.
+    pub fn aid(&self) -> &IdentifierPrefix {
+        match self {
+            Self::Keri(aid) | Self::Webs { aid, .. } => aid,
+        }
+    }
+
+    /// The HTTPS URL of the DID document of a did:webs, none for a did:keri.
+    pub fn url(&self) -> Option<String> {
+        match self {
+            Self::Keri(_) => None,
+            Self::Webs {
+                host,
+                port,
+                path,
+                aid,
+            } => {
+                let port = port.map(|port| format!(":{}", port)).unwrap_or_default();
+                let path: String = path
+                    .iter()
+                    .map(|segment| format!("/{}", encode(segment)))
+                    .collect();
+                Some(format!(
+                    "https://{}{}{}/{}/did.json",
+                    host,
+                    port,
+                    path,
+                    aid.to_str()
+                ))
+            }
+        }
+    }
+}
+
+impl fmt::Display for Did {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Keri(aid) => write!(f, "did:keri:{}", aid.to_str()),
+            Self::Webs {
+                host,
+                port,
+                path,
+                aid,
+            } => {
+                write!(f, "did:webs:{}", encode(host))?;
+                if let Some(port) = port {
+                    write!(f, "%3A{}", port)?;
+                }
+                for segment in path {
+                    write!(f, ":{}", encode(segment))?;
+                }
+                write!(f, ":{}", aid.to_str())
+            }
+        }
+    }
+}
+
+/// Parses a did:keri or did:webs, without a path, query or fragment.
+impl FromStr for Did {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(aid) = s.strip_prefix("did:keri:") {
+            return Ok(Self::Keri(aid.parse()?));
+        }
+        let rest = s.strip_prefix("did:webs:").ok_or_else(|| {
+            Error::DeserializeError(format!("Expected a did:keri or did:webs, got {}", s))
+        })?;
+        let mut parts: Vec<&str> = rest.split(':').collect();
+        let aid = match (parts.len(), parts.pop()) {
+            (2.., Some(aid)) => aid.parse()?,
+            _ => {
+                return Err(Error::DeserializeError(format!(
+                    "Expected a host and an AID in {}",
+                    s
+                )))
+            }
+        };
+        let (host, port) = match decode(parts[0])?.split_once(':') {
+            Some((host, port)) => (host.to_string(), Some(port.parse()?)),
+            None => (decode(parts[0])?, None),
+        };
+        if host.is_empty() {
+            return Err(Error::DeserializeError(format!("Empty host in {}", s)));
+        }
+        let path = parts[1..]
+            .iter()
+            .map(|segment| decode(segment))
+            .collect::<Result<_, _>>()?;
+        Ok(Self::Webs {
+            host,
+            port,
+            path,
+            aid,
+        })
+    }
+}
+
+/// Percent-encodes all but the unreserved characters of RFC 3986.
+fn encode(s: &str) -> String {
+    s.bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+/// Decodes the percent-encoded characters of `s`, which must decode to UTF-8.
+fn decode(s: &str) -> Result<String, Error> {
+    let invalid = || Error::DeserializeError(format!("Invalid percent-encoding: {}", s));
+    let mut bytes = Vec::with_capacity(s.len());
+    let mut rest = s.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        rest = tail;
+        if byte != b'%' {
+            bytes.push(byte);
+            continue;
+        }
+        let hex = rest.get(..2).ok_or_else(invalid)?;
+        let hex = core::str::from_utf8(hex).map_err(|_| invalid())?;
+        bytes.push(u8::from_str_radix(hex, 16).map_err(|_| invalid())?);
+        rest = &rest[2..];
+    }
+    String::from_utf8(bytes).map_err(|_| invalid())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{derivation::self_addressing::SelfAddressing, prefix::BasicPrefix};
+
+    #[test]
+    fn dids() -> Result<(), Error> {
+        let said = SelfAddressing::Blake3_256.derive(b"icp");
+        let aid = IdentifierPrefix::SelfAddressing(said.clone());
+        let keri = Did::keri(aid.clone());
+        assert_eq!(keri.to_string(), format!("did:keri:{}", said.to_str()));
+        assert_eq!(keri.to_string().parse::<Did>()?, keri);
+        assert_eq!(keri.url(), None);
+
+        let webs = Did::webs("example.com", Some(8080), &["dids", "my aid"], aid.clone());
+        let s = format!(
+            "did:webs:example.com%3A8080:dids:my%20aid:{}",
+            said.to_str()
+        );
+        assert_eq!(webs.to_string(), s);
+        assert_eq!(s.parse::<Did>()?, webs);
+        assert_eq!(webs.aid(), &aid);
+        assert_eq!(
+            webs.url(),
+            Some(format!(
+                "https://example.com:8080/dids/my%20aid/{}/did.json",
+                said.to_str()
+            ))
+        );
+
+        // a basic prefix AID, on a host without a port or path
+        let key: BasicPrefix = "DKxy2sgzfplyr-tgwIxS19f2OchFHtLwPWD3v4oYimBx".parse()?;
+        let webs = Did::webs(
+            "example.com",
+            None,
+            &[],
+            IdentifierPrefix::Basic(key.clone()),
+        );
+        assert_eq!(webs.to_string(), format!("did:webs:example.com:{}", key));
+        assert_eq!(webs.to_string().parse::<Did>()?, webs);
+
+        for bad in [
+            "did:web:example.com:EAAA",
+            "did:webs:EKxy2sgzfplyr-tgwIxS19f2OchFHtLwPWD3v4oYimBx",
+            "did:webs:example.com:Ekxy",
+            "did:webs:example.com%3Ax:EKxy2sgzfplyr-tgwIxS19f2OchFHtLwPWD3v4oYimBx",
+            "did:webs:example.com:a%2:EKxy2sgzfplyr-tgwIxS19f2OchFHtLwPWD3v4oYimBx",
+            "did:webs::EKxy2sgzfplyr-tgwIxS19f2OchFHtLwPWD3v4oYimBx",
+        ] {
+            assert!(bad.parse::<Did>().is_err(), "{}", bad);
+        }
+        Ok(())
+    }
+}
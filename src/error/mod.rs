@@ -1,5 +1,5 @@
 use base64::DecodeError;
-use core::num::ParseIntError;
+use core::{fmt, num::ParseIntError};
 use ed25519_dalek;
 use rmp_serde as serde_mgpk;
 use serde_cbor;
@@ -31,6 +31,12 @@ pub enum Error {
         source: serde_mgpk::encode::Error,
     },
 
+    #[error("MessagePack Deserialization error")]
+    MsgPackDeserializationError {
+        #[from]
+        source: serde_mgpk::decode::Error,
+    },
+
     #[error("Error parsing numerical value: {source}")]
     IntegerParseValue {
         #[from]
@@ -58,18 +64,41 @@ pub enum Error {
     #[error("Deserialize error: {0}")]
     DeserializeError(String),
 
+    #[error("Unknown derivation code: {code}")]
+    UnknownCode { code: String },
+
+    #[error("Wrong length, expected {expected} characters, got {got}")]
+    WrongLength { expected: usize, got: usize },
+
+    /// `offset` is counted from the start of the decoded part, e.g. the raw material after the
+    /// derivation code.
+    #[error("Invalid Base64 character at offset {offset}")]
+    InvalidBase64 { offset: usize },
+
+    #[error("Invalid Base64 padding")]
+    InvalidPadding,
+
+    /// A `+` or `/` of the standard Base64 alphabet, where qb64 has `-` and `_`. `offset` is
+    /// counted as for [Error::InvalidBase64].
+    #[error("Standard Base64 character {character:?} at offset {offset}, qb64 is URL-safe Base64")]
+    StandardBase64 { offset: usize, character: char },
+
+    /// A `=` padding character, qb64 being aligned on quadlets by its codes rather than padded.
+    /// `offset` is counted as for [Error::InvalidBase64].
+    #[error("Base64 padding at offset {offset}, qb64 is never padded")]
+    Base64Padding { offset: usize },
+
+    /// The input ends in the middle of a primitive or frame, at least `needed` more characters or
+    /// bytes must be read before parsing can go on.
+    #[error("Not enough input, at least {needed} more needed")]
+    ShortageError { needed: usize },
+
     #[error("Identifier is not indexed into the DB")]
     NotIndexedError,
 
     #[error("Identifier ID is already present in the DB")]
     IdentifierPresentError,
 
-    #[error("Base64 Decoding error")]
-    Base64DecodingError {
-        #[from]
-        source: DecodeError,
-    },
-
     #[error("Improper Prefix Type")]
     ImproperPrefixType,
 
@@ -86,6 +115,12 @@ pub enum Error {
     #[error("Failed to obtain mutable ref to Ark of KeyManager")]
     MutArcKeyVaultError,
 
+    #[error("IO error")]
+    IoError {
+        #[from]
+        source: std::io::Error,
+    },
+
     #[error(transparent)]
     Ed25519DalekSignatureError(#[from] ed25519_dalek::SignatureError),
 
@@ -107,4 +142,74 @@ pub enum Error {
     #[cfg(feature = "query")]
     #[error(transparent)]
     QueryError(#[from] crate::query::QueryError),
+
+    /// A failure to parse a stream, located in it.
+    #[error("{source} at {context}")]
+    ParseError {
+        context: ParseContext,
+        source: Box<Error>,
+    },
+}
+
+/// Where in its input a stream failed to parse.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub struct ParseContext {
+    /// Byte offset of the failing primitive from the start of the input.
+    pub offset: usize,
+    /// Index of the failing primitive among the primitives of the input, count codes included.
+    /// Failing in a group member, it is the index of the first primitive of the member.
+    pub primitive: usize,
+}
+
+impl fmt::Display for ParseContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "byte {} (primitive {})", self.offset, self.primitive)
+    }
+}
+
+impl Error {
+    /// Locates the error `offset` bytes and `primitive` primitives into the input, or shifts it
+    /// by as much when it is located in a part of the input starting there. Shortages are left
+    /// as they are, more input is needed rather than parsing having failed.
+    pub fn at(self, offset: usize, primitive: usize) -> Self {
+        match self {
+            Self::ShortageError { .. } => self,
+            Self::ParseError { context, source } => Self::ParseError {
+                context: ParseContext {
+                    offset: context.offset + offset,
+                    primitive: context.primitive + primitive,
+                },
+                source,
+            },
+            source => Self::ParseError {
+                context: ParseContext { offset, primitive },
+                source: Box::new(source),
+            },
+        }
+    }
+
+    /// Where in its input a parse error occurred.
+    pub fn context(&self) -> Option<ParseContext> {
+        match self {
+            Self::ParseError { context, .. } => Some(*context),
+            _ => None,
+        }
+    }
+
+    /// The error itself, without where it occurred.
+    pub fn root(&self) -> &Self {
+        match self {
+            Self::ParseError { source, .. } => source.root(),
+            _ => self,
+        }
+    }
+}
+
+impl From<DecodeError> for Error {
+    fn from(e: DecodeError) -> Self {
+        match e {
+            DecodeError::InvalidByte(offset, _) => Self::InvalidBase64 { offset },
+            DecodeError::InvalidLength | DecodeError::InvalidLastSymbol(..) => Self::InvalidPadding,
+        }
+    }
 }
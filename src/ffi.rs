@@ -0,0 +1,461 @@
+//! Every function returns a [CesrStatus], results are written through out pointers. Strings and
+//! buffers handed out are owned by the caller and released with [cesr_string_free],
+//! [cesr_bytes_free] and [cesr_frame_free]. The message of the last failure on the calling
+//! thread is available from [cesr_last_error].
+
+use crate::{
+    derivation::self_addressing::SelfAddressing,
+    error::Error,
+    keys::Signer,
+    parser::StreamParser,
+    prefix::{BasicPrefix, IdentifierPrefix, Prefix, SeedPrefix, SelfSigningPrefix},
+};
+use core::{ptr, slice, str::FromStr};
+use std::{
+    cell::RefCell,
+    ffi::{c_char, CStr, CString},
+};
+
+/// Outcome of a call.
+#[repr(C)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum CesrStatus {
+    Ok = 0,
+    NullPointer = 1,
+    InvalidUtf8 = 2,
+    /// Malformed or unsupported primitive, e.g. an unknown code or a wrong length.
+    InvalidInput = 3,
+    /// The buffered stream ends in the middle of a frame.
+    Incomplete = 4,
+    /// No more frames in the stream.
+    End = 5,
+    Failed = 6,
+}
+
+impl From<&Error> for CesrStatus {
+    fn from(error: &Error) -> Self {
+        match error.root() {
+            Error::ShortageError { .. } => Self::Incomplete,
+            Error::DeserializeError(_)
+            | Error::SemanticError(_)
+            | Error::UnknownCode { .. }
+            | Error::WrongLength { .. }
+            | Error::InvalidBase64 { .. }
+            | Error::StandardBase64 { .. }
+            | Error::Base64Padding { .. }
+            | Error::InvalidPadding
+            | Error::IntegerParseValue { .. }
+            | Error::ImproperPrefixType => Self::InvalidInput,
+            _ => Self::Failed,
+        }
+    }
+}
+
+/// Bytes owned by the caller once returned, released with [cesr_bytes_free].
+#[repr(C)]
+#[derive(Debug)]
+pub struct CesrBytes {
+    pub data: *mut u8,
+    pub len: usize,
+}
+
+impl CesrBytes {
+    fn new(bytes: Vec<u8>) -> Self {
+        let bytes = Box::into_raw(bytes.into_boxed_slice());
+        Self {
+            data: bytes as *mut u8,
+            len: bytes.len(),
+        }
+    }
+
+    unsafe fn free(&mut self) {
+        if !self.data.is_null() {
+            drop(Box::from_raw(ptr::slice_from_raw_parts_mut(
+                self.data, self.len,
+            )));
+        }
+        self.data = ptr::null_mut();
+        self.len = 0;
+    }
+}
+
+/// A message and its attachment groups, released with [cesr_frame_free].
+#[repr(C)]
+#[derive(Debug)]
+pub struct CesrFrame {
+    pub message: CesrBytes,
+    pub attachments: CesrBytes,
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+struct Failure {
+    status: CesrStatus,
+    message: String,
+}
+
+impl From<Error> for Failure {
+    fn from(error: Error) -> Self {
+        Self {
+            status: (&error).into(),
+            message: error.to_string(),
+        }
+    }
+}
+
+impl From<CesrStatus> for Failure {
+    fn from(status: CesrStatus) -> Self {
+        Self {
+            status,
+            message: format!("{:?}", status),
+        }
+    }
+}
+
+/// Runs `call`, recording the message of a failure for [cesr_last_error].
+fn status(call: impl FnOnce() -> Result<CesrStatus, Failure>) -> CesrStatus {
+    match call() {
+        Ok(status) => status,
+        Err(Failure { status, message }) => {
+            let message = CString::new(message).unwrap_or_default();
+            LAST_ERROR.with(|last| *last.borrow_mut() = Some(message));
+            status
+        }
+    }
+}
+
+unsafe fn str_arg<'a>(s: *const c_char) -> Result<&'a str, Failure> {
+    if s.is_null() {
+        return Err(CesrStatus::NullPointer.into());
+    }
+    CStr::from_ptr(s)
+        .to_str()
+        .map_err(|_| CesrStatus::InvalidUtf8.into())
+}
+
+unsafe fn bytes_arg<'a>(data: *const u8, len: usize) -> Result<&'a [u8], Failure> {
+    match (data.is_null(), len) {
+        (true, 0) => Ok(&[]),
+        (true, _) => Err(CesrStatus::NullPointer.into()),
+        (false, _) => Ok(slice::from_raw_parts(data, len)),
+    }
+}
+
+unsafe fn write_out<T>(out: *mut T, value: T) -> Result<CesrStatus, Failure> {
+    if out.is_null() {
+        return Err(CesrStatus::NullPointer.into());
+    }
+    out.write(value);
+    Ok(CesrStatus::Ok)
+}
+
+fn string_out(s: String) -> *mut c_char {
+    CString::new(s)
+        .expect("qb64 has no interior nul")
+        .into_raw()
+}
+
+/// Message of the last failure on this thread, null if none. Valid until the next failing call.
+#[no_mangle]
+pub extern "C" fn cesr_last_error() -> *const c_char {
+    LAST_ERROR.with(|last| last.borrow().as_ref().map_or(ptr::null(), |m| m.as_ptr()))
+}
+
+/// # Safety
+/// `s` must be null or a string returned by this library, not freed before.
+#[no_mangle]
+pub unsafe extern "C" fn cesr_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
+
+/// # Safety
+/// `bytes` must be null or point to bytes returned by this library, not freed before.
+#[no_mangle]
+pub unsafe extern "C" fn cesr_bytes_free(bytes: *mut CesrBytes) {
+    if let Some(bytes) = bytes.as_mut() {
+        bytes.free()
+    }
+}
+
+/// # Safety
+/// `frame` must be null or point to a frame returned by this library, not freed before.
+#[no_mangle]
+pub unsafe extern "C" fn cesr_frame_free(frame: *mut CesrFrame) {
+    if let Some(frame) = frame.as_mut() {
+        frame.message.free();
+        frame.attachments.free();
+    }
+}
+
+/// Digests `data` under the digest `code` into a qb64 self-addressing prefix.
+///
+/// # Safety
+/// `code` must be a nul terminated string, `data` valid for `len` bytes and `out` writable.
+#[no_mangle]
+pub unsafe extern "C" fn cesr_digest(
+    code: *const c_char,
+    data: *const u8,
+    len: usize,
+    out: *mut *mut c_char,
+) -> CesrStatus {
+    status(|| {
+        let code = SelfAddressing::from_str(str_arg(code)?)?;
+        let digest = code.derive(bytes_arg(data, len)?).to_str();
+        write_out(out, string_out(digest))
+    })
+}
+
+/// Parses a qb64 identifier prefix or signature into its derivation code and raw bytes.
+///
+/// # Safety
+/// `qb64` must be a nul terminated string, `code` and `raw` writable.
+#[no_mangle]
+pub unsafe extern "C" fn cesr_parse_prefix(
+    qb64: *const c_char,
+    code: *mut *mut c_char,
+    raw: *mut CesrBytes,
+) -> CesrStatus {
+    status(|| {
+        let qb64 = str_arg(qb64)?;
+        let (derivation_code, derivative) = match IdentifierPrefix::from_str(qb64) {
+            Ok(prefix) => (prefix.derivation_code(), prefix.derivative()),
+            Err(_) => {
+                let signature = SelfSigningPrefix::from_str(qb64)?;
+                (signature.derivation_code(), signature.derivative())
+            }
+        };
+        if code.is_null() || raw.is_null() {
+            return Err(CesrStatus::NullPointer.into());
+        }
+        write_out(code, string_out(derivation_code))?;
+        write_out(raw, CesrBytes::new(derivative))
+    })
+}
+
+/// Writes the qb64 public key of the signer of the qb64 `seed`.
+///
+/// # Safety
+/// `seed` must be a nul terminated string and `out` writable.
+#[no_mangle]
+pub unsafe extern "C" fn cesr_public_key(
+    seed: *const c_char,
+    transferable: bool,
+    out: *mut *mut c_char,
+) -> CesrStatus {
+    status(|| {
+        let seed = SeedPrefix::from_str(str_arg(seed)?)?;
+        let key = Signer::from_seed(&seed, transferable)?.basic_prefix();
+        write_out(out, string_out(key.to_str()))
+    })
+}
+
+/// Signs `data` with the qb64 `seed`, writing the qb64 signature.
+///
+/// # Safety
+/// `seed` must be a nul terminated string, `data` valid for `len` bytes and `out` writable.
+#[no_mangle]
+pub unsafe extern "C" fn cesr_sign(
+    seed: *const c_char,
+    data: *const u8,
+    len: usize,
+    out: *mut *mut c_char,
+) -> CesrStatus {
+    status(|| {
+        let seed = SeedPrefix::from_str(str_arg(seed)?)?;
+        let signature = Signer::from_seed(&seed, true)?.sign(bytes_arg(data, len)?);
+        write_out(out, string_out(signature.to_str()))
+    })
+}
+
+/// Verifies the qb64 `signature` over `data` against the qb64 basic prefix `key`.
+///
+/// # Safety
+/// `key` and `signature` must be nul terminated strings, `data` valid for `len` bytes and `valid`
+/// writable.
+#[no_mangle]
+pub unsafe extern "C" fn cesr_verify(
+    key: *const c_char,
+    data: *const u8,
+    len: usize,
+    signature: *const c_char,
+    valid: *mut bool,
+) -> CesrStatus {
+    status(|| {
+        let key = BasicPrefix::from_str(str_arg(key)?)?;
+        let signature = SelfSigningPrefix::from_str(str_arg(signature)?)?;
+        write_out(valid, key.verify(bytes_arg(data, len)?, &signature)?)
+    })
+}
+
+/// Creates a stream parser, released with [cesr_stream_free].
+#[no_mangle]
+pub extern "C" fn cesr_stream_new() -> *mut StreamParser {
+    Box::into_raw(Box::new(StreamParser::new()))
+}
+
+/// # Safety
+/// `parser` must be null or a parser returned by [cesr_stream_new], not freed before.
+#[no_mangle]
+pub unsafe extern "C" fn cesr_stream_free(parser: *mut StreamParser) {
+    if !parser.is_null() {
+        drop(Box::from_raw(parser));
+    }
+}
+
+/// Buffers `len` bytes read from the stream.
+///
+/// # Safety
+/// `parser` must come from [cesr_stream_new] and `data` be valid for `len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn cesr_stream_extend(
+    parser: *mut StreamParser,
+    data: *const u8,
+    len: usize,
+) -> CesrStatus {
+    status(|| {
+        let parser = parser.as_mut().ok_or(CesrStatus::NullPointer)?;
+        parser.extend(bytes_arg(data, len)?);
+        Ok(CesrStatus::Ok)
+    })
+}
+
+/// Marks the end of the stream.
+///
+/// # Safety
+/// `parser` must come from [cesr_stream_new].
+#[no_mangle]
+pub unsafe extern "C" fn cesr_stream_finish(parser: *mut StreamParser) -> CesrStatus {
+    status(|| {
+        parser.as_mut().ok_or(CesrStatus::NullPointer)?.finish();
+        Ok(CesrStatus::Ok)
+    })
+}
+
+/// Parses the next frame into `frame`. Returns [CesrStatus::Incomplete] when more bytes must be
+/// buffered first and [CesrStatus::End] once the finished stream is exhausted.
+///
+/// # Safety
+/// `parser` must come from [cesr_stream_new] and `frame` be writable.
+#[no_mangle]
+pub unsafe extern "C" fn cesr_stream_next(
+    parser: *mut StreamParser,
+    frame: *mut CesrFrame,
+) -> CesrStatus {
+    status(|| {
+        let parser = parser.as_mut().ok_or(CesrStatus::NullPointer)?;
+        if frame.is_null() {
+            return Err(CesrStatus::NullPointer.into());
+        }
+        let finished = parser.is_finished();
+        match parser.next_frame() {
+            Ok(Some(next)) => write_out(
+                frame,
+                CesrFrame {
+                    message: CesrBytes::new(next.message.to_vec()),
+                    attachments: CesrBytes::new(next.attachments.to_vec()),
+                },
+            ),
+            Ok(None) if finished => Ok(CesrStatus::End),
+            Ok(None) | Err(Error::ShortageError { .. }) => Ok(CesrStatus::Incomplete),
+            Err(e) => Err(e.into()),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    unsafe fn take(s: *mut c_char) -> String {
+        let owned = CStr::from_ptr(s).to_str().unwrap().to_owned();
+        cesr_string_free(s);
+        owned
+    }
+
+    #[test]
+    fn sign_and_verify() {
+        // taken from KERIPY: tests/core/test_eventing.py#1512
+        let seed = c"AK8F6AAiYDpXlWdj2O5F5-6wNCCNJh2A4XOlqwR_HwwH";
+        let data = b"hello there";
+        unsafe {
+            let mut out = ptr::null_mut();
+            assert_eq!(
+                cesr_public_key(seed.as_ptr(), true, &mut out),
+                CesrStatus::Ok
+            );
+            let key = CString::new(take(out)).unwrap();
+            assert_eq!(
+                key.to_str().unwrap(),
+                "DErocgXD2RGSyvn3MObcx59jeOsEQhv2TqHirVkzrp0Q"
+            );
+
+            let status = cesr_sign(seed.as_ptr(), data.as_ptr(), data.len(), &mut out);
+            assert_eq!(status, CesrStatus::Ok);
+            let signature = CString::new(take(out)).unwrap();
+            let mut valid = false;
+            let status = cesr_verify(
+                key.as_ptr(),
+                data.as_ptr(),
+                data.len(),
+                signature.as_ptr(),
+                &mut valid,
+            );
+            assert_eq!(status, CesrStatus::Ok);
+            assert!(valid);
+
+            let mut code = ptr::null_mut();
+            let mut raw = CesrBytes::new(vec![]);
+            assert_eq!(
+                cesr_parse_prefix(signature.as_ptr(), &mut code, &mut raw),
+                CesrStatus::Ok
+            );
+            assert_eq!(take(code), "0B");
+            assert_eq!(raw.len, 64);
+            cesr_bytes_free(&mut raw);
+
+            assert_eq!(
+                cesr_parse_prefix(c"Xnot a prefix".as_ptr(), &mut code, &mut raw),
+                CesrStatus::InvalidInput
+            );
+            assert!(!cesr_last_error().is_null());
+            assert_eq!(
+                cesr_digest(ptr::null(), data.as_ptr(), data.len(), &mut out),
+                CesrStatus::NullPointer
+            );
+            assert_eq!(
+                cesr_digest(c"E".as_ptr(), data.as_ptr(), data.len(), &mut out),
+                CesrStatus::Ok
+            );
+            assert_eq!(take(out).len(), 44);
+        }
+    }
+
+    #[test]
+    fn stream() {
+        let ixn = br#"{"v":"KERI10JSON00002e_","t":"ixn","i":"EAAA"}-AABAAAA"#;
+        unsafe {
+            let parser = cesr_stream_new();
+            let mut frame = CesrFrame {
+                message: CesrBytes::new(vec![]),
+                attachments: CesrBytes::new(vec![]),
+            };
+            assert_eq!(cesr_stream_extend(parser, ixn.as_ptr(), 20), CesrStatus::Ok);
+            assert_eq!(cesr_stream_next(parser, &mut frame), CesrStatus::Incomplete);
+            cesr_stream_extend(parser, ixn[20..].as_ptr(), ixn.len() - 20);
+            cesr_stream_finish(parser);
+            assert_eq!(cesr_stream_next(parser, &mut frame), CesrStatus::Ok);
+            assert_eq!(frame.message.len, 46);
+            assert_eq!(
+                slice::from_raw_parts(frame.attachments.data, frame.attachments.len),
+                b"-AABAAAA"
+            );
+            cesr_frame_free(&mut frame);
+            assert_eq!(cesr_stream_next(parser, &mut frame), CesrStatus::End);
+            cesr_stream_free(parser);
+        }
+    }
+}
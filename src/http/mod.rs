@@ -0,0 +1,269 @@
+use crate::{
+    derivation::self_signing::SelfSigning,
+    error::Error,
+    keys::Signer,
+    prefix::{BasicPrefix, Prefix, SelfSigningPrefix},
+};
+use core::{fmt, str::FromStr};
+
+/// Label of the signature in both headers, as Signify and KERIA label it.
+pub const SIGNATURE_LABEL: &str = "signify";
+
+/// The parts of an HTTP request that signatures cover.
+#[derive(Debug, Clone, Copy)]
+pub struct Request<'a> {
+    pub method: &'a str,
+    /// The path of the request, without the query.
+    pub path: &'a str,
+    pub headers: &'a [(&'a str, &'a str)],
+}
+
+impl Request<'_> {
+    /// The value of the header `name`, whatever its case, trimmed.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.trim())
+    }
+}
+
+/// The `Signature-Input` header: which fields of a request are signed, when and by whom.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct SignatureInput {
+    /// The signed fields, `@method`, `@path` or lowercase header names.
+    pub fields: Vec<String>,
+    /// Seconds since the Unix epoch.
+    pub created: u64,
+    /// The AID or key the verifier looks the signing key up with.
+    pub keyid: String,
+    pub alg: String,
+}
+
+/// Both headers of a signed request.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct SignatureHeaders {
+    pub signature_input: String,
+    pub signature: String,
+}
+
+impl SignatureInput {
+    /// The signature base of `request`: a `"<field>": <value>` line for each field then the
+    /// `"@signature-params: <input>"` line, without the quotes of the parameters, as Signify
+    /// serializes it.
+    pub fn base(&self, request: &Request) -> Result<String, Error> {
+        let mut lines = self
+            .fields
+            .iter()
+            .map(|field| {
+                let value = match field.as_str() {
+                    "@method" => Some(request.method),
+                    "@path" => Some(request.path),
+                    _ => request.header(field),
+                };
+                value
+                    .map(|value| format!("\"{}\": {}", field, value))
+                    .ok_or_else(|| Error::SemanticError(format!("Missing signed field: {}", field)))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        lines.push(format!(
+            "\"@signature-params: {};created={};keyid={};alg={}\"",
+            self.field_list(),
+            self.created,
+            self.keyid,
+            self.alg
+        ));
+        Ok(lines.join("\n"))
+    }
+
+    fn field_list(&self) -> String {
+        let fields: Vec<String> = self
+            .fields
+            .iter()
+            .map(|field| format!("\"{}\"", field))
+            .collect();
+        format!("({})", fields.join(" "))
+    }
+}
+
+impl fmt::Display for SignatureInput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}={};created={};keyid=\"{}\";alg=\"{}\"",
+            SIGNATURE_LABEL,
+            self.field_list(),
+            self.created,
+            self.keyid,
+            self.alg
+        )
+    }
+}
+
+impl FromStr for SignatureInput {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || Error::DeserializeError(format!("Invalid Signature-Input: {}", s));
+        let rest = s
+            .trim()
+            .strip_prefix(SIGNATURE_LABEL)
+            .and_then(|rest| rest.strip_prefix("=("))
+            .ok_or_else(invalid)?;
+        let (fields, params) = rest.split_once(')').ok_or_else(invalid)?;
+        let fields = fields
+            .split_whitespace()
+            .map(|field| unquote(field).map(str::to_lowercase).ok_or_else(invalid))
+            .collect::<Result<_, _>>()?;
+        let (mut created, mut keyid, mut alg) = (None, None, None);
+        for param in params.split(';').skip(1) {
+            match param.trim().split_once('=').ok_or_else(invalid)? {
+                ("created", value) => created = Some(value.parse()?),
+                ("keyid", value) => keyid = unquote(value).map(String::from),
+                ("alg", value) => alg = unquote(value).map(String::from),
+                _ => (),
+            }
+        }
+        Ok(Self {
+            fields,
+            created: created.ok_or_else(invalid)?,
+            keyid: keyid.ok_or_else(invalid)?,
+            alg: alg.ok_or_else(invalid)?,
+        })
+    }
+}
+
+fn unquote(s: &str) -> Option<&str> {
+    s.strip_prefix('"')?.strip_suffix('"')
+}
+
+/// The algorithm name of signatures of `code` in `Signature-Input`.
+fn alg(code: SelfSigning) -> &'static str {
+    match code {
+        SelfSigning::Ed25519Sha512 => "ed25519",
+        SelfSigning::ECDSAsecp256k1Sha256 => "ecdsa-secp256k1-sha256",
+        SelfSigning::ECDSAsecp256r1Sha256 => "ecdsa-p256-sha256",
+        SelfSigning::Ed448 => "ed448",
+        SelfSigning::MLDSA44 => "ml-dsa-44",
+        SelfSigning::MLDSA65 => "ml-dsa-65",
+        SelfSigning::MLDSA87 => "ml-dsa-87",
+    }
+}
+
+/// Signs `fields` of `request` as Signify does, e.g. `@method`, `@path`, `signify-resource` and
+/// `signify-timestamp`. The signature is the qb64 of the signature of the signature base, under
+/// `indexed="?0"` as it is not indexed to a key list.
+pub fn sign_request(
+    signer: &Signer,
+    keyid: &str,
+    request: &Request,
+    fields: &[&str],
+    created: u64,
+) -> Result<SignatureHeaders, Error> {
+    let input = SignatureInput {
+        fields: fields.iter().map(|field| field.to_lowercase()).collect(),
+        created,
+        keyid: keyid.into(),
+        alg: alg(signer.code).into(),
+    };
+    let signature = signer.sign(input.base(request)?.as_bytes());
+    Ok(SignatureHeaders {
+        signature_input: input.to_string(),
+        signature: format!(
+            "indexed=\"?0\";{}=\"{}\"",
+            SIGNATURE_LABEL,
+            signature.to_str()
+        ),
+    })
+}
+
+/// Verifies the `Signature` and `Signature-Input` headers of `request` against `key`. Fails when
+/// the headers are missing or malformed, the key being looked up from the `keyid` of the input
+/// beforehand.
+pub fn verify_request(key: &BasicPrefix, request: &Request) -> Result<bool, Error> {
+    let header = |name| {
+        request
+            .header(name)
+            .ok_or_else(|| Error::SemanticError(format!("Missing header: {}", name)))
+    };
+    let input: SignatureInput = header("Signature-Input")?.parse()?;
+    let signature = header("Signature")?
+        .split(';')
+        .find_map(|marker| {
+            let (label, value) = marker.trim().split_once('=')?;
+            (label == SIGNATURE_LABEL).then(|| unquote(value)).flatten()
+        })
+        .ok_or_else(|| Error::DeserializeError("No signify signature".into()))?;
+    let signature = SelfSigningPrefix::from_str(signature)?;
+    if input.alg != alg(signature.derivation) {
+        return Ok(false);
+    }
+    key.verify(input.base(request)?.as_bytes(), &signature)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::signer;
+
+    #[test]
+    fn signed_requests() -> Result<(), Error> {
+        let signer = signer(7, true);
+        let key = signer.basic_prefix();
+        let aid = "EIaGMMWJFPmtXznY1IIiKDIrg-vIyge6mBl2QV8dDjI3";
+        let headers = [
+            ("Signify-Resource", aid),
+            ("Signify-Timestamp", "2022-09-24T00:05:48.196795+00:00"),
+        ];
+        let request = Request {
+            method: "POST",
+            path: "/boot",
+            headers: &headers,
+        };
+        let fields = ["@method", "@path", "signify-resource", "signify-timestamp"];
+        let signed = sign_request(&signer, aid, &request, &fields, 1609459200)?;
+        assert_eq!(
+            signed.signature_input,
+            format!(
+                "signify=(\"@method\" \"@path\" \"signify-resource\" \"signify-timestamp\");\
+                 created=1609459200;keyid=\"{}\";alg=\"ed25519\"",
+                aid
+            )
+        );
+        assert!(signed.signature.starts_with("indexed=\"?0\";signify=\"0B"));
+
+        let input: SignatureInput = signed.signature_input.parse()?;
+        assert_eq!(input.keyid, aid);
+        assert_eq!(
+            input.base(&request)?,
+            format!(
+                "\"@method\": POST\n\"@path\": /boot\n\"signify-resource\": {}\n\
+                 \"signify-timestamp\": 2022-09-24T00:05:48.196795+00:00\n\
+                 \"@signature-params: {};created=1609459200;keyid={};alg=ed25519\"",
+                aid,
+                input.field_list(),
+                aid
+            )
+        );
+
+        let mut headers = headers.to_vec();
+        headers.push(("signature-input", &signed.signature_input));
+        headers.push(("signature", &signed.signature));
+        let mut request = Request {
+            headers: &headers,
+            ..request
+        };
+        assert!(verify_request(&key, &request)?);
+        request.path = "/identifiers";
+        assert!(!verify_request(&key, &request)?);
+
+        // a signed header gone missing
+        let headers = &headers[1..];
+        request.headers = headers;
+        request.path = "/boot";
+        assert!(verify_request(&key, &request).is_err());
+        request.headers = &[];
+        assert!(verify_request(&key, &request).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,172 @@
+use super::{wipe, Encrypter, PublicKey};
+use crate::{
+    error::Error,
+    prefix::{CipherPrefix, SeedPrefix},
+};
+use core::{fmt, str::FromStr};
+use sha2::{Digest, Sha512};
+
+/// Opens libsodium sealed boxes made by an [Encrypter] for the matching X25519 public key.
+#[derive(Clone)]
+pub struct Decrypter {
+    private_key: crypto_box::SecretKey,
+}
+
+impl Decrypter {
+    /// Creates a decrypter from an X25519 private key (code `O`) or from an Ed25519 seed, converted
+    /// to its X25519 counterpart the way libsodium's `crypto_sign_ed25519_sk_to_curve25519` does.
+    pub fn from_seed(seed: &SeedPrefix) -> Result<Self, Error> {
+        let key = match seed {
+            SeedPrefix::RandomSeed256X25519(key) => key.to_owned(),
+            SeedPrefix::RandomSeed256Ed25519(seed) => {
+                let mut key = Sha512::digest(seed)[..32].to_vec();
+                key[0] &= 248;
+                key[31] &= 127;
+                key[31] |= 64;
+                key
+            }
+            _ => return Err(Error::ImproperPrefixType),
+        };
+        let private_key = crypto_box::SecretKey::from_slice(&key)
+            .map_err(|_| Error::SemanticError(format!("Invalid X25519 key length: {}", key.len())));
+        wipe(key);
+        Ok(Self {
+            private_key: private_key?,
+        })
+    }
+
+    /// The encrypter for the public key matching this decrypter.
+    pub fn encrypter(&self) -> Encrypter {
+        let public_key = self.private_key.public_key().as_bytes().to_vec();
+        Encrypter::new(&PublicKey::new(public_key)).expect("X25519 public keys are 32 bytes")
+    }
+
+    pub fn decrypt(&self, cipher: &[u8]) -> Result<Vec<u8>, Error> {
+        self.private_key
+            .unseal(cipher)
+            .map_err(|_| Error::SemanticError("Decryption failed".into()))
+    }
+
+    /// Opens a sealed seed or salt, checking the decrypted primitive matches the cipher code.
+    pub fn decrypt_seed(&self, cipher: &CipherPrefix) -> Result<SeedPrefix, Error> {
+        let (CipherPrefix::X25519Seed(raw) | CipherPrefix::X25519Salt(raw)) = cipher;
+        let plain = self.decrypt(raw)?;
+        let seed = SeedPrefix::from_str(
+            std::str::from_utf8(&plain)
+                .map_err(|e| Error::DeserializeError(format!("Decrypted seed: {}", e)))?,
+        )?;
+        match (cipher, &seed) {
+            (CipherPrefix::X25519Salt(_), SeedPrefix::RandomSeed128(_))
+            | (
+                CipherPrefix::X25519Seed(_),
+                SeedPrefix::RandomSeed256Ed25519(_)
+                | SeedPrefix::RandomSeed256ECDSAsecp256k1(_)
+                | SeedPrefix::RandomSeed256ECDSAsecp256r1(_),
+            ) => Ok(seed),
+            _ => Err(Error::ImproperPrefixType),
+        }
+    }
+}
+
+/// Redacted, the key never ends up in logs.
+impl fmt::Debug for Decrypter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Decrypter").finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        derivation::{basic::Basic, self_signing::SelfSigning},
+        keys::{PrivateKey, Salter, Signer, Tier, Verfer},
+        prefix::Prefix,
+    };
+    use sodiumoxide::crypto::{sealedbox, sign};
+
+    #[test]
+    fn round_trip() -> Result<(), Error> {
+        let decrypter = Decrypter::from_seed(&SeedPrefix::RandomSeed256X25519(vec![7; 32]))?;
+        let encrypter = decrypter.encrypter();
+        assert_eq!(encrypter.basic_prefix().derivation_code(), "C");
+
+        let cipher = encrypter.encrypt(b"hello there")?;
+        assert_eq!(cipher.len(), 11 + 48);
+        assert_eq!(decrypter.decrypt(&cipher)?, b"hello there");
+
+        let other = Decrypter::from_seed(&SeedPrefix::RandomSeed256X25519(vec![8; 32]))?;
+        assert!(other.decrypt(&cipher).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn escrow_seeds() -> Result<(), Error> {
+        // taken from KERIPY: tests/core/test_eventing.py#1512
+        let seed: SeedPrefix = "AK8F6AAiYDpXlWdj2O5F5-6wNCCNJh2A4XOlqwR_HwwH".parse()?;
+        let signer = Signer::from_seed(&seed, true)?;
+
+        // encrypt to the signing key of the escrow controller
+        let encrypter = Encrypter::from_verfer(signer.verfer())?;
+        let decrypter = Decrypter::from_seed(&seed)?;
+        assert_eq!(encrypter, decrypter.encrypter());
+
+        let cipher = encrypter.encrypt_seed(&seed)?;
+        assert!(matches!(cipher, CipherPrefix::X25519Seed(_)));
+        assert_eq!(cipher.to_str().len(), 124);
+        assert_eq!(decrypter.decrypt_seed(&cipher)?, seed);
+
+        let salt = Salter::new(b"0123456789abcdef".to_vec(), Tier::Low)?;
+        let cipher = encrypter.encrypt_seed(&salt.to_str().parse()?)?;
+        assert!(matches!(cipher, CipherPrefix::X25519Salt(_)));
+        assert_eq!(cipher.to_str().len(), 100);
+        assert_eq!(decrypter.decrypt_seed(&cipher)?.to_str(), salt.to_str());
+
+        // the cipher code must match the sealed primitive
+        let swapped = CipherPrefix::X25519Seed(cipher.derivative());
+        assert!(decrypter.decrypt_seed(&swapped).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn libsodium_compat() -> Result<(), Error> {
+        sodiumoxide::init().unwrap();
+        let (sodium_pk, sodium_sk) = sign::gen_keypair();
+        let seed = SeedPrefix::RandomSeed256Ed25519(sodium_sk[..32].to_vec());
+
+        // sealed by libsodium to the converted Ed25519 key
+        let x_pk = sign::ed25519::to_curve25519_pk(&sodium_pk).unwrap();
+        let x_sk = sign::ed25519::to_curve25519_sk(&sodium_sk).unwrap();
+        let decrypter = Decrypter::from_seed(&seed)?;
+        let cipher = sealedbox::seal(b"hello there", &x_pk);
+        assert_eq!(decrypter.decrypt(&cipher)?, b"hello there");
+
+        // and the other way around
+        let verfer = Verfer::new(Basic::Ed25519, PublicKey::new(sodium_pk.0.to_vec()));
+        let cipher = Encrypter::from_verfer(&verfer)?.encrypt(b"hello where")?;
+        assert_eq!(
+            sealedbox::open(&cipher, &x_pk, &x_sk).unwrap(),
+            b"hello where"
+        );
+        assert_eq!(
+            Encrypter::from_verfer(&verfer)?.basic_prefix().derivative(),
+            x_pk.0.to_vec()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn improper_keys() {
+        let verfer = Signer::new(
+            SelfSigning::ECDSAsecp256k1Sha256,
+            PrivateKey::new(vec![7; 32]),
+            true,
+        )
+        .unwrap()
+        .verfer()
+        .clone();
+        assert!(Encrypter::from_verfer(&verfer).is_err());
+        assert!(Decrypter::from_seed(&SeedPrefix::RandomSeed128(vec![0; 16])).is_err());
+        assert!(Decrypter::from_seed(&SeedPrefix::RandomSeed256X25519(vec![0; 31])).is_err());
+    }
+}
@@ -0,0 +1,67 @@
+use super::{PublicKey, Verfer};
+use crate::{
+    derivation::basic::Basic,
+    error::Error,
+    prefix::{BasicPrefix, CipherPrefix, Prefix, SeedPrefix},
+};
+use crypto_box::aead::OsRng;
+use curve25519_dalek::edwards::CompressedEdwardsY;
+
+/// Encrypts to an X25519 public key with libsodium sealed boxes, so only the holder of the
+/// matching private key can decrypt, e.g. when escrowing seeds and salts.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Encrypter {
+    public_key: crypto_box::PublicKey,
+}
+
+impl Encrypter {
+    pub fn new(public_key: &PublicKey) -> Result<Self, Error> {
+        let public_key = crypto_box::PublicKey::from_slice(&public_key.key()).map_err(|_| {
+            Error::SemanticError(format!(
+                "Invalid X25519 key length: {}",
+                public_key.key().len()
+            ))
+        })?;
+        Ok(Self { public_key })
+    }
+
+    /// Creates an encrypter from an X25519 key or from an Ed25519 verification key, converted to
+    /// its X25519 counterpart the way libsodium's `crypto_sign_ed25519_pk_to_curve25519` does.
+    pub fn from_verfer(verfer: &Verfer) -> Result<Self, Error> {
+        match verfer.code {
+            Basic::X25519 => Self::new(&verfer.public_key),
+            Basic::Ed25519 | Basic::Ed25519NT => {
+                let point = CompressedEdwardsY::from_slice(&verfer.public_key.key())
+                    .ok()
+                    .and_then(|y| y.decompress())
+                    .ok_or_else(|| Error::SemanticError("Invalid Ed25519 public key".into()))?;
+                Self::new(&PublicKey::new(point.to_montgomery().to_bytes().to_vec()))
+            }
+            _ => Err(Error::ImproperPrefixType),
+        }
+    }
+
+    /// The public key encrypted to, as an X25519 basic prefix.
+    pub fn basic_prefix(&self) -> BasicPrefix {
+        Basic::X25519.derive(PublicKey::new(self.public_key.as_bytes().to_vec()))
+    }
+
+    /// Seals `data` with a fresh ephemeral key, the cipher is 48 bytes longer than `data`.
+    pub fn encrypt(&self, data: &[u8]) -> Result<Vec<u8>, Error> {
+        self.public_key
+            .seal(&mut OsRng, data)
+            .map_err(|_| Error::SemanticError("Encryption failed".into()))
+    }
+
+    /// Seals the text domain encoding of a seed, either the seed of a signer or a salt.
+    pub fn encrypt_seed(&self, seed: &SeedPrefix) -> Result<CipherPrefix, Error> {
+        let cipher = self.encrypt(seed.to_str().as_bytes())?;
+        match seed {
+            SeedPrefix::RandomSeed128(_) => Ok(CipherPrefix::X25519Salt(cipher)),
+            SeedPrefix::RandomSeed256Ed25519(_)
+            | SeedPrefix::RandomSeed256ECDSAsecp256k1(_)
+            | SeedPrefix::RandomSeed256ECDSAsecp256r1(_) => Ok(CipherPrefix::X25519Seed(cipher)),
+            _ => Err(Error::ImproperPrefixType),
+        }
+    }
+}
@@ -0,0 +1,164 @@
+use super::{Signer, Verfer};
+use crate::{
+    error::Error,
+    prefix::{BasicPrefix, SelfSigningPrefix},
+};
+use core::{
+    future::{ready, Future},
+    pin::{pin, Pin},
+    task::{Context, Poll, Waker},
+};
+use std::{
+    sync::Arc,
+    task::Wake,
+    thread::{self, Thread},
+};
+
+/// Future of a signature made by an [ExternalSigner].
+pub type SignFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<SelfSigningPrefix, Error>> + Send + 'a>>;
+
+/// A signer whose private key never enters the process, e.g. a key held in a PKCS#11 module, a
+/// cloud KMS or a hardware wallet. Only the public key is known locally.
+pub trait ExternalSigner: Send + Sync {
+    /// The verfer of the public key matching the external private key.
+    fn verfer(&self) -> &Verfer;
+
+    /// Signs `data`, or a digest of it if that is what the device signs.
+    fn sign<'a>(&'a self, data: &'a [u8]) -> SignFuture<'a>;
+}
+
+/// Local signers are external signers which are always ready.
+impl ExternalSigner for Signer {
+    fn verfer(&self) -> &Verfer {
+        Signer::verfer(self)
+    }
+
+    fn sign<'a>(&'a self, data: &'a [u8]) -> SignFuture<'a> {
+        Box::pin(ready(Ok(Signer::sign(self, data))))
+    }
+}
+
+/// Gives an [ExternalSigner] the synchronous interface of a [Signer], blocking the current thread
+/// until the signature is in. Signatures are verified against the verfer before being handed out,
+/// so a misbehaving device can not slip in signatures that do not verify.
+///
+/// Blocking inside an async runtime stalls its worker, use [ExternalSigner::sign] there.
+pub struct BlockingSigner<S> {
+    signer: S,
+}
+
+impl<S: ExternalSigner> BlockingSigner<S> {
+    pub fn new(signer: S) -> Self {
+        Self { signer }
+    }
+
+    pub fn into_inner(self) -> S {
+        self.signer
+    }
+
+    pub fn sign(&self, data: &[u8]) -> Result<SelfSigningPrefix, Error> {
+        let signature = block_on(self.signer.sign(data))?;
+        if !self.verfer().verify(&signature.signature, data)? {
+            return Err(Error::FaultySignatureVerification);
+        }
+        Ok(signature)
+    }
+
+    pub fn verfer(&self) -> &Verfer {
+        self.signer.verfer()
+    }
+
+    /// The verfer encoded as a basic prefix, e.g. for the key list of an event.
+    pub fn basic_prefix(&self) -> BasicPrefix {
+        self.verfer().clone().into()
+    }
+}
+
+/// Polls `future` to completion on the current thread, parking it while the future is pending.
+fn block_on<F: Future>(future: F) -> F::Output {
+    struct ThreadWaker(Thread);
+
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark()
+        }
+    }
+
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    let mut future = pin!(future);
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::signer;
+    use core::future::poll_fn;
+    use std::{sync::Mutex, time::Duration};
+
+    /// Signs on another thread, as a remote KMS would.
+    struct Remote {
+        signer: Signer,
+        /// Signs this instead of the data asked for.
+        forge: Option<&'static [u8]>,
+    }
+
+    impl ExternalSigner for Remote {
+        fn verfer(&self) -> &Verfer {
+            self.signer.verfer()
+        }
+
+        fn sign<'a>(&'a self, data: &'a [u8]) -> SignFuture<'a> {
+            let data = self.forge.unwrap_or(data);
+            let signature = Arc::new(Mutex::new(None));
+            let mut requested = false;
+            Box::pin(poll_fn(move |cx| {
+                if let Some(signature) = signature.lock().unwrap().take() {
+                    return Poll::Ready(Ok(signature));
+                }
+                if !requested {
+                    requested = true;
+                    let (signer, data) = (self.signer.clone(), data.to_vec());
+                    let (signature, waker) = (signature.clone(), cx.waker().clone());
+                    thread::spawn(move || {
+                        thread::sleep(Duration::from_millis(10));
+                        *signature.lock().unwrap() = Some(signer.sign(&data));
+                        waker.wake();
+                    });
+                }
+                Poll::Pending
+            }))
+        }
+    }
+
+    #[test]
+    fn blocking_adapter() -> Result<(), Error> {
+        let local = BlockingSigner::new(signer(7, true));
+        let remote = BlockingSigner::new(Remote {
+            signer: signer(7, true),
+            forge: None,
+        });
+        assert_eq!(remote.basic_prefix(), local.basic_prefix());
+        assert_eq!(remote.sign(b"hello there")?, local.sign(b"hello there")?);
+        assert!(local
+            .basic_prefix()
+            .verify(b"hello there", &remote.sign(b"hello there")?)?);
+
+        let forged = BlockingSigner::new(Remote {
+            signer: signer(7, true),
+            forge: Some(b"hello where"),
+        });
+        assert!(matches!(
+            forged.sign(b"hello there"),
+            Err(Error::FaultySignatureVerification)
+        ));
+        Ok(())
+    }
+}
@@ -0,0 +1,192 @@
+use super::{Salter, Signer};
+use crate::{
+    derivation::{self_addressing::SelfAddressing, self_signing::SelfSigning},
+    error::Error,
+    prefix::{BasicPrefix, Prefix, SelfAddressingPrefix},
+};
+
+/// Public part of an establishment event keyset: the current signing keys and the digests the
+/// next keys are pre-rotated to.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Keys {
+    pub verfers: Vec<BasicPrefix>,
+    pub digers: Vec<SelfAddressingPrefix>,
+}
+
+/// Creates and rotates the keysets of a single identifier, deriving every key from a [Salter].
+///
+/// As with the `Manager` of KERIpy, the key at index `kidx` of rotation `ridx` is stretched from the
+/// path `{stem}{ridx:x}{kidx:x}`, where `kidx` counts the keys of all keysets so far. The same
+/// salt, stem and sequence of inception and rotations therefore always give the same keys.
+#[derive(Debug, Clone)]
+pub struct Manager {
+    salter: Salter,
+    code: SelfSigning,
+    digest_code: SelfAddressing,
+    stem: String,
+    temp: bool,
+    /// Keysets by rotation index, the last one is the pre-rotated next keyset.
+    keysets: Vec<Vec<Signer>>,
+    /// Index of the first key of the next keyset.
+    kidx: usize,
+}
+
+impl Manager {
+    pub fn new(salter: Salter, code: SelfSigning) -> Self {
+        Self {
+            salter,
+            code,
+            digest_code: SelfAddressing::Blake3_256,
+            stem: String::new(),
+            temp: false,
+            keysets: vec![],
+            kidx: 0,
+        }
+    }
+
+    /// Code of the next key digests, Blake3-256 by default.
+    pub fn with_digest_code(self, digest_code: SelfAddressing) -> Self {
+        Self {
+            digest_code,
+            ..self
+        }
+    }
+
+    /// Prefix of the key paths, so one salt can serve several identifiers.
+    pub fn with_stem(self, stem: &str) -> Self {
+        Self {
+            stem: stem.into(),
+            ..self
+        }
+    }
+
+    /// Stretches keys with minimal work factors, only meant for tests.
+    pub fn with_temp(self, temp: bool) -> Self {
+        Self { temp, ..self }
+    }
+
+    /// Creates the first keyset of `count` keys together with a next keyset of `ncount` keys.
+    pub fn incept(&mut self, count: usize, ncount: usize) -> Result<Keys, Error> {
+        if !self.keysets.is_empty() {
+            return Err(Error::SemanticError("Keys already incepted".into()));
+        }
+        let current = self.derive(0, count)?;
+        self.keysets.push(current);
+        let next = self.derive(1, ncount)?;
+        self.keysets.push(next);
+        Ok(self.keys())
+    }
+
+    /// Rotates to the pre-rotated next keyset and creates a new next keyset of `ncount` keys.
+    pub fn rotate(&mut self, ncount: usize) -> Result<Keys, Error> {
+        if self.keysets.is_empty() {
+            return Err(Error::SemanticError("Keys not incepted yet".into()));
+        }
+        let next = self.derive(self.keysets.len(), ncount)?;
+        self.keysets.push(next);
+        Ok(self.keys())
+    }
+
+    /// Index of the current keyset, 0 for the inception keys.
+    pub fn ridx(&self) -> Option<usize> {
+        self.keysets.len().checked_sub(2)
+    }
+
+    /// The current keys and the digests of the next keys.
+    pub fn keys(&self) -> Keys {
+        Keys {
+            verfers: self.signers().iter().map(Signer::basic_prefix).collect(),
+            digers: self
+                .keyset(self.keysets.len().saturating_sub(1))
+                .unwrap_or_default()
+                .iter()
+                .map(|signer| self.digest(&signer.basic_prefix()))
+                .collect(),
+        }
+    }
+
+    /// Signers of the current keyset.
+    pub fn signers(&self) -> &[Signer] {
+        self.ridx()
+            .and_then(|ridx| self.keyset(ridx))
+            .unwrap_or_default()
+    }
+
+    /// Signers of the keyset of rotation `ridx`, including the next keyset.
+    pub fn keyset(&self, ridx: usize) -> Option<&[Signer]> {
+        self.keysets.get(ridx).map(Vec::as_slice)
+    }
+
+    /// Digest a next key is committed to, over its qb64 encoding.
+    pub fn digest(&self, key: &BasicPrefix) -> SelfAddressingPrefix {
+        self.digest_code.derive(key.to_str().as_bytes())
+    }
+
+    fn derive(&mut self, ridx: usize, count: usize) -> Result<Vec<Signer>, Error> {
+        let path = format!("{}{:x}", self.stem, ridx);
+        let signers = self
+            .salter
+            .signers(count, self.kidx, &path, self.code, true, self.temp)?;
+        self.kidx += count;
+        Ok(signers)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keys::Tier;
+
+    fn salted() -> Result<Manager, Error> {
+        let salter = Salter::new(b"0123456789abcdef".to_vec(), Tier::Low)?;
+        Ok(Manager::new(salter, SelfSigning::Ed25519Sha512).with_temp(true))
+    }
+
+    #[test]
+    fn incept_and_rotate() -> Result<(), Error> {
+        let mut manager = salted()?;
+        assert_eq!(manager.ridx(), None);
+        assert!(manager.rotate(1).is_err());
+
+        let inception = manager.incept(2, 3)?;
+        assert_eq!(manager.ridx(), Some(0));
+        assert_eq!(inception.verfers.len(), 2);
+        assert_eq!(inception.digers.len(), 3);
+        assert!(manager.incept(1, 1).is_err());
+
+        let rotation = manager.rotate(1)?;
+        assert_eq!(manager.ridx(), Some(1));
+        assert_eq!(rotation.verfers.len(), 3);
+        assert_eq!(rotation.digers.len(), 1);
+        // the new keys are the ones committed to at inception
+        for (key, digest) in rotation.verfers.iter().zip(&inception.digers) {
+            assert!(digest.verify_binding(key.to_str().as_bytes()));
+        }
+        let sig = manager.signers()[2].sign(b"rotated");
+        assert!(rotation.verfers[2].verify(b"rotated", &sig)?);
+
+        assert_eq!(
+            manager.keyset(0).unwrap()[1].basic_prefix(),
+            inception.verfers[1]
+        );
+        assert_eq!(manager.keyset(2).unwrap().len(), 1);
+        assert!(manager.keyset(3).is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn deterministic() -> Result<(), Error> {
+        let mut manager = salted()?;
+        manager.incept(1, 1)?;
+        let keys = manager.rotate(2)?;
+
+        let mut again = salted()?;
+        again.incept(1, 1)?;
+        assert_eq!(again.rotate(2)?, keys);
+
+        let mut other = salted()?.with_stem("other");
+        other.incept(1, 1)?;
+        assert_ne!(other.rotate(2)?, keys);
+        Ok(())
+    }
+}
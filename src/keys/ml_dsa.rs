@@ -0,0 +1,125 @@
+//! ML-DSA (CRYSTALS-Dilithium, FIPS 204) keys, only usable with the `ml-dsa` feature.
+//!
+//! Private keys are the 32 byte seed the key pair is expanded from. Without the feature the
+//! codes still encode and decode but signing and verification fail.
+
+use crate::{derivation::self_signing::SelfSigning, error::Error};
+
+/// Length of the seed ML-DSA key pairs are expanded from.
+pub const SEED_LEN: usize = 32;
+
+#[cfg(feature = "ml-dsa")]
+mod imp {
+    use super::*;
+    use ml_dsa::{
+        signature::{Keypair, Signer, Verifier},
+        EncodedVerifyingKey, MlDsa44, MlDsa65, MlDsa87, MlDsaParams, Seed, Signature, SigningKey,
+        VerifyingKey,
+    };
+
+    fn signing_key<P: MlDsaParams>(seed: &[u8]) -> Result<SigningKey<P>, Error> {
+        let seed = Seed::try_from(seed).map_err(|_| {
+            Error::SemanticError(format!("Invalid ML-DSA seed length: {}", seed.len()))
+        })?;
+        Ok(SigningKey::from_seed(&seed))
+    }
+
+    fn public_key_of<P: MlDsaParams>(seed: &[u8]) -> Result<Vec<u8>, Error> {
+        Ok(signing_key::<P>(seed)?.verifying_key().encode().to_vec())
+    }
+
+    fn sign_with<P: MlDsaParams>(seed: &[u8], msg: &[u8]) -> Result<Vec<u8>, Error> {
+        let sig: Signature<P> = signing_key::<P>(seed)?.sign(msg);
+        Ok(sig.encode().to_vec())
+    }
+
+    fn verify_with<P: MlDsaParams>(key: &[u8], msg: &[u8], sig: &[u8]) -> bool {
+        let key = match EncodedVerifyingKey::<P>::try_from(key) {
+            Ok(key) => VerifyingKey::<P>::decode(&key),
+            Err(_) => return false,
+        };
+        match Signature::<P>::try_from(sig) {
+            Ok(sig) => key.verify(msg, &sig).is_ok(),
+            Err(_) => false,
+        }
+    }
+
+    pub fn public_key(code: SelfSigning, seed: &[u8]) -> Result<Vec<u8>, Error> {
+        match code {
+            SelfSigning::MLDSA44 => public_key_of::<MlDsa44>(seed),
+            SelfSigning::MLDSA65 => public_key_of::<MlDsa65>(seed),
+            SelfSigning::MLDSA87 => public_key_of::<MlDsa87>(seed),
+            _ => Err(Error::ImproperPrefixType),
+        }
+    }
+
+    pub fn sign(code: SelfSigning, seed: &[u8], msg: &[u8]) -> Result<Vec<u8>, Error> {
+        match code {
+            SelfSigning::MLDSA44 => sign_with::<MlDsa44>(seed, msg),
+            SelfSigning::MLDSA65 => sign_with::<MlDsa65>(seed, msg),
+            SelfSigning::MLDSA87 => sign_with::<MlDsa87>(seed, msg),
+            _ => Err(Error::ImproperPrefixType),
+        }
+    }
+
+    pub fn verify(code: SelfSigning, key: &[u8], msg: &[u8], sig: &[u8]) -> Result<bool, Error> {
+        match code {
+            SelfSigning::MLDSA44 => Ok(verify_with::<MlDsa44>(key, msg, sig)),
+            SelfSigning::MLDSA65 => Ok(verify_with::<MlDsa65>(key, msg, sig)),
+            SelfSigning::MLDSA87 => Ok(verify_with::<MlDsa87>(key, msg, sig)),
+            _ => Err(Error::ImproperPrefixType),
+        }
+    }
+}
+
+#[cfg(not(feature = "ml-dsa"))]
+mod imp {
+    use super::*;
+
+    fn disabled() -> Error {
+        Error::SemanticError("ML-DSA support requires the ml-dsa feature".into())
+    }
+
+    pub fn public_key(_code: SelfSigning, _seed: &[u8]) -> Result<Vec<u8>, Error> {
+        Err(disabled())
+    }
+
+    pub fn sign(_code: SelfSigning, _seed: &[u8], _msg: &[u8]) -> Result<Vec<u8>, Error> {
+        Err(disabled())
+    }
+
+    pub fn verify(
+        _code: SelfSigning,
+        _key: &[u8],
+        _msg: &[u8],
+        _sig: &[u8],
+    ) -> Result<bool, Error> {
+        Err(disabled())
+    }
+}
+
+pub(crate) use imp::{public_key, sign, verify};
+
+#[cfg(all(test, feature = "ml-dsa"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sign_and_verify() -> Result<(), Error> {
+        for (code, key_len, sig_len) in [
+            (SelfSigning::MLDSA44, 1312, 2420),
+            (SelfSigning::MLDSA65, 1952, 3309),
+            (SelfSigning::MLDSA87, 2592, 4627),
+        ] {
+            let key = public_key(code, &[7; SEED_LEN])?;
+            assert_eq!(key.len(), key_len);
+            let sig = sign(code, &[7; SEED_LEN], b"hello there")?;
+            assert_eq!(sig.len(), sig_len);
+            assert!(verify(code, &key, b"hello there", &sig)?);
+            assert!(!verify(code, &key, b"hello where", &sig)?);
+            assert!(!verify(code, &key[1..], b"hello there", &sig)?);
+        }
+        assert!(public_key(SelfSigning::MLDSA44, &[7; 31]).is_err());
+        Ok(())
+    }
+}
@@ -1,9 +1,34 @@
-use crate::error::Error;
+use crate::{derivation::self_signing::SelfSigning, error::Error, prefix::SeedPrefix};
+use core::fmt;
 use ed25519_dalek::{ExpandedSecretKey, SecretKey};
 use k256::ecdsa::{signature::Signer as EcdsaSigner, Signature as EcdsaSignature, SigningKey};
 use k256::ecdsa::{signature::Verifier as EcdsaVerifier, VerifyingKey};
+#[cfg(feature = "zeroize")]
 use zeroize::Zeroize;
 
+pub mod decrypter;
+pub mod encrypter;
+pub mod external;
+pub mod formats;
+pub mod manager;
+pub mod ml_dsa;
+pub mod salter;
+pub mod signer;
+pub mod store;
+pub mod verfer;
+
+pub use decrypter::Decrypter;
+pub use encrypter::Encrypter;
+pub use external::{BlockingSigner, ExternalSigner, SignFuture};
+pub use formats::Jwk;
+pub use manager::{Keys, Manager};
+pub use salter::{fresh_nonce, KeyPath, SaltCode, Salter, Tier};
+pub use signer::Signer;
+pub use store::{FileKeyStore, KeyStore};
+#[cfg(feature = "rayon")]
+pub use verfer::par_verify;
+pub use verfer::{verify_batch, Verfer};
+
 #[derive(Debug, Clone, PartialEq, Hash)]
 pub struct PublicKey {
     public_key: Vec<u8>,
@@ -36,12 +61,44 @@ impl PublicKey {
         }
     }
 
+    pub fn verify_ed448(&self, msg: &[u8], sig: &[u8]) -> bool {
+        use ed448_goldilocks_plus::{Signature, VerifyingKey};
+        let key = match <[u8; 57]>::try_from(self.public_key.as_slice()) {
+            Ok(bytes) => VerifyingKey::from_bytes(&bytes),
+            Err(_) => return false,
+        };
+        match (key, Signature::try_from(sig)) {
+            (Ok(key), Ok(sig)) => key.verify_raw(&sig, msg).is_ok(),
+            _ => false,
+        }
+    }
+
+    pub fn verify_ecdsa_p256(&self, msg: &[u8], sig: &[u8]) -> bool {
+        use p256::ecdsa::{signature::Verifier, Signature, VerifyingKey};
+        match (
+            VerifyingKey::from_sec1_bytes(&self.public_key),
+            Signature::try_from(sig),
+        ) {
+            (Ok(key), Ok(sig)) => key.verify(msg, &sig).is_ok(),
+            _ => false,
+        }
+    }
+
+    pub fn verify_ml_dsa(&self, code: SelfSigning, msg: &[u8], sig: &[u8]) -> Result<bool, Error> {
+        ml_dsa::verify(code, &self.public_key, msg, sig)
+    }
+
+    /// Verifies a 64 byte secp256k1 signature over the SHA-256 digest of `msg`. High-S signatures
+    /// are normalized first, signers like keripy do not normalize theirs.
     pub fn verify_ecdsa(&self, msg: &[u8], sig: &[u8]) -> bool {
         match VerifyingKey::from_sec1_bytes(&self.key()) {
             Ok(k) => {
                 use k256::ecdsa::Signature;
                 use std::convert::TryFrom;
-                if let Ok(sig) = Signature::try_from(sig) {
+                if let Ok(mut sig) = Signature::try_from(sig) {
+                    if sig.normalize_s().is_err() {
+                        return false;
+                    }
                     match k.verify(msg, &sig) {
                         Ok(()) => true,
                         Err(_) => false,
@@ -55,21 +112,41 @@ impl PublicKey {
     }
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(PartialEq, Clone)]
 pub struct PrivateKey {
     key: Vec<u8>,
 }
 
+/// Redacted, the key never ends up in logs.
+impl fmt::Debug for PrivateKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PrivateKey").finish_non_exhaustive()
+    }
+}
+
 impl PrivateKey {
     pub fn new(key: Vec<u8>) -> Self {
         Self { key }
     }
 
+    /// Signs the SHA-256 digest of `msg` with a secp256k1 key, the signature is the 64 byte
+    /// concatenation of `r` and a low-S normalized `s`.
     pub fn sign_ecdsa(&self, msg: &[u8]) -> Result<Vec<u8>, Error> {
-        let sig: EcdsaSignature = EcdsaSigner::sign(&SigningKey::from_bytes(&self.key)?, msg);
+        let mut sig: EcdsaSignature = EcdsaSigner::sign(&SigningKey::from_bytes(&self.key)?, msg);
+        sig.normalize_s()?;
         Ok(sig.as_ref().to_vec())
     }
 
+    pub fn sign_ecdsa_p256(&self, msg: &[u8]) -> Result<Vec<u8>, Error> {
+        use p256::ecdsa::{signature::Signer, Signature, SigningKey};
+        let sig: Signature = SigningKey::from_bytes(&self.key)?.sign(msg);
+        Ok(sig.as_ref().to_vec())
+    }
+
+    pub fn sign_ml_dsa(&self, code: SelfSigning, msg: &[u8]) -> Result<Vec<u8>, Error> {
+        ml_dsa::sign(code, &self.key, msg)
+    }
+
     pub fn sign_ed(&self, msg: &[u8]) -> Result<Vec<u8>, Error> {
         let sk = SecretKey::from_bytes(&self.key)?;
         let pk = ed25519_dalek::PublicKey::from(&sk);
@@ -79,14 +156,84 @@ impl PrivateKey {
             .to_vec())
     }
 
+    pub fn sign_ed448(&self, msg: &[u8]) -> Result<Vec<u8>, Error> {
+        Ok(ed448_signing_key(&self.key)?
+            .sign_raw(msg)
+            .to_bytes()
+            .to_vec())
+    }
+
     pub fn key(&self) -> Vec<u8> {
         self.key.clone()
     }
+
+    /// Borrows the key, unlike [PrivateKey::key] this leaves no copy behind to wipe.
+    pub(crate) fn as_bytes(&self) -> &[u8] {
+        &self.key
+    }
 }
 
+/// Generates an Ed25519 key pair from the OS random number generator. The public key becomes a
+/// prefix with `Basic::Ed25519.derive`.
+pub fn generate_ed25519() -> (PublicKey, PrivateKey) {
+    let secret = SecretKey::generate(&mut rand::rngs::OsRng);
+    ed25519_key_pair(&secret)
+}
+
+/// Generates a secp256k1 key pair from the OS random number generator, the public key in its
+/// compressed form for `Basic::ECDSAsecp256k1.derive`.
+pub fn generate_secp256k1() -> (PublicKey, PrivateKey) {
+    let secret = SigningKey::random(&mut crypto_box::aead::OsRng);
+    (
+        PublicKey::new(VerifyingKey::from(&secret).to_bytes().to_vec()),
+        PrivateKey::new(secret.to_bytes().to_vec()),
+    )
+}
+
+/// The Ed25519 key pair of `seed`, either an Ed25519 seed or a plain 256 bit seed.
+pub fn from_seed(seed: &SeedPrefix) -> Result<(PublicKey, PrivateKey), Error> {
+    match seed {
+        SeedPrefix::RandomSeed256Ed25519(raw) | SeedPrefix::RandomSeed256(raw) => {
+            Ok(ed25519_key_pair(&SecretKey::from_bytes(raw)?))
+        }
+        _ => Err(Error::ImproperPrefixType),
+    }
+}
+
+fn ed25519_key_pair(secret: &SecretKey) -> (PublicKey, PrivateKey) {
+    (
+        PublicKey::new(ed25519_dalek::PublicKey::from(secret).to_bytes().to_vec()),
+        PrivateKey::new(secret.to_bytes().to_vec()),
+    )
+}
+
+/// Wipes transient secret material, e.g. a key converted from a seed, once it has been used.
+/// Without the `zeroize` feature the material is only dropped.
+#[cfg(feature = "zeroize")]
+pub(crate) fn wipe(mut secret: Vec<u8>) {
+    secret.zeroize()
+}
+
+#[cfg(not(feature = "zeroize"))]
+pub(crate) fn wipe(_secret: Vec<u8>) {}
+
+/// Ed448 signing key of a 57 byte private key.
+pub(crate) fn ed448_signing_key(key: &[u8]) -> Result<ed448_goldilocks_plus::SigningKey, Error> {
+    ed448_goldilocks_plus::SigningKey::try_from(key)
+        .map_err(|e| Error::SemanticError(format!("Invalid Ed448 key: {}", e)))
+}
+
+#[cfg(feature = "zeroize")]
+impl Zeroize for PrivateKey {
+    fn zeroize(&mut self) {
+        self.key.zeroize()
+    }
+}
+
+#[cfg(feature = "zeroize")]
 impl Drop for PrivateKey {
     fn drop(&mut self) {
-        self.key.zeroize()
+        self.zeroize()
     }
 }
 
@@ -115,7 +262,7 @@ fn libsodium_to_ed25519_dalek_compat() {
     let sodium_sig = sign::sign(msg, &sodium_sk);
 
     assert!(sign::verify_detached(
-        &sign::ed25519::Signature::new(dalek_sig.to_bytes()),
+        &sign::ed25519::Signature::from(dalek_sig.to_bytes()),
         msg,
         &sodium_pk
     ));
@@ -123,7 +270,83 @@ fn libsodium_to_ed25519_dalek_compat() {
     assert!(kp
         .verify(
             msg,
-            &Signature::new(arrayref::array_ref!(sodium_sig, 0, 64).to_owned())
+            &Signature::from(arrayref::array_ref!(sodium_sig, 0, 64).to_owned())
         )
         .is_ok());
 }
+
+#[cfg(feature = "zeroize")]
+#[test]
+fn zeroize_secrets() -> Result<(), Error> {
+    use crate::prefix::{Prefix, SeedPrefix};
+
+    let mut key = PrivateKey::new(vec![7; 32]);
+    key.zeroize();
+    assert!(key.key().is_empty());
+
+    let mut seed = SeedPrefix::RandomSeed256Ed25519(vec![7; 32]);
+    seed.zeroize();
+    assert!(seed.derivative().is_empty());
+
+    let mut salter = Salter::new(vec![7; 16], Tier::Low)?;
+    salter.zeroize();
+    assert!(salter.derivative().is_empty());
+    Ok(())
+}
+
+#[test]
+fn ed25519_key_pairs() -> Result<(), Error> {
+    use crate::{derivation::basic::Basic, prefix::Prefix};
+    use core::str::FromStr;
+
+    let (public_key, private_key) = generate_ed25519();
+    let prefix = Basic::Ed25519.derive(public_key);
+    let signature = crate::prefix::SelfSigningPrefix::new_unchecked(
+        SelfSigning::Ed25519Sha512,
+        private_key.sign_ed(b"data")?,
+    );
+    assert!(prefix.verify(b"data", &signature)?);
+    assert_ne!(generate_ed25519().1, private_key);
+
+    let seed = SeedPrefix::from_str("AK8F6AAiYDpXlWdj2O5F5-6wNCCNJh2A4XOlqwR_HwwH")?;
+    let (public_key, private_key) = from_seed(&seed)?;
+    assert_eq!(
+        Basic::Ed25519.derive(public_key).to_str(),
+        "DErocgXD2RGSyvn3MObcx59jeOsEQhv2TqHirVkzrp0Q"
+    );
+    assert_eq!(
+        from_seed(&SeedPrefix::RandomSeed256(seed.derivative()))?.1,
+        private_key
+    );
+    assert!(from_seed(&SeedPrefix::RandomSeed256ECDSAsecp256k1(vec![7; 32])).is_err());
+    Ok(())
+}
+
+#[test]
+fn secp256k1_low_s() -> Result<(), Error> {
+    use crate::{derivation::basic::Basic, prefix::SelfSigningPrefix};
+    use k256::ecdsa::Signature;
+    use std::convert::TryFrom;
+
+    let (public_key, private_key) = generate_secp256k1();
+    let prefix = Basic::ECDSAsecp256k1.derive(public_key.clone());
+    assert_eq!(prefix.public_key.key().len(), 33);
+    for i in 0..16u8 {
+        let msg = [i; 8];
+        let sig = private_key.sign_ecdsa(&msg)?;
+        assert_eq!(sig.len(), 64);
+        let parsed = Signature::try_from(&sig[..])?;
+        assert!(!bool::from(parsed.s().is_high()));
+        assert!(prefix.verify(
+            &msg,
+            &SelfSigningPrefix::new_unchecked(SelfSigning::ECDSAsecp256k1Sha256, sig.clone())
+        )?);
+
+        // the same signature with a high s, as OpenSSL based signers may make it
+        let high = Signature::from_scalars(*parsed.r(), -*parsed.s())?;
+        assert!(bool::from(high.s().is_high()));
+        assert!(public_key.verify_ecdsa(&msg, high.as_ref()));
+        assert!(!public_key.verify_ecdsa(b"other", high.as_ref()));
+    }
+    Ok(())
+}
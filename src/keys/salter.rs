@@ -0,0 +1,393 @@
+use super::{ml_dsa, PrivateKey, Signer};
+use crate::{
+    derivation::self_signing::SelfSigning,
+    error::Error,
+    prefix::{Prefix, SeedPrefix},
+};
+use argon2::{Algorithm, Argon2, Params, Version};
+use core::{fmt, str::FromStr};
+#[cfg(feature = "zeroize")]
+use zeroize::Zeroize;
+
+/// Security tier of the key stretch, trading derivation time and memory for resistance to brute
+/// forcing. The parameters match the libsodium `INTERACTIVE`, `MODERATE` and `SENSITIVE` limits.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Default)]
+pub enum Tier {
+    #[default]
+    Low,
+    Med,
+    High,
+}
+
+impl Tier {
+    /// Argon2 iterations and memory in KiB.
+    fn params(&self) -> (u32, u32) {
+        match self {
+            Self::Low => (2, 64 * 1024),
+            Self::Med => (3, 256 * 1024),
+            Self::High => (4, 1024 * 1024),
+        }
+    }
+}
+
+/// Strength of a random salt or nonce.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Default)]
+pub enum SaltCode {
+    /// `0A` 128 bits
+    #[default]
+    Salt128,
+    /// `a` 256 bits
+    Salt256,
+}
+
+impl SaltCode {
+    pub fn raw_len(&self) -> usize {
+        match self {
+            Self::Salt128 => 16,
+            Self::Salt256 => 32,
+        }
+    }
+}
+
+/// A fresh random nonce under `code`, e.g. for challenge/response.
+pub fn fresh_nonce(code: SaltCode) -> SeedPrefix {
+    let raw = random_bytes(code.raw_len());
+    match code {
+        SaltCode::Salt128 => SeedPrefix::RandomSeed128(raw),
+        SaltCode::Salt256 => SeedPrefix::RandomSeed256(raw),
+    }
+}
+
+fn random_bytes(len: usize) -> Vec<u8> {
+    use rand::{rngs::OsRng, RngCore};
+
+    let mut raw = vec![0u8; len];
+    OsRng.fill_bytes(&mut raw);
+    raw
+}
+
+/// A hierarchical key path of components joined by `/`, e.g. `my-aid/0/0`. It is stretched
+/// as it is written, so any implementation given the same salt, path and tier derives the same
+/// key.
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct KeyPath(String);
+
+impl KeyPath {
+    /// The path of the single component `root`.
+    pub fn new(root: &str) -> Result<Self, Error> {
+        root.parse()
+    }
+
+    /// The path of the child at `index`, in hex as key indices are in flat paths.
+    pub fn child(&self, index: usize) -> Self {
+        Self(format!("{}/{:x}", self.0, index))
+    }
+
+    /// The path with `component` appended.
+    pub fn join(&self, component: &str) -> Result<Self, Error> {
+        [self.as_str(), component].join("/").parse()
+    }
+
+    pub fn components(&self) -> impl Iterator<Item = &str> {
+        self.0.split('/')
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// Fails on empty components, e.g. of a leading, trailing or doubled `/`.
+impl FromStr for KeyPath {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.split('/').any(str::is_empty) {
+            return Err(Error::SemanticError(format!(
+                "Empty component in key path {:?}",
+                s
+            )));
+        }
+        Ok(Self(s.into()))
+    }
+}
+
+impl AsRef<str> for KeyPath {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for KeyPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// A 128 bit salt (code `0A`) from which keypairs are derived deterministically.<br>
+/// Each key is stretched from the salt and a path with Argon2id, so the same salt, path and tier
+/// always give the same key, as with the `Salter` of KERIpy.
+#[derive(PartialEq, Clone)]
+pub struct Salter {
+    raw: Vec<u8>,
+    pub tier: Tier,
+}
+
+impl Salter {
+    pub const SALT_LEN: usize = 16;
+
+    pub fn new(raw: Vec<u8>, tier: Tier) -> Result<Self, Error> {
+        if raw.len() != Self::SALT_LEN {
+            return Err(Error::SemanticError(format!(
+                "Salt must be {} bytes, got {}",
+                Self::SALT_LEN,
+                raw.len()
+            )));
+        }
+        Ok(Self { raw, tier })
+    }
+
+    /// A fresh random salt.
+    pub fn random(tier: Tier) -> Self {
+        Self {
+            raw: random_bytes(Self::SALT_LEN),
+            tier,
+        }
+    }
+
+    /// Stretches the salt into `size` bytes of key material for `path`.<br>
+    /// `temp` uses minimal work factors, only meant for tests.
+    pub fn stretch(
+        &self,
+        size: usize,
+        path: &str,
+        tier: Tier,
+        temp: bool,
+    ) -> Result<Vec<u8>, Error> {
+        let (t_cost, m_cost) = if temp { (1, 8) } else { tier.params() };
+        let params = Params::new(m_cost, t_cost, 1, Some(size))
+            .map_err(|e| Error::SemanticError(format!("Invalid stretch parameters: {}", e)))?;
+        let mut key = vec![0; size];
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+            .hash_password_into(path.as_bytes(), &self.raw, &mut key)
+            .map_err(|e| Error::SemanticError(format!("Key stretch failed: {}", e)))?;
+        Ok(key)
+    }
+
+    /// Derives the signer at `path`, e.g. the [KeyPath] `my-aid/0/0`, stretched in `tier` or
+    /// else in the tier of the salter.
+    pub fn signer(
+        &self,
+        code: SelfSigning,
+        transferable: bool,
+        path: impl AsRef<str>,
+        tier: Option<Tier>,
+        temp: bool,
+    ) -> Result<Signer, Error> {
+        let size = match code {
+            SelfSigning::Ed25519Sha512
+            | SelfSigning::ECDSAsecp256k1Sha256
+            | SelfSigning::ECDSAsecp256r1Sha256 => 32,
+            SelfSigning::Ed448 => 57,
+            SelfSigning::MLDSA44 | SelfSigning::MLDSA65 | SelfSigning::MLDSA87 => ml_dsa::SEED_LEN,
+        };
+        let key = self.stretch(size, path.as_ref(), tier.unwrap_or(self.tier), temp)?;
+        Signer::new(code, PrivateKey::new(key), transferable)
+    }
+
+    /// Derives `count` signers at consecutive paths `{path}{i:x}` starting from index `start`.
+    pub fn signers(
+        &self,
+        count: usize,
+        start: usize,
+        path: &str,
+        code: SelfSigning,
+        transferable: bool,
+        temp: bool,
+    ) -> Result<Vec<Signer>, Error> {
+        (start..start + count)
+            .map(|i| {
+                let path = format!("{}{:x}", path, i);
+                self.signer(code, transferable, &path, None, temp)
+            })
+            .collect()
+    }
+}
+
+impl TryFrom<SeedPrefix> for Salter {
+    type Error = Error;
+
+    fn try_from(mut seed: SeedPrefix) -> Result<Self, Self::Error> {
+        match &mut seed {
+            SeedPrefix::RandomSeed128(raw) => Self::new(std::mem::take(raw), Tier::default()),
+            _ => Err(Error::ImproperPrefixType),
+        }
+    }
+}
+
+impl FromStr for Salter {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        SeedPrefix::from_str(s)?.try_into()
+    }
+}
+
+impl Prefix for Salter {
+    fn derivative(&self) -> Vec<u8> {
+        self.raw.clone()
+    }
+    fn derivation_code(&self) -> String {
+        "0A".into()
+    }
+}
+
+/// Redacted to the code and tier, the salt never ends up in logs.
+impl fmt::Debug for Salter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Salter")
+            .field("code", &self.derivation_code())
+            .field("tier", &self.tier)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl Zeroize for Salter {
+    fn zeroize(&mut self) {
+        self.raw.zeroize()
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl Drop for Salter {
+    fn drop(&mut self) {
+        self.zeroize()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RAW: &[u8] = b"0123456789abcdef";
+
+    #[test]
+    fn encoding() -> Result<(), Error> {
+        // taken from KERIPY: tests/core/test_coring.py test_salter
+        let salter = Salter::new(RAW.to_vec(), Tier::Low)?;
+        assert_eq!(salter.to_str(), "0AAwMTIzNDU2Nzg5YWJjZGVm");
+        assert_eq!(Salter::from_str("0AAwMTIzNDU2Nzg5YWJjZGVm")?, salter);
+
+        assert!(Salter::new(vec![0; 15], Tier::Low).is_err());
+        assert!(Salter::from_str("AK8F6AAiYDpXlWdj2O5F5-6wNCCNJh2A4XOlqwR_HwwH").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn random() -> Result<(), Error> {
+        let salter = Salter::random(Tier::Med);
+        assert_eq!(salter.to_str().len(), 24);
+        assert_eq!(Salter::from_str(&salter.to_str())?.raw, salter.raw);
+        assert_ne!(Salter::random(Tier::Med), salter);
+
+        let nonce = fresh_nonce(SaltCode::Salt128);
+        assert_eq!(&nonce.to_str()[..2], "0A");
+        assert_eq!(nonce.to_str().len(), 24);
+        let nonce = fresh_nonce(SaltCode::Salt256);
+        assert_eq!(&nonce.to_str()[..1], "a");
+        assert_eq!(nonce.to_str().len(), 44);
+        assert_eq!(SeedPrefix::from_str(&nonce.to_str())?, nonce);
+        assert_ne!(fresh_nonce(SaltCode::Salt256), nonce);
+        Ok(())
+    }
+
+    #[test]
+    fn stretch_matches_libsodium() -> Result<(), Error> {
+        use sodiumoxide::crypto::pwhash::argon2id13;
+        sodiumoxide::init().unwrap();
+
+        let salter = Salter::new(RAW.to_vec(), Tier::Low)?;
+        let mut expected = [0u8; 32];
+        argon2id13::derive_key(
+            &mut expected,
+            b"01",
+            &argon2id13::Salt::from_slice(RAW).unwrap(),
+            argon2id13::OpsLimit(1),
+            argon2id13::MemLimit(8192),
+        )
+        .unwrap();
+        assert_eq!(salter.stretch(32, "01", Tier::Low, true)?, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn derive_signers() -> Result<(), Error> {
+        let salter = Salter::new(RAW.to_vec(), Tier::Low)?;
+        let signer = salter.signer(SelfSigning::Ed25519Sha512, true, "01", None, true)?;
+        assert_eq!(
+            signer,
+            salter.signer(SelfSigning::Ed25519Sha512, true, "01", None, true)?
+        );
+        assert_ne!(
+            signer.basic_prefix(),
+            salter
+                .signer(SelfSigning::Ed25519Sha512, true, "02", None, true)?
+                .basic_prefix()
+        );
+
+        let signers =
+            salter.signers(3, 0, "acct", SelfSigning::ECDSAsecp256k1Sha256, false, true)?;
+        assert_eq!(signers.len(), 3);
+        assert_eq!(
+            signers[1],
+            salter.signer(
+                SelfSigning::ECDSAsecp256k1Sha256,
+                false,
+                "acct1",
+                None,
+                true
+            )?
+        );
+        let sig = signers[2].sign(b"hello there");
+        assert!(signers[2].basic_prefix().verify(b"hello there", &sig)?);
+        Ok(())
+    }
+
+    #[test]
+    fn key_paths() -> Result<(), Error> {
+        let path = KeyPath::new("my-aid")?.child(0).child(10);
+        assert_eq!(path.to_string(), "my-aid/0/a");
+        assert_eq!(path, "my-aid/0/a".parse()?);
+        assert_eq!(path.components().collect::<Vec<_>>(), ["my-aid", "0", "a"]);
+        assert_eq!(KeyPath::new("my-aid")?.join("0")?.join("a")?, path);
+        for bad in ["", "/my-aid", "my-aid/", "my-aid//0"] {
+            assert!(KeyPath::from_str(bad).is_err(), "{:?}", bad);
+        }
+        assert!(KeyPath::new("my-aid")?.join("").is_err());
+
+        // the path is stretched as written
+        let salter = Salter::new(RAW.to_vec(), Tier::Med)?;
+        let signer = salter.signer(SelfSigning::Ed25519Sha512, true, &path, None, true)?;
+        let seed = salter.stretch(32, "my-aid/0/a", Tier::Med, true)?;
+        assert_eq!(
+            signer,
+            Signer::new(SelfSigning::Ed25519Sha512, PrivateKey::new(seed), true)?
+        );
+
+        // in the tier given over that of the salter, which only a non-temp stretch uses
+        let signer = salter.signer(
+            SelfSigning::Ed25519Sha512,
+            true,
+            &path,
+            Some(Tier::Low),
+            false,
+        )?;
+        let seed = salter.stretch(32, "my-aid/0/a", Tier::Low, false)?;
+        assert_eq!(
+            signer,
+            Signer::new(SelfSigning::Ed25519Sha512, PrivateKey::new(seed), true)?
+        );
+        Ok(())
+    }
+}
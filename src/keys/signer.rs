@@ -0,0 +1,288 @@
+use super::{ed448_signing_key, ml_dsa, wipe, PrivateKey, PublicKey, Verfer};
+use crate::{
+    derivation::{basic::Basic, self_signing::SelfSigning},
+    error::Error,
+    prefix::{BasicPrefix, SeedPrefix, SelfSigningPrefix},
+};
+
+/// A signer: a private key together with the signature scheme it signs with and the [Verfer] of
+/// the matching public key.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Signer {
+    pub code: SelfSigning,
+    private_key: PrivateKey,
+    verfer: Verfer,
+}
+
+impl Signer {
+    /// Creates a signer for `private_key`, deriving the public key of the verfer from it.
+    /// The verfer gets the transferable or non-transferable basic code matching the scheme.
+    pub fn new(
+        code: SelfSigning,
+        private_key: PrivateKey,
+        transferable: bool,
+    ) -> Result<Self, Error> {
+        let (basic, public_key) = match code {
+            SelfSigning::Ed25519Sha512 => {
+                let secret = ed25519_dalek::SecretKey::from_bytes(private_key.as_bytes())?;
+                let public_key = ed25519_dalek::PublicKey::from(&secret);
+                (Basic::Ed25519, public_key.as_bytes().to_vec())
+            }
+            SelfSigning::ECDSAsecp256k1Sha256 => {
+                let sk = k256::ecdsa::SigningKey::from_bytes(private_key.as_bytes())?;
+                (
+                    Basic::ECDSAsecp256k1,
+                    k256::ecdsa::VerifyingKey::from(&sk).to_bytes().to_vec(),
+                )
+            }
+            SelfSigning::ECDSAsecp256r1Sha256 => {
+                let sk = p256::ecdsa::SigningKey::from_bytes(private_key.as_bytes())?;
+                (
+                    Basic::ECDSAsecp256r1,
+                    sk.verifying_key()
+                        .to_encoded_point(true)
+                        .as_bytes()
+                        .to_vec(),
+                )
+            }
+            SelfSigning::MLDSA44 | SelfSigning::MLDSA65 | SelfSigning::MLDSA87 => (
+                match code {
+                    SelfSigning::MLDSA44 => Basic::MLDSA44,
+                    SelfSigning::MLDSA65 => Basic::MLDSA65,
+                    _ => Basic::MLDSA87,
+                },
+                ml_dsa::public_key(code, private_key.as_bytes())?,
+            ),
+            SelfSigning::Ed448 => {
+                let sk = ed448_signing_key(private_key.as_bytes())?;
+                (Basic::Ed448, sk.verifying_key().to_bytes().to_vec())
+            }
+        };
+        let basic = basic
+            .with_transferability(transferable)
+            .ok_or(Error::ImproperPrefixType)?;
+        Ok(Self {
+            code,
+            private_key,
+            verfer: Verfer::new(basic, PublicKey::new(public_key)),
+        })
+    }
+
+    /// Creates a signer from a seed, the private key encoded as a CESR primitive.
+    pub fn from_seed(seed: &SeedPrefix, transferable: bool) -> Result<Self, Error> {
+        let (_, private_key) = seed.derive_key_pair()?;
+        let code = match seed {
+            SeedPrefix::RandomSeed256Ed25519(_) => SelfSigning::Ed25519Sha512,
+            SeedPrefix::RandomSeed256ECDSAsecp256k1(_) => SelfSigning::ECDSAsecp256k1Sha256,
+            SeedPrefix::RandomSeed256ECDSAsecp256r1(_) => SelfSigning::ECDSAsecp256r1Sha256,
+            SeedPrefix::RandomSeed448(_) => SelfSigning::Ed448,
+            _ => return Err(Error::ImproperPrefixType),
+        };
+        Self::new(code, private_key, transferable)
+    }
+
+    /// The private key encoded as a seed, the inverse of [Signer::from_seed].
+    pub fn seed(&self) -> Result<SeedPrefix, Error> {
+        let key = self.private_key.key();
+        match self.code {
+            SelfSigning::Ed25519Sha512 => Ok(SeedPrefix::RandomSeed256Ed25519(key)),
+            SelfSigning::ECDSAsecp256k1Sha256 => Ok(SeedPrefix::RandomSeed256ECDSAsecp256k1(key)),
+            SelfSigning::ECDSAsecp256r1Sha256 => Ok(SeedPrefix::RandomSeed256ECDSAsecp256r1(key)),
+            SelfSigning::Ed448 => Ok(SeedPrefix::RandomSeed448(key)),
+            SelfSigning::MLDSA44 | SelfSigning::MLDSA65 | SelfSigning::MLDSA87 => {
+                wipe(key);
+                Err(Error::ImproperPrefixType)
+            }
+        }
+    }
+
+    /// Creates a signer for a freshly generated random private key.
+    pub fn random(code: SelfSigning, transferable: bool) -> Result<Self, Error> {
+        use rand::{rngs::OsRng, RngCore};
+
+        let size = match code {
+            SelfSigning::Ed448 => 57,
+            SelfSigning::MLDSA44 | SelfSigning::MLDSA65 | SelfSigning::MLDSA87 => ml_dsa::SEED_LEN,
+            _ => 32,
+        };
+        let mut key = vec![0u8; size];
+        OsRng.fill_bytes(&mut key);
+        Self::new(code, PrivateKey::new(key), transferable)
+    }
+
+    /// Signs `data`, the key was validated when the signer was created so signing can not fail.
+    pub fn sign(&self, data: &[u8]) -> SelfSigningPrefix {
+        let signature = match self.code {
+            SelfSigning::Ed25519Sha512 => self.private_key.sign_ed(data),
+            SelfSigning::ECDSAsecp256k1Sha256 => self.private_key.sign_ecdsa(data),
+            SelfSigning::ECDSAsecp256r1Sha256 => self.private_key.sign_ecdsa_p256(data),
+            SelfSigning::Ed448 => self.private_key.sign_ed448(data),
+            SelfSigning::MLDSA44 | SelfSigning::MLDSA65 | SelfSigning::MLDSA87 => {
+                self.private_key.sign_ml_dsa(self.code, data)
+            }
+        };
+        self.code
+            .derive(signature.expect("private key validated on signer creation"))
+    }
+
+    pub fn verfer(&self) -> &Verfer {
+        &self.verfer
+    }
+
+    /// The verfer encoded as a basic prefix, e.g. for the key list of an event.
+    pub fn basic_prefix(&self) -> BasicPrefix {
+        self.verfer.clone().into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prefix::Prefix;
+
+    #[test]
+    fn sign_and_verify() -> Result<(), Error> {
+        for (code, transferable, basic) in [
+            (SelfSigning::Ed25519Sha512, true, Basic::Ed25519),
+            (SelfSigning::Ed25519Sha512, false, Basic::Ed25519NT),
+            (
+                SelfSigning::ECDSAsecp256k1Sha256,
+                true,
+                Basic::ECDSAsecp256k1,
+            ),
+            (
+                SelfSigning::ECDSAsecp256k1Sha256,
+                false,
+                Basic::ECDSAsecp256k1NT,
+            ),
+            (
+                SelfSigning::ECDSAsecp256r1Sha256,
+                true,
+                Basic::ECDSAsecp256r1,
+            ),
+            (
+                SelfSigning::ECDSAsecp256r1Sha256,
+                false,
+                Basic::ECDSAsecp256r1NT,
+            ),
+            (SelfSigning::Ed448, true, Basic::Ed448),
+            (SelfSigning::Ed448, false, Basic::Ed448NT),
+        ] {
+            let signer = Signer::random(code, transferable)?;
+            assert_eq!(signer.verfer().code, basic);
+
+            let sig = signer.sign(b"hello there");
+            assert_eq!(sig.derivation, code);
+            assert!(signer.basic_prefix().verify(b"hello there", &sig)?);
+            assert!(!signer.basic_prefix().verify(b"hello where", &sig)?);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn from_seed() -> Result<(), Error> {
+        // taken from KERIPY: tests/core/test_eventing.py#1512
+        let seed: SeedPrefix = "AK8F6AAiYDpXlWdj2O5F5-6wNCCNJh2A4XOlqwR_HwwH".parse()?;
+        let signer = Signer::from_seed(&seed, true)?;
+        assert_eq!(
+            signer.basic_prefix().to_str(),
+            "DErocgXD2RGSyvn3MObcx59jeOsEQhv2TqHirVkzrp0Q"
+        );
+        let sig = signer.sign(b"hello there");
+        assert!(signer.verfer().verify(&sig.signature, b"hello there")?);
+        assert_eq!(signer.seed()?, seed);
+        Ok(())
+    }
+
+    #[test]
+    fn redacted() -> Result<(), Error> {
+        let seed: SeedPrefix = "AK8F6AAiYDpXlWdj2O5F5-6wNCCNJh2A4XOlqwR_HwwH".parse()?;
+        let signer = Signer::from_seed(&seed, true)?;
+        let secret = &seed.to_str()[1..];
+        assert_eq!(format!("{:?}", seed), r#"SeedPrefix { code: "A", .. }"#);
+        assert!(!format!("{:?}", signer).contains(secret));
+        let (_, private_key) = seed.derive_key_pair()?;
+        assert_eq!(format!("{:?}", private_key), "PrivateKey { .. }");
+
+        // public material shows in the text domain
+        let verfer = signer.basic_prefix().to_str();
+        assert_eq!(signer.verfer().to_string(), verfer);
+        assert_eq!(format!("{}", signer.basic_prefix()), verfer);
+        assert_eq!(
+            signer.sign(b"hello").to_string(),
+            signer.sign(b"hello").to_str()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn p256_seed() -> Result<(), Error> {
+        let seed: SeedPrefix = SeedPrefix::RandomSeed256ECDSAsecp256r1(vec![7; 32])
+            .to_str()
+            .parse()?;
+        let signer = Signer::from_seed(&seed, false)?;
+        let prefix = signer.basic_prefix();
+        assert_eq!(&prefix.to_str()[..4], "1AAI");
+        assert_eq!(prefix.to_str().len(), 48);
+
+        let sig = signer.sign(b"hello there");
+        assert_eq!(&sig.to_str()[..2], "0I");
+        let prefix: BasicPrefix = prefix.to_str().parse()?;
+        let sig: SelfSigningPrefix = sig.to_str().parse()?;
+        assert!(prefix.verify(b"hello there", &sig)?);
+        assert!(!prefix.verify(b"hello where", &sig)?);
+        Ok(())
+    }
+
+    #[test]
+    fn ed448_seed() -> Result<(), Error> {
+        let seed = SeedPrefix::RandomSeed448(vec![7; 57]);
+        let seed: SeedPrefix = seed.to_str().parse()?;
+        let signer = Signer::from_seed(&seed, true)?;
+        let prefix = signer.basic_prefix();
+        assert_eq!(&prefix.to_str()[..4], "1AAD");
+        assert_eq!(prefix.to_str().len(), 80);
+
+        let sig = signer.sign(b"hello there");
+        assert_eq!(&sig.to_str()[..4], "1AAE");
+        assert_eq!(sig.to_str().len(), 156);
+        let prefix: BasicPrefix = prefix.to_str().parse()?;
+        let sig: SelfSigningPrefix = sig.to_str().parse()?;
+        assert!(prefix.verify(b"hello there", &sig)?);
+        assert!(!prefix.verify(b"hello where", &sig)?);
+        Ok(())
+    }
+
+    #[cfg(feature = "ml-dsa")]
+    #[test]
+    fn ml_dsa() -> Result<(), Error> {
+        let signer = Signer::random(SelfSigning::MLDSA65, true)?;
+        let prefix = signer.basic_prefix();
+        assert_eq!(&prefix.to_str()[..4], "1ABD");
+
+        let sig = signer.sign(b"hello there");
+        assert_eq!(&sig.to_str()[..4], "1ABH");
+        let prefix: BasicPrefix = prefix.to_str().parse()?;
+        let sig: SelfSigningPrefix = sig.to_str().parse()?;
+        assert!(prefix.verify(b"hello there", &sig)?);
+        assert!(!prefix.verify(b"hello where", &sig)?);
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_keys() {
+        assert!(Signer::new(
+            SelfSigning::Ed25519Sha512,
+            PrivateKey::new(vec![7; 31]),
+            true
+        )
+        .is_err());
+        assert!(Signer::new(
+            SelfSigning::ECDSAsecp256k1Sha256,
+            PrivateKey::new(vec![7; 31]),
+            true
+        )
+        .is_err());
+        assert!(Signer::new(SelfSigning::Ed448, PrivateKey::new(vec![7; 56]), true).is_err());
+    }
+}
@@ -0,0 +1,172 @@
+use super::{Decrypter, Encrypter, Signer};
+use crate::{
+    derivation::self_signing::SelfSigning,
+    error::Error,
+    prefix::{BasicPrefix, CipherPrefix, IdentifierPrefix, Prefix},
+};
+use core::str::FromStr;
+use std::{
+    fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+};
+
+/// Storage of the signers of identifiers, by identifier prefix and key index.
+///
+/// Signers are created through [KeyStore::create], so stores backed by e.g. an HSM can generate
+/// keys their own way.
+pub trait KeyStore {
+    fn get(&self, prefix: &IdentifierPrefix, index: usize) -> Result<Option<Signer>, Error>;
+
+    /// Stores `signer` at `index`, replacing any signer stored there.
+    fn put(
+        &mut self,
+        prefix: &IdentifierPrefix,
+        index: usize,
+        signer: &Signer,
+    ) -> Result<(), Error>;
+
+    /// Indexes of the signers stored for `prefix`, in ascending order.
+    fn list(&self, prefix: &IdentifierPrefix) -> Result<Vec<usize>, Error>;
+
+    /// Creates a signer for a fresh random key and stores it at `index`.
+    fn create(
+        &mut self,
+        prefix: &IdentifierPrefix,
+        index: usize,
+        code: SelfSigning,
+        transferable: bool,
+    ) -> Result<Signer, Error> {
+        let signer = Signer::random(code, transferable)?;
+        self.put(prefix, index, &signer)?;
+        Ok(signer)
+    }
+}
+
+/// Keeps each signer in a file of its own under `{dir}/{prefix}/{index}`, holding the verfer and
+/// the seed sealed to the X25519 wrapping key of the store, see [Encrypter].
+///
+/// Seeds are only ever written encrypted, so the store needs the [Decrypter] of the wrapping key to
+/// read signers back.
+#[derive(Clone)]
+pub struct FileKeyStore {
+    dir: PathBuf,
+    decrypter: Decrypter,
+    encrypter: Encrypter,
+}
+
+impl FileKeyStore {
+    /// Opens the store in `dir`, creating the directory if missing.
+    pub fn open(dir: impl AsRef<Path>, decrypter: Decrypter) -> Result<Self, Error> {
+        fs::create_dir_all(dir.as_ref())?;
+        Ok(Self {
+            dir: dir.as_ref().into(),
+            encrypter: decrypter.encrypter(),
+            decrypter,
+        })
+    }
+
+    fn prefix_dir(&self, prefix: &IdentifierPrefix) -> Result<PathBuf, Error> {
+        match prefix.to_str() {
+            pre if pre.is_empty() => Err(Error::SemanticError("Empty identifier prefix".into())),
+            pre => Ok(self.dir.join(pre)),
+        }
+    }
+}
+
+impl KeyStore for FileKeyStore {
+    fn get(&self, prefix: &IdentifierPrefix, index: usize) -> Result<Option<Signer>, Error> {
+        let contents = match fs::read_to_string(self.prefix_dir(prefix)?.join(index.to_string())) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let (verfer, cipher) = contents
+            .trim_end()
+            .split_once('\n')
+            .ok_or_else(|| Error::DeserializeError("Malformed key file".into()))?;
+        let verfer = BasicPrefix::from_str(verfer)?;
+        let seed = self
+            .decrypter
+            .decrypt_seed(&CipherPrefix::from_str(cipher)?)?;
+        let signer = Signer::from_seed(&seed, verfer.derivation.is_transferable())?;
+        if signer.basic_prefix() != verfer {
+            return Err(Error::SemanticError(
+                "Stored seed does not match its verfer".into(),
+            ));
+        }
+        Ok(Some(signer))
+    }
+
+    fn put(
+        &mut self,
+        prefix: &IdentifierPrefix,
+        index: usize,
+        signer: &Signer,
+    ) -> Result<(), Error> {
+        let cipher = self.encrypter.encrypt_seed(&signer.seed()?)?;
+        let dir = self.prefix_dir(prefix)?;
+        fs::create_dir_all(&dir)?;
+        fs::write(
+            dir.join(index.to_string()),
+            format!("{}\n{}\n", signer.basic_prefix().to_str(), cipher.to_str()),
+        )?;
+        Ok(())
+    }
+
+    fn list(&self, prefix: &IdentifierPrefix) -> Result<Vec<usize>, Error> {
+        let entries = match fs::read_dir(self.prefix_dir(prefix)?) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(vec![]),
+            Err(e) => return Err(e.into()),
+        };
+        let mut indexes = vec![];
+        for entry in entries {
+            if let Some(index) = entry?.file_name().to_str().and_then(|n| n.parse().ok()) {
+                indexes.push(index);
+            }
+        }
+        indexes.sort_unstable();
+        Ok(indexes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{keys::PrivateKey, prefix::SeedPrefix};
+
+    fn open(dir: &Path, wrapping: u8) -> Result<FileKeyStore, Error> {
+        let decrypter = Decrypter::from_seed(&SeedPrefix::RandomSeed256X25519(vec![wrapping; 32]))?;
+        FileKeyStore::open(dir, decrypter)
+    }
+
+    #[test]
+    fn put_get_list() -> Result<(), Error> {
+        let dir = tempfile::tempdir()?;
+        let mut store = open(dir.path(), 7)?;
+        let prefix: IdentifierPrefix = "DErocgXD2RGSyvn3MObcx59jeOsEQhv2TqHirVkzrp0Q".parse()?;
+        assert!(store.get(&prefix, 0)?.is_none());
+        assert!(store.list(&prefix)?.is_empty());
+
+        let signer = Signer::new(
+            SelfSigning::ECDSAsecp256r1Sha256,
+            PrivateKey::new(vec![7; 32]),
+            false,
+        )?;
+        store.put(&prefix, 10, &signer)?;
+        let created = store.create(&prefix, 2, SelfSigning::Ed25519Sha512, true)?;
+        assert_eq!(store.list(&prefix)?, vec![2, 10]);
+        assert_eq!(store.get(&prefix, 10)?, Some(signer.clone()));
+        assert_eq!(store.get(&prefix, 2)?, Some(created));
+
+        // seeds are not stored in the clear
+        let file = fs::read_to_string(dir.path().join(prefix.to_str()).join("10"))?;
+        assert!(!file.contains(&signer.seed()?.to_str()));
+
+        // nor readable without the wrapping key
+        assert!(open(dir.path(), 8)?.get(&prefix, 10).is_err());
+        assert_eq!(open(dir.path(), 7)?.get(&prefix, 10)?, Some(signer));
+        Ok(())
+    }
+}
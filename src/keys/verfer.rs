@@ -0,0 +1,315 @@
+use super::PublicKey;
+use crate::{
+    derivation::{basic::Basic, self_signing::SelfSigning},
+    error::Error,
+    prefix::{verify, BasicPrefix, SelfSigningPrefix, Signature},
+};
+use core::fmt;
+
+/// A verifier: a public key together with the derivation code which tells the signature scheme
+/// signatures made with the matching private key are verified with.
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub struct Verfer {
+    pub code: Basic,
+    pub public_key: PublicKey,
+}
+
+impl Verfer {
+    pub fn new(code: Basic, public_key: PublicKey) -> Self {
+        Self { code, public_key }
+    }
+
+    /// Verifies the raw signature `sig` over `data`.
+    ///
+    /// Returns `Ok(false)` for signatures that do not verify and an error for key types that
+    /// can not sign, like the X25519 and X448 encryption keys.
+    pub fn verify(&self, sig: &[u8], data: &[u8]) -> Result<bool, Error> {
+        match self.code {
+            Basic::Ed25519 | Basic::Ed25519NT => Ok(self.public_key.verify_ed(data, sig)),
+            Basic::ECDSAsecp256k1 | Basic::ECDSAsecp256k1NT => {
+                Ok(self.public_key.verify_ecdsa(data, sig))
+            }
+            Basic::Ed448 | Basic::Ed448NT => Ok(self.public_key.verify_ed448(data, sig)),
+            Basic::ECDSAsecp256r1 | Basic::ECDSAsecp256r1NT => {
+                Ok(self.public_key.verify_ecdsa_p256(data, sig))
+            }
+            Basic::MLDSA44 | Basic::MLDSA44NT => {
+                self.public_key
+                    .verify_ml_dsa(SelfSigning::MLDSA44, data, sig)
+            }
+            Basic::MLDSA65 | Basic::MLDSA65NT => {
+                self.public_key
+                    .verify_ml_dsa(SelfSigning::MLDSA65, data, sig)
+            }
+            Basic::MLDSA87 | Basic::MLDSA87NT => {
+                self.public_key
+                    .verify_ml_dsa(SelfSigning::MLDSA87, data, sig)
+            }
+            Basic::X25519 | Basic::X448 => Err(Error::SemanticError(format!(
+                "Ineligible key type for verification: {:?}",
+                self.code
+            ))),
+        }
+    }
+}
+
+/// Verifies each signature over its data against its verfer, e.g. the receipts of an event.
+///
+/// The Ed25519 signatures are verified together in one batch, which is much faster than one by
+/// one. Only if the batch fails are they verified one by one to tell which do not verify.
+/// Signatures under a code not matching their verfer do not verify.
+///
+/// Each item gets the answer [Verfer::verify] gives it. Batch verification could disagree with it
+/// for keys or signature `R`s off the prime order subgroup or not canonically encoded, so those
+/// items are left out of the batch and verified one by one.
+pub fn verify_batch(items: &[(Verfer, &[u8], SelfSigningPrefix)]) -> Vec<bool> {
+    let verify_one = |(verfer, data, signature): &(Verfer, &[u8], SelfSigningPrefix)| {
+        verify(data, &BasicPrefix::from(verfer.clone()), signature).unwrap_or(false)
+    };
+    let mut verified = vec![false; items.len()];
+    let (mut batch, mut messages, mut signatures, mut keys) = (vec![], vec![], vec![], vec![]);
+    for (i, item) in items.iter().enumerate() {
+        match ed25519_parts(item) {
+            Some((key, signature)) => {
+                batch.push(i);
+                messages.push(item.1);
+                signatures.push(signature);
+                keys.push(key);
+            }
+            None => verified[i] = verify_one(item),
+        }
+    }
+
+    let batch_verified = ed25519_dalek::verify_batch(&messages, &signatures, &keys).is_ok();
+    for i in batch {
+        verified[i] = batch_verified || verify_one(&items[i]);
+    }
+    verified
+}
+
+/// Number of signatures [par_verify] hands to each thread as one batch.
+#[cfg(feature = "rayon")]
+const PAR_BATCH_LEN: usize = 64;
+
+/// [verify_batch] spread over the rayon thread pool, each thread verifying a batch of the items.
+#[cfg(feature = "rayon")]
+pub fn par_verify(items: &[(Verfer, &[u8], SelfSigningPrefix)]) -> Vec<bool> {
+    use rayon::prelude::*;
+    items
+        .par_chunks(PAR_BATCH_LEN)
+        .flat_map_iter(verify_batch)
+        .collect()
+}
+
+/// The dalek key and signature of an Ed25519 item, `None` for any other item.
+fn ed25519_parts(
+    (verfer, _, signature): &(Verfer, &[u8], SelfSigningPrefix),
+) -> Option<(ed25519_dalek::PublicKey, ed25519_dalek::Signature)> {
+    if !matches!(verfer.code, Basic::Ed25519 | Basic::Ed25519NT)
+        || signature.derivation != SelfSigning::Ed25519Sha512
+    {
+        return None;
+    }
+    let signature = Signature::<64>::try_from(signature).ok()?;
+    if !prime_order(&verfer.public_key.key()) || !prime_order(&signature.signature[..32]) {
+        return None;
+    }
+    Some((
+        ed25519_dalek::PublicKey::from_bytes(&verfer.public_key.key()).ok()?,
+        ed25519_dalek::Signature::from_bytes(&signature.signature).ok()?,
+    ))
+}
+
+/// Whether `point` is the canonical encoding of a point of the prime order subgroup, neither
+/// small order nor mixed with a small order component.
+fn prime_order(point: &[u8]) -> bool {
+    use curve25519_dalek::edwards::CompressedEdwardsY;
+    CompressedEdwardsY::from_slice(point)
+        .ok()
+        .and_then(|compressed| {
+            compressed
+                .decompress()
+                .filter(|decompressed| decompressed.compress() == compressed)
+        })
+        .is_some_and(|point| point.is_torsion_free())
+}
+
+impl From<BasicPrefix> for Verfer {
+    fn from(prefix: BasicPrefix) -> Self {
+        Self::new(prefix.derivation, prefix.public_key)
+    }
+}
+
+impl From<Verfer> for BasicPrefix {
+    fn from(verfer: Verfer) -> Self {
+        BasicPrefix::new_unchecked(verfer.code, verfer.public_key)
+    }
+}
+
+/// The basic prefix of the key, in the text domain (qb64).
+impl fmt::Display for Verfer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&BasicPrefix::from(self.clone()), f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keys::PrivateKey;
+
+    #[test]
+    fn verify_ed25519() -> Result<(), Error> {
+        use ed25519_dalek::Keypair;
+        use rand::rngs::OsRng;
+
+        let kp = Keypair::generate(&mut OsRng);
+        let signer = PrivateKey::new(kp.secret.to_bytes().to_vec());
+        let verfer = Verfer::new(
+            Basic::Ed25519,
+            PublicKey::new(kp.public.to_bytes().to_vec()),
+        );
+
+        let sig = signer.sign_ed(b"hello there")?;
+        assert!(verfer.verify(&sig, b"hello there")?);
+        assert!(!verfer.verify(&sig, b"hello where")?);
+        assert!(!verfer.verify(&sig[1..], b"hello there")?);
+        Ok(())
+    }
+
+    #[test]
+    fn batch() -> Result<(), Error> {
+        use crate::{
+            keys::{generate_ed25519, Signer},
+            test_utils::signer,
+        };
+
+        let k1 = Signer::new(
+            SelfSigning::ECDSAsecp256k1Sha256,
+            PrivateKey::new(vec![7; 32]),
+            false,
+        )?;
+        let receipts: Vec<_> = (0..8)
+            .map(|i| {
+                let signer = signer(i, true);
+                Ok((
+                    signer.verfer().clone(),
+                    &b"event"[..],
+                    signer.sign(b"event"),
+                ))
+            })
+            .chain([Ok((k1.verfer().clone(), &b"event"[..], k1.sign(b"event")))])
+            .collect::<Result<_, Error>>()?;
+        assert_eq!(verify_batch(&receipts), vec![true; 9]);
+        assert!(verify_batch(&[]).is_empty());
+
+        let mut forged = receipts.clone();
+        forged[2].1 = b"other event";
+        forged[8].1 = b"other event";
+        // signed with another key
+        forged[5].2 = signer(9, true).sign(b"event");
+        // a secp256k1 signature for an Ed25519 key
+        forged[6].2 = k1.sign(b"event");
+        let mut expected = vec![true; 9];
+        for i in [2, 5, 6, 8] {
+            expected[i] = false;
+        }
+        assert_eq!(verify_batch(&forged), expected);
+        #[cfg(feature = "rayon")]
+        {
+            let many: Vec<_> = forged.iter().cycle().take(150).cloned().collect();
+            let expected: Vec<_> = expected.iter().cycle().take(150).cloned().collect();
+            assert_eq!(par_verify(&many), expected);
+        }
+
+        // encryption keys never verify
+        let (key, _) = generate_ed25519();
+        let receipt = (
+            Verfer::new(Basic::X25519, key),
+            &b"event"[..],
+            signer(0, true).sign(b"event"),
+        );
+        assert_eq!(verify_batch(&[receipt]), vec![false]);
+
+        // a key of order 8 with an identity `R` and a zero `s`, accepted by single verification
+        // for one message hash out of 8, by a batch for one random combination out of 8
+        let torsion = [
+            0x26, 0xe8, 0x95, 0x8f, 0xc2, 0xb2, 0x27, 0xb0, 0x45, 0xc3, 0xf4, 0x89, 0xf2, 0xef,
+            0x98, 0xf0, 0xd5, 0xdf, 0xac, 0x05, 0xd3, 0xc6, 0x33, 0x39, 0xb1, 0x38, 0x02, 0x88,
+            0x6d, 0x53, 0xfc, 0x05,
+        ];
+        let mut identity = [0u8; 64];
+        identity[0] = 1;
+        let verfer = Verfer::new(Basic::Ed25519, PublicKey::new(torsion.to_vec()));
+        let messages: Vec<[u8; 1]> = (0..16).map(|i| [i]).collect();
+        let small: Vec<_> = messages
+            .iter()
+            .map(|message| {
+                let sig = SelfSigning::Ed25519Sha512.derive(identity.to_vec());
+                (verfer.clone(), &message[..], sig)
+            })
+            .chain(receipts.iter().cloned())
+            .collect();
+        let single: Vec<bool> = small
+            .iter()
+            .map(|(verfer, data, sig)| verfer.verify(&sig.signature, data))
+            .collect::<Result<_, _>>()?;
+        assert!(single.contains(&true) && single.contains(&false));
+        assert!(small[..16].iter().all(|item| ed25519_parts(item).is_none()));
+        assert!(small[16..24]
+            .iter()
+            .all(|item| ed25519_parts(item).is_some()));
+        for _ in 0..4 {
+            assert_eq!(verify_batch(&small), single);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn verify_ecdsa() -> Result<(), Error> {
+        use k256::ecdsa::SigningKey;
+
+        let sk = SigningKey::from_bytes(&[7u8; 32])?;
+        let signer = PrivateKey::new(sk.to_bytes().to_vec());
+        let verfer = Verfer::new(
+            Basic::ECDSAsecp256k1NT,
+            PublicKey::new(k256::ecdsa::VerifyingKey::from(&sk).to_bytes().to_vec()),
+        );
+
+        let sig = signer.sign_ecdsa(b"hello there")?;
+        assert!(verfer.verify(&sig, b"hello there")?);
+        assert!(!verfer.verify(&sig, b"hello where")?);
+        Ok(())
+    }
+
+    #[test]
+    fn verify_ed448() -> Result<(), Error> {
+        use ed448_goldilocks_plus::SigningKey;
+
+        let sk = SigningKey::try_from(&[7u8; 57][..]).unwrap();
+        let verfer = Verfer::new(
+            Basic::Ed448,
+            PublicKey::new(sk.verifying_key().to_bytes().to_vec()),
+        );
+
+        let sig = sk.sign_raw(b"hello there").to_bytes();
+        assert!(verfer.verify(&sig, b"hello there")?);
+        assert!(!verfer.verify(&sig, b"hello where")?);
+        assert!(!verfer.verify(&sig[..64], b"hello there")?);
+        Ok(())
+    }
+
+    #[test]
+    fn ineligible_key_type() {
+        let verfer = Verfer::new(Basic::X25519, PublicKey::new(vec![0; 32]));
+        assert!(verfer.verify(&[0; 64], b"hello there").is_err());
+    }
+
+    #[test]
+    fn basic_prefix_conversion() {
+        let prefix = BasicPrefix::new_unchecked(Basic::Ed25519NT, PublicKey::new(vec![0; 32]));
+        let verfer = Verfer::from(prefix.clone());
+        assert_eq!(verfer.code, Basic::Ed25519NT);
+        assert_eq!(BasicPrefix::from(verfer), prefix);
+    }
+}
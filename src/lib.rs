@@ -5,14 +5,77 @@
 //! [this link]: https://weboftrust.github.io/ietf-cesr/draft-ssmith-cesr.html
 //! [variant]:  https://www.rfc-editor.org/rfc/rfc4648#section-5
 
+/// Authentic chained data containers (ACDCs) and their disclosure variants.
+pub mod acdc;
+
+/// Human readable breakdowns of CESR streams, for debugging.
+pub mod annotate;
+
+/// Building and parsing of the CESR attachment groups of KERI events.
+pub mod attachments;
+
+/// Representative workloads for benchmarking backends and feature flags.
+#[cfg(feature = "bench")]
+pub mod bench;
+
+/// The CESR code tables with the sizes of every code.
+pub mod codex;
+
+/// Reading of streams written by keripy releases that deviate from the specification.
+#[cfg(feature = "keripy-compat")]
+pub mod compat;
+
 /// Parses `Vec[u8]`s into raw types
 pub mod derivation;
 
+/// Decentralized identifiers of AIDs, of the did:keri and did:webs methods.
+pub mod did;
+
 /// Listing of error types used across the crate
 pub mod error;
 
+/// Signing of HTTP requests with the Signature and Signature-Input headers of Signify.
+pub mod http;
+
 /// Cryptographic keypair module for all supported key algorithms.
 pub mod keys;
 
+/// Builders and parsers of KERI messages with their SAIDs and attachments.
+pub mod message;
+
+/// Framing of CESR streams into messages and their attachment groups.
+pub mod parser;
+
 /// Parsing and raw type module for self certifying identifiers.
 pub mod prefix;
+
+/// Self-addressing identifiers (SAIDs) of self-addressing data.
+pub mod said;
+
+/// Serialization of field maps byte for byte as KERIpy does.
+pub mod ser;
+
+/// Self-framing serialized messages.
+pub mod serder;
+
+/// KERI signing thresholds and their satisfaction by indexed signatures.
+pub mod threshold;
+
+/// Loading and checking of shared conformance test vectors.
+#[cfg(feature = "vectors")]
+pub mod vectors;
+
+/// Version strings of self-framing messages.
+pub mod version;
+
+/// Bindings for browsers and other JavaScript hosts.
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+/// C API for KERI implementations in other languages.
+#[cfg(feature = "ffi")]
+pub mod ffi;
+
+/// Property based testing strategies for CESR primitives and attachment groups.
+#[cfg(any(test, feature = "test-utils"))]
+pub mod test_utils;
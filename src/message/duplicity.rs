@@ -0,0 +1,145 @@
+use super::Message;
+use crate::{
+    attachments::EventSeal,
+    error::Error,
+    parser::CesrParser,
+    prefix::{Prefix, SelfAddressingPrefix},
+    serder::Serder,
+};
+
+/// Evidence of duplicity: two signed messages committing the same identifier at the same
+/// sequence number to events of different SAIDs, e.g. two forked interaction events of a
+/// controller or the receipts of a witness for both.
+///
+/// The evidence goes as a CESR stream of both messages with their attachments, so the
+/// signatures can be verified by whoever holds the key state of the signers.
+#[derive(Debug, Clone)]
+pub struct Duplicity {
+    pub first: Message,
+    pub second: Message,
+}
+
+impl Duplicity {
+    /// Compares two signed key events or receipts. Returns the evidence when they commit to
+    /// different SAIDs, none when they are the same event. Fails when they are not of the same
+    /// prefix and sequence number.
+    pub fn detect(first: &Message, second: &Message) -> Result<Option<Self>, Error> {
+        let (seal, other) = (
+            EventSeal::of(&first.serder)?,
+            EventSeal::of(&second.serder)?,
+        );
+        if seal.prefix != other.prefix || seal.sn != other.sn {
+            return Err(Error::SemanticError(format!(
+                "Events at different locations: {} at {} and {} at {}",
+                seal.prefix.to_str(),
+                seal.sn,
+                other.prefix.to_str(),
+                other.sn
+            )));
+        }
+        Ok((seal.said != other.said).then(|| Self {
+            first: first.clone(),
+            second: second.clone(),
+        }))
+    }
+
+    /// The seal naming the event of each message, their prefix and sequence number the same.
+    pub fn seals(&self) -> Result<(EventSeal, EventSeal), Error> {
+        Ok((
+            EventSeal::of(&self.first.serder)?,
+            EventSeal::of(&self.second.serder)?,
+        ))
+    }
+
+    /// The event digest pair, the SAIDs of the conflicting events.
+    pub fn digests(&self) -> Result<(SelfAddressingPrefix, SelfAddressingPrefix), Error> {
+        let (first, second) = self.seals()?;
+        Ok((first.said, second.said))
+    }
+
+    /// Both messages with their attachments, as a CESR stream.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, Error> {
+        Ok([self.first.to_bytes()?, self.second.to_bytes()?].concat())
+    }
+
+    /// Parses the evidence from a stream of exactly two messages, checking they are duplicitous.
+    pub fn from_bytes(stream: &[u8]) -> Result<Self, Error> {
+        let mut parser = CesrParser::new(stream);
+        let mut message = || -> Result<Message, Error> {
+            let frame = parser
+                .next_frame()?
+                .ok_or_else(|| Error::SemanticError("Missing message of duplicity".into()))?;
+            Ok(Message {
+                serder: Serder::from_raw(frame.message)?,
+                attachments: frame.parse_attachments()?.into(),
+            })
+        };
+        let (first, second) = (message()?, message()?);
+        if parser.next_frame()?.is_some() {
+            return Err(Error::SemanticError(
+                "Unexpected message after duplicity".into(),
+            ));
+        }
+        Self::detect(&first, &second)?
+            .ok_or_else(|| Error::SemanticError("Messages of the same event".into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        message::{InceptionBuilder, InteractionBuilder, ReceiptBuilder},
+        prefix::IdentifierPrefix,
+        test_utils::signer,
+    };
+
+    #[test]
+    fn duplicity() -> Result<(), Error> {
+        let controller = signer(1, true);
+        let icp = InceptionBuilder::new()
+            .keys(vec![controller.basic_prefix()])
+            .build()?;
+        let prefix: IdentifierPrefix = icp.serder.get("i").unwrap().as_str().unwrap().parse()?;
+        let fork = |anchor: &str| {
+            InteractionBuilder::new(prefix.clone(), 1, icp.said().unwrap())
+                .anchor(anchor)
+                .build()?
+                .sign(&[signer(1, true)])
+        };
+        let (first, second) = (fork("first")?, fork("second")?);
+
+        assert!(Duplicity::detect(&first, &first.clone())?.is_none());
+        assert!(Duplicity::detect(&icp, &first).is_err());
+        let duplicity = Duplicity::detect(&first, &second)?.unwrap();
+        assert_eq!(duplicity.digests()?, (first.said()?, second.said()?));
+        let (seal, _) = duplicity.seals()?;
+        assert_eq!((seal.prefix, seal.sn), (prefix, 1));
+
+        let parsed = Duplicity::from_bytes(&duplicity.to_bytes()?)?;
+        assert_eq!(parsed.digests()?, duplicity.digests()?);
+        assert_eq!(parsed.to_bytes()?, duplicity.to_bytes()?);
+        let bytes = duplicity.to_bytes()?;
+        let frame = CesrParser::new(&bytes).nth(1).unwrap()?;
+        let siger = frame.parse_attachments()?.controller_signatures[0].clone();
+        assert!(siger
+            .resolve(&[controller.basic_prefix()])?
+            .verify(second.serder.raw())?);
+
+        // receipts of both forks by the same witness
+        let witness = signer(5, false);
+        let receipt = |event: &Message| {
+            ReceiptBuilder::new(&event.serder)
+                .and_then(|builder| builder.nontrans(&witness).build())
+        };
+        let receipts = Duplicity::detect(&receipt(&first)?, &receipt(&second)?)?.unwrap();
+        assert_eq!(receipts.digests()?, duplicity.digests()?);
+
+        let same = [first.to_bytes()?, first.to_bytes()?].concat();
+        assert!(Duplicity::from_bytes(&same).is_err());
+        assert!(Duplicity::from_bytes(&first.to_bytes()?).is_err());
+        let three = [duplicity.to_bytes()?, first.to_bytes()?].concat();
+        assert!(Duplicity::from_bytes(&three).is_err());
+        Ok(())
+    }
+}
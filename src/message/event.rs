@@ -0,0 +1,686 @@
+use super::{
+    check_threshold, check_unique, fields, hex, majority, saidify, witness_threshold, Message,
+};
+use crate::{
+    derivation::self_addressing::SelfAddressing,
+    error::Error,
+    prefix::{BasicPrefix, IdentifierPrefix, Prefix, SelfAddressingPrefix},
+    threshold::SignatureThreshold,
+    version::SerializationKind,
+};
+use serde_json::Value;
+
+/// Fields of every builder: how the message is serialized and digested, and the seals it
+/// anchors in its `a` field.
+#[derive(Debug, Clone)]
+struct Common {
+    kind: SerializationKind,
+    code: SelfAddressing,
+    anchors: Vec<Value>,
+}
+
+impl Default for Common {
+    fn default() -> Self {
+        Self {
+            kind: SerializationKind::JSON,
+            code: SelfAddressing::Blake3_256,
+            anchors: vec![],
+        }
+    }
+}
+
+/// Builder methods shared by the event builders.
+macro_rules! common {
+    () => {
+        /// Serialization kind of the message, JSON by default.
+        pub fn kind(mut self, kind: SerializationKind) -> Self {
+            self.common.kind = kind;
+            self
+        }
+
+        /// Digest code of the SAID, and of the prefix of self-addressing identifiers,
+        /// Blake3-256 by default.
+        pub fn said_code(mut self, code: SelfAddressing) -> Self {
+            self.common.code = code;
+            self
+        }
+
+        /// Anchors a seal in the `a` field, e.g. the digest of a credential.
+        pub fn anchor(mut self, seal: impl Into<Value>) -> Self {
+            self.common.anchors.push(seal.into());
+            self
+        }
+
+        pub fn anchors<S: Into<Value>>(self, seals: impl IntoIterator<Item = S>) -> Self {
+            seals
+                .into_iter()
+                .fold(self, |builder, seal| builder.anchor(seal))
+        }
+    };
+}
+
+fn prefixes<P: Prefix>(prefixes: &[P]) -> Value {
+    prefixes.iter().map(|p| Value::from(p.to_str())).collect()
+}
+
+fn threshold(threshold: &SignatureThreshold) -> Result<Value, Error> {
+    Ok(serde_json::to_value(threshold)?)
+}
+
+/// Builds the inception (`icp`) event of an identifier, or the delegated inception (`dip`) event
+/// of an identifier with a delegator.
+///
+/// The identifier prefix is self-addressing by default, the SAID of the event. Thresholds
+/// default to a majority of the keys, and the witness threshold to [ample](super::ample) of the witnesses.
+#[derive(Debug, Clone, Default)]
+pub struct InceptionBuilder {
+    common: Common,
+    basic: bool,
+    keys: Vec<BasicPrefix>,
+    threshold: Option<SignatureThreshold>,
+    next: Vec<SelfAddressingPrefix>,
+    next_threshold: Option<SignatureThreshold>,
+    witnesses: Vec<BasicPrefix>,
+    witness_threshold: Option<u64>,
+    config: Vec<String>,
+    delegator: Option<IdentifierPrefix>,
+}
+
+impl InceptionBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    common!();
+
+    /// Sets the current signing keys, the `k` field.
+    pub fn keys(mut self, keys: Vec<BasicPrefix>) -> Self {
+        self.keys = keys;
+        self
+    }
+
+    /// Sets the signing threshold of the keys, the `kt` field.
+    pub fn threshold(mut self, threshold: SignatureThreshold) -> Self {
+        self.threshold = Some(threshold);
+        self
+    }
+
+    /// Sets the digests of the next keys, the `n` field, see
+    /// [next_digests](crate::prefix::next_digests).
+    pub fn next_keys(mut self, digests: Vec<SelfAddressingPrefix>) -> Self {
+        self.next = digests;
+        self
+    }
+
+    /// Sets the signing threshold of the next keys, the `nt` field.
+    pub fn next_threshold(mut self, threshold: SignatureThreshold) -> Self {
+        self.next_threshold = Some(threshold);
+        self
+    }
+
+    /// Sets the witnesses, the `b` field.
+    pub fn witnesses(mut self, witnesses: Vec<BasicPrefix>) -> Self {
+        self.witnesses = witnesses;
+        self
+    }
+
+    /// Sets the number of witness receipts the event needs, the `bt` field.
+    pub fn witness_threshold(mut self, toad: u64) -> Self {
+        self.witness_threshold = Some(toad);
+        self
+    }
+
+    /// Adds a configuration trait to the `c` field, e.g. `EO` for establishment only.
+    pub fn config(mut self, config: impl Into<String>) -> Self {
+        self.config.push(config.into());
+        self
+    }
+
+    /// Uses the single signing key as the identifier prefix instead of the SAID of the event.
+    /// Identifiers of non-transferable keys cannot commit to next keys.
+    pub fn basic_prefix(mut self) -> Self {
+        self.basic = true;
+        self
+    }
+
+    /// Makes the event a delegated inception by `delegator`, the `di` field. The delegator has
+    /// to anchor the [seal](Message::seal) of the event for it to be accepted.
+    pub fn delegator(mut self, delegator: IdentifierPrefix) -> Self {
+        self.delegator = Some(delegator);
+        self
+    }
+
+    pub fn build(&self) -> Result<Message, Error> {
+        if self.keys.is_empty() {
+            return Err(Error::SemanticError("Inception without keys".into()));
+        }
+        if self.basic && self.delegator.is_some() {
+            return Err(Error::SemanticError(
+                "Delegated identifiers must be self-addressing".into(),
+            ));
+        }
+        let kt = self
+            .threshold
+            .clone()
+            .unwrap_or_else(|| majority(self.keys.len()));
+        check_threshold(&kt, self.keys.len(), "signing")?;
+        let nt = self
+            .next_threshold
+            .clone()
+            .unwrap_or_else(|| majority(self.next.len()));
+        check_threshold(&nt, self.next.len(), "next signing")?;
+        check_unique(&self.witnesses, "witnesses")?;
+        let bt = witness_threshold(self.witness_threshold, &self.witnesses)?;
+
+        let prefix = match (self.basic, self.keys.as_slice()) {
+            (false, _) => String::new(),
+            (true, [key]) if key.is_transferable() || self.next.is_empty() => key.to_str(),
+            (true, [_]) => {
+                return Err(Error::SemanticError(
+                    "Non-transferable prefix with next keys".into(),
+                ))
+            }
+            (true, _) => {
+                return Err(Error::SemanticError(
+                    "Basic prefix of more than one key".into(),
+                ))
+            }
+        };
+
+        let mut ked = fields(match self.delegator {
+            Some(_) => "dip",
+            None => "icp",
+        });
+        ked.insert("i".into(), prefix.into());
+        ked.insert("s".into(), hex(0));
+        ked.insert("kt".into(), threshold(&kt)?);
+        ked.insert("k".into(), prefixes(&self.keys));
+        ked.insert("nt".into(), threshold(&nt)?);
+        ked.insert("n".into(), prefixes(&self.next));
+        ked.insert("bt".into(), hex(bt));
+        ked.insert("b".into(), prefixes(&self.witnesses));
+        ked.insert("c".into(), self.config.clone().into());
+        ked.insert("a".into(), self.common.anchors.clone().into());
+        if let Some(delegator) = &self.delegator {
+            ked.insert("di".into(), delegator.to_str().into());
+        }
+        Ok(Message::new(saidify(
+            ked,
+            &self.common.code,
+            self.common.kind,
+        )?))
+    }
+}
+
+/// Builds a rotation (`rot`) event, which replaces the keys of an identifier by the next keys
+/// committed to in its prior establishment event and changes its witnesses, or the delegated
+/// rotation (`drt`) event of a delegated identifier.
+#[derive(Debug, Clone)]
+pub struct RotationBuilder {
+    common: Common,
+    prefix: IdentifierPrefix,
+    sn: u64,
+    prior: SelfAddressingPrefix,
+    keys: Vec<BasicPrefix>,
+    threshold: Option<SignatureThreshold>,
+    next: Vec<SelfAddressingPrefix>,
+    next_threshold: Option<SignatureThreshold>,
+    witnesses: Vec<BasicPrefix>,
+    cuts: Vec<BasicPrefix>,
+    adds: Vec<BasicPrefix>,
+    witness_threshold: Option<u64>,
+    delegated: bool,
+}
+
+impl RotationBuilder {
+    /// Rotation of `prefix` at sequence number `sn`, following the event of SAID `prior`.
+    pub fn new(prefix: IdentifierPrefix, sn: u64, prior: SelfAddressingPrefix) -> Self {
+        Self {
+            common: Common::default(),
+            prefix,
+            sn,
+            prior,
+            keys: vec![],
+            threshold: None,
+            next: vec![],
+            next_threshold: None,
+            witnesses: vec![],
+            cuts: vec![],
+            adds: vec![],
+            witness_threshold: None,
+            delegated: false,
+        }
+    }
+
+    common!();
+
+    /// Sets the new signing keys, the `k` field.
+    pub fn keys(mut self, keys: Vec<BasicPrefix>) -> Self {
+        self.keys = keys;
+        self
+    }
+
+    /// Sets the signing threshold of the keys, the `kt` field.
+    pub fn threshold(mut self, threshold: SignatureThreshold) -> Self {
+        self.threshold = Some(threshold);
+        self
+    }
+
+    /// Sets the digests of the next keys, the `n` field.
+    pub fn next_keys(mut self, digests: Vec<SelfAddressingPrefix>) -> Self {
+        self.next = digests;
+        self
+    }
+
+    /// Sets the signing threshold of the next keys, the `nt` field.
+    pub fn next_threshold(mut self, threshold: SignatureThreshold) -> Self {
+        self.next_threshold = Some(threshold);
+        self
+    }
+
+    /// Sets the witnesses before the rotation, which the cuts and adds apply to.
+    pub fn witnesses(mut self, witnesses: Vec<BasicPrefix>) -> Self {
+        self.witnesses = witnesses;
+        self
+    }
+
+    /// Sets the witnesses removed by the rotation, the `br` field.
+    pub fn cuts(mut self, cuts: Vec<BasicPrefix>) -> Self {
+        self.cuts = cuts;
+        self
+    }
+
+    /// Sets the witnesses added by the rotation, the `ba` field.
+    pub fn adds(mut self, adds: Vec<BasicPrefix>) -> Self {
+        self.adds = adds;
+        self
+    }
+
+    /// Sets the number of witness receipts the event needs, the `bt` field. Defaults to [ample](super::ample)
+    /// of the witnesses after the rotation.
+    pub fn witness_threshold(mut self, toad: u64) -> Self {
+        self.witness_threshold = Some(toad);
+        self
+    }
+
+    /// Makes the event a delegated rotation, which the delegator has to anchor like the
+    /// delegated inception.
+    pub fn delegated(mut self) -> Self {
+        self.delegated = true;
+        self
+    }
+
+    pub fn build(&self) -> Result<Message, Error> {
+        if self.sn == 0 {
+            return Err(Error::SemanticError("Rotation at sequence number 0".into()));
+        }
+        if self.keys.is_empty() {
+            return Err(Error::SemanticError("Rotation without keys".into()));
+        }
+        let kt = self
+            .threshold
+            .clone()
+            .unwrap_or_else(|| majority(self.keys.len()));
+        check_threshold(&kt, self.keys.len(), "signing")?;
+        let nt = self
+            .next_threshold
+            .clone()
+            .unwrap_or_else(|| majority(self.next.len()));
+        check_threshold(&nt, self.next.len(), "next signing")?;
+
+        check_unique(&self.witnesses, "witnesses")?;
+        check_unique(&self.cuts, "cuts")?;
+        check_unique(&self.adds, "adds")?;
+        if let Some(cut) = self.cuts.iter().find(|c| !self.witnesses.contains(c)) {
+            return Err(Error::SemanticError(format!(
+                "Cut of a witness not in the witnesses: {}",
+                cut.to_str()
+            )));
+        }
+        let mut witnesses: Vec<BasicPrefix> = self
+            .witnesses
+            .iter()
+            .filter(|w| !self.cuts.contains(w))
+            .cloned()
+            .collect();
+        if let Some(add) = self.adds.iter().find(|a| witnesses.contains(a)) {
+            return Err(Error::SemanticError(format!(
+                "Add of a witness already in the witnesses: {}",
+                add.to_str()
+            )));
+        }
+        witnesses.extend(self.adds.iter().cloned());
+        let bt = witness_threshold(self.witness_threshold, &witnesses)?;
+
+        let mut ked = fields(match self.delegated {
+            true => "drt",
+            false => "rot",
+        });
+        ked.insert("i".into(), self.prefix.to_str().into());
+        ked.insert("s".into(), hex(self.sn));
+        ked.insert("p".into(), self.prior.to_str().into());
+        ked.insert("kt".into(), threshold(&kt)?);
+        ked.insert("k".into(), prefixes(&self.keys));
+        ked.insert("nt".into(), threshold(&nt)?);
+        ked.insert("n".into(), prefixes(&self.next));
+        ked.insert("bt".into(), hex(bt));
+        ked.insert("br".into(), prefixes(&self.cuts));
+        ked.insert("ba".into(), prefixes(&self.adds));
+        ked.insert("a".into(), self.common.anchors.clone().into());
+        Ok(Message::new(saidify(
+            ked,
+            &self.common.code,
+            self.common.kind,
+        )?))
+    }
+}
+
+/// Builds an interaction (`ixn`) event, which anchors seals without changing the keys.
+#[derive(Debug, Clone)]
+pub struct InteractionBuilder {
+    common: Common,
+    prefix: IdentifierPrefix,
+    sn: u64,
+    prior: SelfAddressingPrefix,
+}
+
+impl InteractionBuilder {
+    /// Interaction of `prefix` at sequence number `sn`, following the event of SAID `prior`.
+    pub fn new(prefix: IdentifierPrefix, sn: u64, prior: SelfAddressingPrefix) -> Self {
+        Self {
+            common: Common::default(),
+            prefix,
+            sn,
+            prior,
+        }
+    }
+
+    common!();
+
+    pub fn build(&self) -> Result<Message, Error> {
+        if self.sn == 0 {
+            return Err(Error::SemanticError(
+                "Interaction at sequence number 0".into(),
+            ));
+        }
+        let mut ked = fields("ixn");
+        ked.insert("i".into(), self.prefix.to_str().into());
+        ked.insert("s".into(), hex(self.sn));
+        ked.insert("p".into(), self.prior.to_str().into());
+        ked.insert("a".into(), self.common.anchors.clone().into());
+        Ok(Message::new(saidify(
+            ked,
+            &self.common.code,
+            self.common.kind,
+        )?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        attachments::Attachments, parser::CesrParser, prefix::next_digests, serder::Serder,
+        test_utils::signer,
+    };
+    use core::str::FromStr;
+    use serde_json::json;
+
+    fn keys(seeds: &[u8]) -> Vec<BasicPrefix> {
+        seeds
+            .iter()
+            .map(|s| signer(*s, true).basic_prefix())
+            .collect()
+    }
+
+    #[test]
+    fn inception() -> Result<(), Error> {
+        let next = next_digests(&keys(&[3, 4]), SelfAddressing::Blake3_256);
+        let icp = InceptionBuilder::new()
+            .keys(keys(&[1, 2]))
+            .next_keys(next.clone())
+            .witnesses(keys(&[5]))
+            .config("EO")
+            .build()?;
+        let serder = icp.serder.clone();
+        let labels: Vec<&str> = serder.ked().keys().map(String::as_str).collect();
+        assert_eq!(
+            labels,
+            ["v", "t", "d", "i", "s", "kt", "k", "nt", "n", "bt", "b", "c", "a"]
+        );
+        assert_eq!(serder.ilk(), Some("icp"));
+        assert_eq!(serder.sn()?, 0);
+        assert_eq!(serder.get("kt"), Some(&json!("1")));
+        assert_eq!(
+            serder.get("n"),
+            Some(&json!([next[0].to_str(), next[1].to_str()]))
+        );
+        assert_eq!(serder.get("bt"), Some(&json!("1")));
+        assert_eq!(serder.get("c"), Some(&json!(["EO"])));
+        // self-addressing prefix
+        assert_eq!(serder.get("i"), serder.get("d"));
+        assert!(serder.verify_said()?);
+
+        let signed = icp.sign(&[signer(1, true), signer(2, true)])?;
+        let bytes = signed.to_bytes()?;
+        let frame = CesrParser::new(&bytes).next_frame()?.unwrap();
+        assert_eq!(frame.message, serder.raw());
+        let attachments = Attachments::from_str(std::str::from_utf8(frame.attachments).unwrap())?;
+        let sigs = &attachments.controller_signatures;
+        assert_eq!(sigs.len(), 2);
+        assert_eq!(sigs[1].code.index, 1);
+        assert!(keys(&[2])[0].verify(serder.raw(), &sigs[1].signature)?);
+        Ok(())
+    }
+
+    #[cfg(feature = "ml-dsa")]
+    #[test]
+    fn unindexed_schemes() -> Result<(), Error> {
+        use crate::{
+            derivation::self_signing::SelfSigning, keys::Signer, message::receipt::ReceiptBuilder,
+        };
+
+        // the indexed code table has no ML-DSA codes yet
+        let pq = Signer::random(SelfSigning::MLDSA44, true)?;
+        let icp = InceptionBuilder::new()
+            .keys(vec![pq.basic_prefix()])
+            .build()?;
+        assert!(ReceiptBuilder::new(&icp.serder)?.witness(&pq, 0).is_err());
+        assert!(icp.sign(&[pq]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn basic_prefix() -> Result<(), Error> {
+        let key = signer(1, true).basic_prefix().with_transferability(false)?;
+        let icp = InceptionBuilder::new()
+            .keys(vec![key.clone()])
+            .basic_prefix()
+            .kind(SerializationKind::CBOR)
+            .said_code(SelfAddressing::SHA3_256)
+            .build()?;
+        assert_eq!(icp.serder.get("i"), Some(&json!(key.to_str())));
+        assert_eq!(icp.said()?.derivation, SelfAddressing::SHA3_256);
+        assert_eq!(Serder::from_raw(icp.serder.raw())?, icp.serder);
+        assert!(icp.serder.verify_said()?);
+
+        let next = next_digests(&keys(&[2]), SelfAddressing::Blake3_256);
+        let builder = InceptionBuilder::new().keys(vec![key]).basic_prefix();
+        assert!(builder.clone().next_keys(next).build().is_err());
+        assert!(builder.keys(keys(&[1, 2])).build().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn rotation_and_interaction() -> Result<(), Error> {
+        let icp = InceptionBuilder::new()
+            .keys(keys(&[1]))
+            .next_keys(next_digests(&keys(&[2]), SelfAddressing::Blake3_256))
+            .witnesses(keys(&[5, 6]))
+            .build()?;
+        let prefix = IdentifierPrefix::from_str(icp.serder.get("i").unwrap().as_str().unwrap())?;
+
+        let rot = RotationBuilder::new(prefix.clone(), 1, icp.said()?)
+            .keys(keys(&[2]))
+            .witnesses(keys(&[5, 6]))
+            .cuts(keys(&[5]))
+            .adds(keys(&[7, 8]))
+            .build()?;
+        let serder = &rot.serder;
+        let labels: Vec<&str> = serder.ked().keys().map(String::as_str).collect();
+        assert_eq!(
+            labels,
+            ["v", "t", "d", "i", "s", "p", "kt", "k", "nt", "n", "bt", "br", "ba", "a"]
+        );
+        assert_eq!(serder.get("p"), Some(&json!(icp.said()?.to_str())));
+        assert_eq!(serder.get("nt"), Some(&json!("0")));
+        // three witnesses after the rotation
+        assert_eq!(serder.get("bt"), Some(&json!("3")));
+        assert!(serder.verify_said()?);
+
+        let seal = json!({"d": SelfAddressing::Blake3_256.derive(b"credential").to_str()});
+        let ixn = InteractionBuilder::new(prefix.clone(), 2, rot.said()?)
+            .anchor(seal.clone())
+            .build()?;
+        assert_eq!(ixn.serder.sn()?, 2);
+        assert_eq!(ixn.serder.get("a"), Some(&json!([seal])));
+        assert!(ixn.serder.verify_said()?);
+
+        let rot = || RotationBuilder::new(prefix.clone(), 1, icp.said().unwrap());
+        assert!(rot().build().is_err());
+        assert!(rot().keys(keys(&[2])).cuts(keys(&[5])).build().is_err());
+        assert!(rot()
+            .keys(keys(&[2]))
+            .witnesses(keys(&[5]))
+            .adds(keys(&[5]))
+            .build()
+            .is_err());
+        assert!(rot()
+            .keys(keys(&[2]))
+            .threshold(SignatureThreshold::Simple(2))
+            .build()
+            .is_err());
+        assert!(RotationBuilder::new(prefix.clone(), 0, icp.said()?)
+            .keys(keys(&[2]))
+            .build()
+            .is_err());
+        assert!(InteractionBuilder::new(prefix, 0, icp.said()?)
+            .build()
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn reordered_rotation() -> Result<(), Error> {
+        let icp = InceptionBuilder::new()
+            .keys(keys(&[1]))
+            .next_keys(next_digests(&keys(&[2, 3]), SelfAddressing::Blake3_256))
+            .build()?;
+        let prefix = IdentifierPrefix::from_str(icp.serder.get("i").unwrap().as_str().unwrap())?;
+        // the keys committed to as 2, 3 rotated in as 3, 2
+        let rot = RotationBuilder::new(prefix, 1, icp.said()?)
+            .keys(keys(&[3, 2]))
+            .build()?
+            .sign_dual(&[(&signer(3, true), 0, 1), (&signer(2, true), 1, 0)])?;
+        let bytes = rot.to_bytes()?;
+        let frame = CesrParser::new(&bytes).next_frame()?.unwrap();
+        let sigs = frame.parse_attachments()?.controller_signatures;
+        let indices: Vec<_> = sigs
+            .iter()
+            .map(|sig| (sig.code.index(), sig.code.ondex()))
+            .collect();
+        assert_eq!(indices, [(0, Some(1)), (1, Some(0))]);
+        assert!(keys(&[3])[0].verify(rot.serder.raw(), &sigs[0].signature)?);
+        Ok(())
+    }
+
+    #[test]
+    fn delegation() -> Result<(), Error> {
+        use crate::attachments::EventSeal;
+
+        let delegator = InceptionBuilder::new().keys(keys(&[9])).build()?;
+        let delegator_prefix: IdentifierPrefix = delegator
+            .serder
+            .get("i")
+            .unwrap()
+            .as_str()
+            .unwrap()
+            .parse()?;
+        let dip = InceptionBuilder::new()
+            .keys(keys(&[1]))
+            .delegator(delegator_prefix.clone())
+            .build()?;
+        let serder = dip.serder.clone();
+        assert_eq!(serder.ilk(), Some("dip"));
+        assert_eq!(
+            serder.ked().keys().next_back().map(String::as_str),
+            Some("di")
+        );
+        assert_eq!(serder.get("di"), Some(&json!(delegator_prefix.to_str())));
+        assert_eq!(serder.get("i"), serder.get("d"));
+        assert!(serder.verify_said()?);
+
+        let seal = dip.seal()?;
+        assert_eq!(seal, EventSeal::of(&serder)?);
+        assert_eq!(
+            Value::from(seal.clone()),
+            json!({"i": serder.get("i").unwrap(), "s": "0", "d": serder.get("d").unwrap()})
+        );
+        let unanchored =
+            InteractionBuilder::new(delegator_prefix.clone(), 1, delegator.said()?).build()?;
+        assert!(!seal.anchored_in(&unanchored.serder));
+        assert!(dip.clone().delegated_by(&unanchored.serder).is_err());
+
+        let anchoring = InteractionBuilder::new(delegator_prefix.clone(), 1, delegator.said()?)
+            .anchor(seal.clone())
+            .build()?;
+        let delegated = dip
+            .delegated_by(&anchoring.serder)?
+            .sign(&[signer(1, true)])?;
+        let bytes = delegated.to_bytes()?;
+        let frame = CesrParser::new(&bytes).next_frame()?.unwrap();
+        let source = &frame.parse_attachments()?.seal_source_couples;
+        assert_eq!(source[0].sn, 1);
+        assert_eq!(source[0].said, anchoring.said()?);
+
+        let prefix: IdentifierPrefix = serder.get("i").unwrap().as_str().unwrap().parse()?;
+        let drt = RotationBuilder::new(prefix, 1, serder.said()?)
+            .keys(keys(&[2]))
+            .delegated()
+            .build()?;
+        assert_eq!(drt.serder.ilk(), Some("drt"));
+        assert!(drt.serder.verify_said()?);
+
+        assert!(InceptionBuilder::new()
+            .keys(keys(&[1]))
+            .basic_prefix()
+            .delegator(delegator_prefix)
+            .build()
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_inception() {
+        assert!(InceptionBuilder::new().build().is_err());
+        let builder = InceptionBuilder::new().keys(keys(&[1, 2]));
+        assert!(builder
+            .clone()
+            .threshold(SignatureThreshold::Simple(3))
+            .build()
+            .is_err());
+        assert!(builder
+            .clone()
+            .threshold("[\"1/2\",\"1/2\",\"1/2\"]".parse().unwrap())
+            .build()
+            .is_err());
+        assert!(builder.clone().witnesses(keys(&[5, 5])).build().is_err());
+        assert!(builder
+            .clone()
+            .witnesses(keys(&[5]))
+            .witness_threshold(2)
+            .build()
+            .is_err());
+        assert!(builder.witness_threshold(1).build().is_err());
+    }
+}
@@ -0,0 +1,271 @@
+use super::{check_ilk, field, fields, saidify, Message};
+use crate::{
+    attachments::AttachmentsBuilder,
+    derivation::self_addressing::SelfAddressing,
+    error::Error,
+    prefix::{Dater, IdentifierPrefix, Prefix, SadPath, SelfAddressingPrefix},
+    said,
+    serder::Serder,
+    version::SerializationKind,
+};
+use core::str::FromStr;
+use serde_json::{Map, Value};
+
+/// Label of the embedded messages block.
+const EMBEDS_LABEL: &str = "e";
+
+/// Builds an exchange (`exn`) message between peers: a `route` with the query parameters `q`, the
+/// data block `a` and the messages embedded in the `e` block, e.g. the inception events of a
+/// multisig group proposal.
+///
+/// The attachments of an embedded message go along as pathed material (`-L`) at its path, e.g.
+/// `-e-icp`. The `e` block has a SAID of its own.
+#[derive(Debug, Clone)]
+pub struct ExchangeBuilder {
+    route: String,
+    sender: IdentifierPrefix,
+    recipient: Option<IdentifierPrefix>,
+    prior: Option<SelfAddressingPrefix>,
+    query: Map<String, Value>,
+    data: Map<String, Value>,
+    embeds: Vec<(String, Message)>,
+    datetime: Option<Dater>,
+    kind: SerializationKind,
+    code: SelfAddressing,
+}
+
+impl ExchangeBuilder {
+    /// Message of `sender` on `route`.
+    pub fn new(sender: IdentifierPrefix, route: impl Into<String>) -> Self {
+        Self {
+            route: route.into(),
+            sender,
+            recipient: None,
+            prior: None,
+            query: Map::new(),
+            data: Map::new(),
+            embeds: vec![],
+            datetime: None,
+            kind: SerializationKind::JSON,
+            code: SelfAddressing::Blake3_256,
+        }
+    }
+
+    /// The identifier the message is for, the `rp` field.
+    pub fn recipient(mut self, recipient: IdentifierPrefix) -> Self {
+        self.recipient = Some(recipient);
+        self
+    }
+
+    /// SAID of the exchange message this one follows up on, the `p` field.
+    pub fn prior(mut self, prior: SelfAddressingPrefix) -> Self {
+        self.prior = Some(prior);
+        self
+    }
+
+    /// Sets the query parameters, the `q` field.
+    pub fn query(mut self, query: Map<String, Value>) -> Self {
+        self.query = query;
+        self
+    }
+
+    /// Sets the data block, the `a` field.
+    pub fn data(mut self, data: Map<String, Value>) -> Self {
+        self.data = data;
+        self
+    }
+
+    /// Embeds `message` at `label` of the `e` block, its attachments as pathed material.
+    pub fn embed(mut self, label: impl Into<String>, message: Message) -> Self {
+        self.embeds.push((label.into(), message));
+        self
+    }
+
+    /// Datetime of the message, the `dt` field, now by default.
+    pub fn datetime(mut self, datetime: Dater) -> Self {
+        self.datetime = Some(datetime);
+        self
+    }
+
+    /// Serialization kind of the message, JSON by default.
+    pub fn kind(mut self, kind: SerializationKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    /// Digest code of the SAIDs, Blake3-256 by default.
+    pub fn said_code(mut self, code: SelfAddressing) -> Self {
+        self.code = code;
+        self
+    }
+
+    pub fn build(&self) -> Result<Message, Error> {
+        let mut attachments = AttachmentsBuilder::new();
+        let mut embeds = Map::new();
+        if !self.embeds.is_empty() {
+            embeds.insert("d".into(), "".into());
+            for (label, message) in &self.embeds {
+                if embeds.contains_key(label) {
+                    return Err(Error::SemanticError(format!(
+                        "Duplicate embedded message: {}",
+                        label
+                    )));
+                }
+                embeds.insert(label.clone(), message.serder.ked().clone().into());
+                let path = SadPath::new([EMBEDS_LABEL, label])?;
+                attachments = attachments.pathed_material(path, message.attachments.clone());
+            }
+            let said = said::compute_said(&embeds, &["d"], &self.code, self.kind)?;
+            embeds.insert("d".into(), said.to_str().into());
+        }
+
+        let text = |prefix: Option<String>| Value::from(prefix.unwrap_or_default());
+        let datetime = self.datetime.clone().unwrap_or_else(Dater::now);
+        let mut ked = fields("exn");
+        ked.insert("i".into(), self.sender.to_str().into());
+        ked.insert(
+            "rp".into(),
+            text(self.recipient.as_ref().map(Prefix::to_str)),
+        );
+        ked.insert("p".into(), text(self.prior.as_ref().map(Prefix::to_str)));
+        ked.insert("dt".into(), datetime.iso8601().into());
+        ked.insert("r".into(), self.route.clone().into());
+        ked.insert("q".into(), self.query.clone().into());
+        ked.insert("a".into(), self.data.clone().into());
+        ked.insert(EMBEDS_LABEL.into(), embeds.into());
+        Ok(Message {
+            serder: saidify(ked, &self.code, self.kind)?,
+            attachments,
+        })
+    }
+}
+
+/// An exchange (`exn`) as parsed from a stream, its SAID and the SAID of its `e` block
+/// verified.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Exchange {
+    pub said: SelfAddressingPrefix,
+    pub sender: IdentifierPrefix,
+    pub recipient: Option<IdentifierPrefix>,
+    pub prior: Option<SelfAddressingPrefix>,
+    pub datetime: Dater,
+    pub route: String,
+    pub query: Map<String, Value>,
+    pub data: Map<String, Value>,
+    pub embeds: Map<String, Value>,
+    kind: SerializationKind,
+}
+
+impl Exchange {
+    pub fn from_serder(serder: &Serder) -> Result<Self, Error> {
+        check_ilk(serder, "exn")?;
+        if !serder.verify_said()? {
+            return Err(Error::IncorrectDigest);
+        }
+        let block = |label: &str| {
+            serder
+                .get(label)
+                .and_then(Value::as_object)
+                .cloned()
+                .ok_or_else(|| Error::SemanticError(format!("Missing {} field", label)))
+        };
+        let embeds = block(EMBEDS_LABEL)?;
+        if !embeds.is_empty() && !said::verify_said(&embeds, "d", serder.kind())? {
+            return Err(Error::IncorrectDigest);
+        }
+        fn optional<T: FromStr<Err = Error>>(s: &str) -> Result<Option<T>, Error> {
+            match s {
+                "" => Ok(None),
+                s => s.parse().map(Some),
+            }
+        }
+        Ok(Self {
+            said: serder.said()?,
+            sender: field(serder, "i")?.parse()?,
+            recipient: optional(field(serder, "rp")?)?,
+            prior: optional(field(serder, "p")?)?,
+            datetime: Dater::from_iso8601(field(serder, "dt")?)?,
+            route: field(serder, "r")?.into(),
+            query: block("q")?,
+            data: block("a")?,
+            embeds,
+            kind: serder.kind(),
+        })
+    }
+
+    /// The message embedded at `label` of the `e` block, serialized as the exchange is.
+    pub fn embedded(&self, label: &str) -> Result<Serder, Error> {
+        match self.embeds.get(label) {
+            Some(Value::Object(ked)) if label != "d" => Serder::from_ked(ked.clone(), self.kind),
+            _ => Err(Error::SemanticError(format!(
+                "No embedded message at {}",
+                label
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{message::InceptionBuilder, parser::CesrParser, test_utils::signer};
+    use serde_json::json;
+
+    #[test]
+    fn exchange() -> Result<(), Error> {
+        let member = signer(1, true);
+        let icp = InceptionBuilder::new()
+            .keys(vec![member.basic_prefix(), signer(2, true).basic_prefix()])
+            .build()?
+            .sign(&[signer(1, true)])?;
+        let sender = IdentifierPrefix::Basic(member.basic_prefix());
+        let Value::Object(data) = json!({"gid": icp.serder.get("i").unwrap()}) else {
+            unreachable!()
+        };
+        let exn = ExchangeBuilder::new(sender.clone(), "/multisig/icp")
+            .data(data.clone())
+            .embed("icp", icp.clone())
+            .build()?;
+        let labels: Vec<&str> = exn.serder.ked().keys().map(String::as_str).collect();
+        assert_eq!(
+            labels,
+            ["v", "t", "d", "i", "rp", "p", "dt", "r", "q", "a", "e"]
+        );
+
+        let bytes = exn.clone().sign(&[member])?.to_bytes()?;
+        let frame = CesrParser::new(&bytes).next_frame()?.unwrap();
+        let exchange = Exchange::from_serder(&Serder::from_raw(frame.message)?)?;
+        assert_eq!(exchange.said, exn.said()?);
+        assert_eq!(exchange.sender, sender);
+        assert_eq!(exchange.recipient, None);
+        assert_eq!(exchange.route, "/multisig/icp");
+        assert_eq!(exchange.data, data);
+        assert_eq!(exchange.embedded("icp")?, icp.serder);
+        assert!(exchange.embedded("d").is_err());
+        assert!(exchange.embedded("rot").is_err());
+
+        // the signatures of the embedded event go along at its path
+        let attachments = frame.parse_attachments()?;
+        assert_eq!(attachments.controller_signatures.len(), 1);
+        let (path, embedded) = &attachments.pathed_material[0];
+        assert_eq!(path.to_path(), "-e-icp");
+        assert!(embedded.controller_signatures[0]
+            .clone()
+            .resolve(&[signer(1, true).basic_prefix()])?
+            .verify(icp.serder.raw())?);
+
+        let mut ked = exn.serder.ked().clone();
+        // a stale SAID of the `e` block under a fresh SAID of the message
+        ked["e"]["icp"]["s"] = "1".into();
+        let said = exn.said()?;
+        ked.insert("d".into(), "".into());
+        let resaidified = super::saidify(ked, &said.derivation, SerializationKind::JSON)?;
+        assert!(Exchange::from_serder(&resaidified).is_err());
+
+        let twice = ExchangeBuilder::new(sender, "/multisig/icp")
+            .embed("icp", icp.clone())
+            .embed("icp", icp);
+        assert!(twice.build().is_err());
+        Ok(())
+    }
+}
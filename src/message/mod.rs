@@ -0,0 +1,271 @@
+use crate::{
+    attachments::{AttachmentsBuilder, EventSeal, TransIdxSigGroup},
+    derivation::{attached_signature_code::AttachedSignatureCode, self_addressing::SelfAddressing},
+    error::Error,
+    keys::Signer,
+    prefix::{
+        AttachedSignaturePrefix, BasicPrefix, Cigar, IdentifierPrefix, Prefix, SelfAddressingPrefix,
+    },
+    serder::Serder,
+    threshold::SignatureThreshold,
+    version::SerializationKind,
+};
+use serde_json::{Map, Value};
+
+pub mod duplicity;
+pub mod event;
+pub mod exchange;
+pub mod query;
+pub mod receipt;
+pub mod reply;
+pub mod tel;
+
+pub use duplicity::Duplicity;
+pub use event::{InceptionBuilder, InteractionBuilder, RotationBuilder};
+pub use exchange::{Exchange, ExchangeBuilder};
+pub use query::{Query, QueryBuilder};
+pub use receipt::{Receipt, ReceiptBuilder, ReceiptSet};
+pub use reply::{Reply, ReplyBuilder};
+pub use tel::{verify_tel_event, IssuanceBuilder, RegistryInceptionBuilder, RevocationBuilder};
+
+/// Version string of KERI 1.0 messages, sized once the message is serialized.
+const VERSION: &str = "KERI10JSON000000_";
+
+/// A KERI message together with the attachment groups that go with it in a stream.
+#[derive(Debug, Clone)]
+pub struct Message {
+    pub serder: Serder,
+    pub attachments: AttachmentsBuilder,
+}
+
+impl Message {
+    pub fn new(serder: Serder) -> Self {
+        Self {
+            serder,
+            attachments: AttachmentsBuilder::new(),
+        }
+    }
+
+    pub fn said(&self) -> Result<SelfAddressingPrefix, Error> {
+        self.serder.said()
+    }
+
+    /// Attaches the signatures of `signers` over the message, indexed by their position in
+    /// `signers`, which is expected to be the order of the keys of the event. Fails for
+    /// schemes with no indexed signature code, e.g. ML-DSA.
+    pub fn sign(mut self, signers: &[Signer]) -> Result<Self, Error> {
+        let sigs = indexed_signatures(signers, self.serder.raw())?;
+        self.attachments = self.attachments.controller_signatures(sigs);
+        Ok(self)
+    }
+
+    /// Attaches the signatures of `signers` over the message, each with the index of its key in
+    /// the current key list and the ondex of its key digest in the prior next key list, e.g. for
+    /// a rotation that reorders the keys committed to.
+    pub fn sign_dual(mut self, signers: &[(&Signer, u16, u16)]) -> Result<Self, Error> {
+        let sigs = signers
+            .iter()
+            .map(|(signer, index, ondex)| {
+                let sig = signer.sign(self.serder.raw());
+                AttachedSignatureCode::dual(sig.derivation, *index, *ondex)
+                    .map(|code| AttachedSignaturePrefix::with_code(code, sig.signature))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        self.attachments = self.attachments.controller_signatures(sigs);
+        Ok(self)
+    }
+
+    /// Attaches the signature of a nontransferable `signer` as a receipt couple (`-C`), e.g. a
+    /// witness replying about its own endpoints.
+    pub fn endorse(mut self, signer: &Signer) -> Self {
+        let cigar = Cigar::new(signer.basic_prefix(), signer.sign(self.serder.raw()));
+        self.attachments = self.attachments.receipt_couple(cigar);
+        self
+    }
+
+    /// Attaches the signatures of the controller of a transferable identifier as a
+    /// transferable indexed signature group (`-F`), with the establishment event of `prefix`
+    /// at `sn` of SAID `said` holding the keys of `signers`.
+    pub fn endorse_trans(
+        mut self,
+        prefix: IdentifierPrefix,
+        sn: u64,
+        said: SelfAddressingPrefix,
+        signers: &[Signer],
+    ) -> Result<Self, Error> {
+        let sigs = indexed_signatures(signers, self.serder.raw())?;
+        let group = TransIdxSigGroup::new(prefix, sn, said, sigs);
+        self.attachments = self.attachments.trans_idx_sig_group(group);
+        Ok(self)
+    }
+
+    /// Seal of the event, for another identifier to anchor, e.g. the delegator of a delegated
+    /// event.
+    pub fn seal(&self) -> Result<EventSeal, Error> {
+        EventSeal::of(&self.serder)
+    }
+
+    /// Attaches the seal source couple (`-G`) of the event of the delegator anchoring this
+    /// delegated event. Fails unless `anchoring` anchors the seal of this event.
+    pub fn delegated_by(mut self, anchoring: &Serder) -> Result<Self, Error> {
+        if !self.seal()?.anchored_in(anchoring) {
+            return Err(Error::SemanticError(format!(
+                "Event {} is not anchored in {}",
+                self.said()?.to_str(),
+                anchoring.said()?.to_str()
+            )));
+        }
+        self.attachments = self
+            .attachments
+            .seal_source_couple(anchoring.sn()?, anchoring.said()?);
+        Ok(self)
+    }
+
+    /// The serialized message followed by its attachment groups, ready to be streamed.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, Error> {
+        Ok([self.serder.raw(), self.attachments.build()?.as_bytes()].concat())
+    }
+}
+
+/// Signatures of `signers` over `data`, indexed by their position in `signers`.
+fn indexed_signatures(
+    signers: &[Signer],
+    data: &[u8],
+) -> Result<Vec<AttachedSignaturePrefix>, Error> {
+    signers
+        .iter()
+        .enumerate()
+        .map(|(i, signer)| {
+            let index = u16::try_from(i)
+                .map_err(|_| Error::SemanticError(format!("Signer index {} too big", i)))?;
+            let sig = signer.sign(data);
+            AttachedSignatureCode::dual(sig.derivation, index, index)
+                .map(|code| AttachedSignaturePrefix::with_code(code, sig.signature))
+        })
+        .collect()
+}
+
+/// The fields every KERI message starts with: version string, message type and an empty SAID.
+fn fields(ilk: &str) -> Map<String, Value> {
+    let mut ked = Map::new();
+    ked.insert("v".into(), VERSION.into());
+    ked.insert("t".into(), ilk.into());
+    ked.insert("d".into(), "".into());
+    ked
+}
+
+/// Serializes `ked` as `kind` with its SAID embedded, and its prefix too when it was left empty
+/// for a self-addressing identifier.
+fn saidify(
+    mut ked: Map<String, Value>,
+    code: &SelfAddressing,
+    kind: SerializationKind,
+) -> Result<Serder, Error> {
+    let said = Serder::from_ked(ked.clone(), kind)?.compute_said(code)?;
+    ked.insert("d".into(), said.to_str().into());
+    if ked.get("i").and_then(Value::as_str) == Some("") {
+        ked.insert("i".into(), said.to_str().into());
+    }
+    Serder::from_ked(ked, kind)
+}
+
+/// Sequence numbers are hex strings in KERI messages.
+fn hex(sn: u64) -> Value {
+    format!("{:x}", sn).into()
+}
+
+/// Fails unless `serder` is a message of type `ilk`.
+fn check_ilk(serder: &Serder, ilk: &str) -> Result<(), Error> {
+    match serder.ilk() {
+        Some(found) if found == ilk => Ok(()),
+        found => Err(Error::SemanticError(format!(
+            "Expected a {} message, got {:?}",
+            ilk, found
+        ))),
+    }
+}
+
+/// The string field `label` of `serder`.
+fn field<'a>(serder: &'a Serder, label: &str) -> Result<&'a str, Error> {
+    serder
+        .get(label)
+        .and_then(Value::as_str)
+        .ok_or_else(|| Error::SemanticError(format!("Missing {} field", label)))
+}
+
+/// Checks `threshold` can be met by a list of `len` keys, `what` naming the list in errors.
+fn check_threshold(threshold: &SignatureThreshold, len: usize, what: &str) -> Result<(), Error> {
+    let valid = match threshold {
+        SignatureThreshold::Simple(threshold) => *threshold as usize <= len,
+        SignatureThreshold::Weighted(_) => threshold.key_count() == Some(len),
+    };
+    match valid {
+        true => Ok(()),
+        false => Err(Error::SemanticError(format!(
+            "Invalid {} threshold {} for {} keys",
+            what, threshold, len
+        ))),
+    }
+}
+
+/// Fails when `witnesses` holds the same witness twice.
+fn check_unique(witnesses: &[BasicPrefix], what: &str) -> Result<(), Error> {
+    for (i, witness) in witnesses.iter().enumerate() {
+        if witnesses[..i].contains(witness) {
+            return Err(Error::SemanticError(format!(
+                "Duplicate witness in {}: {}",
+                what,
+                witness.to_str()
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Checks the witness threshold `toad` of `witnesses`, and returns it or its default.
+fn witness_threshold(toad: Option<u64>, witnesses: &[BasicPrefix]) -> Result<u64, Error> {
+    let toad = toad.unwrap_or_else(|| ample(witnesses.len()));
+    let valid = match witnesses.len() {
+        0 => toad == 0,
+        len => (1..=len as u64).contains(&toad),
+    };
+    match valid {
+        true => Ok(toad),
+        false => Err(Error::SemanticError(format!(
+            "Invalid witness threshold {} for {} witnesses",
+            toad,
+            witnesses.len()
+        ))),
+    }
+}
+
+/// Default signing threshold of `len` keys, a majority.
+fn majority(len: usize) -> SignatureThreshold {
+    SignatureThreshold::Simple(len.div_ceil(2) as u64)
+}
+
+/// Default witness threshold of `len` witnesses as in KERIpy, the smallest number of receipts
+/// that still guarantees agreement with up to a third of faulty witnesses.
+pub fn ample(len: usize) -> u64 {
+    if len == 0 {
+        return 0;
+    }
+    let faulty = [((len - 1).div_ceil(3)).max(1), ((len - 1) / 3).max(1)];
+    let min = faulty
+        .iter()
+        .map(|f| (len + f + 1).div_ceil(2))
+        .min()
+        .unwrap_or(len);
+    min.min(len) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ample_witnesses() {
+        let ample: Vec<u64> = (0..=13).map(ample).collect();
+        assert_eq!(ample, [0, 1, 2, 3, 3, 4, 4, 5, 6, 6, 7, 8, 8, 9]);
+    }
+}
@@ -0,0 +1,132 @@
+use super::{check_ilk, field, fields, saidify, Message};
+use crate::{
+    derivation::self_addressing::SelfAddressing,
+    error::Error,
+    prefix::{Dater, SelfAddressingPrefix},
+    serder::Serder,
+    version::SerializationKind,
+};
+use serde_json::{Map, Value};
+
+/// Builds a query (`qry`) message, which asks on `route` with the parameters `q`, e.g. for the
+/// log of an identifier on `logs`. Replies go to the return route `rr`.
+#[derive(Debug, Clone)]
+pub struct QueryBuilder {
+    route: String,
+    return_route: String,
+    query: Map<String, Value>,
+    datetime: Option<Dater>,
+    kind: SerializationKind,
+    code: SelfAddressing,
+}
+
+impl QueryBuilder {
+    pub fn new(route: impl Into<String>, query: Map<String, Value>) -> Self {
+        Self {
+            route: route.into(),
+            return_route: String::new(),
+            query,
+            datetime: None,
+            kind: SerializationKind::JSON,
+            code: SelfAddressing::Blake3_256,
+        }
+    }
+
+    /// Route replies are expected on, the `rr` field.
+    pub fn return_route(mut self, route: impl Into<String>) -> Self {
+        self.return_route = route.into();
+        self
+    }
+
+    /// Datetime of the query, the `dt` field, now by default.
+    pub fn datetime(mut self, datetime: Dater) -> Self {
+        self.datetime = Some(datetime);
+        self
+    }
+
+    /// Serialization kind of the query, JSON by default.
+    pub fn kind(mut self, kind: SerializationKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    /// Digest code of the SAID, Blake3-256 by default.
+    pub fn said_code(mut self, code: SelfAddressing) -> Self {
+        self.code = code;
+        self
+    }
+
+    pub fn build(&self) -> Result<Message, Error> {
+        let datetime = self.datetime.clone().unwrap_or_else(Dater::now);
+        let mut ked = fields("qry");
+        ked.insert("dt".into(), datetime.iso8601().into());
+        ked.insert("r".into(), self.route.clone().into());
+        ked.insert("rr".into(), self.return_route.clone().into());
+        ked.insert("q".into(), self.query.clone().into());
+        Ok(Message::new(saidify(ked, &self.code, self.kind)?))
+    }
+}
+
+/// A query (`qry`) as parsed from a stream, its SAID verified.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Query {
+    pub said: SelfAddressingPrefix,
+    pub datetime: Dater,
+    pub route: String,
+    pub return_route: String,
+    pub query: Map<String, Value>,
+}
+
+impl Query {
+    pub fn from_serder(serder: &Serder) -> Result<Self, Error> {
+        check_ilk(serder, "qry")?;
+        if !serder.verify_said()? {
+            return Err(Error::IncorrectDigest);
+        }
+        let query = serder
+            .get("q")
+            .and_then(Value::as_object)
+            .ok_or_else(|| Error::SemanticError("Missing q field".into()))?;
+        Ok(Self {
+            said: serder.said()?,
+            datetime: Dater::from_iso8601(field(serder, "dt")?)?,
+            route: field(serder, "r")?.into(),
+            return_route: field(serder, "rr")?.into(),
+            query: query.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn query() -> Result<(), Error> {
+        let Value::Object(params) = json!({"i": "EAAA", "s": "0"}) else {
+            unreachable!()
+        };
+        let datetime = Dater::from_iso8601("2021-01-01T00:00:00.000000+00:00")?;
+        let qry = QueryBuilder::new("logs", params.clone())
+            .return_route("log/processor")
+            .datetime(datetime.clone())
+            .kind(SerializationKind::CBOR)
+            .build()?;
+        let labels: Vec<&str> = qry.serder.ked().keys().map(String::as_str).collect();
+        assert_eq!(labels, ["v", "t", "d", "dt", "r", "rr", "q"]);
+
+        let query = Query::from_serder(&Serder::from_raw(qry.serder.raw())?)?;
+        assert_eq!(query.said, qry.said()?);
+        assert_eq!(query.datetime, datetime);
+        assert_eq!(query.route, "logs");
+        assert_eq!(query.return_route, "log/processor");
+        assert_eq!(query.query, params);
+
+        let mut ked = qry.serder.ked().clone();
+        ked.insert("rr".into(), "elsewhere".into());
+        let tampered = Serder::from_ked(ked, SerializationKind::CBOR)?;
+        assert!(Query::from_serder(&tampered).is_err());
+        Ok(())
+    }
+}
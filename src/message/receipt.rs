@@ -0,0 +1,364 @@
+use super::{
+    check_ilk, check_unique, field, fields, hex, indexed_signatures, witness_threshold, Message,
+};
+use crate::{
+    attachments::{Attachments, AttachmentsBuilder, TransIdxSigGroup},
+    derivation::attached_signature_code::AttachedSignatureCode,
+    error::Error,
+    keys::Signer,
+    prefix::{
+        AttachedSignaturePrefix, BasicPrefix, Cigar, IdentifierPrefix, Prefix,
+        SelfAddressingPrefix, Siger,
+    },
+    serder::Serder,
+    version::SerializationKind,
+};
+use core::str::FromStr;
+
+/// Builds the receipt (`rct`) of an event, which names the event by its prefix, sequence
+/// number and SAID and carries the signatures of the receiptors over the event as attachments:
+/// witness signatures (`-B`) indexed into the witness list of the event, receipt couples (`-C`)
+/// of nontransferable receiptors and indexed signature groups (`-F`) of transferable ones.
+#[derive(Debug, Clone)]
+pub struct ReceiptBuilder {
+    event: Serder,
+    kind: SerializationKind,
+    attachments: AttachmentsBuilder,
+}
+
+impl ReceiptBuilder {
+    pub fn new(event: &Serder) -> Result<Self, Error> {
+        Receipt::of(event)?;
+        Ok(Self {
+            event: event.clone(),
+            kind: SerializationKind::JSON,
+            attachments: AttachmentsBuilder::new(),
+        })
+    }
+
+    /// Serialization kind of the receipt, JSON by default.
+    pub fn kind(mut self, kind: SerializationKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    /// Adds the signature of the witness at `index` in the witness list of the event, under a
+    /// current only code as witnesses have no prior next key list.
+    pub fn witness(mut self, signer: &Signer, index: u16) -> Result<Self, Error> {
+        let sig = signer.sign(self.event.raw());
+        let code = AttachedSignatureCode::current_only(sig.derivation, index)?;
+        let sig = AttachedSignaturePrefix::with_code(code, sig.signature);
+        self.attachments = self.attachments.witness_signature(sig);
+        Ok(self)
+    }
+
+    /// Adds the signature of a nontransferable receiptor.
+    pub fn nontrans(mut self, signer: &Signer) -> Self {
+        let cigar = Cigar::new(signer.basic_prefix(), signer.sign(self.event.raw()));
+        self.attachments = self.attachments.receipt_couple(cigar);
+        self
+    }
+
+    /// Adds the signatures of a transferable receiptor, whose keys are those of the
+    /// establishment event of `prefix` at `sn` of SAID `said`.
+    pub fn trans(
+        mut self,
+        prefix: IdentifierPrefix,
+        sn: u64,
+        said: SelfAddressingPrefix,
+        signers: &[Signer],
+    ) -> Result<Self, Error> {
+        let sigs = indexed_signatures(signers, self.event.raw())?;
+        let group = TransIdxSigGroup::new(prefix, sn, said, sigs);
+        self.attachments = self.attachments.trans_idx_sig_group(group);
+        Ok(self)
+    }
+
+    pub fn build(&self) -> Result<Message, Error> {
+        let receipt = Receipt::of(&self.event)?;
+        let mut ked = fields("rct");
+        ked.insert("d".into(), receipt.said.to_str().into());
+        ked.insert("i".into(), receipt.prefix.to_str().into());
+        ked.insert("s".into(), hex(receipt.sn));
+        Ok(Message {
+            serder: Serder::from_ked(ked, self.kind)?,
+            attachments: self.attachments.clone(),
+        })
+    }
+}
+
+/// A receipt (`rct`) as parsed from a stream: the prefix, sequence number and SAID of the
+/// receipted event.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Receipt {
+    pub prefix: IdentifierPrefix,
+    pub sn: u64,
+    pub said: SelfAddressingPrefix,
+}
+
+impl Receipt {
+    pub fn from_serder(serder: &Serder) -> Result<Self, Error> {
+        check_ilk(serder, "rct")?;
+        Self::of(serder)
+    }
+
+    /// The event named by a receipt of `event`.
+    fn of(event: &Serder) -> Result<Self, Error> {
+        Ok(Self {
+            prefix: IdentifierPrefix::from_str(field(event, "i")?)?,
+            sn: event.sn()?,
+            said: event.said()?,
+        })
+    }
+
+    /// Whether this is a receipt of `event`.
+    pub fn receipts(&self, event: &Serder) -> Result<bool, Error> {
+        Ok(Self::of(event)? == *self)
+    }
+
+    /// The nontransferable receiptors whose receipt couples in `attachments` verify over
+    /// `event`. Fails if this is not a receipt of `event`.
+    pub fn receiptors(
+        &self,
+        event: &Serder,
+        attachments: &Attachments,
+    ) -> Result<Vec<BasicPrefix>, Error> {
+        if !self.receipts(event)? {
+            return Err(Error::SemanticError(format!(
+                "Receipt of {} is not one of {}",
+                self.said.to_str(),
+                event.said()?.to_str()
+            )));
+        }
+        let mut receiptors = vec![];
+        for cigar in &attachments.receipt_couples {
+            if cigar.verify(event.raw())? {
+                receiptors.push(cigar.verfer.clone());
+            }
+        }
+        Ok(receiptors)
+    }
+}
+
+/// The receipts of the witnesses of an event as they come in, as witness signatures (`-B`) or
+/// receipt couples (`-C`), each verified over the event and kept once per witness. The event is
+/// fully witnessed once the receipts reach the witness threshold (toad).
+#[derive(Debug, Clone)]
+pub struct ReceiptSet {
+    event: Serder,
+    witnesses: Vec<BasicPrefix>,
+    toad: u64,
+    receipts: Vec<Option<Cigar>>,
+}
+
+impl ReceiptSet {
+    /// Receipts of `event` by `witnesses`, the witness list of the key state the event
+    /// establishes, of which `toad` must receipt it.
+    pub fn new(event: &Serder, witnesses: Vec<BasicPrefix>, toad: u64) -> Result<Self, Error> {
+        Receipt::of(event)?;
+        check_unique(&witnesses, "witness list")?;
+        let toad = witness_threshold(Some(toad), &witnesses)?;
+        Ok(Self {
+            event: event.clone(),
+            receipts: vec![None; witnesses.len()],
+            witnesses,
+            toad,
+        })
+    }
+
+    /// Adds the receipt couple of a witness. Returns whether the witness had not receipted yet.
+    /// Fails if the receiptor is not a witness or its signature does not verify.
+    pub fn add(&mut self, cigar: &Cigar) -> Result<bool, Error> {
+        let index = self
+            .witnesses
+            .iter()
+            .position(|witness| *witness == cigar.verfer)
+            .ok_or_else(|| {
+                Error::SemanticError(format!("Not a witness: {}", cigar.verfer.to_str()))
+            })?;
+        if !cigar.verify(self.event.raw())? {
+            return Err(Error::FaultySignatureVerification);
+        }
+        Ok(self.receipts[index].replace(cigar.clone()).is_none())
+    }
+
+    /// Adds the signature of the witness at its index in the witness list, see [Self::add].
+    pub fn add_indexed(&mut self, siger: &Siger) -> Result<bool, Error> {
+        let witness = self.witnesses.get(siger.index() as usize).ok_or_else(|| {
+            Error::SemanticError(format!(
+                "Signature index {} out of range of {} witnesses",
+                siger.index(),
+                self.witnesses.len()
+            ))
+        })?;
+        self.add(&Cigar::new(witness.clone(), siger.signature.clone()))
+    }
+
+    /// Adds the witness signatures and receipt couples of `attachments`. Returns the number of
+    /// witnesses which had not receipted yet.
+    pub fn add_attachments(&mut self, attachments: &Attachments) -> Result<usize, Error> {
+        let mut added = 0;
+        for siger in &attachments.witness_signatures {
+            added += self.add_indexed(siger)? as usize;
+        }
+        for cigar in &attachments.receipt_couples {
+            added += self.add(cigar)? as usize;
+        }
+        Ok(added)
+    }
+
+    /// Adds the signatures attached to `receipt`, which must be a receipt of the event.
+    pub fn add_receipt(
+        &mut self,
+        receipt: &Serder,
+        attachments: &Attachments,
+    ) -> Result<usize, Error> {
+        if !Receipt::from_serder(receipt)?.receipts(&self.event)? {
+            return Err(Error::SemanticError(format!(
+                "Receipt of {} is not one of {}",
+                receipt.said()?.to_str(),
+                self.event.said()?.to_str()
+            )));
+        }
+        self.add_attachments(attachments)
+    }
+
+    /// The receipt couples of the witnesses which receipted, in the order of the witness list.
+    pub fn cigars(&self) -> Vec<Cigar> {
+        self.receipts.iter().flatten().cloned().collect()
+    }
+
+    pub fn receiptors(&self) -> Vec<BasicPrefix> {
+        self.receipts
+            .iter()
+            .flatten()
+            .map(|cigar| cigar.verfer.clone())
+            .collect()
+    }
+
+    /// The witnesses which did not receipt yet.
+    pub fn missing(&self) -> Vec<BasicPrefix> {
+        self.witnesses
+            .iter()
+            .zip(&self.receipts)
+            .filter(|(_, receipt)| receipt.is_none())
+            .map(|(witness, _)| witness.clone())
+            .collect()
+    }
+
+    pub fn count(&self) -> usize {
+        self.receipts.iter().flatten().count()
+    }
+
+    /// Whether at least the witness threshold of witnesses receipted.
+    pub fn satisfied(&self) -> bool {
+        self.count() as u64 >= self.toad
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{message::InceptionBuilder, parser::CesrParser, test_utils::signer};
+
+    #[test]
+    fn receipt() -> Result<(), Error> {
+        let witness = signer(5, false);
+        let icp = InceptionBuilder::new()
+            .keys(vec![signer(1, true).basic_prefix()])
+            .witnesses(vec![witness.basic_prefix()])
+            .build()?;
+        let event = &icp.serder;
+
+        let validator = signer(7, true);
+        let rct = ReceiptBuilder::new(event)?
+            .witness(&witness, 0)?
+            .nontrans(&witness)
+            .trans(
+                IdentifierPrefix::Basic(validator.basic_prefix()),
+                0,
+                event.said()?,
+                &[validator],
+            )?
+            .build()?;
+        let labels: Vec<&str> = rct.serder.ked().keys().map(String::as_str).collect();
+        assert_eq!(labels, ["v", "t", "d", "i", "s"]);
+        assert_eq!(rct.serder.said()?, event.said()?);
+
+        let bytes = rct.to_bytes()?;
+        let frame = CesrParser::new(&bytes).next_frame()?.unwrap();
+        let serder = Serder::from_raw(frame.message)?;
+        let attachments = frame.parse_attachments()?;
+        assert_eq!(attachments.witness_signatures.len(), 1);
+        assert_eq!(attachments.trans_idx_sig_groups.len(), 1);
+        assert!(attachments.witness_signatures[0]
+            .clone()
+            .resolve(&[witness.basic_prefix()])?
+            .verify(event.raw())?);
+
+        let receipt = Receipt::from_serder(&serder)?;
+        assert_eq!(receipt.sn, 0);
+        assert!(receipt.receipts(event)?);
+        assert_eq!(
+            receipt.receiptors(event, &attachments)?,
+            [witness.basic_prefix()]
+        );
+
+        let other = InceptionBuilder::new()
+            .keys(vec![signer(2, true).basic_prefix()])
+            .build()?;
+        assert!(!receipt.receipts(&other.serder)?);
+        assert!(receipt.receiptors(&other.serder, &attachments).is_err());
+        assert!(Receipt::from_serder(event).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn receipt_set() -> Result<(), Error> {
+        let witnesses: Vec<Signer> = (5..8).map(|seed| signer(seed, false)).collect();
+        let prefixes: Vec<BasicPrefix> = witnesses.iter().map(Signer::basic_prefix).collect();
+        let icp = InceptionBuilder::new()
+            .keys(vec![signer(1, true).basic_prefix()])
+            .witnesses(prefixes.clone())
+            .witness_threshold(2)
+            .build()?;
+        let event = &icp.serder;
+        let mut set = ReceiptSet::new(event, prefixes.clone(), 2)?;
+        assert!(!set.satisfied());
+
+        // the same witness receipting indexed and as a couple counts once
+        let rct = ReceiptBuilder::new(event)?
+            .witness(&witnesses[0], 0)?
+            .nontrans(&witnesses[0])
+            .build()?;
+        let bytes = rct.to_bytes()?;
+        let frame = CesrParser::new(&bytes).next_frame()?.unwrap();
+        let attachments = frame.parse_attachments()?;
+        assert_eq!(set.add_receipt(&rct.serder, &attachments)?, 1);
+        assert!(!set.satisfied());
+
+        let cigar = Cigar::new(prefixes[2].clone(), witnesses[2].sign(event.raw()));
+        assert!(set.add(&cigar)?);
+        assert!(!set.add(&cigar)?);
+        assert!(set.satisfied());
+        assert_eq!(set.count(), 2);
+        assert_eq!(set.receiptors(), [prefixes[0].clone(), prefixes[2].clone()]);
+        assert_eq!(set.missing(), [prefixes[1].clone()]);
+        assert_eq!(set.cigars()[1], cigar);
+
+        let stranger = signer(9, false);
+        let foreign = Cigar::new(stranger.basic_prefix(), stranger.sign(event.raw()));
+        assert!(set.add(&foreign).is_err());
+        let forged = Cigar::new(prefixes[1].clone(), witnesses[1].sign(b"other event"));
+        assert!(set.add(&forged).is_err());
+        let other = InceptionBuilder::new()
+            .keys(vec![signer(2, true).basic_prefix()])
+            .build()?;
+        let other_rct = ReceiptBuilder::new(&other.serder)?.build()?;
+        assert!(set.add_receipt(&other_rct.serder, &attachments).is_err());
+
+        assert!(ReceiptSet::new(event, prefixes.clone(), 4).is_err());
+        assert!(ReceiptSet::new(event, vec![prefixes[0].clone(); 2], 1).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,150 @@
+use super::{check_ilk, field, fields, saidify, Message};
+use crate::{
+    derivation::self_addressing::SelfAddressing,
+    error::Error,
+    prefix::{Dater, SelfAddressingPrefix},
+    serder::Serder,
+    version::SerializationKind,
+};
+use serde_json::{Map, Value};
+
+/// Builds a reply (`rpy`) message, which answers on `route` with the data block `a`, e.g. the
+/// endpoint of a witness on `/end/role/add`. The SAID covers the route and the data block.
+///
+/// Replies are signed with [Message::endorse] by nontransferable identifiers and with
+/// [Message::endorse_trans] by transferable ones.
+#[derive(Debug, Clone)]
+pub struct ReplyBuilder {
+    route: String,
+    data: Map<String, Value>,
+    datetime: Option<Dater>,
+    kind: SerializationKind,
+    code: SelfAddressing,
+}
+
+impl ReplyBuilder {
+    pub fn new(route: impl Into<String>, data: Map<String, Value>) -> Self {
+        Self {
+            route: route.into(),
+            data,
+            datetime: None,
+            kind: SerializationKind::JSON,
+            code: SelfAddressing::Blake3_256,
+        }
+    }
+
+    /// Datetime of the reply, the `dt` field, now by default.
+    pub fn datetime(mut self, datetime: Dater) -> Self {
+        self.datetime = Some(datetime);
+        self
+    }
+
+    /// Serialization kind of the reply, JSON by default.
+    pub fn kind(mut self, kind: SerializationKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    /// Digest code of the SAID, Blake3-256 by default.
+    pub fn said_code(mut self, code: SelfAddressing) -> Self {
+        self.code = code;
+        self
+    }
+
+    pub fn build(&self) -> Result<Message, Error> {
+        let datetime = self.datetime.clone().unwrap_or_else(Dater::now);
+        let mut ked = fields("rpy");
+        ked.insert("dt".into(), datetime.iso8601().into());
+        ked.insert("r".into(), self.route.clone().into());
+        ked.insert("a".into(), self.data.clone().into());
+        Ok(Message::new(saidify(ked, &self.code, self.kind)?))
+    }
+}
+
+/// A reply (`rpy`) as parsed from a stream, its SAID verified.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Reply {
+    pub said: SelfAddressingPrefix,
+    pub datetime: Dater,
+    pub route: String,
+    pub data: Map<String, Value>,
+}
+
+impl Reply {
+    pub fn from_serder(serder: &Serder) -> Result<Self, Error> {
+        check_ilk(serder, "rpy")?;
+        if !serder.verify_said()? {
+            return Err(Error::IncorrectDigest);
+        }
+        let data = serder
+            .get("a")
+            .and_then(Value::as_object)
+            .ok_or_else(|| Error::SemanticError("Missing a field".into()))?;
+        Ok(Self {
+            said: serder.said()?,
+            datetime: Dater::from_iso8601(field(serder, "dt")?)?,
+            route: field(serder, "r")?.into(),
+            data: data.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        parser::CesrParser,
+        prefix::{IdentifierPrefix, Prefix},
+        test_utils::signer,
+    };
+    use serde_json::json;
+
+    fn data(value: Value) -> Map<String, Value> {
+        match value {
+            Value::Object(data) => data,
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn reply() -> Result<(), Error> {
+        let witness = signer(5, false);
+        let data = data(json!({
+            "cid": witness.basic_prefix().to_str(),
+            "role": "witness",
+            "eid": witness.basic_prefix().to_str(),
+        }));
+        let datetime = Dater::from_iso8601("2021-01-01T00:00:00.000000+00:00")?;
+        let rpy = ReplyBuilder::new("/end/role/add", data.clone())
+            .datetime(datetime.clone())
+            .build()?;
+        let labels: Vec<&str> = rpy.serder.ked().keys().map(String::as_str).collect();
+        assert_eq!(labels, ["v", "t", "d", "dt", "r", "a"]);
+
+        let controller = signer(1, true);
+        let prefix = IdentifierPrefix::Basic(controller.basic_prefix());
+        let said = rpy.said()?;
+        let bytes = rpy
+            .endorse(&witness)
+            .endorse_trans(prefix, 0, said.clone(), &[controller])?
+            .to_bytes()?;
+
+        let frame = CesrParser::new(&bytes).next_frame()?.unwrap();
+        let serder = Serder::from_raw(frame.message)?;
+        let reply = Reply::from_serder(&serder)?;
+        assert_eq!(reply.said, said);
+        assert_eq!(reply.datetime, datetime);
+        assert_eq!(reply.route, "/end/role/add");
+        assert_eq!(reply.data, data);
+
+        let attachments = frame.parse_attachments()?;
+        assert!(attachments.receipt_couples[0].verify(serder.raw())?);
+        assert_eq!(attachments.trans_idx_sig_groups[0].digest, said);
+
+        let mut ked = serder.ked().clone();
+        ked.insert("r".into(), "/end/role/cut".into());
+        let tampered = Serder::from_ked(ked, SerializationKind::JSON)?;
+        assert!(Reply::from_serder(&tampered).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,393 @@
+use super::{check_unique, fields, hex, saidify, witness_threshold, Message};
+use crate::{
+    attachments::EventSeal,
+    derivation::self_addressing::SelfAddressing,
+    error::Error,
+    keys::{fresh_nonce, SaltCode},
+    prefix::{BasicPrefix, Dater, IdentifierPrefix, Prefix, SeedPrefix, SelfAddressingPrefix},
+    serder::Serder,
+    version::SerializationKind,
+};
+use serde_json::Value;
+
+/// Configuration trait of registries without backers.
+pub const NO_BACKERS: &str = "NB";
+
+/// Builds the inception (`vcp`) event of a credential registry of `issuer`, whose transaction
+/// event log (TEL) records the issuance and revocation of credentials.
+///
+/// The registry identifier is the SAID of the event. The nonce `n`, random by default, tells
+/// apart the registries of an issuer with the same configuration.
+#[derive(Debug, Clone)]
+pub struct RegistryInceptionBuilder {
+    issuer: IdentifierPrefix,
+    config: Vec<String>,
+    backers: Vec<BasicPrefix>,
+    backer_threshold: Option<u64>,
+    nonce: Option<SeedPrefix>,
+    kind: SerializationKind,
+    code: SelfAddressing,
+}
+
+impl RegistryInceptionBuilder {
+    pub fn new(issuer: IdentifierPrefix) -> Self {
+        Self {
+            issuer,
+            config: vec![],
+            backers: vec![],
+            backer_threshold: None,
+            nonce: None,
+            kind: SerializationKind::JSON,
+            code: SelfAddressing::Blake3_256,
+        }
+    }
+
+    /// Makes the registry backerless, its events anchored in the key event log of the issuer
+    /// only.
+    pub fn no_backers(mut self) -> Self {
+        self.config.push(NO_BACKERS.into());
+        self
+    }
+
+    /// Sets the backers of the registry, the `b` field.
+    pub fn backers(mut self, backers: Vec<BasicPrefix>) -> Self {
+        self.backers = backers;
+        self
+    }
+
+    /// Sets the number of backer receipts events need, the `bt` field.
+    pub fn backer_threshold(mut self, toad: u64) -> Self {
+        self.backer_threshold = Some(toad);
+        self
+    }
+
+    /// Sets the nonce, the `n` field, e.g. to rebuild a known registry.
+    pub fn nonce(mut self, nonce: SeedPrefix) -> Self {
+        self.nonce = Some(nonce);
+        self
+    }
+
+    /// Serialization kind of the event, JSON by default.
+    pub fn kind(mut self, kind: SerializationKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    /// Digest code of the SAID and registry identifier, Blake3-256 by default.
+    pub fn said_code(mut self, code: SelfAddressing) -> Self {
+        self.code = code;
+        self
+    }
+
+    pub fn build(&self) -> Result<Message, Error> {
+        if self.config.iter().any(|c| c == NO_BACKERS) && !self.backers.is_empty() {
+            return Err(Error::SemanticError(
+                "Backers of a backerless registry".into(),
+            ));
+        }
+        check_unique(&self.backers, "backers")?;
+        let bt = witness_threshold(self.backer_threshold, &self.backers)?;
+        let nonce = self
+            .nonce
+            .clone()
+            .unwrap_or_else(|| fresh_nonce(SaltCode::Salt128));
+
+        let mut ked = fields("vcp");
+        ked.insert("i".into(), "".into());
+        ked.insert("ii".into(), self.issuer.to_str().into());
+        ked.insert("s".into(), hex(0));
+        ked.insert("c".into(), self.config.clone().into());
+        ked.insert("bt".into(), hex(bt));
+        ked.insert(
+            "b".into(),
+            self.backers
+                .iter()
+                .map(|b| Value::from(b.to_str()))
+                .collect(),
+        );
+        ked.insert("n".into(), nonce.to_str().into());
+        Ok(Message::new(saidify(ked, &self.code, self.kind)?))
+    }
+}
+
+/// Builds the issuance of a credential in a registry: a simple issuance (`iss`) in backerless
+/// registries, or a backed one (`bis`) sealing the latest event of the registry.
+#[derive(Debug, Clone)]
+pub struct IssuanceBuilder {
+    credential: SelfAddressingPrefix,
+    registry: SelfAddressingPrefix,
+    backed: Option<(u64, SelfAddressingPrefix)>,
+    datetime: Option<Dater>,
+    kind: SerializationKind,
+    code: SelfAddressing,
+}
+
+impl IssuanceBuilder {
+    /// Issuance of the credential of SAID `credential` in the registry `registry`.
+    pub fn new(credential: SelfAddressingPrefix, registry: SelfAddressingPrefix) -> Self {
+        Self {
+            credential,
+            registry,
+            backed: None,
+            datetime: None,
+            kind: SerializationKind::JSON,
+            code: SelfAddressing::Blake3_256,
+        }
+    }
+
+    /// Makes the issuance backed, sealing the registry event at `sn` of SAID `said`.
+    pub fn backed(mut self, sn: u64, said: SelfAddressingPrefix) -> Self {
+        self.backed = Some((sn, said));
+        self
+    }
+
+    /// Datetime of the issuance, the `dt` field, now by default.
+    pub fn datetime(mut self, datetime: Dater) -> Self {
+        self.datetime = Some(datetime);
+        self
+    }
+
+    /// Serialization kind of the event, JSON by default.
+    pub fn kind(mut self, kind: SerializationKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    /// Digest code of the SAID, Blake3-256 by default.
+    pub fn said_code(mut self, code: SelfAddressing) -> Self {
+        self.code = code;
+        self
+    }
+
+    pub fn build(&self) -> Result<Message, Error> {
+        let datetime = self.datetime.clone().unwrap_or_else(Dater::now);
+        let mut ked = match &self.backed {
+            None => {
+                let mut ked = fields("iss");
+                ked.insert("i".into(), self.credential.to_str().into());
+                ked.insert("s".into(), hex(0));
+                ked.insert("ri".into(), self.registry.to_str().into());
+                ked
+            }
+            Some((sn, said)) => {
+                let mut ked = fields("bis");
+                ked.insert("i".into(), self.credential.to_str().into());
+                ked.insert("ii".into(), self.registry.to_str().into());
+                ked.insert("s".into(), hex(0));
+                ked.insert("ra".into(), registry_seal(&self.registry, *sn, said));
+                ked
+            }
+        };
+        ked.insert("dt".into(), datetime.iso8601().into());
+        Ok(Message::new(saidify(ked, &self.code, self.kind)?))
+    }
+}
+
+/// Builds the revocation of a credential: a simple revocation (`rev`) in backerless
+/// registries, or a backed one (`brv`) sealing the latest event of the registry.
+#[derive(Debug, Clone)]
+pub struct RevocationBuilder {
+    credential: SelfAddressingPrefix,
+    registry: SelfAddressingPrefix,
+    prior: SelfAddressingPrefix,
+    backed: Option<(u64, SelfAddressingPrefix)>,
+    datetime: Option<Dater>,
+    kind: SerializationKind,
+    code: SelfAddressing,
+}
+
+impl RevocationBuilder {
+    /// Revocation of the credential of SAID `credential` in the registry `registry`, following
+    /// its issuance of SAID `prior`.
+    pub fn new(
+        credential: SelfAddressingPrefix,
+        registry: SelfAddressingPrefix,
+        prior: SelfAddressingPrefix,
+    ) -> Self {
+        Self {
+            credential,
+            registry,
+            prior,
+            backed: None,
+            datetime: None,
+            kind: SerializationKind::JSON,
+            code: SelfAddressing::Blake3_256,
+        }
+    }
+
+    /// Makes the revocation backed, sealing the registry event at `sn` of SAID `said`.
+    pub fn backed(mut self, sn: u64, said: SelfAddressingPrefix) -> Self {
+        self.backed = Some((sn, said));
+        self
+    }
+
+    /// Datetime of the revocation, the `dt` field, now by default.
+    pub fn datetime(mut self, datetime: Dater) -> Self {
+        self.datetime = Some(datetime);
+        self
+    }
+
+    /// Serialization kind of the event, JSON by default.
+    pub fn kind(mut self, kind: SerializationKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    /// Digest code of the SAID, Blake3-256 by default.
+    pub fn said_code(mut self, code: SelfAddressing) -> Self {
+        self.code = code;
+        self
+    }
+
+    pub fn build(&self) -> Result<Message, Error> {
+        let datetime = self.datetime.clone().unwrap_or_else(Dater::now);
+        let mut ked = fields(match self.backed {
+            None => "rev",
+            Some(_) => "brv",
+        });
+        ked.insert("i".into(), self.credential.to_str().into());
+        ked.insert("s".into(), hex(1));
+        match &self.backed {
+            None => {
+                ked.insert("ri".into(), self.registry.to_str().into());
+                ked.insert("p".into(), self.prior.to_str().into());
+            }
+            Some((sn, said)) => {
+                ked.insert("p".into(), self.prior.to_str().into());
+                ked.insert("ra".into(), registry_seal(&self.registry, *sn, said));
+            }
+        }
+        ked.insert("dt".into(), datetime.iso8601().into());
+        Ok(Message::new(saidify(ked, &self.code, self.kind)?))
+    }
+}
+
+/// The `ra` seal of the registry event at `sn` of SAID `said`.
+fn registry_seal(registry: &SelfAddressingPrefix, sn: u64, said: &SelfAddressingPrefix) -> Value {
+    let registry = IdentifierPrefix::SelfAddressing(registry.clone());
+    EventSeal::new(registry, sn, said.clone()).into()
+}
+
+/// Checks a TEL event: its SAID, the registry identifier of a registry inception being its
+/// SAID, and the sequence number of issuances (0) and revocations (1). Fails for messages that
+/// are not TEL events.
+pub fn verify_tel_event(serder: &Serder) -> Result<bool, Error> {
+    let sn = match serder.ilk() {
+        Some("vcp" | "iss" | "bis") => 0,
+        Some("rev" | "brv") => 1,
+        ilk => {
+            return Err(Error::SemanticError(format!(
+                "Expected a TEL event, got {:?}",
+                ilk
+            )))
+        }
+    };
+    if serder.ilk() == Some("vcp") && serder.get("i") != serder.get("d") {
+        return Ok(false);
+    }
+    Ok(serder.sn()? == sn && serder.verify_said()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn labels(message: &Message) -> Vec<&str> {
+        message.serder.ked().keys().map(String::as_str).collect()
+    }
+
+    #[test]
+    fn registry() -> Result<(), Error> {
+        let issuer: IdentifierPrefix = "EKYLUMmNPZeEs77Zvclf0bSN5IN-mLfLpx2ySb-HDlk4".parse()?;
+        let vcp = RegistryInceptionBuilder::new(issuer.clone())
+            .no_backers()
+            .build()?;
+        assert_eq!(
+            labels(&vcp),
+            ["v", "t", "d", "i", "ii", "s", "c", "bt", "b", "n"]
+        );
+        assert_eq!(vcp.serder.get("i"), vcp.serder.get("d"));
+        assert_eq!(vcp.serder.get("c"), Some(&json!(["NB"])));
+        assert!(verify_tel_event(&vcp.serder)?);
+
+        // a fresh nonce makes a fresh registry, the same nonce the same one
+        let other = RegistryInceptionBuilder::new(issuer.clone())
+            .no_backers()
+            .build()?;
+        assert_ne!(other.said()?, vcp.said()?);
+        let nonce: SeedPrefix = vcp.serder.get("n").unwrap().as_str().unwrap().parse()?;
+        let again = RegistryInceptionBuilder::new(issuer.clone())
+            .no_backers()
+            .nonce(nonce)
+            .build()?;
+        assert_eq!(again.serder, vcp.serder);
+
+        let backer: BasicPrefix = "BGKVzj4ve0VSd8z_AmvhLg4lqcC_9WYX90k03q-R_Ydo".parse()?;
+        let backed = RegistryInceptionBuilder::new(issuer.clone())
+            .backers(vec![backer.clone()])
+            .build()?;
+        assert_eq!(backed.serder.get("bt"), Some(&json!("1")));
+        assert!(RegistryInceptionBuilder::new(issuer)
+            .no_backers()
+            .backers(vec![backer])
+            .build()
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn issuance_and_revocation() -> Result<(), Error> {
+        let credential = SelfAddressing::Blake3_256.derive(b"credential");
+        let registry = SelfAddressing::Blake3_256.derive(b"registry");
+        let datetime = Dater::from_iso8601("2021-01-01T00:00:00.000000+00:00")?;
+
+        let iss = IssuanceBuilder::new(credential.clone(), registry.clone())
+            .datetime(datetime.clone())
+            .build()?;
+        assert_eq!(labels(&iss), ["v", "t", "d", "i", "s", "ri", "dt"]);
+        assert!(verify_tel_event(&iss.serder)?);
+        // the issuer anchors the seal of the issuance in its key event log
+        assert_eq!(iss.seal()?.prefix.to_str(), credential.to_str());
+
+        let rev = RevocationBuilder::new(credential.clone(), registry.clone(), iss.said()?)
+            .datetime(datetime.clone())
+            .build()?;
+        assert_eq!(labels(&rev), ["v", "t", "d", "i", "s", "ri", "p", "dt"]);
+        assert_eq!(rev.serder.sn()?, 1);
+        assert!(verify_tel_event(&rev.serder)?);
+
+        let anchor = SelfAddressing::Blake3_256.derive(b"registry event");
+        let bis = IssuanceBuilder::new(credential.clone(), registry.clone())
+            .backed(0, anchor.clone())
+            .datetime(datetime.clone())
+            .build()?;
+        assert_eq!(labels(&bis), ["v", "t", "d", "i", "ii", "s", "ra", "dt"]);
+        assert_eq!(
+            bis.serder.get("ra"),
+            Some(&json!({"i": registry.to_str(), "s": "0", "d": anchor.to_str()}))
+        );
+        assert!(verify_tel_event(&bis.serder)?);
+
+        let brv = RevocationBuilder::new(credential, registry, bis.said()?)
+            .backed(1, anchor)
+            .datetime(datetime)
+            .build()?;
+        assert_eq!(labels(&brv), ["v", "t", "d", "i", "s", "p", "ra", "dt"]);
+        assert!(verify_tel_event(&brv.serder)?);
+
+        let mut ked = brv.serder.ked().clone();
+        ked.insert("s".into(), "0".into());
+        let sn = Serder::from_ked(ked, SerializationKind::JSON)?;
+        assert!(!verify_tel_event(&sn)?);
+        let mut ked = iss.serder.ked().clone();
+        ked.insert("dt".into(), "2022-01-01T00:00:00.000000+00:00".into());
+        let tampered = Serder::from_ked(ked, SerializationKind::JSON)?;
+        assert!(!verify_tel_event(&tampered)?);
+        assert!(verify_tel_event(&Serder::from_raw(
+            b"{\"v\":\"KERI10JSON000023_\",\"t\":\"ixn\"}"
+        )?)
+        .is_err());
+        Ok(())
+    }
+}
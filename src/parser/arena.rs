@@ -0,0 +1,66 @@
+use super::{io::CesrReader, CesrParser, Frame};
+use crate::error::Error;
+use bumpalo::{collections::Vec as BumpVec, Bump};
+use std::io::Read;
+
+/// Parses all frames of `stream` into `bump`. The stream is copied into the arena once and the
+/// frames borrow from the copy, so they are allocated contiguously and freed with the arena.
+pub fn parse_into<'b>(bump: &'b Bump, stream: &[u8]) -> Result<BumpVec<'b, Frame<'b>>, Error> {
+    let stream: &'b [u8] = bump.alloc_slice_copy(stream);
+    let mut frames = BumpVec::new_in(bump);
+    for frame in CesrParser::new(stream) {
+        frames.push(frame?);
+    }
+    Ok(frames)
+}
+
+impl Frame<'_> {
+    /// Copies the frame into `bump`, e.g. out of a buffer that is about to be reused.
+    pub fn copy_into<'b>(&self, bump: &'b Bump) -> Frame<'b> {
+        Frame {
+            message: bump.alloc_slice_copy(self.message),
+            attachments: bump.alloc_slice_copy(self.attachments),
+        }
+    }
+}
+
+impl<R: Read> CesrReader<R> {
+    /// Reads the next frame into `bump` instead of a pair of `Vec`s, `Ok(None)` once the reader is
+    /// exhausted.
+    pub fn read_frame_into<'b>(&mut self, bump: &'b Bump) -> Result<Option<Frame<'b>>, Error> {
+        self.read_with(|frame| frame.copy_into(bump))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const IXN: &str = r#"{"v":"KERI10JSON00002e_","t":"ixn","i":"EAAA"}"#;
+    const ROT: &str = r#"{"v":"KERI10JSON000023_","t":"rot"}"#;
+    const SIGS: &str = "-AABAAAA";
+
+    #[test]
+    fn arena() -> Result<(), Error> {
+        let stream = [IXN, SIGS, ROT, IXN, SIGS].join("");
+        let mut bump = Bump::new();
+        {
+            let frames = parse_into(&bump, stream.as_bytes())?;
+            assert_eq!(frames.len(), 3);
+            assert_eq!(frames[1].message, ROT.as_bytes());
+            assert_eq!(frames[2].attachments, SIGS.as_bytes());
+        }
+        bump.reset();
+
+        let mut reader = CesrReader::new(stream.as_bytes());
+        let mut frames = vec![];
+        while let Some(frame) = reader.read_frame_into(&bump)? {
+            frames.push(frame);
+        }
+        assert_eq!(frames.len(), 3);
+        assert_eq!(frames[0].message, IXN.as_bytes());
+
+        assert!(parse_into(&bump, &stream.as_bytes()[..40]).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,134 @@
+use super::{OwnedFrame, StreamParser};
+use crate::error::Error;
+use core::{
+    pin::Pin,
+    task::{ready, Context, Poll},
+};
+use futures_core::Stream;
+use tokio::io::{AsyncRead, ReadBuf};
+
+/// Size of the chunks read from the underlying reader.
+const CHUNK_LEN: usize = 4096;
+
+/// Reads frames off an asynchronous byte stream, e.g. a TCP connection of a witness, as a
+/// [Stream] of [OwnedFrame]s.
+///
+/// The stream ends when the reader does. A frame cut short by the end of the reader is yielded
+/// as [Error::ShortageError], after any error the stream ends.
+pub struct CesrStreamReader<R> {
+    reader: R,
+    parser: StreamParser,
+    chunk: Box<[u8; CHUNK_LEN]>,
+    done: bool,
+}
+
+impl<R: AsyncRead + Unpin> CesrStreamReader<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            parser: StreamParser::new(),
+            chunk: Box::new([0; CHUNK_LEN]),
+            done: false,
+        }
+    }
+
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R: AsyncRead + Unpin> Stream for CesrStreamReader<R> {
+    type Item = Result<OwnedFrame, Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        if this.done {
+            return Poll::Ready(None);
+        }
+        loop {
+            let finished = this.parser.is_finished();
+            match this.parser.next_frame() {
+                Ok(Some(frame)) => return Poll::Ready(Some(Ok(frame.into_owned()))),
+                Ok(None) | Err(Error::ShortageError { .. }) if !finished => {}
+                Ok(None) => {
+                    this.done = true;
+                    return Poll::Ready(None);
+                }
+                Err(e) => {
+                    this.done = true;
+                    return Poll::Ready(Some(Err(e)));
+                }
+            }
+
+            let mut buf = ReadBuf::new(&mut this.chunk[..]);
+            if let Err(e) = ready!(Pin::new(&mut this.reader).poll_read(cx, &mut buf)) {
+                this.done = true;
+                return Poll::Ready(Some(Err(e.into())));
+            }
+            match buf.filled() {
+                [] => this.parser.finish(),
+                bytes => this.parser.extend(bytes),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::{executor::block_on, StreamExt};
+
+    const IXN: &str = r#"{"v":"KERI10JSON00002e_","t":"ixn","i":"EAAA"}"#;
+    const ROT: &str = r#"{"v":"KERI10JSON000023_","t":"rot"}"#;
+    const SIGS: &str = "-AABAAAA";
+
+    /// Hands out the stream three bytes at a time.
+    struct Trickle<'a>(&'a [u8]);
+
+    impl AsyncRead for Trickle<'_> {
+        fn poll_read(
+            mut self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+            buf: &mut ReadBuf<'_>,
+        ) -> Poll<std::io::Result<()>> {
+            let len = self.0.len().min(3).min(buf.remaining());
+            buf.put_slice(&self.0[..len]);
+            self.0 = &self.0[len..];
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    #[test]
+    fn read_frames() -> Result<(), Error> {
+        let stream = [IXN, SIGS, ROT, IXN].join("");
+        let reader = CesrStreamReader::new(Trickle(stream.as_bytes()));
+        let frames = block_on(reader.collect::<Vec<_>>())
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(frames.len(), 3);
+        assert_eq!(frames[0].message, IXN.as_bytes());
+        assert_eq!(frames[0].attachments, SIGS.as_bytes());
+        assert!(frames[1].as_frame().parse_attachments().is_ok());
+        assert_eq!(frames[2].message, IXN.as_bytes());
+
+        // whole stream in one read
+        let reader = CesrStreamReader::new(stream.as_bytes());
+        let all = block_on(reader.collect::<Vec<_>>())
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(all, frames);
+        Ok(())
+    }
+
+    #[test]
+    fn truncated_stream() {
+        let stream = [IXN, &ROT[..20]].join("");
+        let mut reader = CesrStreamReader::new(Trickle(stream.as_bytes()));
+        assert!(block_on(reader.next()).unwrap().is_ok());
+        assert!(matches!(
+            block_on(reader.next()),
+            Some(Err(Error::ShortageError { .. }))
+        ));
+        assert!(block_on(reader.next()).is_none());
+    }
+}
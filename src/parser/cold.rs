@@ -0,0 +1,101 @@
+use crate::error::Error;
+
+/// Cold start stream types, inferred from the first tritet (three most significant bits) of the
+/// first byte of a stream.
+///
+/// See the [cold start] section of the CESR specification.
+///
+/// [cold start]: https://weboftrust.github.io/ietf-cesr/draft-ssmith-cesr.html#name-stream-parsing-rules
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum Cold {
+    /// `0b000` annotated Base64 text domain material
+    AnB64,
+    /// `0b001` text domain count code, starts with `-`
+    CtB64,
+    /// `0b010` text domain op code, starts with `_`
+    OpB64,
+    /// `0b011` JSON map, starts with `{`
+    JSON,
+    /// `0b100` MessagePack fixed map
+    MGPK1,
+    /// `0b101` CBOR map
+    CBOR,
+    /// `0b110` MessagePack big map (map16 or map32)
+    MGPK2,
+    /// `0b111` binary domain count code or op code
+    CtOpB2,
+}
+
+impl Cold {
+    /// Maps the first tritet of `byte` to its stream type.
+    pub fn from_byte(byte: u8) -> Self {
+        match byte >> 5 {
+            0b000 => Self::AnB64,
+            0b001 => Self::CtB64,
+            0b010 => Self::OpB64,
+            0b011 => Self::JSON,
+            0b100 => Self::MGPK1,
+            0b101 => Self::CBOR,
+            0b110 => Self::MGPK2,
+            _ => Self::CtOpB2,
+        }
+    }
+
+    /// Whether the stream starts with a serialized message.
+    pub fn is_message(&self) -> bool {
+        matches!(self, Self::JSON | Self::MGPK1 | Self::CBOR | Self::MGPK2)
+    }
+
+    /// Whether the stream starts with text domain (qb64) CESR material.
+    pub fn is_text(&self) -> bool {
+        matches!(self, Self::AnB64 | Self::CtB64 | Self::OpB64)
+    }
+
+    /// Whether the stream starts with binary domain (qb2) CESR material.
+    pub fn is_binary(&self) -> bool {
+        matches!(self, Self::CtOpB2)
+    }
+}
+
+/// Inspects the first byte of a stream to tell which decoder the stream should be routed to.
+pub fn sniff(stream: &[u8]) -> Result<Cold, Error> {
+    stream
+        .first()
+        .map(|b| Cold::from_byte(*b))
+        .ok_or_else(|| Error::DeserializeError("Empty stream".into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sniff_stream_types() -> Result<(), Error> {
+        assert_eq!(sniff(b"-AAB")?, Cold::CtB64);
+        assert_eq!(sniff(b"_AAA")?, Cold::OpB64);
+        assert_eq!(sniff(br#"{"v":"KERI10JSON00002e_"}"#)?, Cold::JSON);
+        // MessagePack fixmap of 3 entries
+        assert_eq!(sniff(&[0x83, 0xa1, 0x76])?, Cold::MGPK1);
+        // CBOR map of 3 entries
+        assert_eq!(sniff(&[0xa3, 0x61, 0x76])?, Cold::CBOR);
+        // MessagePack map16 and map32
+        assert_eq!(sniff(&[0xde, 0x00, 0x10])?, Cold::MGPK2);
+        assert_eq!(sniff(&[0xdf, 0x00, 0x00])?, Cold::MGPK2);
+        // `-A` and `_A` in the binary domain
+        assert_eq!(sniff(&[0xf8, 0x00, 0x01])?, Cold::CtOpB2);
+        assert_eq!(sniff(&[0xfc, 0x00, 0x00])?, Cold::CtOpB2);
+        assert_eq!(sniff(&[0x0a])?, Cold::AnB64);
+
+        assert!(sniff(&[]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn stream_families() {
+        assert!(Cold::JSON.is_message());
+        assert!(Cold::CBOR.is_message());
+        assert!(Cold::MGPK1.is_message() && Cold::MGPK2.is_message());
+        assert!(Cold::CtB64.is_text() && !Cold::CtB64.is_message());
+        assert!(Cold::CtOpB2.is_binary() && !Cold::CtOpB2.is_text());
+    }
+}
@@ -0,0 +1,173 @@
+use super::{is_qb64_char, Frame, OwnedFrame, StreamParser};
+use crate::{error::Error, version::VersionString};
+use std::io::{ErrorKind, Read, Write};
+
+/// Size of the chunks read from the underlying reader.
+const CHUNK_LEN: usize = 4096;
+
+/// Reads frames off a byte stream, e.g. a file or a socket, as an iterator of [OwnedFrame]s.
+///
+/// Iteration ends with the reader. A frame cut short by the end of the reader is yielded as
+/// [Error::ShortageError], after any error iteration ends.
+pub struct CesrReader<R> {
+    reader: R,
+    parser: StreamParser,
+    chunk: Box<[u8; CHUNK_LEN]>,
+    done: bool,
+}
+
+impl<R: Read> CesrReader<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            parser: StreamParser::new(),
+            chunk: Box::new([0; CHUNK_LEN]),
+            done: false,
+        }
+    }
+
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    /// Reads the next frame, `Ok(None)` once the reader is exhausted.
+    pub fn read_frame(&mut self) -> Result<Option<OwnedFrame>, Error> {
+        self.read_with(|frame| frame.into_owned())
+    }
+
+    /// Reads the next frame and hands it to `f` while it still borrows the buffer.
+    pub(crate) fn read_with<T>(&mut self, f: impl FnOnce(Frame) -> T) -> Result<Option<T>, Error> {
+        loop {
+            let finished = self.parser.is_finished();
+            match self.parser.next_frame() {
+                Ok(Some(frame)) => return Ok(Some(f(frame))),
+                Ok(None) | Err(Error::ShortageError { .. }) if !finished => {}
+                result => return result.map(|_| None),
+            }
+            match self.reader.read(&mut self.chunk[..]) {
+                Ok(0) => self.parser.finish(),
+                Ok(len) => self.parser.extend(&self.chunk[..len]),
+                Err(e) if e.kind() == ErrorKind::Interrupted => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+}
+
+impl<R: Read> Iterator for CesrReader<R> {
+    type Item = Result<OwnedFrame, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let frame = self.read_frame().transpose();
+        self.done = !matches!(frame, Some(Ok(_)));
+        frame
+    }
+}
+
+/// Writes frames to a byte stream, checking each frame is sized by its version string and its
+/// attachments are whole text domain groups so readers can frame the stream again.
+pub struct CesrWriter<W> {
+    writer: W,
+}
+
+impl<W: Write> CesrWriter<W> {
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    /// Writes a message followed by its attachment groups.
+    pub fn write_frame(&mut self, message: &[u8], attachments: &[u8]) -> Result<(), Error> {
+        let size = VersionString::find(message)?.size;
+        if size != message.len() {
+            return Err(Error::SemanticError(format!(
+                "Version string size {} does not match message of {} bytes",
+                size,
+                message.len()
+            )));
+        }
+        if !attachments.iter().all(|b| is_qb64_char(*b))
+            || !attachments.len().is_multiple_of(4)
+            || attachments.first().is_some_and(|b| *b != b'-')
+        {
+            return Err(Error::SemanticError(
+                "Attachments must be whole text domain groups".into(),
+            ));
+        }
+        self.writer.write_all(message)?;
+        self.writer.write_all(attachments)?;
+        Ok(())
+    }
+
+    pub fn write(&mut self, frame: &Frame) -> Result<(), Error> {
+        self.write_frame(frame.message, frame.attachments)
+    }
+
+    pub fn flush(&mut self) -> Result<(), Error> {
+        Ok(self.writer.flush()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const IXN: &str = r#"{"v":"KERI10JSON00002e_","t":"ixn","i":"EAAA"}"#;
+    const ROT: &str = r#"{"v":"KERI10JSON000023_","t":"rot"}"#;
+    const SIGS: &str = "-AABAAAA";
+
+    #[test]
+    fn round_trip() -> Result<(), Error> {
+        let mut writer = CesrWriter::new(vec![]);
+        writer.write_frame(IXN.as_bytes(), SIGS.as_bytes())?;
+        writer.write_frame(ROT.as_bytes(), b"")?;
+        writer.write_frame(IXN.as_bytes(), SIGS.as_bytes())?;
+        writer.flush()?;
+        let stream = writer.into_inner();
+        assert_eq!(stream, [IXN, SIGS, ROT, IXN, SIGS].join("").as_bytes());
+
+        let frames = CesrReader::new(&stream[..]).collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(frames.len(), 3);
+        assert_eq!(frames[1].message, ROT.as_bytes());
+        assert_eq!(frames[2].attachments, SIGS.as_bytes());
+
+        // frames read back are written as they are
+        let mut writer = CesrWriter::new(vec![]);
+        for frame in &frames {
+            writer.write(&frame.as_frame())?;
+        }
+        assert_eq!(writer.into_inner(), stream);
+        Ok(())
+    }
+
+    #[test]
+    fn truncated_stream() {
+        let stream = [IXN, SIGS, &ROT[..30]].join("");
+        let mut reader = CesrReader::new(stream.as_bytes());
+        assert!(reader.next().unwrap().is_ok());
+        assert!(matches!(
+            reader.next(),
+            Some(Err(Error::ShortageError { needed: 5 }))
+        ));
+        assert!(reader.next().is_none());
+    }
+
+    #[test]
+    fn unframed_writes() {
+        let mut writer = CesrWriter::new(vec![]);
+        // size does not match the version string
+        assert!(writer.write_frame(&IXN.as_bytes()[1..], b"").is_err());
+        assert!(writer.write_frame(b"-AAB", b"").is_err());
+        // not whole groups
+        assert!(writer.write_frame(ROT.as_bytes(), b"-AA").is_err());
+        assert!(writer.write_frame(ROT.as_bytes(), b"AAAA").is_err());
+        assert!(writer.write_frame(ROT.as_bytes(), b"-A{B").is_err());
+        assert!(writer.into_inner().is_empty());
+    }
+}
@@ -0,0 +1,510 @@
+use crate::{
+    attachments::Attachments,
+    derivation::{
+        ascii,
+        counter::{Counter, CounterCode},
+        genus::{GenusVersion, GENUS_VERSION_LEN},
+    },
+    error::Error,
+    version::{VersionString, MAX_VERSION_OFFSET, VERSION_2_LEN},
+};
+use core::str::FromStr;
+
+#[cfg(feature = "arena")]
+pub mod arena;
+#[cfg(feature = "async")]
+pub mod r#async;
+pub mod cold;
+pub mod io;
+pub mod options;
+pub mod stream;
+pub mod transcode;
+
+#[cfg(feature = "arena")]
+pub use arena::parse_into;
+pub use cold::{sniff, Cold};
+pub use options::ParseOptions;
+#[cfg(feature = "async")]
+pub use r#async::CesrStreamReader;
+pub use stream::StreamParser;
+pub use transcode::{transcode, Domain};
+
+/// A single frame of a CESR stream: one self-framing serialized message followed by the
+/// CESR attachment groups that belong to it.
+///
+/// Both parts borrow from the stream handed to the [CesrParser] so no bytes are copied.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Frame<'a> {
+    /// The serialized message (JSON, CBOR or MessagePack), sized by its version string.
+    pub message: &'a [u8],
+
+    /// The text domain attachment groups following the message, may be empty.
+    pub attachments: &'a [u8],
+}
+
+impl Frame<'_> {
+    /// Parses the attachment groups of the frame into their typed structures. Failures are
+    /// located from the start of the frame, its message being its first primitive.
+    pub fn parse_attachments(&self) -> Result<Attachments, Error> {
+        let attachments = self.attachments_text()?;
+        Attachments::from_str(attachments).map_err(|e| e.at(self.message.len(), 1))
+    }
+
+    /// Parses the attachment groups of the frame with `genus_version` active at their start,
+    /// switched by the genus version codes among them, e.g. the one a [CesrParser] left active.
+    /// Failures are located as by [Frame::parse_attachments].
+    pub fn parse_attachments_in(
+        &self,
+        genus_version: &mut GenusVersion,
+    ) -> Result<Attachments, Error> {
+        self.parse_attachments_with(genus_version, &ParseOptions::default())
+    }
+
+    /// Parses the attachment groups of the frame like [Frame::parse_attachments_in], as
+    /// strictly as `options` tell, e.g. the options of the [CesrParser] that framed it.
+    pub fn parse_attachments_with(
+        &self,
+        genus_version: &mut GenusVersion,
+        options: &ParseOptions,
+    ) -> Result<Attachments, Error> {
+        let attachments = self.attachments_text()?;
+        let mut primitive = 0;
+        let located = |e: Error, rest: &str, primitive| {
+            e.at(
+                self.message.len() + attachments.len() - rest.len(),
+                1 + primitive,
+            )
+        };
+        match Attachments::parse_groups(attachments, genus_version, options, 0, &mut primitive) {
+            Ok((attachments, "")) => Ok(attachments),
+            Ok((_, rest)) => {
+                let e =
+                    Error::DeserializeError(format!("Unexpected data after attachments: {}", rest));
+                Err(located(e, rest, primitive))
+            }
+            Err(e) => Err(e.at(self.message.len(), 1)),
+        }
+    }
+
+    /// The attachments as text. A [CesrParser] only frames text domain characters, but frames
+    /// built by hand may hold anything.
+    fn attachments_text(&self) -> Result<&str, Error> {
+        core::str::from_utf8(self.attachments).map_err(|e| {
+            Error::DeserializeError(format!("Attachments are not text: {}", e))
+                .at(self.message.len() + e.valid_up_to(), 1)
+        })
+    }
+
+    /// Copies the frame out of the stream.
+    pub fn into_owned(self) -> OwnedFrame {
+        OwnedFrame {
+            message: self.message.to_vec(),
+            attachments: self.attachments.to_vec(),
+        }
+    }
+}
+
+/// A [Frame] that owns its bytes, for streams read through a buffer that is reused.
+#[derive(Debug, PartialEq, Clone)]
+pub struct OwnedFrame {
+    pub message: Vec<u8>,
+    pub attachments: Vec<u8>,
+}
+
+impl OwnedFrame {
+    /// Borrows the frame.
+    pub fn as_frame(&self) -> Frame<'_> {
+        Frame {
+            message: &self.message,
+            attachments: &self.attachments,
+        }
+    }
+}
+
+/// Incremental parser over a stream of interleaved serialized messages and CESR attachment groups.
+///
+/// Messages are located with their version string which carries the size of the serialization.
+/// Everything after a message up to the start of the next message is treated as its attachments,
+/// unless they are pipelined: an attached material group (`-V`) counting their quadlets is taken
+/// as a whole, ending the frame. Frames are yielded one at a time either through
+/// [CesrParser::next_frame] or by iterating.
+///
+/// Genus version codes where a message is expected switch the code tables of the stream, the
+/// active [GenusVersion] is tracked from frame to frame.
+///
+/// Failures are located by their offset in the stream and the index of the failing primitive
+/// among the genus version codes and messages of the stream, attachments being parsed apart
+/// with [Frame::parse_attachments].
+pub struct CesrParser<'a> {
+    stream: &'a [u8],
+    offset: usize,
+    primitive: usize,
+    genus_version: GenusVersion,
+    options: ParseOptions,
+}
+
+impl<'a> CesrParser<'a> {
+    pub fn new(stream: &'a [u8]) -> Self {
+        Self {
+            stream,
+            offset: 0,
+            primitive: 0,
+            genus_version: GenusVersion::default(),
+            options: ParseOptions::default(),
+        }
+    }
+
+    /// Starts parsing with `genus_version` active instead of the KERI/ACDC 1.0 tables.
+    pub fn with_genus_version(self, genus_version: GenusVersion) -> Self {
+        Self {
+            genus_version,
+            ..self
+        }
+    }
+
+    /// Parses as strictly as `options` tell, frames bigger than their bound failing. Their
+    /// attachments are parsed with the same options by [Frame::parse_attachments_with].
+    pub fn with_options(self, options: ParseOptions) -> Self {
+        Self { options, ..self }
+    }
+
+    pub fn options(&self) -> &ParseOptions {
+        &self.options
+    }
+
+    /// The genus version active after the frames parsed so far.
+    pub fn genus_version(&self) -> GenusVersion {
+        self.genus_version
+    }
+
+    /// Number of bytes of the stream consumed by the frames parsed so far.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Number of genus version codes and messages parsed so far.
+    pub fn primitives(&self) -> usize {
+        self.primitive
+    }
+
+    /// The part of the stream that has not been parsed yet.
+    pub fn remaining(&self) -> &'a [u8] {
+        &self.stream[self.offset..]
+    }
+
+    /// Parses the next frame out of the stream. Returns `Ok(None)` once the stream is exhausted.
+    /// A message cut short by the end of the stream fails with [Error::ShortageError], leaving the
+    /// parser where it was.
+    pub fn next_frame(&mut self) -> Result<Option<Frame<'a>>, Error> {
+        let stream_len = self.stream.len();
+        let at = |e: Error, rest: &[u8], primitive| e.at(stream_len - rest.len(), primitive);
+        let mut rest = self.remaining();
+        let mut genus_version = self.genus_version;
+        let mut primitive = self.primitive;
+        while rest.starts_with(b"-") {
+            let code = ascii(&rest[..rest.len().min(GENUS_VERSION_LEN)]);
+            match GenusVersion::from_stream_in(code, &genus_version)
+                .map_err(|e| at(e, rest, primitive))?
+            {
+                Some((declared, _)) => genus_version = declared,
+                None => break,
+            }
+            rest = &rest[GENUS_VERSION_LEN..];
+            primitive += 1;
+        }
+        if rest.is_empty() {
+            self.offset = self.stream.len();
+            self.primitive = primitive;
+            self.genus_version = genus_version;
+            return Ok(None);
+        }
+
+        let cold = sniff(rest).map_err(|e| at(e, rest, primitive))?;
+        if !cold.is_message() {
+            let e =
+                Error::DeserializeError(format!("Expected a serialized message, got {:?}", cold));
+            return Err(at(e, rest, primitive));
+        }
+        let size = match VersionString::find(rest) {
+            Ok(vs) => vs.size,
+            // the version string may still be on its way
+            Err(_) if rest.len() < MAX_VERSION_OFFSET + VERSION_2_LEN => {
+                return Err(Error::ShortageError { needed: 1 })
+            }
+            Err(e) => return Err(at(e, rest, primitive)),
+        };
+        let too_big = |size: usize| {
+            let max = self.options.max_frame_size?;
+            let e = Error::DeserializeError(format!("Frame of {} bytes over {}", size, max));
+            (size > max).then(|| at(e, rest, primitive))
+        };
+        if let Some(e) = too_big(size) {
+            return Err(e);
+        }
+        if rest.len() < size {
+            return Err(Error::ShortageError {
+                needed: size - rest.len(),
+            });
+        }
+        let (message, rest) = rest.split_at(size);
+        let scanned = rest.iter().take_while(|b| is_qb64_char(**b)).count();
+        let attachments_len = match pipelined_len(rest, &genus_version) {
+            Some(len) if scanned >= len => len,
+            Some(len) if scanned == rest.len() => {
+                if let Some(e) = too_big(size + len) {
+                    return Err(e);
+                }
+                return Err(Error::ShortageError {
+                    needed: len - rest.len(),
+                });
+            }
+            _ => scanned,
+        };
+        let attachments = &rest[..attachments_len];
+        if let Some(e) = too_big(size + attachments_len) {
+            return Err(e);
+        }
+
+        self.offset = self.stream.len() - rest.len() + attachments_len;
+        self.primitive = primitive + 1;
+        self.genus_version = genus_version;
+        Ok(Some(Frame {
+            message,
+            attachments,
+        }))
+    }
+}
+
+/// Yields frames until the stream is exhausted or the first error, after which iteration stops.
+impl<'a> Iterator for CesrParser<'a> {
+    type Item = Result<Frame<'a>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.next_frame() {
+            Ok(frame) => frame.map(Ok),
+            Err(e) => {
+                self.offset = self.stream.len();
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+/// Length of the pipelined attachments at the start of `attachments`, the attached material
+/// group framing all of them, none when they are not pipelined. Only its count code has to be
+/// in `attachments`.
+pub(crate) fn pipelined_len(attachments: &[u8], genus_version: &GenusVersion) -> Option<usize> {
+    let head = ascii(&attachments[..attachments.len().min(8)]);
+    match Counter::from_stream_in(head, genus_version) {
+        Ok((counter, rest))
+            if matches!(
+                counter.code,
+                CounterCode::AttachedMaterialQuadlets | CounterCode::BigAttachedMaterialQuadlets
+            ) =>
+        {
+            Some(head.len() - rest.len() + counter.count as usize * 4)
+        }
+        _ => None,
+    }
+}
+
+/// Characters of the text domain: the URL-safe Base64 alphabet which also covers the count code
+/// (`-`) and op code (`_`) selectors.
+pub(crate) fn is_qb64_char(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'-' || b == b'_'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ParseContext;
+
+    const IXN: &str = r#"{"v":"KERI10JSON00002e_","t":"ixn","i":"EAAA"}"#;
+    const ROT: &str = r#"{"v":"KERI10JSON000023_","t":"rot"}"#;
+    const SIGS: &str = "-AABAAAA";
+
+    #[test]
+    fn parse_frames() -> Result<(), Error> {
+        let stream = [IXN, SIGS, ROT, IXN, SIGS].join("");
+        let mut parser = CesrParser::new(stream.as_bytes());
+
+        let frame = parser.next_frame()?.unwrap();
+        assert_eq!(frame.message, IXN.as_bytes());
+        assert_eq!(frame.attachments, SIGS.as_bytes());
+        assert_eq!(parser.offset(), IXN.len() + SIGS.len());
+
+        let frame = parser.next_frame()?.unwrap();
+        assert_eq!(frame.message, ROT.as_bytes());
+        assert!(frame.attachments.is_empty());
+        assert_eq!(frame.parse_attachments()?, Attachments::default());
+
+        let frame = parser.next_frame()?.unwrap();
+        assert_eq!(frame.message, IXN.as_bytes());
+        assert_eq!(frame.attachments, SIGS.as_bytes());
+
+        assert!(parser.next_frame()?.is_none());
+        assert!(parser.remaining().is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn iterate_frames() {
+        let stream = [IXN, SIGS, ROT].join("");
+        let frames = CesrParser::new(stream.as_bytes())
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(frames.len(), 2);
+
+        // iteration stops after the first error
+        let stream = [ROT, r#"{"t":"ixn"}"#, ROT].join("");
+        let mut parser = CesrParser::new(stream.as_bytes());
+        assert!(parser.next().unwrap().is_ok());
+        assert!(parser.next().unwrap().is_err());
+        assert!(parser.next().is_none());
+    }
+
+    #[test]
+    fn locate_failures() {
+        let late = r#"{"padding":"xxxxx","v":"KERI10JSON000023_"}"#;
+        let stream = [ROT, IXN, SIGS, late].join("");
+        let e = CesrParser::new(stream.as_bytes())
+            .find_map(Result::err)
+            .unwrap();
+        let context = ParseContext {
+            offset: ROT.len() + IXN.len() + SIGS.len(),
+            primitive: 2,
+        };
+        assert_eq!(e.context(), Some(context));
+        assert!(e.to_string().ends_with(&format!("at {}", context)));
+
+        // a corrupt signature after a sound one, located from the start of the frame
+        let sig = format!("AA{}", "A".repeat(86));
+        let attachments = format!("-AAC{}ZZ{}", sig, "A".repeat(86));
+        let frame = Frame {
+            message: ROT.as_bytes(),
+            attachments: attachments.as_bytes(),
+        };
+        let e = frame.parse_attachments().unwrap_err();
+        let context = ParseContext {
+            offset: ROT.len() + 4 + sig.len(),
+            primitive: 3,
+        };
+        assert_eq!(e.context(), Some(context));
+        assert!(matches!(e.root(), Error::UnknownCode { .. }));
+        let e = frame
+            .parse_attachments_in(&mut GenusVersion::default())
+            .unwrap_err();
+        assert_eq!(e.context(), Some(context));
+
+        // shortages are not located
+        let frame = Frame {
+            message: ROT.as_bytes(),
+            attachments: b"-AAB",
+        };
+        assert!(frame.parse_attachments().unwrap_err().context().is_none());
+
+        // frames built by hand may hold bytes that are not text
+        let frame = Frame {
+            message: b"{}",
+            attachments: &[0xff, 0xfe],
+        };
+        let e = frame.parse_attachments().unwrap_err();
+        assert!(matches!(e.root(), Error::DeserializeError(_)));
+        assert_eq!(e.context().map(|context| context.offset), Some(2));
+        assert!(frame
+            .parse_attachments_in(&mut GenusVersion::default())
+            .is_err());
+    }
+
+    #[test]
+    fn max_frame_size() {
+        let stream = [IXN, SIGS, ROT].join("");
+        let frames = |max_frame_size| {
+            CesrParser::new(stream.as_bytes())
+                .with_options(ParseOptions::strict().max_frame_size(max_frame_size))
+                .collect::<Result<Vec<_>, _>>()
+        };
+        assert_eq!(frames(stream.len()).unwrap().len(), 2);
+
+        // the signed interaction event is over, its message alone is not
+        let e = frames(IXN.len()).unwrap_err();
+        assert_eq!(e.context().map(|context| context.offset), Some(0));
+        assert!(frames(IXN.len() + SIGS.len()).is_ok());
+        assert!(frames(IXN.len() - 1).is_err());
+    }
+
+    #[test]
+    fn malformed_messages() {
+        // not a message
+        assert!(CesrParser::new(SIGS.as_bytes()).next_frame().is_err());
+        // no version string
+        assert!(CesrParser::new(br#"{"t":"ixn"}"#).next_frame().is_err());
+        // version string too far into the message
+        let late = r#"{"padding":"xxxxx","v":"KERI10JSON000023_"}"#;
+        assert!(CesrParser::new(late.as_bytes()).next_frame().is_err());
+        // truncated message
+        assert!(matches!(
+            CesrParser::new(&IXN.as_bytes()[..20]).next_frame(),
+            Err(Error::ShortageError { needed: 1 })
+        ));
+        assert!(matches!(
+            CesrParser::new(&IXN.as_bytes()[..40]).next_frame(),
+            Err(Error::ShortageError { needed: 6 })
+        ));
+    }
+
+    #[test]
+    fn cbor_frames() -> Result<(), Error> {
+        use crate::{serder::Serder, version::SerializationKind};
+
+        let ixn = Serder::from_str(IXN)?;
+        let cbor = Serder::from_ked(ixn.ked().clone(), SerializationKind::CBOR)?;
+        let stream = [cbor.raw(), SIGS.as_bytes(), IXN.as_bytes()].concat();
+        let mut parser = CesrParser::new(&stream);
+
+        let frame = parser.next_frame()?.unwrap();
+        assert_eq!(sniff(frame.message)?, Cold::CBOR);
+        assert_eq!(Serder::from_raw(frame.message)?, cbor);
+        assert_eq!(frame.attachments, SIGS.as_bytes());
+        assert_eq!(parser.next_frame()?.unwrap().message, IXN.as_bytes());
+        Ok(())
+    }
+
+    #[test]
+    fn genus_version() -> Result<(), Error> {
+        let v1 = GenusVersion::KERIACDC_1.to_str_in(&GenusVersion::KERIACDC_2);
+        let v2 = GenusVersion::KERIACDC_2.to_str();
+        let stream = [v2.as_str(), IXN, ROT, &v1, IXN, SIGS].join("");
+        let mut parser = CesrParser::new(stream.as_bytes());
+
+        let frame = parser.next_frame()?.unwrap();
+        assert_eq!(frame.message, IXN.as_bytes());
+        assert_eq!(parser.genus_version(), GenusVersion::KERIACDC_2);
+        // the switch back is among the attachments of the frame before
+        let frame = parser.next_frame()?.unwrap();
+        assert_eq!(frame.attachments, v1.as_bytes());
+        let mut genus_version = parser.genus_version();
+        assert_eq!(
+            frame.parse_attachments_in(&mut genus_version)?,
+            Attachments::default()
+        );
+        assert_eq!(genus_version, GenusVersion::KERIACDC_1);
+
+        // `-A` is only a group of controller signatures in the CESR 1 tables
+        let frame = parser.next_frame()?.unwrap();
+        let rejected = |mut genus_version: GenusVersion| {
+            matches!(frame.parse_attachments_in(&mut genus_version).as_ref().map_err(Error::root),
+                Err(Error::DeserializeError(e)) if e.starts_with("Unsupported attachment group"))
+        };
+        assert!(!rejected(genus_version));
+        assert!(rejected(GenusVersion::KERIACDC_2));
+        assert!(parser.next_frame()?.is_none());
+
+        // a declaration cut short
+        assert!(matches!(
+            CesrParser::new(&v1.as_bytes()[..6]).next_frame(),
+            Err(Error::ShortageError { needed: 2 })
+        ));
+        Ok(())
+    }
+}
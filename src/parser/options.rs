@@ -0,0 +1,61 @@
+/// Default bound on the nesting of attachment groups, e.g. pathed material within pathed
+/// material.
+pub const DEFAULT_MAX_DEPTH: usize = 8;
+
+/// How strictly streams are parsed, and the bounds put on them.
+///
+/// Validators parse strictly, the default: an attachment group the parser does not handle is an
+/// error. Relays and gateways parse leniently, skipping the groups whose extent they can tell
+/// without understanding them: those counted in quadlets, which under CESR 2 is every group.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct ParseOptions {
+    /// Fail on count codes of the active code table the parser does not handle, rather than
+    /// skipping their groups.
+    pub strict: bool,
+    /// Skip the groups of count codes missing from the active code table, sized by their
+    /// selector, rather than failing. Only CESR 2 groups can be skipped so, CESR 1 groups
+    /// counting members whose size is unknown.
+    pub allow_unknown_codes: bool,
+    /// Bound on the size in bytes of a frame, its message and attachments together.
+    pub max_frame_size: Option<usize>,
+    /// Bound on the nesting of attachment groups, top level groups being at depth 0.
+    pub max_depth: usize,
+}
+
+impl ParseOptions {
+    /// Fails on anything not understood, with no bound on frame size.
+    pub const fn strict() -> Self {
+        Self {
+            strict: true,
+            allow_unknown_codes: false,
+            max_frame_size: None,
+            max_depth: DEFAULT_MAX_DEPTH,
+        }
+    }
+
+    /// Skips what can be skipped, known and unknown count codes alike.
+    pub const fn lenient() -> Self {
+        Self {
+            strict: false,
+            allow_unknown_codes: true,
+            ..Self::strict()
+        }
+    }
+
+    pub fn max_frame_size(self, max_frame_size: usize) -> Self {
+        Self {
+            max_frame_size: Some(max_frame_size),
+            ..self
+        }
+    }
+
+    pub fn max_depth(self, max_depth: usize) -> Self {
+        Self { max_depth, ..self }
+    }
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self::strict()
+    }
+}
@@ -0,0 +1,231 @@
+use super::{pipelined_len, CesrParser, Frame, ParseOptions};
+use crate::{derivation::genus::GenusVersion, error::Error};
+
+/// Resumable parser over a stream read in chunks, e.g. from a socket.
+///
+/// Bytes are buffered with [StreamParser::extend] and frames parsed out of the buffer with
+/// [StreamParser::next_frame]. A frame cut short by the end of the buffer fails with
+/// [Error::ShortageError] and is parsed again from its start once more bytes are in, frames before it
+/// are consumed and never parsed again.
+///
+/// Since attachments run up to the next message, the attachments of the last buffered frame are
+/// only complete once the next message starts or [StreamParser::finish] marks the end of the
+/// stream. Pipelined attachments, counted up front, are complete as soon as they are buffered.
+///
+/// Failures are located in the whole stream, as by [CesrParser].
+#[derive(Debug, Default, Clone)]
+pub struct StreamParser {
+    buffer: Vec<u8>,
+    /// Number of bytes of the buffer consumed by the frames parsed so far.
+    offset: usize,
+    /// Number of bytes of the stream dropped from the buffer.
+    dropped: usize,
+    /// Number of genus version codes and messages parsed so far.
+    primitive: usize,
+    finished: bool,
+    genus_version: GenusVersion,
+    options: ParseOptions,
+}
+
+impl StreamParser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses as strictly as `options` tell, see [CesrParser::with_options].
+    pub fn with_options(self, options: ParseOptions) -> Self {
+        Self { options, ..self }
+    }
+
+    pub fn options(&self) -> &ParseOptions {
+        &self.options
+    }
+
+    /// Appends bytes read from the stream, dropping the bytes of the frames parsed so far.
+    pub fn extend(&mut self, bytes: &[u8]) {
+        self.buffer.drain(..self.offset);
+        self.dropped += self.offset;
+        self.offset = 0;
+        self.buffer.extend_from_slice(bytes);
+    }
+
+    /// Marks the end of the stream, the buffered bytes are all there is.
+    pub fn finish(&mut self) {
+        self.finished = true;
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// The genus version active after the frames parsed so far, see [CesrParser::genus_version].
+    pub fn genus_version(&self) -> GenusVersion {
+        self.genus_version
+    }
+
+    /// The buffered bytes not parsed into frames yet.
+    pub fn remaining(&self) -> &[u8] {
+        &self.buffer[self.offset..]
+    }
+
+    /// Parses the next frame out of the buffer. Returns `Ok(None)` when nothing is buffered and
+    /// [Error::ShortageError] when the buffered bytes do not hold a whole frame yet.
+    pub fn next_frame(&mut self) -> Result<Option<Frame<'_>>, Error> {
+        let mut parser = CesrParser::new(&self.buffer[self.offset..])
+            .with_genus_version(self.genus_version)
+            .with_options(self.options);
+        let (start, primitive) = (self.dropped + self.offset, self.primitive);
+        let frame = parser.next_frame().map_err(|e| e.at(start, primitive))?;
+        let frame_len = match frame {
+            None => {
+                // genus version codes may have been consumed
+                self.offset = self.buffer.len();
+                self.primitive += parser.primitives();
+                self.genus_version = parser.genus_version();
+                return Ok(None);
+            }
+            Some(frame) => (
+                frame.message.len(),
+                frame.attachments.len(),
+                pipelined_len(frame.attachments, &parser.genus_version()).is_some(),
+            ),
+        };
+        let (message_len, attachments_len, pipelined) = frame_len;
+        if !self.finished && !pipelined && parser.remaining().is_empty() {
+            return Err(Error::ShortageError { needed: 1 });
+        }
+
+        let end = self.offset + parser.offset();
+        let start = end - message_len - attachments_len;
+        self.offset = end;
+        self.primitive += parser.primitives();
+        self.genus_version = parser.genus_version();
+        let (message, attachments) = self.buffer[start..end].split_at(message_len);
+        Ok(Some(Frame {
+            message,
+            attachments,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const IXN: &str = r#"{"v":"KERI10JSON00002e_","t":"ixn","i":"EAAA"}"#;
+    const ROT: &str = r#"{"v":"KERI10JSON000023_","t":"rot"}"#;
+    const SIGS: &str = "-AABAAAA";
+
+    #[test]
+    fn byte_by_byte() -> Result<(), Error> {
+        let stream = [IXN, SIGS, ROT, IXN, SIGS].join("");
+        let mut parser = StreamParser::new();
+        let mut frames = vec![];
+        for byte in stream.as_bytes() {
+            parser.extend(&[*byte]);
+            match parser.next_frame() {
+                Ok(Some(frame)) => {
+                    frames.push((frame.message.to_vec(), frame.attachments.to_vec()))
+                }
+                Err(Error::ShortageError { .. }) => {}
+                other => panic!("unexpected {:?}", other),
+            }
+        }
+        // the attachments of the last frame may go on
+        assert!(matches!(
+            parser.next_frame(),
+            Err(Error::ShortageError { needed: 1 })
+        ));
+        parser.finish();
+        let frame = parser.next_frame()?.unwrap();
+        frames.push((frame.message.to_vec(), frame.attachments.to_vec()));
+        assert!(parser.next_frame()?.is_none());
+
+        let expected: Vec<_> = CesrParser::new(stream.as_bytes())
+            .map(|frame| frame.map(|f| (f.message.to_vec(), f.attachments.to_vec())))
+            .collect::<Result<_, _>>()?;
+        assert_eq!(frames, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn resumes_after_parsed_frames() -> Result<(), Error> {
+        let mut parser = StreamParser::new();
+        parser.extend([IXN, SIGS, &ROT[..10]].join("").as_bytes());
+        assert_eq!(parser.next_frame()?.unwrap().attachments, SIGS.as_bytes());
+        assert!(matches!(
+            parser.next_frame(),
+            Err(Error::ShortageError { needed: 1 })
+        ));
+        assert_eq!(parser.remaining(), &ROT.as_bytes()[..10]);
+
+        parser.extend(&ROT.as_bytes()[10..30]);
+        assert!(matches!(
+            parser.next_frame(),
+            Err(Error::ShortageError { needed: 5 })
+        ));
+        parser.extend(&ROT.as_bytes()[30..]);
+        parser.finish();
+        assert_eq!(parser.next_frame()?.unwrap().message, ROT.as_bytes());
+        assert!(parser.remaining().is_empty());
+
+        // failures are located in the whole stream, past the bytes already dropped
+        let mut parser = StreamParser::new();
+        parser.extend([IXN, SIGS, ROT].join("").as_bytes());
+        parser.next_frame()?;
+        let late = r#"{"padding":"xxxxx","v":"KERI10JSON000023_"}"#;
+        parser.extend([SIGS, late].join("").as_bytes());
+        parser.next_frame()?;
+        let e = parser.next_frame().unwrap_err();
+        let context = e.context().unwrap();
+        assert_eq!(
+            context.offset,
+            IXN.len() + SIGS.len() + ROT.len() + SIGS.len()
+        );
+        assert_eq!(context.primitive, 2);
+
+        // malformed frames are still errors
+        let mut parser = StreamParser::new();
+        parser.extend(SIGS.as_bytes());
+        assert!(matches!(
+            parser.next_frame().as_ref().map_err(Error::root),
+            Err(Error::DeserializeError(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn pipelined() -> Result<(), Error> {
+        let pipelined = ["-VAC", SIGS].join("");
+        let mut parser = StreamParser::new();
+        parser.extend([IXN, &pipelined[..8]].join("").as_bytes());
+        assert!(matches!(
+            parser.next_frame(),
+            Err(Error::ShortageError { needed: 4 })
+        ));
+        // complete once the counted quadlets are in, with no next message or end of stream
+        parser.extend(&pipelined.as_bytes()[8..]);
+        assert_eq!(
+            parser.next_frame()?.unwrap().attachments,
+            pipelined.as_bytes()
+        );
+        assert!(parser.next_frame()?.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn genus_version() -> Result<(), Error> {
+        let mut parser = StreamParser::new();
+        parser.extend(GenusVersion::KERIACDC_2.to_str().as_bytes());
+        assert!(parser.next_frame()?.is_none());
+        assert_eq!(parser.genus_version(), GenusVersion::KERIACDC_2);
+
+        parser.extend([IXN, SIGS].join("").as_bytes());
+        parser.finish();
+        let frame = parser.next_frame()?.unwrap();
+        assert_eq!(frame.message, IXN.as_bytes());
+        assert_eq!(frame.attachments, SIGS.as_bytes());
+        assert_eq!(parser.genus_version(), GenusVersion::KERIACDC_2);
+        Ok(())
+    }
+}
@@ -0,0 +1,243 @@
+use super::{sniff, Cold};
+use crate::{
+    annotate::{counts_quadlets, layout, Member},
+    codex::b64,
+    derivation::{
+        ascii, attached_signature_code::AttachedSignatureCode, counter::Counter,
+        genus::GenusVersion, DerivationCode,
+    },
+    error::Error,
+    prefix::opaque,
+    version::VersionString,
+};
+use core::str::FromStr;
+
+/// Longest code of a primitive, counter or genus version, in Base64 characters.
+const MAX_CODE_LEN: usize = 8;
+
+/// Domains of the CESR material of a stream, messages being the same in both.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum Domain {
+    /// Base64 characters (qb64), for logs and text transports
+    Text,
+    /// Raw bytes (qb2), three for every four characters of the text domain
+    Binary,
+}
+
+/// Converts the attachment groups and genus version codes of `stream` to `domain`, copying its
+/// messages as they are so that every frame keeps its message and attachments. Material already
+/// in `domain` is copied too, so streams mixing both domains are converted as well.
+///
+/// Groups are walked primitive by primitive to tell where they end in the binary domain, but
+/// nothing is decoded beyond the codes. Failures are located by their offset in `stream` and the
+/// index of the failing message, code or primitive; a stream cut short fails with
+/// [Error::ShortageError].
+pub fn transcode(stream: &[u8], domain: Domain) -> Result<Vec<u8>, Error> {
+    let mut transcoder = Transcoder {
+        stream,
+        offset: 0,
+        primitive: 0,
+        from: domain,
+        to: domain,
+        genus_version: GenusVersion::default(),
+        out: Vec::with_capacity(stream.len()),
+    };
+    while transcoder.offset < stream.len() {
+        transcoder
+            .next()
+            .map_err(|e| e.at(transcoder.offset, transcoder.primitive))?;
+    }
+    Ok(transcoder.out)
+}
+
+struct Transcoder<'a> {
+    stream: &'a [u8],
+    offset: usize,
+    primitive: usize,
+    /// Domain of the groups being walked.
+    from: Domain,
+    to: Domain,
+    genus_version: GenusVersion,
+    out: Vec<u8>,
+}
+
+impl<'a> Transcoder<'a> {
+    fn rest(&self) -> &'a [u8] {
+        &self.stream[self.offset..]
+    }
+
+    /// Converts the message, or the run of groups, at the offset.
+    fn next(&mut self) -> Result<(), Error> {
+        let rest = self.rest();
+        self.from = match sniff(rest)? {
+            cold if cold.is_message() => {
+                let size = VersionString::find(rest)?.size;
+                let message = rest.get(..size).ok_or_else(|| Error::ShortageError {
+                    needed: size - rest.len(),
+                })?;
+                self.out.extend_from_slice(message);
+                self.offset += size;
+                self.primitive += 1;
+                return Ok(());
+            }
+            Cold::CtB64 => Domain::Text,
+            Cold::CtOpB2 => Domain::Binary,
+            cold => {
+                return Err(Error::DeserializeError(format!(
+                    "Expected a message or a count code, got {:?}",
+                    cold
+                )))
+            }
+        };
+        self.group()?;
+        while self.offset < self.stream.len() && self.head()?.starts_with('-') {
+            self.group()?;
+        }
+        Ok(())
+    }
+
+    /// The code at the offset in the text domain, or as much of it as the stream holds.
+    fn head(&self) -> Result<String, Error> {
+        let rest = self.rest();
+        Ok(match self.from {
+            Domain::Text => ascii(&rest[..rest.len().min(MAX_CODE_LEN)]).into(),
+            Domain::Binary => {
+                let len = rest.len().min(MAX_CODE_LEN * 3 / 4);
+                b64::encode(&rest[..len - len % 3])
+            }
+        })
+    }
+
+    /// Converts the next `len` characters, a whole number of quadlets in the text domain.
+    fn take(&mut self, len: usize) -> Result<(), Error> {
+        let size = match self.from {
+            Domain::Text => len,
+            Domain::Binary => len / 4 * 3,
+        };
+        let rest = self.rest();
+        let material = rest.get(..size).ok_or_else(|| Error::ShortageError {
+            needed: size - rest.len(),
+        })?;
+        match (self.from, self.to) {
+            (Domain::Text, Domain::Binary) => self.out.extend(b64::decode(material)?),
+            (Domain::Binary, Domain::Text) => self.out.extend(b64::encode(material).bytes()),
+            _ => self.out.extend_from_slice(material),
+        }
+        self.offset += size;
+        self.primitive += 1;
+        Ok(())
+    }
+
+    /// Converts the group, or the genus version code, at the offset.
+    fn group(&mut self) -> Result<(), Error> {
+        let head = self.head()?;
+        if let Some((declared, rest)) = GenusVersion::from_stream_in(&head, &self.genus_version)? {
+            self.genus_version = declared;
+            return self.take(head.len() - rest.len());
+        }
+        let (counter, rest) = Counter::from_stream_in(&head, &self.genus_version)?;
+        self.take(head.len() - rest.len())?;
+        if self.genus_version.counts_quadlets() || counts_quadlets(counter.code) {
+            // aligned material converts as a whole, whatever it holds
+            return self.take(counter.count as usize * 4);
+        }
+        for _ in 0..counter.count {
+            match layout(counter.code) {
+                Some(layout) => layout.iter().try_for_each(|member| self.member(*member))?,
+                None => self.member(Member::Primitive)?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Converts one member of a group, a nested group wherever a count code is met.
+    fn member(&mut self, member: Member) -> Result<(), Error> {
+        let head = self.head()?;
+        if head.starts_with('-') || matches!(member, Member::Group) {
+            return self.group();
+        }
+        let len = match member {
+            Member::Indexed => AttachedSignatureCode::from_str(&head)?.prefix_b64_len(),
+            _ => opaque::qb64_len(&head)?,
+        };
+        self.take(len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        attachments::AttachmentsBuilder,
+        message::{InceptionBuilder, InteractionBuilder, ReceiptBuilder},
+        parser::CesrParser,
+        prefix::{IdentifierPrefix, SadPath},
+        test_utils::signer,
+    };
+
+    #[test]
+    fn round_trip() -> Result<(), Error> {
+        let controller = signer(1, true);
+        let icp = InceptionBuilder::new()
+            .keys(vec![controller.basic_prefix()])
+            .build()?
+            .sign(&[signer(1, true)])?;
+        let prefix: IdentifierPrefix = icp.serder.get("i").unwrap().as_str().unwrap().parse()?;
+        let ixn = InteractionBuilder::new(prefix, 1, icp.said()?)
+            .build()?
+            .sign(&[signer(1, true)])?;
+        let receipt = ReceiptBuilder::new(&ixn.serder)?
+            .nontrans(&signer(5, false))
+            .build()?;
+        // a group of material of an unknown code, and a CESR 2 frame
+        let v2 = GenusVersion::KERIACDC_2;
+        let pathed = AttachmentsBuilder::new()
+            .pathed_material(SadPath::from_path("-a")?, ixn.attachments.clone())
+            .build_in(&v2)?;
+        let text = [
+            icp.to_bytes()?,
+            b"-VAC4CABAAEC".to_vec(),
+            ixn.to_bytes()?,
+            receipt.to_bytes()?,
+            v2.to_str().into_bytes(),
+            ixn.serder.raw().to_vec(),
+            pathed.into_bytes(),
+        ]
+        .concat();
+
+        let binary = transcode(&text, Domain::Binary)?;
+        assert_eq!(transcode(&binary, Domain::Text)?, text);
+        assert_eq!(transcode(&binary, Domain::Binary)?, binary);
+        assert_eq!(transcode(&text, Domain::Text)?, text);
+        let messages: usize = CesrParser::new(&text)
+            .map(|frame| frame.map(|frame| frame.message.len()))
+            .sum::<Result<_, _>>()?;
+        assert_eq!(binary.len() - messages, (text.len() - messages) / 4 * 3);
+
+        // frames keep their attachments, now in the binary domain
+        let signed = [ixn.serder.raw().to_vec(), ixn.attachments.build_qb2()?].concat();
+        assert!(binary
+            .windows(signed.len())
+            .any(|window| window == signed.as_slice()));
+
+        // a stream cut short, or with a corrupt code
+        let cut = &binary[..binary.len() - 3];
+        assert!(matches!(
+            transcode(cut, Domain::Text),
+            Err(Error::ShortageError { .. })
+        ));
+        let corrupt = [icp.serder.raw(), b"-AAB*AAA"].concat();
+        let e = transcode(&corrupt, Domain::Binary).unwrap_err();
+        assert_eq!(
+            e.context()
+                .map(|context| (context.offset, context.primitive)),
+            Some((icp.serder.raw().len() + 4, 2))
+        );
+        assert!(matches!(
+            transcode(&[0xf8], Domain::Text),
+            Err(Error::ShortageError { .. })
+        ));
+        assert!(transcode(b"AAAA", Domain::Binary).is_err());
+        Ok(())
+    }
+}
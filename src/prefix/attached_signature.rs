@@ -1,25 +1,36 @@
 use super::{Prefix, SelfSigningPrefix};
 use crate::{
+    codex,
     derivation::{
         attached_signature_code::AttachedSignatureCode, self_signing::SelfSigning, DerivationCode,
     },
     error::Error,
 };
-use base64::decode_config;
 use core::str::FromStr;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
+/// A signature together with the index of the signing key, see [AttachedSignatureCode].
 #[derive(Debug, PartialEq, Clone)]
 pub struct AttachedSignaturePrefix {
-    pub index: u16,
+    pub code: AttachedSignatureCode,
     pub signature: SelfSigningPrefix,
 }
 
 impl AttachedSignaturePrefix {
-    pub fn new(code: SelfSigning, signature: Vec<u8>, index: u16) -> Self {
+    /// Attached signature under a dual index code with the ondex equal to `index`, see
+    /// [AttachedSignatureCode::new].
+    pub fn new(code: SelfSigning, signature: Vec<u8>, index: u16) -> Result<Self, Error> {
+        Ok(Self {
+            signature: SelfSigningPrefix::new_unchecked(code, signature),
+            code: AttachedSignatureCode::new(code, index)?,
+        })
+    }
+
+    /// Attached signature under an explicit indexed signature code, e.g. a current only code.
+    pub fn with_code(code: AttachedSignatureCode, signature: Vec<u8>) -> Self {
         Self {
-            signature: SelfSigningPrefix::new(code, signature),
-            index,
+            signature: SelfSigningPrefix::new_unchecked(code.code.signature(), signature),
+            code,
         }
     }
 }
@@ -31,16 +42,15 @@ impl FromStr for AttachedSignaturePrefix {
         let code = AttachedSignatureCode::from_str(s)?;
 
         if (s.len()) == code.prefix_b64_len() {
-            Ok(Self::new(
-                code.code,
-                decode_config(&s[code.code_len()..code.prefix_b64_len()], base64::URL_SAFE)?,
-                code.index,
+            Ok(Self::with_code(
+                code,
+                codex::decode(&s[code.code_len()..], code.lead_size())?,
             ))
         } else {
-            Err(Error::SemanticError(format!(
-                "Incorrect Prefix Length: {}",
-                s
-            )))
+            Err(Error::WrongLength {
+                expected: code.prefix_b64_len(),
+                got: s.len(),
+            })
         }
     }
 }
@@ -50,7 +60,14 @@ impl Prefix for AttachedSignaturePrefix {
         self.signature.signature.to_vec()
     }
     fn derivation_code(&self) -> String {
-        AttachedSignatureCode::new(self.signature.derivation, self.index).to_str()
+        self.code.to_str()
+    }
+
+    fn normalized(&self) -> Self {
+        Self {
+            code: self.code.normalized(),
+            ..self.clone()
+        }
     }
 }
 
@@ -79,21 +96,21 @@ impl<'de> Deserialize<'de> for AttachedSignaturePrefix {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::derivation::self_signing::SelfSigning;
+    use crate::derivation::{attached_signature_code::Indexer, self_signing::SelfSigning};
 
     #[test]
     fn deserialize() -> Result<(), Error> {
         let attached_ed_1 = "ABAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA";
-        let attached_secp_2 = "BCAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA";
-        let attached_448_3 = "0AADAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA";
+        let attached_secp_2 = "CCAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA";
+        let attached_448_3 = "0ADDAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA";
 
         let pref_ed_1 = AttachedSignaturePrefix::from_str(attached_ed_1)?;
         let pref_secp_2 = AttachedSignaturePrefix::from_str(attached_secp_2)?;
         let pref_448_3 = AttachedSignaturePrefix::from_str(attached_448_3)?;
 
-        assert_eq!(1, pref_ed_1.index);
-        assert_eq!(2, pref_secp_2.index);
-        assert_eq!(3, pref_448_3.index);
+        assert_eq!(1, pref_ed_1.code.index);
+        assert_eq!(2, pref_secp_2.code.index);
+        assert_eq!(3, pref_448_3.code.index);
 
         assert_eq!(SelfSigning::Ed25519Sha512, pref_ed_1.signature.derivation);
         assert_eq!(
@@ -104,20 +121,68 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn current_only_and_big_codes() -> Result<(), Error> {
+        let crt = AttachedSignaturePrefix::with_code(
+            AttachedSignatureCode::with_indexer(Indexer::Ed25519CrtSig, 2, None)?,
+            vec![0u8; 64],
+        );
+        assert_eq!(["BC".to_string(), "A".repeat(86)].join(""), crt.to_str());
+        assert_eq!(AttachedSignaturePrefix::from_str(&crt.to_str())?, crt);
+
+        let big = AttachedSignaturePrefix::with_code(
+            AttachedSignatureCode::with_indexer(Indexer::Ed448BigSig, 70, Some(3))?,
+            vec![0u8; 114],
+        );
+        assert_eq!(160, big.to_str().len());
+        assert_eq!(AttachedSignaturePrefix::from_str(&big.to_str())?, big);
+        assert_eq!(SelfSigning::Ed448, big.signature.derivation);
+        Ok(())
+    }
+
+    #[test]
+    fn normalized() -> Result<(), Error> {
+        let signature = vec![7u8; 64];
+        let big = AttachedSignaturePrefix::with_code(
+            AttachedSignatureCode::with_indexer(Indexer::Ed25519BigSig, 2, Some(2))?,
+            signature.clone(),
+        );
+        let small = AttachedSignaturePrefix::new(SelfSigning::Ed25519Sha512, signature.clone(), 2)?;
+        assert_ne!(big, small);
+        assert_eq!(big.normalized(), small);
+        assert_eq!(small.normalized(), small);
+        assert!(big.same_material(&small));
+        assert!(big.same_material(&small.signature));
+        assert!(!big.same_material(&AttachedSignaturePrefix::new(
+            SelfSigning::Ed25519Sha512,
+            vec![8u8; 64],
+            2
+        )?));
+
+        // indices that only fit in the big code, or an ondex different from the index
+        for (index, ondex) in [(70, Some(70)), (2, Some(3))] {
+            let code = AttachedSignatureCode::with_indexer(Indexer::Ed25519BigSig, index, ondex)?;
+            assert_eq!(code.normalized(), code);
+        }
+        let crt = AttachedSignatureCode::with_indexer(Indexer::ECDSAsecp256r1BigCrtSig, 5, None)?;
+        assert_eq!(crt.normalized().code, Indexer::ECDSAsecp256r1CrtSig);
+        Ok(())
+    }
+
     #[test]
     fn serialize() -> Result<(), Error> {
-        let pref_ed_2 = AttachedSignaturePrefix::new(SelfSigning::Ed25519Sha512, vec![0u8; 64], 2);
+        let pref_ed_2 = AttachedSignaturePrefix::new(SelfSigning::Ed25519Sha512, vec![0u8; 64], 2)?;
         let pref_secp_6 =
-            AttachedSignaturePrefix::new(SelfSigning::ECDSAsecp256k1Sha256, vec![0u8; 64], 6);
-        let pref_448_4 = AttachedSignaturePrefix::new(SelfSigning::Ed448, vec![0u8; 114], 4);
+            AttachedSignaturePrefix::new(SelfSigning::ECDSAsecp256k1Sha256, vec![0u8; 64], 6)?;
+        let pref_448_4 = AttachedSignaturePrefix::new(SelfSigning::Ed448, vec![0u8; 114], 4)?;
 
         assert_eq!(88, pref_ed_2.to_str().len());
         assert_eq!(88, pref_secp_6.to_str().len());
         assert_eq!(156, pref_448_4.to_str().len());
 
         assert_eq!("ACAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA", pref_ed_2.to_str());
-        assert_eq!("BGAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA", pref_secp_6.to_str());
-        assert_eq!("0AAEAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA", pref_448_4.to_str());
+        assert_eq!("CGAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA", pref_secp_6.to_str());
+        assert_eq!("0AEEAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA", pref_448_4.to_str());
         Ok(())
     }
 }
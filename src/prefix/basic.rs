@@ -1,10 +1,10 @@
-use super::{verify, Prefix, SelfSigningPrefix};
+use super::{check_derivative_len, verify, Prefix, SelfSigningPrefix};
 use crate::{
+    codex,
     derivation::{basic::Basic, DerivationCode},
     error::Error,
     keys::PublicKey,
 };
-use base64::decode_config;
 use core::str::FromStr;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
@@ -17,7 +17,14 @@ pub struct BasicPrefix {
 }
 
 impl BasicPrefix {
-    pub fn new(code: Basic, public_key: PublicKey) -> Self {
+    /// Fails when the key is not as long as `code` requires.
+    pub fn try_new(code: Basic, public_key: PublicKey) -> Result<Self, Error> {
+        check_derivative_len(&code, &public_key.key())?;
+        Ok(Self::new_unchecked(code, public_key))
+    }
+
+    /// Takes the key as it is, the qb64 of a key of the wrong length is not valid CESR.
+    pub fn new_unchecked(code: Basic, public_key: PublicKey) -> Self {
         Self {
             derivation: code,
             public_key,
@@ -30,6 +37,20 @@ impl BasicPrefix {
     pub fn verify(&self, data: &[u8], signature: &SelfSigningPrefix) -> Result<bool, Error> {
         verify(data, self, signature)
     }
+
+    /// Whether the identifier may rotate its keys, see [Basic::is_transferable].
+    pub fn is_transferable(&self) -> bool {
+        self.derivation.is_transferable()
+    }
+
+    /// The same public key under the transferable or the non-transferable code of its key type.
+    pub fn with_transferability(&self, transferable: bool) -> Result<Self, Error> {
+        let code = self
+            .derivation
+            .with_transferability(transferable)
+            .ok_or(Error::ImproperPrefixType)?;
+        Ok(Self::new_unchecked(code, self.public_key.clone()))
+    }
 }
 
 impl PartialEq for BasicPrefix {
@@ -46,14 +67,13 @@ impl FromStr for BasicPrefix {
         let code = Basic::from_str(s)?;
 
         if s.len() == code.prefix_b64_len() {
-            let k_vec =
-                decode_config(&s[code.code_len()..code.prefix_b64_len()], base64::URL_SAFE)?;
-            Ok(Self::new(code, PublicKey::new(k_vec)))
+            let k_vec = codex::decode(&s[code.code_len()..], code.lead_size())?;
+            Ok(Self::new_unchecked(code, PublicKey::new(k_vec)))
         } else {
-            Err(Error::SemanticError(format!(
-                "Incorrect Prefix Length: {}",
-                s
-            )))
+            Err(Error::WrongLength {
+                expected: code.prefix_b64_len(),
+                got: s.len(),
+            })
         }
     }
 }
@@ -65,6 +85,74 @@ impl Prefix for BasicPrefix {
     fn derivation_code(&self) -> String {
         self.derivation.to_str()
     }
+    fn lead_size(&self) -> usize {
+        self.derivation.lead_size()
+    }
+}
+
+/// The key under the transferable code, see [BasicPrefix::with_transferability].
+impl From<ed25519_dalek::PublicKey> for BasicPrefix {
+    fn from(key: ed25519_dalek::PublicKey) -> Self {
+        Self::new_unchecked(Basic::Ed25519, PublicKey::new(key.to_bytes().to_vec()))
+    }
+}
+
+/// The compressed key under the transferable code.
+impl From<k256::ecdsa::VerifyingKey> for BasicPrefix {
+    fn from(key: k256::ecdsa::VerifyingKey) -> Self {
+        Self::new_unchecked(
+            Basic::ECDSAsecp256k1,
+            PublicKey::new(key.to_bytes().to_vec()),
+        )
+    }
+}
+
+/// The compressed key under the transferable code.
+impl From<p256::ecdsa::VerifyingKey> for BasicPrefix {
+    fn from(key: p256::ecdsa::VerifyingKey) -> Self {
+        let point = key.to_encoded_point(true);
+        Self::new_unchecked(
+            Basic::ECDSAsecp256r1,
+            PublicKey::new(point.as_bytes().to_vec()),
+        )
+    }
+}
+
+impl TryFrom<&BasicPrefix> for ed25519_dalek::PublicKey {
+    type Error = Error;
+
+    fn try_from(prefix: &BasicPrefix) -> Result<Self, Self::Error> {
+        match prefix.derivation {
+            Basic::Ed25519 | Basic::Ed25519NT => Ok(Self::from_bytes(&prefix.public_key.key())?),
+            _ => Err(Error::ImproperPrefixType),
+        }
+    }
+}
+
+impl TryFrom<&BasicPrefix> for k256::ecdsa::VerifyingKey {
+    type Error = Error;
+
+    fn try_from(prefix: &BasicPrefix) -> Result<Self, Self::Error> {
+        match prefix.derivation {
+            Basic::ECDSAsecp256k1 | Basic::ECDSAsecp256k1NT => {
+                Ok(Self::from_sec1_bytes(&prefix.public_key.key())?)
+            }
+            _ => Err(Error::ImproperPrefixType),
+        }
+    }
+}
+
+impl TryFrom<&BasicPrefix> for p256::ecdsa::VerifyingKey {
+    type Error = Error;
+
+    fn try_from(prefix: &BasicPrefix) -> Result<Self, Self::Error> {
+        match prefix.derivation {
+            Basic::ECDSAsecp256r1 | Basic::ECDSAsecp256r1NT => {
+                Ok(Self::from_sec1_bytes(&prefix.public_key.key())?)
+            }
+            _ => Err(Error::ImproperPrefixType),
+        }
+    }
 }
 
 /// Serde compatible Serialize
@@ -121,7 +209,7 @@ fn to_from_string() {
     let signer = PrivateKey::new(kp.secret.to_bytes().to_vec());
 
     let message = b"hello there";
-    let sig = SelfSigningPrefix::new(
+    let sig = SelfSigningPrefix::new_unchecked(
         crate::derivation::self_signing::SelfSigning::Ed25519Sha512,
         signer.sign_ed(message).unwrap(),
     );
@@ -143,3 +231,105 @@ fn to_from_string() {
 
     assert!(deserialized.verify(message, &sig).unwrap());
 }
+
+#[test]
+fn transferability() -> Result<(), Error> {
+    let key = PublicKey::new(vec![0; 32]);
+    let transferable = BasicPrefix::from_str("DAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA")?;
+    let non_transferable = BasicPrefix::from_str("BAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA")?;
+    assert!(transferable.is_transferable());
+    assert!(!non_transferable.is_transferable());
+    assert_eq!(transferable.with_transferability(false)?, non_transferable);
+    assert_eq!(non_transferable.with_transferability(true)?, transferable);
+    assert_eq!(transferable.public_key, key);
+
+    let encryption = Basic::X25519.derive(key);
+    assert!(!encryption.is_transferable());
+    assert!(matches!(
+        encryption.with_transferability(true),
+        Err(Error::ImproperPrefixType)
+    ));
+    Ok(())
+}
+
+#[test]
+fn crypto_interop() -> Result<(), Error> {
+    use crate::derivation::self_signing::SelfSigning;
+    use k256::ecdsa::signature::{Signer, Verifier};
+
+    let secret = ed25519_dalek::SecretKey::from_bytes(&[1; 32])?;
+    let public = ed25519_dalek::PublicKey::from(&secret);
+    let prefix = BasicPrefix::from(public);
+    assert_eq!(prefix.derivation, Basic::Ed25519);
+    assert_eq!(ed25519_dalek::PublicKey::try_from(&prefix)?, public);
+    let keypair = ed25519_dalek::Keypair { secret, public };
+    let signature = SelfSigningPrefix::from(keypair.sign(b"hello"));
+    assert!(prefix.verify(b"hello", &signature)?);
+    assert_eq!(
+        ed25519_dalek::Signature::try_from(&signature)?,
+        keypair.sign(b"hello")
+    );
+
+    let sk = k256::ecdsa::SigningKey::from_bytes(&[1; 32])?;
+    let prefix = BasicPrefix::from(sk.verifying_key());
+    let vk = k256::ecdsa::VerifyingKey::try_from(&prefix)?;
+    let signature = SelfSigningPrefix::from(Signer::<k256::ecdsa::Signature>::sign(&sk, b"hello"));
+    assert!(prefix.verify(b"hello", &signature)?);
+    assert!(vk
+        .verify(b"hello", &k256::ecdsa::Signature::try_from(&signature)?)
+        .is_ok());
+
+    let sk = p256::ecdsa::SigningKey::from_bytes(&[1; 32])?;
+    let prefix = BasicPrefix::from(sk.verifying_key());
+    let vk = p256::ecdsa::VerifyingKey::try_from(&prefix)?;
+    let signature = SelfSigningPrefix::from(Signer::<p256::ecdsa::Signature>::sign(&sk, b"hello"));
+    assert!(prefix.verify(b"hello", &signature)?);
+    assert!(vk
+        .verify(b"hello", &p256::ecdsa::Signature::try_from(&signature)?)
+        .is_ok());
+
+    // keys and signatures of other schemes, or malformed
+    assert!(matches!(
+        ed25519_dalek::PublicKey::try_from(&prefix),
+        Err(Error::ImproperPrefixType)
+    ));
+    assert!(matches!(
+        k256::ecdsa::Signature::try_from(&signature),
+        Err(Error::ImproperPrefixType)
+    ));
+    let short = SelfSigningPrefix::new_unchecked(SelfSigning::ECDSAsecp256r1Sha256, vec![1; 10]);
+    assert!(p256::ecdsa::Signature::try_from(&short).is_err());
+    Ok(())
+}
+
+#[test]
+fn derivative_lengths() -> Result<(), Error> {
+    use crate::derivation::{
+        self_addressing::SelfAddressing, self_signing::SelfSigning, CodeTable,
+    };
+    use crate::prefix::SelfAddressingPrefix;
+
+    for (_, code) in Basic::CODES {
+        let len = code.derivative_len();
+        let prefix = BasicPrefix::try_new(*code, PublicKey::new(vec![1; len]))?;
+        assert_eq!(BasicPrefix::from_str(&prefix.to_str())?, prefix);
+        assert!(BasicPrefix::try_new(*code, PublicKey::new(vec![1; len - 1])).is_err());
+    }
+    for (_, code) in SelfSigning::CODES {
+        let len = code.derivative_len();
+        let prefix = SelfSigningPrefix::try_new(*code, vec![1; len])?;
+        assert_eq!(SelfSigningPrefix::from_str(&prefix.to_str())?, prefix);
+        assert!(SelfSigningPrefix::try_new(*code, vec![1; len + 1]).is_err());
+    }
+    for (_, code) in SelfAddressing::CODES {
+        let len = code.derivative_len();
+        let prefix = SelfAddressingPrefix::try_new(*code, vec![1; len])?;
+        assert_eq!(SelfAddressingPrefix::from_str(&prefix.to_str())?, prefix);
+        assert!(SelfAddressingPrefix::try_new(*code, vec![]).is_err());
+    }
+
+    // the escape hatch takes anything, even if it can not be parsed back
+    let short = BasicPrefix::new_unchecked(Basic::Ed25519, PublicKey::new(vec![1; 16]));
+    assert!(BasicPrefix::from_str(&short.to_str()).is_err());
+    Ok(())
+}
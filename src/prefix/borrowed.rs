@@ -0,0 +1,118 @@
+use super::{BasicPrefix, SelfAddressingPrefix, SelfSigningPrefix, Siger};
+use crate::{
+    codex::b64,
+    derivation::{
+        attached_signature_code::AttachedSignatureCode, basic::Basic,
+        self_addressing::SelfAddressing, self_signing::SelfSigning, DerivationCode,
+    },
+    error::Error,
+    keys::PublicKey,
+};
+use core::str::FromStr;
+
+/// Borrowed view of a primitive in a binary domain (qb2) buffer: its code and its raw material,
+/// left in the buffer instead of copied into a fresh `Vec`. Text domain (qb64) material has to
+/// be Base64 decoded first so only binary streams can be parsed into views.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct PrefixRef<'a, C> {
+    pub code: C,
+    pub raw: &'a [u8],
+}
+
+impl<'a, C: DerivationCode + FromStr<Err = Error>> PrefixRef<'a, C> {
+    /// Parses a primitive from the start of a binary domain (qb2) stream, returning it with the
+    /// rest of the stream.
+    pub fn from_qb2(qb2: &'a [u8]) -> Result<(Self, &'a [u8]), Error> {
+        // six bytes hold the longest codes, e.g. big indexed signature codes
+        let head = b64::encode(&qb2[..qb2.len().min(6)]);
+        let code = C::from_str(&head)?;
+        let size = code.prefix_b2_len();
+        let primitive = qb2.get(..size).ok_or_else(|| Error::ShortageError {
+            needed: size - qb2.len(),
+        })?;
+        // the code is followed by its pad bits up to the next byte, then by the lead bytes
+        let start = (code.code_len() * 6).div_ceil(8) + code.lead_size();
+        let pad = b64::encode(&primitive[..start]);
+        if !pad[code.code_len()..].bytes().all(|c| c == b'A') {
+            return Err(Error::InvalidPadding);
+        }
+        Ok((
+            Self {
+                code,
+                raw: &primitive[start..],
+            },
+            &qb2[size..],
+        ))
+    }
+}
+
+impl PrefixRef<'_, Basic> {
+    /// Copies the key out of the buffer.
+    pub fn to_owned(&self) -> BasicPrefix {
+        BasicPrefix::new_unchecked(self.code, PublicKey::new(self.raw.to_vec()))
+    }
+}
+
+impl PrefixRef<'_, SelfAddressing> {
+    /// Copies the digest out of the buffer.
+    pub fn to_owned(&self) -> SelfAddressingPrefix {
+        SelfAddressingPrefix::new_unchecked(self.code, self.raw.to_vec())
+    }
+}
+
+impl PrefixRef<'_, SelfSigning> {
+    /// Copies the signature out of the buffer.
+    pub fn to_owned(&self) -> SelfSigningPrefix {
+        SelfSigningPrefix::new_unchecked(self.code, self.raw.to_vec())
+    }
+}
+
+impl PrefixRef<'_, AttachedSignatureCode> {
+    /// Copies the indexed signature out of the buffer.
+    pub fn to_owned(&self) -> Siger {
+        Siger::new(self.code, self.raw.to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prefix::{AttachedSignaturePrefix, Prefix};
+
+    #[test]
+    fn views() -> Result<(), Error> {
+        let digest = SelfAddressing::Blake3_256.derive(b"event");
+        let key = BasicPrefix::new_unchecked(Basic::Ed25519, PublicKey::new(vec![7; 32]));
+        let sig = AttachedSignaturePrefix::new(SelfSigning::Ed25519Sha512, vec![9; 64], 2)?;
+        let stream = [digest.to_qb2()?, key.to_qb2()?, sig.to_qb2()?].concat();
+
+        let (view, rest) = PrefixRef::<SelfAddressing>::from_qb2(&stream)?;
+        assert_eq!(view.raw, &digest.digest[..]);
+        assert_eq!(view.to_owned(), digest);
+        let (view, rest) = PrefixRef::<Basic>::from_qb2(rest)?;
+        assert_eq!(view.raw, &[7; 32]);
+        assert_eq!(view.to_owned(), key);
+        let (view, rest) = PrefixRef::<AttachedSignatureCode>::from_qb2(rest)?;
+        assert_eq!(view.code.index, 2);
+        assert_eq!(view.to_owned(), Siger::from(sig.clone()));
+        assert!(rest.is_empty());
+
+        // lead bytes are left out of the raw material
+        let ml_dsa = SelfSigningPrefix::new_unchecked(SelfSigning::MLDSA44, vec![1; 2420]);
+        let qb2 = ml_dsa.to_qb2()?;
+        let (view, _) = PrefixRef::<SelfSigning>::from_qb2(&qb2)?;
+        assert_eq!(view.raw.len(), 2420);
+        assert_eq!(view.to_owned(), ml_dsa);
+
+        let qb2 = sig.to_qb2()?;
+        assert!(matches!(
+            PrefixRef::<AttachedSignatureCode>::from_qb2(&qb2[..40]),
+            Err(Error::ShortageError { needed: 26 })
+        ));
+        // pad bits after a one character code
+        let mut qb2 = digest.to_qb2()?;
+        qb2[0] |= 1;
+        assert!(PrefixRef::<SelfAddressing>::from_qb2(&qb2).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,130 @@
+use super::{split_at_code_len, BasicPrefix, Prefix, SelfSigningPrefix};
+use crate::{
+    derivation::{
+        basic::Basic,
+        counter::{Counter, CounterCode},
+        self_signing::SelfSigning,
+        DerivationCode,
+    },
+    error::Error,
+};
+use core::str::FromStr;
+
+/// A non-indexed signature (cigar) couple: a signature together with the basic prefix of the key
+/// that made it. Nontransferable identifiers, e.g. witnesses, receipt events with cigars since
+/// their prefix is their key, there is no key list to index into.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Cigar {
+    pub verfer: BasicPrefix,
+    pub signature: SelfSigningPrefix,
+}
+
+impl Cigar {
+    pub fn new(verfer: BasicPrefix, signature: SelfSigningPrefix) -> Self {
+        Self { verfer, signature }
+    }
+
+    pub fn verify(&self, data: &[u8]) -> Result<bool, Error> {
+        self.verfer.verify(data, &self.signature)
+    }
+
+    /// Text domain (qb64) representation of the couple, the verfer followed by the signature.
+    pub fn to_str(&self) -> String {
+        [self.verfer.to_str(), self.signature.to_str()].join("")
+    }
+
+    /// Parses one couple from the start of `s`, returning it with the rest of `s`.
+    pub fn from_stream(s: &str) -> Result<(Self, &str), Error> {
+        let (verfer, rest) = split_at_code_len(s, Basic::from_str(s)?)?;
+        let (signature, rest) = split_at_code_len(rest, SelfSigning::from_str(rest)?)?;
+        Ok((Self::new(verfer.parse()?, signature.parse()?), rest))
+    }
+}
+
+/// Parses a single couple, `s` must hold nothing else.
+impl FromStr for Cigar {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match Self::from_stream(s)? {
+            (cigar, "") => Ok(cigar),
+            (_, rest) => Err(Error::WrongLength {
+                expected: s.len() - rest.len(),
+                got: s.len(),
+            }),
+        }
+    }
+}
+
+/// Encodes cigars as a nontransferable receipt couples group (`-C` count code).
+pub fn encode_couples(cigars: &[Cigar]) -> Result<String, Error> {
+    let counter = Counter::new(CounterCode::NonTransReceiptCouples, cigars.len() as u32)?;
+    Ok(std::iter::once(counter.to_str())
+        .chain(cigars.iter().map(Cigar::to_str))
+        .collect())
+}
+
+/// Parses a nontransferable receipt couples group from the start of `s`, returning the cigars
+/// with the rest of `s`.
+pub fn decode_couples(s: &str) -> Result<(Vec<Cigar>, &str), Error> {
+    let counter = Counter::from_str(s)?;
+    if counter.code != CounterCode::NonTransReceiptCouples {
+        return Err(Error::SemanticError(format!(
+            "Expected receipt couples, got count code {}",
+            counter.code.to_str()
+        )));
+    }
+    let mut rest = &s[counter.prefix_b64_len()..];
+    let mut cigars = Vec::with_capacity(counter.count as usize);
+    for _ in 0..counter.count {
+        let (cigar, tail) = Cigar::from_stream(rest)?;
+        cigars.push(cigar);
+        rest = tail;
+    }
+    Ok((cigars, rest))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::signer;
+
+    fn cigar(seed: u8, data: &[u8]) -> Cigar {
+        let signer = signer(seed, false);
+        Cigar::new(signer.basic_prefix(), signer.sign(data))
+    }
+
+    #[test]
+    fn couple() -> Result<(), Error> {
+        let cigar = cigar(1, b"receipted event");
+        assert!(cigar.verify(b"receipted event")?);
+        assert!(!cigar.verify(b"other event")?);
+
+        let couple = cigar.to_str();
+        assert_eq!(&couple[..1], "B");
+        assert_eq!(&couple[44..46], "0B");
+        assert_eq!(couple.len(), 44 + 88);
+        assert_eq!(Cigar::from_str(&couple)?, cigar);
+
+        assert!(Cigar::from_str(&couple[..100]).is_err());
+        assert!(Cigar::from_str(&[&couple, "-AAB"].join("")).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn couples_group() -> Result<(), Error> {
+        let cigars = vec![cigar(1, b"event"), cigar(2, b"event")];
+        let group = encode_couples(&cigars)?;
+        assert_eq!(&group[..4], "-CAC");
+
+        let stream = [group.as_str(), "-AAB"].join("");
+        let (decoded, rest) = decode_couples(&stream)?;
+        assert_eq!(decoded, cigars);
+        assert_eq!(rest, "-AAB");
+
+        assert_eq!(decode_couples("-CAA")?, (vec![], ""));
+        assert!(decode_couples("-AAB").is_err());
+        assert!(decode_couples(&group[..group.len() - 1]).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,62 @@
+use super::Prefix;
+use crate::{codex, derivation::lookup, error::Error};
+use core::str::FromStr;
+
+/// A CESR primitive encrypted to an X25519 public key with a libsodium sealed box.<br>
+/// The plain text sealed in the box is the text domain (qb64) encoding of the primitive, so
+/// the derivation code of the decrypted primitive is recovered together with its key material.<br>
+/// Each of these cipher types is found in the [master code table].
+///
+/// [master code table]: https://weboftrust.github.io/ietf-cesr/draft-ssmith-cesr.html#name-master-code-table
+#[derive(Debug, PartialEq, Clone)]
+pub enum CipherPrefix {
+    /// A sealed 256 bit seed, e.g. the private key of a signer
+    X25519Seed(Vec<u8>),
+    /// A sealed 128 bit salt
+    X25519Salt(Vec<u8>),
+}
+
+impl FromStr for CipherPrefix {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        type Variant = fn(Vec<u8>) -> CipherPrefix;
+        const CODES: &[(&str, Variant)] = &[
+            ("P", CipherPrefix::X25519Seed),
+            ("1AAH", CipherPrefix::X25519Salt),
+        ];
+        let (code, cipher) = lookup(CODES, s)?;
+        Ok(cipher(codex::decode(&s[code.len()..], 0)?))
+    }
+}
+
+impl Prefix for CipherPrefix {
+    fn derivative(&self) -> Vec<u8> {
+        match self {
+            Self::X25519Seed(cipher) | Self::X25519Salt(cipher) => cipher.to_owned(),
+        }
+    }
+    fn derivation_code(&self) -> String {
+        match self {
+            Self::X25519Seed(_) => "P".to_string(),
+            Self::X25519Salt(_) => "1AAH".to_string(),
+        }
+    }
+}
+
+#[test]
+fn test_cipher_codes() -> Result<(), Error> {
+    let cipher = CipherPrefix::X25519Seed(vec![0; 92]);
+    assert_eq!(cipher.to_str().len(), 124);
+    assert_eq!(CipherPrefix::from_str(&cipher.to_str())?, cipher);
+
+    let cipher = CipherPrefix::X25519Salt(vec![0; 72]);
+    assert_eq!(&cipher.to_str()[..4], "1AAH");
+    assert_eq!(cipher.to_str().len(), 100);
+    assert_eq!(CipherPrefix::from_str(&cipher.to_str())?, cipher);
+
+    assert!(CipherPrefix::from_str("").is_err());
+    assert!(CipherPrefix::from_str("1AAA").is_err());
+    assert!(CipherPrefix::from_str("1").is_err());
+    Ok(())
+}
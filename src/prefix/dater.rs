@@ -0,0 +1,185 @@
+use super::Prefix;
+use crate::{codex::b64, error::Error};
+use chrono::{DateTime, FixedOffset, SecondsFormat, TimeZone, Utc};
+use core::str::FromStr;
+
+/// Code of an ISO-8601 datetime primitive.
+const DATER_CODE: &str = "1AAG";
+
+/// Length of an ISO-8601 datetime with microseconds and UTC offset,
+/// e.g. `2020-08-22T17:50:09.988921+00:00`.
+const DATETIME_LEN: usize = 32;
+
+/// An ISO-8601 datetime primitive, code `1AAG`, e.g. the time an event was first seen.
+///
+/// The datetime is written into the text domain as is, with the characters outside of Base64
+/// replaced: `:` by `c`, `.` by `d` and `+` by `p`.
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct Dater {
+    datetime: [u8; DATETIME_LEN],
+}
+
+impl Dater {
+    /// Parses an ISO-8601 datetime with microseconds and UTC offset,
+    /// e.g. `2020-08-22T17:50:09.988921+00:00`.
+    pub fn from_iso8601(datetime: &str) -> Result<Self, Error> {
+        if datetime.len() != DATETIME_LEN {
+            return Err(Error::SemanticError(format!(
+                "Datetime must have {} characters: {}",
+                DATETIME_LEN, datetime
+            )));
+        }
+        DateTime::parse_from_rfc3339(datetime)
+            .map_err(|e| Error::SemanticError(format!("Invalid datetime {}: {}", datetime, e)))?;
+        let dater = Self {
+            datetime: datetime
+                .as_bytes()
+                .try_into()
+                .expect("length checked above"),
+        };
+        if !dater
+            .to_b64()
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(Error::SemanticError(format!(
+                "Invalid datetime: {}",
+                datetime
+            )));
+        }
+        Ok(dater)
+    }
+
+    /// The current time in UTC.
+    pub fn now() -> Self {
+        Utc::now()
+            .try_into()
+            .expect("the current year has four digits")
+    }
+
+    /// Parses a datetime primitive from the start of `s`, returning it with the rest of `s`.
+    pub fn from_stream(s: &str) -> Result<(Self, &str), Error> {
+        let len = DATER_CODE.len() + DATETIME_LEN;
+        match s.get(..len) {
+            Some(dater) => Ok((dater.parse()?, &s[len..])),
+            None => Err(Error::DeserializeError(format!("Invalid datetime: {}", s))),
+        }
+    }
+
+    /// The ISO-8601 datetime.
+    pub fn iso8601(&self) -> &str {
+        core::str::from_utf8(&self.datetime).expect("datetime validated on creation")
+    }
+
+    pub fn datetime(&self) -> DateTime<FixedOffset> {
+        DateTime::parse_from_rfc3339(self.iso8601()).expect("datetime validated on creation")
+    }
+
+    fn to_b64(&self) -> String {
+        self.datetime
+            .iter()
+            .map(|c| match c {
+                b':' => 'c',
+                b'.' => 'd',
+                b'+' => 'p',
+                c => *c as char,
+            })
+            .collect()
+    }
+}
+
+/// Truncates the datetime to microseconds. Fails for years outside of 0 to 9999, which a
+/// datetime primitive has no room for.
+impl<Tz: TimeZone> TryFrom<DateTime<Tz>> for Dater {
+    type Error = Error;
+
+    fn try_from(datetime: DateTime<Tz>) -> Result<Self, Self::Error> {
+        Self::from_iso8601(
+            &datetime
+                .fixed_offset()
+                .to_rfc3339_opts(SecondsFormat::Micros, false),
+        )
+    }
+}
+
+impl FromStr for Dater {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.get(..4) != Some(DATER_CODE) || s.len() != DATER_CODE.len() + DATETIME_LEN {
+            return Err(Error::DeserializeError(format!("Invalid datetime: {}", s)));
+        }
+        let datetime: String = s[4..]
+            .chars()
+            .map(|c| match c {
+                'c' => ':',
+                'd' => '.',
+                'p' => '+',
+                c => c,
+            })
+            .collect();
+        Self::from_iso8601(&datetime)
+    }
+}
+
+impl Prefix for Dater {
+    fn derivative(&self) -> Vec<u8> {
+        b64::decode(self.to_b64()).expect("datetime validated on creation")
+    }
+
+    fn derivation_code(&self) -> String {
+        DATER_CODE.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dater() -> Result<(), Error> {
+        // taken from KERIPY: tests/core/test_coring.py test_dater
+        let dater = Dater::from_iso8601("2020-08-22T17:50:09.988921+00:00")?;
+        assert_eq!(dater.to_str(), "1AAG2020-08-22T17c50c09d988921p00c00");
+        assert_eq!(dater.derivative().len(), 24);
+        assert_eq!(
+            Dater::from_str("1AAG2020-08-22T17c50c09d988921p00c00")?,
+            dater
+        );
+        assert_eq!(Dater::from_qb2(&dater.to_qb2()?)?, dater);
+
+        let dater = Dater::from_iso8601("2020-08-22T17:50:09.988921-01:00")?;
+        assert_eq!(dater.to_str(), "1AAG2020-08-22T17c50c09d988921-01c00");
+        assert_eq!(dater.datetime().timestamp(), 1598122209);
+
+        assert!(Dater::from_str("1AAG2020-08-22").is_err());
+        assert!(Dater::from_iso8601("2020-08-22T17:50:09+00:00").is_err());
+        assert!(Dater::from_iso8601("2020-08-22T17:50:09.988921 00:00").is_err());
+        assert!(Dater::from_iso8601("2020-08-22 17:50:09.988921+00:00").is_err());
+        assert!(Dater::from_iso8601("2020-13-22T17:50:09.988921+00:00").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn chrono_conversion() -> Result<(), Error> {
+        let datetime = Utc.with_ymd_and_hms(2020, 8, 22, 17, 50, 9).unwrap()
+            + chrono::Duration::nanoseconds(988_921_500);
+        let dater = Dater::try_from(datetime)?;
+        assert_eq!(dater.iso8601(), "2020-08-22T17:50:09.988921+00:00");
+        assert_eq!(dater.datetime().timestamp_subsec_micros(), 988921);
+
+        let offset = FixedOffset::east_opt(2 * 3600).unwrap();
+        let dater = Dater::try_from(datetime.with_timezone(&offset))?;
+        assert_eq!(dater.iso8601(), "2020-08-22T19:50:09.988921+02:00");
+        assert_eq!(Dater::from_str(&dater.to_str())?, dater);
+
+        assert_eq!(Dater::now().iso8601().len(), DATETIME_LEN);
+
+        // years of other than four digits
+        let late = Utc.with_ymd_and_hms(10000, 1, 1, 0, 0, 0).unwrap();
+        assert!(Dater::try_from(late).is_err());
+        let early = Utc.with_ymd_and_hms(-1, 1, 1, 0, 0, 0).unwrap();
+        assert!(Dater::try_from(early).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,162 @@
+use super::{check_derivative_len, Prefix, SelfAddressingPrefix, SelfSigningPrefix};
+use crate::{
+    derivation::{self_addressing::SelfAddressing, self_signing::SelfSigning, DerivationCode},
+    error::Error,
+};
+use core::{fmt, str::FromStr};
+use std::hash::{Hash, Hasher};
+use subtle::ConstantTimeEq;
+
+/// Defines a primitive of `N` raw bytes held inline under a code of `$code`, with its
+/// conversions from and to the heap allocated `$prefix` of the same material.
+macro_rules! fixed {
+    ($(#[$doc:meta])* $name:ident, $code:ty, $prefix:ident, $field:ident) => {
+        $(#[$doc])*
+        #[derive(Debug, Clone, Copy)]
+        pub struct $name<const N: usize> {
+            pub derivation: $code,
+            pub $field: [u8; N],
+        }
+
+        impl<const N: usize> $name<N> {
+            /// Fails when `code` is not of `N` bytes of material.
+            pub fn new(code: $code, $field: [u8; N]) -> Result<Self, Error> {
+                check_derivative_len(&code, &$field)?;
+                Ok(Self {
+                    derivation: code,
+                    $field,
+                })
+            }
+
+            /// Takes the material from a vector, failing when it is not `N` bytes long or `code`
+            /// is not of `N` bytes of material.
+            pub fn from_vec(code: $code, $field: Vec<u8>) -> Result<Self, Error> {
+                check_derivative_len(&code, &$field)?;
+                let len = $field.len();
+                let $field = $field.try_into().map_err(|_| Error::WrongLength {
+                    expected: N,
+                    got: len,
+                })?;
+                Ok(Self {
+                    derivation: code,
+                    $field,
+                })
+            }
+        }
+
+        /// Compares the material in constant time.
+        impl<const N: usize> PartialEq for $name<N> {
+            fn eq(&self, other: &Self) -> bool {
+                self.derivation == other.derivation
+                    && bool::from(self.$field.ct_eq(&other.$field))
+            }
+        }
+
+        impl<const N: usize> Eq for $name<N> {}
+
+        impl<const N: usize> Hash for $name<N> {
+            fn hash<H: Hasher>(&self, state: &mut H) {
+                self.derivation.hash(state);
+                self.$field.hash(state);
+            }
+        }
+
+        impl<const N: usize> TryFrom<$prefix> for $name<N> {
+            type Error = Error;
+
+            fn try_from(prefix: $prefix) -> Result<Self, Self::Error> {
+                Self::from_vec(prefix.derivation, prefix.$field)
+            }
+        }
+
+        impl<const N: usize> TryFrom<&$prefix> for $name<N> {
+            type Error = Error;
+
+            fn try_from(prefix: &$prefix) -> Result<Self, Self::Error> {
+                Self::from_vec(prefix.derivation, prefix.$field.clone())
+            }
+        }
+
+        impl<const N: usize> From<$name<N>> for $prefix {
+            fn from(fixed: $name<N>) -> Self {
+                Self::new_unchecked(fixed.derivation, fixed.$field.to_vec())
+            }
+        }
+
+        impl<const N: usize> FromStr for $name<N> {
+            type Err = Error;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                $prefix::from_str(s)?.try_into()
+            }
+        }
+
+        impl<const N: usize> Prefix for $name<N> {
+            fn derivative(&self) -> Vec<u8> {
+                self.$field.to_vec()
+            }
+            fn derivation_code(&self) -> String {
+                self.derivation.to_str()
+            }
+            fn lead_size(&self) -> usize {
+                self.derivation.lead_size()
+            }
+        }
+
+        impl<const N: usize> fmt::Display for $name<N> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(&self.to_str())
+            }
+        }
+    };
+}
+
+fixed!(
+    /// A digest of `N` bytes held inline, e.g. `Digest<32>` for Blake3-256 or SHA3-256 SAIDs.
+    Digest,
+    SelfAddressing,
+    SelfAddressingPrefix,
+    digest
+);
+
+fixed!(
+    /// A signature of `N` bytes held inline, e.g. `Signature<64>` for Ed25519 or ECDSA.
+    Signature,
+    SelfSigning,
+    SelfSigningPrefix,
+    signature
+);
+
+impl<const N: usize> Digest<N> {
+    /// Checks the digest of `data` matches, in constant time.
+    pub fn verify_binding(&self, data: &[u8]) -> bool {
+        bool::from(self.derivation.digest(data).ct_eq(&self.digest))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inline() -> Result<(), Error> {
+        let said = SelfAddressing::Blake3_256.derive(b"data");
+        let digest = Digest::<32>::try_from(&said)?;
+        assert!(digest.verify_binding(b"data"));
+        assert_eq!(digest.to_str(), said.to_str());
+        assert_eq!(Digest::<32>::from_str(&said.to_str())?, digest);
+        assert_eq!(SelfAddressingPrefix::from(digest), said);
+
+        // the code and the size must agree
+        assert!(Digest::<64>::try_from(&said).is_err());
+        assert!(Digest::new(SelfAddressing::SHA2_512, [0; 32]).is_err());
+        assert!(Digest::<32>::from_vec(SelfAddressing::Blake3_256, vec![0; 31]).is_err());
+
+        let signature = Signature::new(SelfSigning::Ed25519Sha512, [7; 64])?;
+        let prefix = SelfSigningPrefix::from(signature);
+        assert_eq!(prefix.signature, vec![7; 64]);
+        assert_eq!(Signature::<64>::try_from(prefix)?, signature);
+        assert!(Signature::new(SelfSigning::Ed448, [7; 64]).is_err());
+        Ok(())
+    }
+}
@@ -1,23 +1,54 @@
 use crate::{
-    derivation::{basic::Basic, self_signing::SelfSigning},
+    codex::{self, b64},
+    derivation::{
+        ascii, basic::Basic, self_addressing::SelfAddressing, self_signing::SelfSigning,
+        split_at_len, DerivationCode,
+    },
     error::Error,
 };
-use base64::encode_config;
-use core::str::FromStr;
+use core::{fmt, str::FromStr};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use subtle::ConstantTimeEq;
 
 pub mod attached_signature;
 pub mod basic;
+pub mod borrowed;
+pub mod cigar;
+pub mod cipher;
+pub mod dater;
+pub mod fixed;
+pub mod next_keys;
+pub mod number;
+pub mod opaque;
+pub mod primitive;
+pub mod sad_path;
 pub mod seed;
 pub mod self_addressing;
 pub mod self_signing;
+pub mod siger;
+pub mod tagger;
+pub mod variable;
 
 pub use attached_signature::AttachedSignaturePrefix;
 pub use basic::BasicPrefix;
+pub use borrowed::PrefixRef;
+pub use cigar::Cigar;
+pub use cipher::CipherPrefix;
+pub use dater::Dater;
+pub use fixed::{Digest, Signature};
+pub use next_keys::{exposed, next_digests, verify_next_keys};
+#[cfg(feature = "legacy-next-keys")]
+pub use next_keys::{legacy_next_digest, verify_legacy_next};
+pub use number::{Number, Seqner};
+pub use opaque::OpaqueMatter;
+pub use primitive::{decode_any, Primitive};
+pub use sad_path::SadPath;
 pub use seed::SeedPrefix;
 pub use self_addressing::SelfAddressingPrefix;
 pub use self_signing::SelfSigningPrefix;
-
+pub use siger::Siger;
+pub use tagger::Tagger;
+pub use variable::VariablePrefix;
 
 // TODO should this be called CESRType rather than Prefix since it is applicable to any CESR type?
 /// A CESR supported data type has a registered entry in the [master code table](https://weboftrust.github.io/ietf-cesr/draft-ssmith-cesr.html#name-master-code-table).
@@ -26,7 +57,7 @@ pub use self_signing::SelfSigningPrefix;
 ///
 /// See section 2.2.1 of the [KERI white paper](https://github.com/SmithSamuelM/Papers/blob/master/whitepapers/KERI_WP_2.x.web.pdf)
 /// for a complete description
-pub trait Prefix: FromStr<Err=Error> {
+pub trait Prefix: FromStr<Err = Error> {
     /// The raw bytes of the cryptographic primitive.
     /// This is not the Base64 encoded version.
     fn derivative(&self) -> Vec<u8>;
@@ -35,19 +66,146 @@ pub trait Prefix: FromStr<Err=Error> {
     /// This indicates both the cryptographic signing scheme used by the data type, in the case of a
     /// cryptographic primitive, as well as the length of the data type.
     fn derivation_code(&self) -> String;
+
+    /// Number of zero bytes prepended to the derivative to align it on a 24 bit boundary.
+    fn lead_size(&self) -> usize {
+        0
+    }
+
+    /// Text domain (qb64) representation of the prefix, see [codex::encode].
     fn to_str(&self) -> String {
         // empty data cannot be prefixed!
         match self.derivative().len() {
             0 => "".to_string(),
-            _ => {
-                let dc = self.derivation_code();
-                let ec = encode_config(self.derivative(), base64::URL_SAFE_NO_PAD);
-                [dc, ec].join("")
-            }
+            _ => codex::encode(
+                &self.derivation_code(),
+                self.lead_size(),
+                &self.derivative(),
+            ),
+        }
+    }
+
+    /// Text domain (qb64) representation of the prefix as bytes, for streams kept in byte buffers.
+    fn to_qb64b(&self) -> Vec<u8> {
+        self.to_str().into_bytes()
+    }
+
+    /// Binary domain (qb2) representation of the prefix, the Base64 decoding of [Prefix::to_str]
+    /// including the derivation code.
+    /// The text representation must align on a 24 bit boundary, any padding is part of the code.
+    fn to_qb2(&self) -> Result<Vec<u8>, Error> {
+        let qb64 = self.to_str();
+        if !qb64.len().is_multiple_of(4) {
+            return Err(Error::SemanticError(format!(
+                "Prefix not aligned on 24 bit boundary: {}",
+                qb64
+            )));
+        }
+        b64::decode(qb64)
+    }
+
+    /// Compares the derivatives in constant time, so the time taken does not tell how many
+    /// leading bytes match. Codes and lengths are not secret and compared as usual.
+    fn ct_eq(&self, other: &Self) -> bool
+    where
+        Self: Sized,
+    {
+        self.derivation_code() == other.derivation_code()
+            && bool::from(self.derivative().ct_eq(&other.derivative()))
+    }
+
+    /// Whether `other` carries the same raw material whatever its code, e.g. a key under its
+    /// transferable and non-transferable code, or a signature indexed and not. The derivatives
+    /// are compared in constant time.
+    fn same_material<P: Prefix>(&self, other: &P) -> bool
+    where
+        Self: Sized,
+    {
+        bool::from(self.derivative().ct_eq(&other.derivative()))
+    }
+
+    /// The prefix under its canonical code, the smallest one its material fits in. Prefixes whose
+    /// code has a single width are returned as they are.
+    fn normalized(&self) -> Self
+    where
+        Self: Sized + Clone,
+    {
+        self.clone()
+    }
+
+    /// Parses a prefix from its binary domain (qb2) representation.
+    fn from_qb2(qb2: &[u8]) -> Result<Self, Error>
+    where
+        Self: Sized,
+    {
+        if !qb2.len().is_multiple_of(3) {
+            return Err(Error::SemanticError(format!(
+                "Prefix not aligned on 24 bit boundary: {} bytes",
+                qb2.len()
+            )));
         }
+        Self::from_str(&b64::encode(qb2))
+    }
+
+    /// Parses a prefix from its text domain (qb64) representation held in a byte buffer, without
+    /// copying it into a `String`.
+    fn from_qb64b(qb64b: &[u8]) -> Result<Self, Error>
+    where
+        Self: Sized,
+    {
+        match ascii(qb64b) {
+            qb64 if qb64.len() == qb64b.len() => Self::from_str(qb64),
+            qb64 => Err(Error::InvalidBase64 { offset: qb64.len() }),
+        }
+    }
+}
+
+/// Checks that `raw` is as long as the derivative of `code`, the check of the `try_new`
+/// constructors.
+pub(crate) fn check_derivative_len(code: &impl DerivationCode, raw: &[u8]) -> Result<(), Error> {
+    match code.derivative_len() {
+        len if len == raw.len() => Ok(()),
+        len => Err(Error::SemanticError(format!(
+            "Expected {} bytes of derivative for code {}, got {}",
+            len,
+            code.to_str(),
+            raw.len()
+        ))),
     }
 }
 
+/// Displays public primitives in the text domain (qb64). Seeds and salts are left out, their
+/// material only leaves through an explicit [Prefix::to_str].
+macro_rules! display {
+    ($($prefix:ty),* $(,)?) => {
+        $(
+            impl fmt::Display for $prefix {
+                fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    f.write_str(&self.to_str())
+                }
+            }
+        )*
+    };
+}
+
+display!(
+    AttachedSignaturePrefix,
+    BasicPrefix,
+    Cigar,
+    CipherPrefix,
+    Dater,
+    IdentifierPrefix,
+    Number,
+    OpaqueMatter,
+    SadPath,
+    Seqner,
+    SelfAddressingPrefix,
+    SelfSigningPrefix,
+    Siger,
+    Tagger,
+    VariablePrefix,
+);
+
 /// An IdentifierPrefix is a unique fingerprint of a public key in a self-certifying identifier.
 /// A Prefix has two parts including a derivation code and a derivation (labelled a "deriviative" here).
 ///
@@ -59,6 +217,22 @@ pub enum IdentifierPrefix {
     SelfSigning(SelfSigningPrefix),
 }
 
+impl IdentifierPrefix {
+    /// Parses one prefix from the start of `s`, returning it with the rest of `s`.
+    pub fn from_stream(s: &str) -> Result<(Self, &str), Error> {
+        if let Ok(code) = Basic::from_str(s) {
+            let (prefix, rest) = split_at_code_len(s, code)?;
+            return Ok((Self::Basic(prefix.parse()?), rest));
+        }
+        if let Ok(code) = SelfAddressing::from_str(s) {
+            let (prefix, rest) = split_at_code_len(s, code)?;
+            return Ok((Self::SelfAddressing(prefix.parse()?), rest));
+        }
+        let (prefix, rest) = split_at_code_len(s, SelfSigning::from_str(s)?)?;
+        Ok((Self::SelfSigning(prefix.parse()?), rest))
+    }
+}
+
 /// The [FromStr] trait is the primary parsing function for String-encoded KERI Identifiers
 impl FromStr for IdentifierPrefix {
     type Err = Error;
@@ -66,9 +240,13 @@ impl FromStr for IdentifierPrefix {
         match BasicPrefix::from_str(s) {
             Ok(bp) => Ok(Self::Basic(bp)),
             Err(err) => {
-                match err {
-                    Error::Base64DecodingError { source: _ } => return Err(err),
-                    _ => (),
+                if let Error::InvalidBase64 { .. }
+                | Error::StandardBase64 { .. }
+                | Error::Base64Padding { .. }
+                | Error::InvalidPadding
+                | Error::WrongLength { .. } = err
+                {
+                    return Err(err);
                 }
                 match SelfAddressingPrefix::from_str(s) {
                     Ok(sa) => Ok(Self::SelfAddressing(sa)),
@@ -95,13 +273,20 @@ impl Prefix for IdentifierPrefix {
             Self::SelfSigning(ssp) => ssp.derivation_code(),
         }
     }
+    fn lead_size(&self) -> usize {
+        match self {
+            Self::Basic(bp) => bp.lead_size(),
+            Self::SelfAddressing(sap) => sap.lead_size(),
+            Self::SelfSigning(ssp) => ssp.lead_size(),
+        }
+    }
 }
 
 /// Serde compatible Serialize
 impl Serialize for IdentifierPrefix {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-        where
-            S: Serializer,
+    where
+        S: Serializer,
     {
         serializer.serialize_str(&self.to_str())
     }
@@ -110,8 +295,8 @@ impl Serialize for IdentifierPrefix {
 /// Serde compatible Deserialize. Expects a String and hands it off to from_str
 impl<'de> Deserialize<'de> for IdentifierPrefix {
     fn deserialize<D>(deserializer: D) -> Result<IdentifierPrefix, D::Error>
-        where
-            D: Deserializer<'de>,
+    where
+        D: Deserializer<'de>,
     {
         let s = String::deserialize(deserializer)?;
 
@@ -150,22 +335,65 @@ pub fn verify(
                 .verify_ecdsa(data.as_ref(), &signature.signature)),
             _ => Err(Error::SemanticError("wrong sig type".to_string())),
         },
+        Basic::Ed448 | Basic::Ed448NT => match signature.derivation {
+            SelfSigning::Ed448 => Ok(key
+                .public_key
+                .verify_ed448(data.as_ref(), &signature.signature)),
+            _ => Err(Error::SemanticError("wrong sig type".to_string())),
+        },
+        Basic::ECDSAsecp256r1 | Basic::ECDSAsecp256r1NT => match signature.derivation {
+            SelfSigning::ECDSAsecp256r1Sha256 => Ok(key
+                .public_key
+                .verify_ecdsa_p256(data.as_ref(), &signature.signature)),
+            _ => Err(Error::SemanticError("wrong sig type".to_string())),
+        },
+        Basic::MLDSA44 | Basic::MLDSA44NT => match signature.derivation {
+            SelfSigning::MLDSA44 => key.public_key.verify_ml_dsa(
+                SelfSigning::MLDSA44,
+                data.as_ref(),
+                &signature.signature,
+            ),
+            _ => Err(Error::SemanticError("wrong sig type".to_string())),
+        },
+        Basic::MLDSA65 | Basic::MLDSA65NT => match signature.derivation {
+            SelfSigning::MLDSA65 => key.public_key.verify_ml_dsa(
+                SelfSigning::MLDSA65,
+                data.as_ref(),
+                &signature.signature,
+            ),
+            _ => Err(Error::SemanticError("wrong sig type".to_string())),
+        },
+        Basic::MLDSA87 | Basic::MLDSA87NT => match signature.derivation {
+            SelfSigning::MLDSA87 => key.public_key.verify_ml_dsa(
+                SelfSigning::MLDSA87,
+                data.as_ref(),
+                &signature.signature,
+            ),
+            _ => Err(Error::SemanticError("wrong sig type".to_string())),
+        },
         _ => Err(Error::SemanticError("ineligible key type".to_string())),
     }
 }
 
 /// Derive
 ///
+/// Splits the primitive with derivation code `code` off the start of `s`.
+pub(crate) fn split_at_code_len(s: &str, code: impl DerivationCode) -> Result<(&str, &str), Error> {
+    split_at_len(s, code.prefix_b64_len())
+}
+
 /// Derives either transferable or non-transferable identifier prefixes based on
 /// the given random Seed Prefix.
 pub fn derive(seed: &SeedPrefix, transferable: bool) -> Result<BasicPrefix, Error> {
     let (pk, _) = seed.derive_key_pair()?;
-    Ok(BasicPrefix::new(
+    Ok(BasicPrefix::new_unchecked(
         match seed {
             SeedPrefix::RandomSeed256Ed25519(_) if transferable => Basic::Ed25519,
             SeedPrefix::RandomSeed256Ed25519(_) if !transferable => Basic::Ed25519NT,
             SeedPrefix::RandomSeed256ECDSAsecp256k1(_) if transferable => Basic::ECDSAsecp256k1,
             SeedPrefix::RandomSeed256ECDSAsecp256k1(_) if !transferable => Basic::ECDSAsecp256k1NT,
+            SeedPrefix::RandomSeed256ECDSAsecp256r1(_) if transferable => Basic::ECDSAsecp256r1,
+            SeedPrefix::RandomSeed256ECDSAsecp256r1(_) if !transferable => Basic::ECDSAsecp256r1NT,
             _ => return Err(Error::ImproperPrefixType),
         },
         pk,
@@ -175,13 +403,9 @@ pub fn derive(seed: &SeedPrefix, transferable: bool) -> Result<BasicPrefix, Erro
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{
-        derivation::self_addressing::SelfAddressing,
-        keys::{PrivateKey, PublicKey},
-    };
+    use crate::keys::{PrivateKey, PublicKey};
     use ed25519_dalek::Keypair;
     use rand::rngs::OsRng;
-    use std::borrow::Borrow;
 
     #[test]
     fn simple_deserialize() -> Result<(), Error> {
@@ -203,36 +427,81 @@ mod tests {
         assert!(IdentifierPrefix::from_str("CBBBBBAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA").is_ok());
 
         // too short
-        assert!(!IdentifierPrefix::from_str("BAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA").is_ok());
+        assert!(matches!(
+            IdentifierPrefix::from_str("BAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA").unwrap_err(),
+            Error::WrongLength {
+                expected: 44,
+                got: 43
+            }
+        ));
 
         // too long
         assert!(
-            !IdentifierPrefix::from_str("BAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA").is_ok()
+            IdentifierPrefix::from_str("BAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA").is_err()
         );
 
         // not a real prefix
-        assert!(
-            match IdentifierPrefix::from_str("ZAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA")
-                .unwrap_err()
-            {
-                Error::DeserializeError(msg) => msg.contains("Unknown master code"),
-                _ => false,
-            }
-        );
+        assert!(matches!(
+            IdentifierPrefix::from_str("ZAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA").unwrap_err(),
+            Error::UnknownCode { code } if code.starts_with('Z')
+        ));
 
         // not base 64 URL
-        assert!(
-            match IdentifierPrefix::from_str("BAAAAAAAAAAAAAAAAAAA/AAAAAAAAAAAAAAAAAAAAAAA")
-                .unwrap_err()
-            {
-                Error::Base64DecodingError { source: _ } => true,
-                _ => false,
+        assert!(matches!(
+            IdentifierPrefix::from_str("BAAAAAAAAAAAAAAAAAAA/AAAAAAAAAAAAAAAAAAAAAAA").unwrap_err(),
+            Error::StandardBase64 {
+                offset: 19,
+                character: '/'
             }
-        );
+        ));
+        assert!(matches!(
+            IdentifierPrefix::from_str("BAAAAAAAAAAAAAAAAAAA.AAAAAAAAAAAAAAAAAAAAAAA").unwrap_err(),
+            Error::InvalidBase64 { offset: 19 }
+        ));
 
         Ok(())
     }
 
+    #[test]
+    fn stream() -> Result<(), Error> {
+        let basic = ["D".to_string(), "A".repeat(43)].join("");
+        let digest = ["E".to_string(), "A".repeat(43)].join("");
+        let stream = [basic.as_str(), &digest, "-AAB"].join("");
+        let (prefix, rest) = IdentifierPrefix::from_stream(&stream)?;
+        assert_eq!(prefix, IdentifierPrefix::from_str(&basic)?);
+        let (prefix, rest) = IdentifierPrefix::from_stream(rest)?;
+        assert!(matches!(prefix, IdentifierPrefix::SelfAddressing(_)));
+        assert_eq!(rest, "-AAB");
+
+        // truncation is told apart from corruption
+        assert!(matches!(
+            IdentifierPrefix::from_stream(&digest[..40]),
+            Err(Error::ShortageError { needed: 4 })
+        ));
+        assert!(matches!(
+            IdentifierPrefix::from_stream("-AAB"),
+            Err(Error::UnknownCode { .. })
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn malformed_input() {
+        // inputs which used to panic while fuzzing the parsers
+        let long = ["B".to_string(), "A".repeat(42), "é".into()].join("");
+        for s in ["", "0", "1", "1A", "1é", "0é", "é", &long] {
+            assert!(IdentifierPrefix::from_str(s).is_err(), "{}", s);
+            assert!(IdentifierPrefix::from_stream(s).is_err(), "{}", s);
+            assert!(SelfSigningPrefix::from_str(s).is_err(), "{}", s);
+            assert!(SeedPrefix::from_str(s).is_err(), "{}", s);
+            assert!(CipherPrefix::from_str(s).is_err(), "{}", s);
+        }
+        assert!(AttachedSignaturePrefix::from_str("0Aé").is_err());
+        assert!(Tagger::from_str("0Jé").is_err());
+        assert!(VariablePrefix::from_str("5BAA").is_err());
+        assert!(crate::attachments::Attachments::from_str("-AABé").is_err());
+    }
+
     #[test]
     fn simple_serialize() -> Result<(), Error> {
         let pref = Basic::Ed25519NT.derive(PublicKey::new(
@@ -259,13 +528,13 @@ mod tests {
 
         let key_prefix = Basic::Ed25519NT.derive(pub_key);
 
-        let sig = priv_key.sign_ed(&data_string.as_bytes())?;
+        let sig = priv_key.sign_ed(data_string.as_bytes())?;
         let sig_prefix = SelfSigningPrefix {
             derivation: SelfSigning::Ed25519Sha512,
             signature: sig,
         };
 
-        let check = key_prefix.verify(&data_string.as_bytes(), &sig_prefix);
+        let check = key_prefix.verify(data_string.as_bytes(), &sig_prefix);
         assert!(check.is_ok());
         assert!(check.unwrap());
 
@@ -277,8 +546,8 @@ mod tests {
         /// Helper function that checks whether all codes fulfill the condition
         /// given by predicate `pred`.
         fn all_codes<F>(codes: Vec<(&str, usize)>, pred: F) -> Result<(), Error>
-            where
-                F: Fn(IdentifierPrefix) -> bool,
+        where
+            F: Fn(IdentifierPrefix) -> bool,
         {
             for (code, length) in codes {
                 let pref: IdentifierPrefix =
@@ -292,12 +561,9 @@ mod tests {
         // All codes that are mapped to `BasicPrefix`.
         let basic_codes = vec!["B", "C", "D", "L", "1AAA", "1AAB", "1AAC", "1AAD"].into_iter();
         // Allowed string lengths for respective basic codes.
-        let allowed_lengths = vec![43, 43, 43, 75, 47, 47, 76, 76].into_iter();
+        let allowed_lengths = vec![43, 43, 43, 75, 44, 44, 76, 76].into_iter();
         let is_basic = |identifier| matches!(&identifier, IdentifierPrefix::Basic(_));
-        all_codes(
-            basic_codes.zip(allowed_lengths).collect(),
-            is_basic,
-        )?;
+        all_codes(basic_codes.zip(allowed_lengths).collect(), is_basic)?;
 
         // All codes that are mapped to `SelfAddressingPrefix`.
         let self_adressing_codes =
@@ -324,6 +590,58 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn qb2_round_trip() -> Result<(), Error> {
+        let pref: IdentifierPrefix = ["B".to_string(), "A".repeat(43)].join("").parse()?;
+        let qb2 = pref.to_qb2()?;
+        assert_eq!(qb2.len(), Basic::Ed25519NT.prefix_b2_len());
+        // code `B` is 0b000001 followed by the key bits
+        assert_eq!(qb2[0], 0b0000_0100);
+        assert!(qb2[1..].iter().all(|b| *b == 0));
+        assert_eq!(IdentifierPrefix::from_qb2(&qb2)?, pref);
+
+        let digest = SelfAddressing::Blake3_512.derive(b"abcdefghijklmnopqrstuvwxyz0123456789");
+        let qb2 = digest.to_qb2()?;
+        assert_eq!(qb2.len(), 66);
+        assert_eq!(SelfAddressingPrefix::from_qb2(&qb2)?, digest);
+
+        let sig = SelfSigningPrefix::new_unchecked(SelfSigning::Ed448, vec![7; 114]);
+        let qb2 = sig.to_qb2()?;
+        assert_eq!(qb2.len(), SelfSigning::Ed448.prefix_b2_len());
+        assert_eq!(SelfSigningPrefix::from_qb2(&qb2)?, sig);
+
+        let attached = AttachedSignaturePrefix::new(SelfSigning::Ed25519Sha512, vec![1; 64], 3)?;
+        let qb2 = attached.to_qb2()?;
+        assert_eq!(AttachedSignaturePrefix::from_qb2(&qb2)?, attached);
+
+        // not aligned on 24 bit boundary
+        assert!(SelfSigningPrefix::from_qb2(&qb2[1..]).is_err());
+        assert!(
+            SelfSigningPrefix::new_unchecked(SelfSigning::Ed25519Sha512, vec![0; 63])
+                .to_qb2()
+                .is_err()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn qb64b_round_trip() -> Result<(), Error> {
+        let digest = SelfAddressing::Blake3_256.derive(b"abcdefghijklmnopqrstuvwxyz0123456789");
+        let qb64b = digest.to_qb64b();
+        assert_eq!(qb64b, digest.to_str().as_bytes());
+        assert_eq!(SelfAddressingPrefix::from_qb64b(&qb64b)?, digest);
+        assert_eq!(IdentifierPrefix::from_qb64b(&qb64b)?.to_qb64b(), qb64b);
+
+        let mut qb64b = qb64b;
+        qb64b[10] = 0xc3;
+        assert!(matches!(
+            SelfAddressingPrefix::from_qb64b(&qb64b),
+            Err(Error::InvalidBase64 { offset: 10 })
+        ));
+        Ok(())
+    }
+
     #[test]
     fn prefix_serialization() -> Result<(), Error> {
         // The lengths of respective vectors are chosen according to [0, Section 14.2]
@@ -331,7 +649,7 @@ mod tests {
 
         // Test BasicPrefix serialization.
         assert_eq!(
-            BasicPrefix::new(
+            BasicPrefix::new_unchecked(
                 Basic::Ed25519NT,
                 PublicKey::new(
                     ed25519_dalek::PublicKey::from_bytes(&[0; 32])?
@@ -339,11 +657,11 @@ mod tests {
                         .to_vec()
                 ),
             )
-                .to_str(),
+            .to_str(),
             ["B".to_string(), "A".repeat(43)].join("")
         );
         assert_eq!(
-            BasicPrefix::new(
+            BasicPrefix::new_unchecked(
                 Basic::X25519,
                 PublicKey::new(
                     ed25519_dalek::PublicKey::from_bytes(&[0; 32])?
@@ -351,11 +669,11 @@ mod tests {
                         .to_vec()
                 ),
             )
-                .to_str(),
+            .to_str(),
             ["C".to_string(), "A".repeat(43)].join("")
         );
         assert_eq!(
-            BasicPrefix::new(
+            BasicPrefix::new_unchecked(
                 Basic::Ed25519,
                 PublicKey::new(
                     ed25519_dalek::PublicKey::from_bytes(&[0; 32])?
@@ -363,79 +681,82 @@ mod tests {
                         .to_vec()
                 ),
             )
-                .to_str(),
+            .to_str(),
             ["D".to_string(), "A".repeat(43)].join("")
         );
         assert_eq!(
-            BasicPrefix::new(Basic::X448, PublicKey::new([0; 56].to_vec())).to_str(),
+            BasicPrefix::new_unchecked(Basic::X448, PublicKey::new([0; 56].to_vec())).to_str(),
             ["L".to_string(), "A".repeat(75)].join("")
         );
         assert_eq!(
-            BasicPrefix::new(Basic::ECDSAsecp256k1NT, PublicKey::new([0; 33].to_vec())).to_str(),
+            BasicPrefix::new_unchecked(Basic::ECDSAsecp256k1NT, PublicKey::new([0; 33].to_vec()))
+                .to_str(),
             ["1AAA".to_string(), "A".repeat(44)].join("")
         );
         assert_eq!(
-            BasicPrefix::new(Basic::ECDSAsecp256k1, PublicKey::new([0; 33].to_vec())).to_str(),
+            BasicPrefix::new_unchecked(Basic::ECDSAsecp256k1, PublicKey::new([0; 33].to_vec()))
+                .to_str(),
             ["1AAB".to_string(), "A".repeat(44)].join("")
         );
         assert_eq!(
-            BasicPrefix::new(Basic::Ed448NT, PublicKey::new([0; 57].to_vec())).to_str(),
+            BasicPrefix::new_unchecked(Basic::Ed448NT, PublicKey::new([0; 57].to_vec())).to_str(),
             ["1AAC".to_string(), "A".repeat(76)].join("")
         );
         assert_eq!(
-            BasicPrefix::new(Basic::Ed448, PublicKey::new([0; 57].to_vec())).to_str(),
+            BasicPrefix::new_unchecked(Basic::Ed448, PublicKey::new([0; 57].to_vec())).to_str(),
             ["1AAD".to_string(), "A".repeat(76)].join("")
         );
 
         // Test SelfAddressingPrefix serialization.
         assert_eq!(
-            SelfAddressingPrefix::new(SelfAddressing::Blake3_256, vec![0; 32]).to_str(),
+            SelfAddressingPrefix::new_unchecked(SelfAddressing::Blake3_256, vec![0; 32]).to_str(),
             ["E".to_string(), "A".repeat(43)].join("")
         );
         assert_eq!(
-            SelfAddressingPrefix::new(SelfAddressing::Blake2B256(vec!()), vec![0; 32]).to_str(),
+            SelfAddressingPrefix::new_unchecked(SelfAddressing::Blake2B256, vec![0; 32]).to_str(),
             ["F".to_string(), "A".repeat(43)].join("")
         );
         assert_eq!(
-            SelfAddressingPrefix::new(SelfAddressing::Blake2S256(vec!()), vec![0; 32]).to_str(),
+            SelfAddressingPrefix::new_unchecked(SelfAddressing::Blake2S256, vec![0; 32]).to_str(),
             ["G".to_string(), "A".repeat(43)].join("")
         );
         assert_eq!(
-            SelfAddressingPrefix::new(SelfAddressing::SHA3_256, vec![0; 32]).to_str(),
+            SelfAddressingPrefix::new_unchecked(SelfAddressing::SHA3_256, vec![0; 32]).to_str(),
             ["H".to_string(), "A".repeat(43)].join("")
         );
         assert_eq!(
-            SelfAddressingPrefix::new(SelfAddressing::SHA2_256, vec![0; 32]).to_str(),
+            SelfAddressingPrefix::new_unchecked(SelfAddressing::SHA2_256, vec![0; 32]).to_str(),
             ["I".to_string(), "A".repeat(43)].join("")
         );
         assert_eq!(
-            SelfAddressingPrefix::new(SelfAddressing::Blake3_512, vec![0; 64]).to_str(),
+            SelfAddressingPrefix::new_unchecked(SelfAddressing::Blake3_512, vec![0; 64]).to_str(),
             ["0D".to_string(), "A".repeat(86)].join("")
         );
         assert_eq!(
-            SelfAddressingPrefix::new(SelfAddressing::SHA3_512, vec![0; 64]).to_str(),
+            SelfAddressingPrefix::new_unchecked(SelfAddressing::SHA3_512, vec![0; 64]).to_str(),
             ["0E".to_string(), "A".repeat(86)].join("")
         );
         assert_eq!(
-            SelfAddressingPrefix::new(SelfAddressing::Blake2B512, vec![0; 64]).to_str(),
+            SelfAddressingPrefix::new_unchecked(SelfAddressing::Blake2B512, vec![0; 64]).to_str(),
             ["0F".to_string(), "A".repeat(86)].join("")
         );
         assert_eq!(
-            SelfAddressingPrefix::new(SelfAddressing::SHA2_512, vec![0; 64]).to_str(),
+            SelfAddressingPrefix::new_unchecked(SelfAddressing::SHA2_512, vec![0; 64]).to_str(),
             ["0G".to_string(), "A".repeat(86)].join("")
         );
 
         // Test SelfSigningPrefix serialization.
         assert_eq!(
-            SelfSigningPrefix::new(SelfSigning::ECDSAsecp256k1Sha256, vec![0; 64]).to_str(),
+            SelfSigningPrefix::new_unchecked(SelfSigning::ECDSAsecp256k1Sha256, vec![0; 64])
+                .to_str(),
             ["0C".to_string(), "A".repeat(86)].join("")
         );
         assert_eq!(
-            SelfSigningPrefix::new(SelfSigning::Ed25519Sha512, vec![0; 64]).to_str(),
+            SelfSigningPrefix::new_unchecked(SelfSigning::Ed25519Sha512, vec![0; 64]).to_str(),
             ["0B".to_string(), "A".repeat(86)].join("")
         );
         assert_eq!(
-            SelfSigningPrefix::new(SelfSigning::Ed448, vec![0; 114]).to_str(),
+            SelfSigningPrefix::new_unchecked(SelfSigning::Ed448, vec![0; 114]).to_str(),
             ["1AAE".to_string(), "A".repeat(152)].join("")
         );
 
@@ -0,0 +1,147 @@
+use super::{BasicPrefix, Prefix, SelfAddressingPrefix};
+use crate::{derivation::self_addressing::SelfAddressing, threshold::SignatureThreshold};
+
+/// Digests of the next `keys` for the `n` field of establishment events, one per key over its
+/// qb64.
+pub fn next_digests(keys: &[BasicPrefix], code: SelfAddressing) -> Vec<SelfAddressingPrefix> {
+    keys.iter()
+        .map(|key| code.derive(key.to_str().as_bytes()))
+        .collect()
+}
+
+/// Positions in the prior next `digests` of the revealed rotation `keys`. Each digest is
+/// checked under its own code.
+pub fn exposed(keys: &[BasicPrefix], digests: &[SelfAddressingPrefix]) -> Vec<u16> {
+    let keys: Vec<String> = keys.iter().map(Prefix::to_str).collect();
+    digests
+        .iter()
+        .enumerate()
+        .filter(|(_, digest)| keys.iter().any(|key| digest.verify_binding(key.as_bytes())))
+        .map(|(i, _)| i as u16)
+        .collect()
+}
+
+/// Whether the revealed rotation `keys` include enough of the keys committed to by the prior
+/// next `digests` to satisfy the prior next `threshold`.
+pub fn verify_next_keys(
+    keys: &[BasicPrefix],
+    digests: &[SelfAddressingPrefix],
+    threshold: &SignatureThreshold,
+) -> bool {
+    threshold.satisfied(&exposed(keys, digests))
+}
+
+/// The single next key digest of early keripy events, the XOR of the digests of the threshold
+/// limen and of every key.
+#[cfg(feature = "legacy-next-keys")]
+pub fn legacy_next_digest(
+    threshold: &SignatureThreshold,
+    keys: &[BasicPrefix],
+    code: SelfAddressing,
+) -> SelfAddressingPrefix {
+    let mut digest = code.digest(limen(threshold).as_bytes());
+    for key in keys {
+        let key = code.digest(key.to_str().as_bytes());
+        digest.iter_mut().zip(key).for_each(|(d, k)| *d ^= k);
+    }
+    SelfAddressingPrefix::new_unchecked(code, digest)
+}
+
+/// Whether the revealed rotation `keys` and `threshold` are those committed to by the legacy
+/// next key `digest`. Legacy commitments reveal all next keys at once.
+#[cfg(feature = "legacy-next-keys")]
+pub fn verify_legacy_next(
+    threshold: &SignatureThreshold,
+    keys: &[BasicPrefix],
+    digest: &SelfAddressingPrefix,
+) -> bool {
+    legacy_next_digest(threshold, keys, digest.derivation) == *digest
+}
+
+/// Text form of a threshold digested by legacy commitments, `&` between clauses and `,` between
+/// weights.
+#[cfg(feature = "legacy-next-keys")]
+fn limen(threshold: &SignatureThreshold) -> String {
+    match threshold {
+        SignatureThreshold::Simple(threshold) => format!("{:x}", threshold),
+        SignatureThreshold::Weighted(clauses) => clauses
+            .iter()
+            .map(|clause| {
+                clause
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(",")
+            })
+            .collect::<Vec<_>>()
+            .join("&"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{error::Error, keys::Signer, prefix::SeedPrefix};
+
+    fn keys() -> Result<Vec<BasicPrefix>, Error> {
+        [
+            "AK8F6AAiYDpXlWdj2O5F5-6wNCCNJh2A4XOlqwR_HwwH",
+            "AOs8-zNPPh0EhavdrCfCiTk9nGeO8e6VxUCzwdKXJAd0",
+            "AHMBU5PsIJN2U9m7j0SGyvs8YD8fkym2noELzxIrzfdG",
+        ]
+        .iter()
+        .map(|seed| Ok(Signer::from_seed(&seed.parse::<SeedPrefix>()?, true)?.basic_prefix()))
+        .collect()
+    }
+
+    #[test]
+    fn next_keys() -> Result<(), Error> {
+        let keys = keys()?;
+        let digests = next_digests(&keys, SelfAddressing::Blake3_256);
+        assert_eq!(
+            keys[0].to_str(),
+            "DErocgXD2RGSyvn3MObcx59jeOsEQhv2TqHirVkzrp0Q"
+        );
+        assert!(digests[0].verify_binding(keys[0].to_str().as_bytes()));
+
+        let threshold: SignatureThreshold = r#"["1/2","1/2","1/2"]"#.parse()?;
+        assert_eq!(exposed(&keys, &digests), vec![0, 1, 2]);
+        assert_eq!(exposed(&keys[1..], &digests), vec![1, 2]);
+        assert!(verify_next_keys(
+            &[keys[2].clone(), keys[0].clone()],
+            &digests,
+            &threshold
+        ));
+        assert!(!verify_next_keys(&keys[..1], &digests, &threshold));
+        assert!(!verify_next_keys(&keys[..2], &digests[2..], &threshold));
+        Ok(())
+    }
+
+    #[cfg(feature = "legacy-next-keys")]
+    #[test]
+    fn legacy() -> Result<(), Error> {
+        let keys = keys()?;
+        let threshold = SignatureThreshold::Simple(2);
+        let digest = legacy_next_digest(&threshold, &keys[..1], SelfAddressing::Blake3_256);
+        let expected: Vec<u8> = SelfAddressing::Blake3_256
+            .digest(b"2")
+            .iter()
+            .zip(SelfAddressing::Blake3_256.digest(keys[0].to_str().as_bytes()))
+            .map(|(l, k)| l ^ k)
+            .collect();
+        assert_eq!(digest.digest, expected);
+
+        let digest = legacy_next_digest(&threshold, &keys, SelfAddressing::Blake3_256);
+        assert!(verify_legacy_next(&threshold, &keys, &digest));
+        assert!(!verify_legacy_next(&threshold, &keys[..2], &digest));
+        assert!(!verify_legacy_next(
+            &SignatureThreshold::Simple(3),
+            &keys,
+            &digest
+        ));
+
+        let weighted: SignatureThreshold = r#"[["1/2","1/2"],["1"]]"#.parse()?;
+        assert_eq!(limen(&weighted), "1/2,1/2&1");
+        Ok(())
+    }
+}
@@ -0,0 +1,281 @@
+use super::{split_at_code_len, Prefix};
+use crate::{
+    codex,
+    derivation::{number::NumberCode, DerivationCode},
+    error::Error,
+};
+use core::str::FromStr;
+
+/// An unsigned integer primitive under one of the fixed size [NumberCode]s.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct Number {
+    code: NumberCode,
+    num: u128,
+}
+
+impl Number {
+    /// `num` under the smallest code it fits in.
+    pub fn new(num: u128) -> Self {
+        Self {
+            code: NumberCode::for_num(num),
+            num,
+        }
+    }
+
+    /// `num` under `code`, which must be wide enough for it.
+    pub fn with_code(code: NumberCode, num: u128) -> Result<Self, Error> {
+        if code.raw_len() < NumberCode::for_num(num).raw_len() {
+            return Err(Error::SemanticError(format!(
+                "Number {} too big for code {}",
+                num,
+                code.to_str()
+            )));
+        }
+        Ok(Self { code, num })
+    }
+
+    pub fn code(&self) -> NumberCode {
+        self.code
+    }
+
+    pub fn num(&self) -> u128 {
+        self.num
+    }
+
+    /// Parses one primitive from the start of `s`, returning it with the rest of `s`.
+    pub fn from_stream(s: &str) -> Result<(Self, &str), Error> {
+        let code = NumberCode::from_str(s)?;
+        let (prefix, rest) = split_at_code_len(s, code)?;
+        let raw = codex::decode(&prefix[code.code_len()..], 0)?;
+        if raw.len() != code.raw_len() {
+            return Err(Error::DeserializeError(format!(
+                "Invalid number: {}",
+                prefix
+            )));
+        }
+        let (high, low) = raw.split_at(raw.len().saturating_sub(16));
+        if high.iter().any(|b| *b != 0) {
+            return Err(Error::SemanticError(format!(
+                "Number too big for u128: {}",
+                prefix
+            )));
+        }
+        let mut bytes = [0u8; 16];
+        bytes[16 - low.len()..].copy_from_slice(low);
+        let num = u128::from_be_bytes(bytes);
+        Ok((Self { code, num }, rest))
+    }
+}
+
+impl From<u64> for Number {
+    fn from(num: u64) -> Self {
+        Self::new(num as u128)
+    }
+}
+
+impl From<u128> for Number {
+    fn from(num: u128) -> Self {
+        Self::new(num)
+    }
+}
+
+impl TryFrom<Number> for u64 {
+    type Error = Error;
+
+    fn try_from(number: Number) -> Result<Self, Self::Error> {
+        u64::try_from(number.num)
+            .map_err(|_| Error::SemanticError(format!("Number too big for u64: {}", number.num)))
+    }
+}
+
+/// Parses a single primitive, `s` must hold nothing else.
+impl FromStr for Number {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match Self::from_stream(s)? {
+            (number, "") => Ok(number),
+            (_, rest) => Err(Error::WrongLength {
+                expected: s.len() - rest.len(),
+                got: s.len(),
+            }),
+        }
+    }
+}
+
+impl Prefix for Number {
+    fn derivative(&self) -> Vec<u8> {
+        let bytes = self.num.to_be_bytes();
+        let len = self.code.raw_len();
+        match len.checked_sub(bytes.len()) {
+            Some(pad) => [vec![0; pad], bytes.to_vec()].concat(),
+            None => bytes[bytes.len() - len..].to_vec(),
+        }
+    }
+
+    fn derivation_code(&self) -> String {
+        self.code.to_str()
+    }
+
+    /// Numbers are the same whatever the width of their code, leading zeros are not compared.
+    fn same_material<P: Prefix>(&self, other: &P) -> bool {
+        significant(&self.derivative()) == significant(&other.derivative())
+    }
+
+    fn normalized(&self) -> Self {
+        Self::new(self.num)
+    }
+}
+
+fn significant(raw: &[u8]) -> &[u8] {
+    let zeros = raw.iter().take_while(|byte| **byte == 0).count();
+    &raw[zeros..]
+}
+
+/// A sequence number or ordinal, always under the 128 bit `0A` code as KERI attaches them.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct Seqner {
+    sn: u128,
+}
+
+impl Seqner {
+    pub fn new(sn: u128) -> Self {
+        Self { sn }
+    }
+
+    pub fn sn(&self) -> u128 {
+        self.sn
+    }
+
+    /// Parses one primitive from the start of `s`, returning it with the rest of `s`.
+    pub fn from_stream(s: &str) -> Result<(Self, &str), Error> {
+        let (number, rest) = Number::from_stream(s)?;
+        Ok((Self::try_from(number)?, rest))
+    }
+}
+
+impl From<u64> for Seqner {
+    fn from(sn: u64) -> Self {
+        Self::new(sn as u128)
+    }
+}
+
+impl TryFrom<Number> for Seqner {
+    type Error = Error;
+
+    fn try_from(number: Number) -> Result<Self, Self::Error> {
+        match number.code {
+            NumberCode::Huge => Ok(Self::new(number.num)),
+            code => Err(Error::SemanticError(format!(
+                "Sequence number must have code 0A, not {}",
+                code.to_str()
+            ))),
+        }
+    }
+}
+
+impl From<Seqner> for Number {
+    fn from(seqner: Seqner) -> Self {
+        Self {
+            code: NumberCode::Huge,
+            num: seqner.sn,
+        }
+    }
+}
+
+impl TryFrom<Seqner> for u64 {
+    type Error = Error;
+
+    fn try_from(seqner: Seqner) -> Result<Self, Self::Error> {
+        u64::try_from(Number::from(seqner))
+    }
+}
+
+impl FromStr for Seqner {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::try_from(Number::from_str(s)?)
+    }
+}
+
+impl Prefix for Seqner {
+    fn derivative(&self) -> Vec<u8> {
+        self.sn.to_be_bytes().to_vec()
+    }
+
+    fn derivation_code(&self) -> String {
+        NumberCode::Huge.to_str()
+    }
+
+    fn same_material<P: Prefix>(&self, other: &P) -> bool {
+        Number::from(*self).same_material(other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn number() -> Result<(), Error> {
+        for (num, qb64) in [
+            (0, "MAAA"),
+            (5, "MAAF"),
+            (0xffff, "MP__"),
+            (0x1_0000, "0HAAAQAA"),
+            (u64::MAX as u128, "NP__________"),
+        ] {
+            let number = Number::new(num);
+            assert_eq!(number.to_str(), qb64);
+            assert_eq!(Number::from_str(qb64)?, number);
+            assert_eq!(Number::from_qb2(&number.to_qb2()?)?, number);
+        }
+        assert_eq!(u64::try_from(Number::from(u64::MAX))?, u64::MAX);
+        assert!(u64::try_from(Number::new(u64::MAX as u128 + 1)).is_err());
+
+        for code in NumberCode::ALL {
+            let number = Number::with_code(code, 0xffff)?;
+            assert_eq!(number.to_str().len(), code.prefix_b64_len());
+            assert_eq!(Number::from_str(&number.to_str())?, number);
+        }
+        let vast = Number::with_code(NumberCode::Vast, u128::MAX)?;
+        assert_eq!(Number::from_str(&vast.to_str())?.num(), u128::MAX);
+        assert!(Number::with_code(NumberCode::Short, 0x1_0000).is_err());
+
+        // the same number under codes of different widths
+        let wide = Number::with_code(NumberCode::Long, 5)?;
+        assert_ne!(wide, Number::new(5));
+        assert_eq!(wide.normalized(), Number::new(5));
+        assert!(wide.same_material(&Number::new(5)));
+        assert!(Seqner::new(5).same_material(&wide));
+        assert!(!wide.same_material(&Number::new(6)));
+
+        // vast numbers beyond u128
+        assert!(Number::from_str(&["UP".to_string(), "_".repeat(22)].join("")).is_err());
+        assert!(Number::from_str("MAA").is_err());
+        assert!(Number::from_str("MAAAM").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn seqner() -> Result<(), Error> {
+        assert_eq!(Seqner::from(0).to_str(), "0AAAAAAAAAAAAAAAAAAAAAAA");
+        assert_eq!(Seqner::from(5).to_str(), "0AAAAAAAAAAAAAAAAAAAAAAF");
+
+        let seqner = Seqner::new(u128::MAX);
+        assert_eq!(Seqner::from_str(&seqner.to_str())?, seqner);
+        assert_eq!(Seqner::from_qb2(&seqner.to_qb2()?)?, seqner);
+        assert!(u64::try_from(seqner).is_err());
+        assert_eq!(u64::try_from(Seqner::from(u64::MAX))?, u64::MAX);
+
+        let (seqner, rest) = Seqner::from_stream("0AAAAAAAAAAAAAAAAAAAAAAF-AAB")?;
+        assert_eq!(seqner.sn(), 5);
+        assert_eq!(rest, "-AAB");
+
+        // numbers under other codes
+        assert!(Seqner::from_str("MAAF").is_err());
+        assert!(Seqner::from_str("0A").is_err());
+        Ok(())
+    }
+}
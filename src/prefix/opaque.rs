@@ -0,0 +1,146 @@
+use super::Prefix;
+use crate::{
+    codex::{self, Sizage},
+    derivation::{b64_to_u64, lookup, split_at_len},
+    error::Error,
+};
+use core::str::FromStr;
+
+/// Material under a code whose size is known but whose meaning is not, kept as it is so that a
+/// relay can pass it on unchanged: a code of the master code table with no type of its own, or a
+/// variable size code missing from the table, sized by its selector.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct OpaqueMatter {
+    /// The whole code, hard and soft part.
+    pub code: String,
+    pub raw: Vec<u8>,
+}
+
+impl OpaqueMatter {
+    /// Parses one primitive from the start of `s`, returning it with the rest of `s`.
+    pub fn from_stream(s: &str) -> Result<(Self, &str), Error> {
+        let (sizage, len) = sized(s)?;
+        let (qb64, rest) = split_at_len(s, len)?;
+        let (code, material) = split_at_len(qb64, sizage.cs())?;
+        let raw = codex::decode(material, sizage.ls)?;
+        Ok((
+            Self {
+                code: code.into(),
+                raw,
+            },
+            rest,
+        ))
+    }
+}
+
+/// Length of the primitive of the master code table, or of unknown variable size, at the start
+/// of `s`. Only its code has to be in `s`.
+pub(crate) fn qb64_len(s: &str) -> Result<usize, Error> {
+    sized(s).map(|(_, len)| len)
+}
+
+/// Sizes of the code at the start of `s`, with the length of its primitive.
+fn sized(s: &str) -> Result<(Sizage, usize), Error> {
+    let sizage = sizage(s)?;
+    let len = match sizage.fs {
+        Some(fs) => fs,
+        None => {
+            let (code, _) = split_at_len(s, sizage.cs())?;
+            let (_, soft) = split_at_len(code, sizage.hs)?;
+            sizage.cs() + b64_to_u64(soft)? as usize * 4
+        }
+    };
+    Ok((sizage, len))
+}
+
+/// Sizes of the code at the start of `s`, from the master code table or else from the selector
+/// of variable size codes: `4` to `6` start small codes with a 2 character hard and soft part,
+/// `7` to `9` big ones with 4 and 4, each with 0 to 2 lead bytes.
+fn sizage(s: &str) -> Result<Sizage, Error> {
+    let unknown = match lookup(codex::MATTER, s) {
+        Ok(&(_, sizage)) => return Ok(sizage),
+        Err(Error::UnknownCode { code }) => Error::UnknownCode { code },
+        Err(e) => return Err(e),
+    };
+    match s.as_bytes().first() {
+        Some(selector @ b'4'..=b'6') => Ok(variable(2, (selector - b'4') as usize)),
+        Some(selector @ b'7'..=b'9') => Ok(variable(4, (selector - b'7') as usize)),
+        _ => Err(unknown),
+    }
+}
+
+fn variable(size: usize, ls: usize) -> Sizage {
+    Sizage {
+        hs: size,
+        ss: size,
+        fs: None,
+        ls,
+    }
+}
+
+/// Parses a single primitive, `s` must hold nothing else.
+impl FromStr for OpaqueMatter {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match Self::from_stream(s)? {
+            (opaque, "") => Ok(opaque),
+            (_, rest) => Err(Error::WrongLength {
+                expected: s.len() - rest.len(),
+                got: s.len(),
+            }),
+        }
+    }
+}
+
+impl Prefix for OpaqueMatter {
+    fn derivative(&self) -> Vec<u8> {
+        self.raw.clone()
+    }
+
+    fn derivation_code(&self) -> String {
+        self.code.clone()
+    }
+
+    fn lead_size(&self) -> usize {
+        sizage(&self.code).map_or(0, |sizage| sizage.ls)
+    }
+
+    /// Like variable size primitives, empty material is still prefixed.
+    fn to_str(&self) -> String {
+        codex::encode(&self.code, self.lead_size(), &self.raw)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn passthrough() -> Result<(), Error> {
+        // a big variable size code of 1 quadlet with 1 lead byte, and a small one of 0 quadlets
+        for qb64 in [
+            "8ZZZAAABAAEC",
+            "4CAA",
+            "DKxy2sgzfplyr-tgwIxS19f2OchFHtLwPWD3v4oYimBx",
+        ] {
+            let stream = [qb64, "MAAB"].join("");
+            let (opaque, rest) = OpaqueMatter::from_stream(&stream)?;
+            assert_eq!((opaque.to_str().as_str(), rest), (qb64, "MAAB"));
+        }
+        let opaque = OpaqueMatter::from_str("8ZZZAAABAAEC")?;
+        assert_eq!((opaque.code.as_str(), opaque.raw), ("8ZZZAAAB", vec![1, 2]));
+
+        assert!(matches!(
+            OpaqueMatter::from_str("VAAA"),
+            Err(Error::UnknownCode { .. })
+        ));
+        assert!(matches!(
+            OpaqueMatter::from_str("4CAB"),
+            Err(Error::ShortageError { .. })
+        ));
+        assert!(OpaqueMatter::from_str("5CABAQID").is_err());
+        assert!(OpaqueMatter::from_str("4éAA").is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,178 @@
+use super::{
+    BasicPrefix, CipherPrefix, Dater, Number, OpaqueMatter, Prefix, SeedPrefix,
+    SelfAddressingPrefix, SelfSigningPrefix, Tagger, VariablePrefix,
+};
+use crate::{
+    codex,
+    derivation::{
+        basic::Basic, counter::Counter, genus::GenusVersion, lookup,
+        self_addressing::SelfAddressing, self_signing::SelfSigning, split_at_len, tag::TagCode,
+        DerivationCode,
+    },
+    error::Error,
+    parser::ParseOptions,
+};
+use core::str::FromStr;
+
+/// Any primitive of the master code table, or a counter of the count code table, told apart by
+/// its code alone.
+///
+/// Codes are ambiguous in two places, where the expected type has to come from the context:
+/// `0A` is both a 128 bit salt and a huge number and decodes as a salt, and indexed signatures
+/// share their codes with the master code table so they never decode as such.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Primitive {
+    Key(BasicPrefix),
+    Digest(SelfAddressingPrefix),
+    Signature(SelfSigningPrefix),
+    Seed(SeedPrefix),
+    Cipher(CipherPrefix),
+    Number(Number),
+    Datetime(Dater),
+    Tag(Tagger),
+    Variable(VariablePrefix),
+    Counter(Counter),
+    /// Material of a known size but unknown meaning, only parsed when unknown codes are allowed.
+    Opaque(OpaqueMatter),
+}
+
+impl Primitive {
+    /// Parses one primitive from the start of `s`, returning it with the rest of `s`.
+    pub fn from_stream(s: &str) -> Result<(Self, &str), Error> {
+        Self::from_stream_with(s, &ParseOptions::default())
+    }
+
+    /// Parses one primitive like [Primitive::from_stream], but with
+    /// [ParseOptions::allow_unknown_codes] a primitive whose code is sized but has no type, see
+    /// [OpaqueMatter], is kept opaque instead of failing.
+    pub fn from_stream_with<'a>(
+        s: &'a str,
+        options: &ParseOptions,
+    ) -> Result<(Self, &'a str), Error> {
+        match Self::parse(s) {
+            Err(Error::UnknownCode { .. })
+                if options.allow_unknown_codes && !s.starts_with('-') =>
+            {
+                let (opaque, rest) = OpaqueMatter::from_stream(s)?;
+                Ok((Self::Opaque(opaque), rest))
+            }
+            parsed => parsed,
+        }
+    }
+
+    fn parse(s: &str) -> Result<(Self, &str), Error> {
+        if s.starts_with('-') {
+            let (counter, rest) = Counter::from_stream_in(s, &GenusVersion::default())?;
+            return Ok((Self::Counter(counter), rest));
+        }
+        let &(code, sizage) = lookup(codex::MATTER, s)?;
+        let Some(fs) = sizage.fs else {
+            let (variable, rest) = VariablePrefix::from_stream(s)?;
+            return Ok((Self::Variable(variable), rest));
+        };
+        let (qb64, rest) = split_at_len(s, fs)?;
+        let primitive = match code {
+            "A" | "J" | "K" | "O" | "Q" | "a" | "0A" => Self::Seed(qb64.parse()?),
+            "P" | "1AAH" => Self::Cipher(qb64.parse()?),
+            "M" | "N" | "R" | "S" | "T" | "U" | "0H" => Self::Number(qb64.parse()?),
+            "1AAG" => Self::Datetime(qb64.parse()?),
+            _ if TagCode::from_str(code).is_ok() => Self::Tag(qb64.parse()?),
+            _ if Basic::from_str(code).is_ok() => Self::Key(qb64.parse()?),
+            _ if SelfAddressing::from_str(code).is_ok() => Self::Digest(qb64.parse()?),
+            _ if SelfSigning::from_str(code).is_ok() => Self::Signature(qb64.parse()?),
+            _ => return Err(Error::UnknownCode { code: code.into() }),
+        };
+        Ok((primitive, rest))
+    }
+
+    /// Text domain (qb64) representation of the primitive.
+    pub fn to_str(&self) -> String {
+        match self {
+            Self::Key(key) => key.to_str(),
+            Self::Digest(digest) => digest.to_str(),
+            Self::Signature(signature) => signature.to_str(),
+            Self::Seed(seed) => seed.to_str(),
+            Self::Cipher(cipher) => cipher.to_str(),
+            Self::Number(number) => number.to_str(),
+            Self::Datetime(dater) => dater.to_str(),
+            Self::Tag(tagger) => tagger.to_str(),
+            Self::Variable(variable) => variable.to_str(),
+            Self::Counter(counter) => counter.to_str(),
+            Self::Opaque(opaque) => opaque.to_str(),
+        }
+    }
+}
+
+/// Parses a single primitive, `s` must hold nothing else.
+impl FromStr for Primitive {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match Self::from_stream(s)? {
+            (primitive, "") => Ok(primitive),
+            (_, rest) => Err(Error::WrongLength {
+                expected: s.len() - rest.len(),
+                got: s.len(),
+            }),
+        }
+    }
+}
+
+/// Decodes any primitive from its text domain (qb64) representation, for tools that inspect
+/// arbitrary CESR text instead of expecting a given type.
+pub fn decode_any(qb64: &str) -> Result<Primitive, Error> {
+    Primitive::from_str(qb64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::derivation::counter::CounterCode;
+
+    #[test]
+    fn decode() -> Result<(), Error> {
+        let digest = SelfAddressing::Blake3_256.derive(b"event");
+        let key = "DKxy2sgzfplyr-tgwIxS19f2OchFHtLwPWD3v4oYimBx";
+        let signature = "0BDh4sTjQxkw0apltmbtQGZSoB7iW4UlWsp1ZRNamgIFcaJY2JQ_5gDvd0zTHN6wW6wYzKxq4vQq7yg9_Ygzz1YG";
+        for qb64 in [
+            key,
+            signature,
+            "MAAB",
+            "1AAG2020-08-22T17c50c09d988921p00c00",
+            "Xicp",
+            "4BABYWJj",
+            "-AAB",
+            "ABm80B4M6xDDdq0u9b0Lnj7SnhE7UVwwjcvq4ikr73Mu",
+            &digest.to_str(),
+        ] {
+            assert_eq!(decode_any(qb64)?.to_str(), qb64);
+        }
+        assert_eq!(decode_any(&digest.to_str())?, Primitive::Digest(digest));
+        assert!(matches!(decode_any(key)?, Primitive::Key(_)));
+        assert!(matches!(decode_any(signature)?, Primitive::Signature(_)));
+        assert!(matches!(decode_any("MAAB")?, Primitive::Number(_)));
+        assert!(matches!(decode_any("Xicp")?, Primitive::Tag(_)));
+        assert_eq!(
+            decode_any("-AAB")?,
+            Primitive::Counter(Counter::new(CounterCode::ControllerIdxSigs, 1)?)
+        );
+
+        let (primitive, rest) = Primitive::from_stream("MAAB-AAB")?;
+        assert!(matches!(primitive, Primitive::Number(_)));
+        assert_eq!(rest, "-AAB");
+
+        assert!(decode_any("MAAB-AAB").is_err());
+        assert!(decode_any("MAA").is_err());
+        assert!(decode_any("VAAA").is_err());
+        assert!(decode_any("").is_err());
+
+        // unknown variable size codes pass through when allowed
+        let lenient = ParseOptions::lenient();
+        assert!(decode_any("4CABAAEC").is_err());
+        let (primitive, rest) = Primitive::from_stream_with("4CABAAEC-AAB", &lenient)?;
+        assert!(matches!(&primitive, Primitive::Opaque(opaque) if opaque.raw == [0, 1, 2]));
+        assert_eq!((primitive.to_str().as_str(), rest), ("4CABAAEC", "-AAB"));
+        assert!(Primitive::from_stream_with("VAAA", &lenient).is_err());
+        Ok(())
+    }
+}
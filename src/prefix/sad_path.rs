@@ -0,0 +1,166 @@
+use super::{Prefix, VariablePrefix};
+use crate::error::Error;
+use core::str::FromStr;
+use serde_json::Value;
+
+/// A path into a self-addressing data structure (SAD), e.g. `-a-i` for the `i` field of the `a`
+/// block of an ACDC, carried as a variable size Base64 string primitive.<br>
+/// Segments are field labels or indices, an index into a map selects its field in insertion
+/// order. The empty path `-` is the root.
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Default)]
+pub struct SadPath {
+    segments: Vec<String>,
+}
+
+impl SadPath {
+    pub fn new<S: Into<String>>(segments: impl IntoIterator<Item = S>) -> Result<Self, Error> {
+        let segments: Vec<String> = segments.into_iter().map(Into::into).collect();
+        if let Some(segment) = segments.iter().find(|segment| {
+            segment.is_empty()
+                || !segment
+                    .bytes()
+                    .all(|c| c.is_ascii_alphanumeric() || c == b'_')
+        }) {
+            return Err(Error::SemanticError(format!(
+                "Invalid path segment: {:?}",
+                segment
+            )));
+        }
+        Ok(Self { segments })
+    }
+
+    /// Parses a path in the text form, e.g. `-a-i`.
+    pub fn from_path(path: &str) -> Result<Self, Error> {
+        match path.strip_prefix('-') {
+            Some("") => Ok(Self::default()),
+            Some(segments) => Self::new(segments.split('-')),
+            None => Err(Error::SemanticError(format!(
+                "Paths must start with -: {}",
+                path
+            ))),
+        }
+    }
+
+    pub fn segments(&self) -> &[String] {
+        &self.segments
+    }
+
+    /// The path in the text form, e.g. `-a-i`.
+    pub fn to_path(&self) -> String {
+        match self.segments.is_empty() {
+            true => "-".into(),
+            false => self
+                .segments
+                .iter()
+                .map(|segment| ["-", segment].join(""))
+                .collect(),
+        }
+    }
+
+    /// The value at the path in `sad`, `None` if there is none.
+    pub fn resolve<'a>(&self, sad: &'a Value) -> Option<&'a Value> {
+        self.segments
+            .iter()
+            .try_fold(sad, |value, segment| match value {
+                Value::Object(map) => map.get(segment).or_else(|| {
+                    segment
+                        .parse::<usize>()
+                        .ok()
+                        .and_then(|i| map.values().nth(i))
+                }),
+                Value::Array(values) => values.get(segment.parse::<usize>().ok()?),
+                _ => None,
+            })
+    }
+
+    /// Parses one primitive from the start of `s`, returning it with the rest of `s`.
+    pub fn from_stream(s: &str) -> Result<(Self, &str), Error> {
+        let (prefix, rest) = VariablePrefix::from_stream(s)?;
+        let path = prefix.to_text().ok_or_else(|| {
+            Error::DeserializeError(format!("Paths must be Base64 strings: {}", s))
+        })?;
+        Ok((Self::from_path(&path)?, rest))
+    }
+
+    fn to_variable(&self) -> VariablePrefix {
+        VariablePrefix::text(&self.to_path()).expect("segments validated on creation")
+    }
+}
+
+/// Parses a single primitive, `s` must hold nothing else.
+impl FromStr for SadPath {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match Self::from_stream(s)? {
+            (path, "") => Ok(path),
+            (_, rest) => Err(Error::WrongLength {
+                expected: s.len() - rest.len(),
+                got: s.len(),
+            }),
+        }
+    }
+}
+
+impl Prefix for SadPath {
+    fn derivative(&self) -> Vec<u8> {
+        self.to_variable().derivative()
+    }
+
+    fn derivation_code(&self) -> String {
+        self.to_variable().derivation_code()
+    }
+
+    fn to_str(&self) -> String {
+        self.to_variable().to_str()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn encoding() -> Result<(), Error> {
+        for (segments, path, qb64) in [
+            (vec![], "-", "6AABAAA-"),
+            (vec!["a"], "-a", "5AABAA-a"),
+            (vec!["a", "i"], "-a-i", "4AAB-a-i"),
+            (vec!["a", "0", "d"], "-a-0-d", "5AACAA-a-0-d"),
+        ] {
+            let sad_path = SadPath::new(segments)?;
+            assert_eq!(sad_path.to_path(), path);
+            assert_eq!(SadPath::from_path(path)?, sad_path);
+            assert_eq!(sad_path.to_str(), qb64);
+            assert_eq!(SadPath::from_str(qb64)?, sad_path);
+            assert_eq!(SadPath::from_qb2(&sad_path.to_qb2()?)?, sad_path);
+        }
+
+        assert!(SadPath::from_path("a-i").is_err());
+        assert!(SadPath::from_path("-a--i").is_err());
+        assert!(SadPath::new(["a.b"]).is_err());
+        // opaque bytes are no path
+        assert!(SadPath::from_str("4BABYWJj").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn resolve() -> Result<(), Error> {
+        let sad = json!({
+            "d": "EBdXt3gIXOf2BBWNHdSXCJnFJL5OuQPyM5K0neuniccM",
+            "a": {"i": "EIaGMMWJFPmtXznY1IIiKDIrg-vIyge6mBl2QV8dDjI3", "dt": "2021-06-27"},
+            "e": [{"n": "first"}, {"n": "second"}]
+        });
+        let resolve = |path| SadPath::from_path(path).map(|p| p.resolve(&sad).cloned());
+        assert_eq!(resolve("-")?, Some(sad.clone()));
+        assert_eq!(resolve("-a-dt")?, Some(json!("2021-06-27")));
+        assert_eq!(resolve("-e-1-n")?, Some(json!("second")));
+        // indices into maps select fields in insertion order
+        assert_eq!(resolve("-1-0")?, resolve("-a-i")?);
+        assert_eq!(resolve("-a-x")?, None);
+        assert_eq!(resolve("-e-2")?, None);
+        assert_eq!(resolve("-d-0")?, None);
+        Ok(())
+    }
+}
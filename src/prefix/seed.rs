@@ -1,12 +1,15 @@
 use super::Prefix;
 use crate::{
+    codex,
+    derivation::lookup,
     error::Error,
-    keys::{PrivateKey, PublicKey},
+    keys::{ed448_signing_key, PrivateKey, PublicKey},
 };
-use base64::decode_config;
-use core::str::FromStr;
+use core::{fmt, str::FromStr};
 use ed25519_dalek::SecretKey;
 use k256::ecdsa::{SigningKey, VerifyingKey};
+#[cfg(feature = "zeroize")]
+use zeroize::Zeroize;
 
 // TODO rename this from [SeedPrefix] to KeySeed
 /// A seed in this context is a signing key also known as a private key.
@@ -14,12 +17,17 @@ use k256::ecdsa::{SigningKey, VerifyingKey};
 /// Each of these seed types is found in the [master code table].<br>
 ///
 /// [master code table]: https://weboftrust.github.io/ietf-cesr/draft-ssmith-cesr.html#name-master-code-table
-#[derive(Debug, PartialEq, Clone)]
+#[derive(PartialEq, Clone)]
 pub enum SeedPrefix {
     RandomSeed128(Vec<u8>),
     RandomSeed256Ed25519(Vec<u8>),
     RandomSeed256ECDSAsecp256k1(Vec<u8>),
     RandomSeed448(Vec<u8>),
+    /// X25519 private decryption key
+    RandomSeed256X25519(Vec<u8>),
+    RandomSeed256ECDSAsecp256r1(Vec<u8>),
+    /// 256 bit salt or nonce
+    RandomSeed256(Vec<u8>),
 }
 
 impl SeedPrefix {
@@ -40,6 +48,34 @@ impl SeedPrefix {
                     PrivateKey::new(sk.to_bytes().to_vec()),
                 ))
             }
+            Self::RandomSeed256ECDSAsecp256r1(seed) => {
+                let sk = p256::ecdsa::SigningKey::from_bytes(seed)?;
+                Ok((
+                    PublicKey::new(
+                        sk.verifying_key()
+                            .to_encoded_point(true)
+                            .as_bytes()
+                            .to_vec(),
+                    ),
+                    PrivateKey::new(sk.to_bytes().to_vec()),
+                ))
+            }
+            Self::RandomSeed448(seed) => {
+                let sk = ed448_signing_key(seed)?;
+                Ok((
+                    PublicKey::new(sk.verifying_key().to_bytes().to_vec()),
+                    PrivateKey::new(seed.to_owned()),
+                ))
+            }
+            Self::RandomSeed256X25519(seed) => {
+                let sk = crypto_box::SecretKey::from_slice(seed).map_err(|_| {
+                    Error::SemanticError(format!("Invalid X25519 key length: {}", seed.len()))
+                })?;
+                Ok((
+                    PublicKey::new(sk.public_key().as_bytes().to_vec()),
+                    PrivateKey::new(sk.to_bytes().to_vec()),
+                ))
+            }
             _ => Err(Error::ImproperPrefixType),
         }
     }
@@ -54,40 +90,20 @@ impl FromStr for SeedPrefix {
 
     // TODO KB ? 12/03/22 Is there a problem with base64 decoding strings here?
     //   Is it making more difficult the T>R>B>R>T round trip?
-    // TODO KB ? 12/03/22 Is there a good way to specify the decode_config base64:URL_SAFE
-    //   once in a config module and then reuse that everywhere in the program so it only has to be
-    //   declared once?
     /// The parsing function for seeds supporting Base64 encodings.<br>
-
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match &s[..1] {
-            "A" => Ok(Self::RandomSeed256Ed25519(decode_config(
-                &s[1..],
-                base64::URL_SAFE,
-            )?)),
-            "J" => Ok(Self::RandomSeed256ECDSAsecp256k1(decode_config(
-                &s[1..],
-                base64::URL_SAFE,
-            )?)),
-            "K" => Ok(Self::RandomSeed448(decode_config(
-                &s[1..],
-                base64::URL_SAFE,
-            )?)),
-            "0" => match &s[1..2] {
-                "A" => Ok(Self::RandomSeed128(decode_config(
-                    &s[2..],
-                    base64::URL_SAFE,
-                )?)),
-                _ => Err(Error::DeserializeError(format!(
-                    "Unknown seed prefix code: {}",
-                    s
-                ))),
-            },
-            _ => Err(Error::DeserializeError(format!(
-                "Unknown seed prefix code: {}",
-                s
-            ))),
-        }
+        type Variant = fn(Vec<u8>) -> SeedPrefix;
+        const CODES: &[(&str, Variant)] = &[
+            ("A", SeedPrefix::RandomSeed256Ed25519),
+            ("J", SeedPrefix::RandomSeed256ECDSAsecp256k1),
+            ("K", SeedPrefix::RandomSeed448),
+            ("O", SeedPrefix::RandomSeed256X25519),
+            ("Q", SeedPrefix::RandomSeed256ECDSAsecp256r1),
+            ("a", SeedPrefix::RandomSeed256),
+            ("0A", SeedPrefix::RandomSeed128),
+        ];
+        let (code, seed) = lookup(CODES, s)?;
+        Ok(seed(codex::decode(&s[code.len()..], 0)?))
     }
 }
 
@@ -98,6 +114,9 @@ impl Prefix for SeedPrefix {
             Self::RandomSeed256ECDSAsecp256k1(seed) => seed.to_owned(),
             Self::RandomSeed448(seed) => seed.to_owned(),
             Self::RandomSeed128(seed) => seed.to_owned(),
+            Self::RandomSeed256X25519(seed) => seed.to_owned(),
+            Self::RandomSeed256ECDSAsecp256r1(seed) => seed.to_owned(),
+            Self::RandomSeed256(seed) => seed.to_owned(),
         }
     }
     fn derivation_code(&self) -> String {
@@ -106,28 +125,62 @@ impl Prefix for SeedPrefix {
             Self::RandomSeed256ECDSAsecp256k1(_) => "J".to_string(),
             Self::RandomSeed448(_) => "K".to_string(),
             Self::RandomSeed128(_) => "0A".to_string(),
+            Self::RandomSeed256X25519(_) => "O".to_string(),
+            Self::RandomSeed256ECDSAsecp256r1(_) => "Q".to_string(),
+            Self::RandomSeed256(_) => "a".to_string(),
+        }
+    }
+}
+
+/// Redacted to the code, the seed never ends up in logs.
+impl fmt::Debug for SeedPrefix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SeedPrefix")
+            .field("code", &self.derivation_code())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl Zeroize for SeedPrefix {
+    fn zeroize(&mut self) {
+        match self {
+            Self::RandomSeed128(seed)
+            | Self::RandomSeed256Ed25519(seed)
+            | Self::RandomSeed256ECDSAsecp256k1(seed)
+            | Self::RandomSeed448(seed)
+            | Self::RandomSeed256X25519(seed)
+            | Self::RandomSeed256ECDSAsecp256r1(seed)
+            | Self::RandomSeed256(seed) => seed.zeroize(),
         }
     }
 }
 
+#[cfg(feature = "zeroize")]
+impl Drop for SeedPrefix {
+    fn drop(&mut self) {
+        self.zeroize()
+    }
+}
+
 #[test]
 fn test_derive_keypair() -> Result<(), Error> {
     use base64::URL_SAFE;
 
     // TODO fix the encoding order like in THC/keriox/PR#38: https://github.com/THCLab/keriox/pull/38/files
     // taken from KERIPY: tests/core/test_eventing.py#1512
-    let seeds = vec![
-        "ArwXoACJgOleVZ2PY7kXn7rA0II0mHYDhc6WrBH8fDAc",
-        "A6zz7M08-HQSFq92sJ8KJOT2cZ47x7pXFQLPB0pckB3Q",
-        "AcwFTk-wgk3ZT2buPRIbK-zxgPx-TKbaegQvPEivN90Y",
-        "Alntkt3u6dDgiQxTATr01dy8M72uuaZEf9eTdM-70Gk8",
-        "A1-QxDkso9-MR1A8rZz_Naw6fgaAtayda8hrbkRVVu1E",
-        "AKuYMe09COczwf2nIoD5AE119n7GLFOVFlNLxZcKuswc",
-        "AxFfJTcSuEE11FINfXMqWttkZGnUZ8KaREhrnyAXTsjw",
-        "ALq-w1UKkdrppwZzGTtz4PWYEeWm0-sDHzOv5sq96xJY",
+    let seeds = [
+        "AK8F6AAiYDpXlWdj2O5F5-6wNCCNJh2A4XOlqwR_HwwH",
+        "AOs8-zNPPh0EhavdrCfCiTk9nGeO8e6VxUCzwdKXJAd0",
+        "AHMBU5PsIJN2U9m7j0SGyvs8YD8fkym2noELzxIrzfdG",
+        "AJZ7ZLd7unQ4IkMUwE69NXcvDO9rrmmRH_Xk3TPu9BpP",
+        "ANfkMQ5LKPfjEdQPK2c_zWsOn4GgLWsnWvIa25EVVbtR",
+        "ACrmDHtPQjnM8H9pyKA-QBNdfZ-xixTlRZTS8WXCrrMH",
+        "AMRXyU3ErhBNdRSDX1zKlrbZGRp1GfCmkRIa58gF07I8",
+        "AC6vsNVCpHa6acGcxk7c-D1mBHlptPrAx8zr-bKvesSW",
     ];
 
-    let expected_pubkeys = vec![
+    let expected_pubkeys = [
         "SuhyBcPZEZLK-fcw5tzHn2N46wRCG_ZOoeKtWTOunRA=",
         "VcuJOOJF1IE8svqEtrSuyQjGTd2HhfAkt9y2QkUtFJI=",
         "T1iAhBWCkvChxNWsby2J0pJyxBIxbAtbLA0Ljx-Grh8=",
@@ -1,19 +1,32 @@
-use super::Prefix;
-use crate::derivation::{self_addressing::SelfAddressing, DerivationCode};
+use super::{check_derivative_len, Prefix};
+use crate::codex;
+use crate::derivation::{
+    self_addressing::{DigestPolicy, KeyedSelfAddressing, SelfAddressing},
+    DerivationCode,
+};
 use crate::error::Error;
-use base64::decode_config;
-use core::{fmt, str::FromStr};
+use crate::{said, serder::Serder};
+use core::str::FromStr;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::hash::{Hash, Hasher};
+use subtle::ConstantTimeEq;
 
 // TODO should this be renamed to SelfAddressingIdentifier or SelfAddressingDatatype?
-#[derive(Debug, PartialEq, Clone, Hash)]
+#[derive(Debug, Clone)]
 pub struct SelfAddressingPrefix {
     pub derivation: SelfAddressing,
     pub digest: Vec<u8>,
 }
 
 impl SelfAddressingPrefix {
-    pub fn new(code: SelfAddressing, digest: Vec<u8>) -> Self {
+    /// Fails when the digest is not as long as `code` requires.
+    pub fn try_new(code: SelfAddressing, digest: Vec<u8>) -> Result<Self, Error> {
+        check_derivative_len(&code, &digest)?;
+        Ok(Self::new_unchecked(code, digest))
+    }
+
+    /// Takes the digest as it is, the qb64 of a digest of the wrong length is not valid CESR.
+    pub fn new_unchecked(code: SelfAddressing, digest: Vec<u8>) -> Self {
         Self {
             derivation: code,
             digest,
@@ -21,8 +34,64 @@ impl SelfAddressingPrefix {
     }
 
     /// Checks whether a computed digest of the serialized data matches the original digest.
-    pub fn verify_binding(&self, sed: &[u8]) -> bool {
-        self.derivation.digest(sed) == self.digest
+    /// The digests are compared in constant time. Keyed Blake2 digests never match here, they
+    /// are verified with their key by [SelfAddressingPrefix::verify_keyed].
+    pub fn verify_binding(&self, data: &[u8]) -> bool {
+        bool::from(self.derivation.digest(data).ct_eq(&self.digest))
+    }
+
+    /// Checks whether the Blake2 digest of `data` keyed with `key` matches, in constant time.
+    /// The key is not part of the prefix and must be supplied by the verifier. Fails for codes
+    /// other than the Blake2 ones and for keys too long for them.
+    pub fn verify_keyed(&self, key: &[u8], data: &[u8]) -> Result<bool, Error> {
+        let digest = KeyedSelfAddressing::new(self.derivation)?.digest(key, data)?;
+        Ok(bool::from(digest.ct_eq(&self.digest)))
+    }
+
+    /// Derives the self-addressing prefix of a serialized inception (`icp` or `dip`) event the way
+    /// KERIpy incepts: the digest under `code` of the event with both its `i` and `d` fields
+    /// filled with dummy `#` characters, whatever they held before.
+    pub fn derive_from_icp(code: SelfAddressing, icp: &[u8]) -> Result<Self, Error> {
+        let serder = Serder::from_raw(icp)?;
+        match serder.ilk() {
+            Some("icp" | "dip") => {}
+            ilk => {
+                return Err(Error::SemanticError(format!(
+                    "Expected an inception event, got {:?}",
+                    ilk
+                )))
+            }
+        }
+        said::compute_said(serder.ked(), &["d", "i"], &code, serder.kind())
+    }
+
+    /// Whether the digest is under one of the approved `codes` and binds `data`, for validators
+    /// that accept any of several digest algorithms.
+    pub fn matches_any(&self, data: &[u8], codes: &[SelfAddressing]) -> bool {
+        codes.contains(&self.derivation) && self.verify_binding(data)
+    }
+
+    /// Whether the digest binds `data`, failing when its algorithm is not allowed by `policy`
+    /// whether it binds or not.
+    pub fn verify_with_policy(&self, data: &[u8], policy: &DigestPolicy) -> Result<bool, Error> {
+        policy.check(self.derivation)?;
+        Ok(self.verify_binding(data))
+    }
+}
+
+/// Compares the digests in constant time.
+impl PartialEq for SelfAddressingPrefix {
+    fn eq(&self, other: &Self) -> bool {
+        self.derivation == other.derivation && bool::from(self.digest.ct_eq(&other.digest))
+    }
+}
+
+impl Eq for SelfAddressingPrefix {}
+
+impl Hash for SelfAddressingPrefix {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.derivation.hash(state);
+        self.digest.hash(state);
     }
 }
 
@@ -32,17 +101,13 @@ impl FromStr for SelfAddressingPrefix {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let code = SelfAddressing::from_str(s)?;
         let c_len = code.code_len();
-        let p_len = code.prefix_b64_len();
         if s.len() == code.prefix_b64_len() {
-            Ok(Self::new(
-                code,
-                decode_config(&s[c_len..p_len], base64::URL_SAFE)?,
-            ))
+            Ok(Self::new_unchecked(code, codex::decode(&s[c_len..], 0)?))
         } else {
-            Err(Error::SemanticError(format!(
-                "Incorrect Prefix Length: {}",
-                s
-            )))
+            Err(Error::WrongLength {
+                expected: code.prefix_b64_len(),
+                got: s.len(),
+            })
         }
     }
 }
@@ -56,12 +121,6 @@ impl Prefix for SelfAddressingPrefix {
     }
 }
 
-impl fmt::Display for SelfAddressingPrefix {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.to_str())
-    }
-}
-
 /// Serde compatible Serialize
 impl Serialize for SelfAddressingPrefix {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
@@ -92,3 +151,110 @@ impl Default for SelfAddressingPrefix {
         }
     }
 }
+
+#[test]
+fn constant_time_eq() {
+    let digest = SelfAddressing::Blake3_256.derive(b"data");
+    let other = SelfAddressing::Blake3_256.derive(b"other data");
+    assert!(digest.verify_binding(b"data"));
+    assert!(!digest.verify_binding(b"other data"));
+    assert!(digest.ct_eq(&digest.clone()));
+    assert!(!digest.ct_eq(&other));
+    assert_eq!(digest, digest.clone());
+    assert_ne!(digest, other);
+    // same digest under another code
+    let sha = SelfAddressingPrefix::new_unchecked(SelfAddressing::SHA3_256, digest.digest.clone());
+    assert!(!digest.ct_eq(&sha));
+    assert_ne!(digest, sha);
+}
+
+#[test]
+fn digest_agility() {
+    let approved = [SelfAddressing::Blake3_256, SelfAddressing::SHA3_256];
+    for code in approved {
+        let digest = code.derive(b"data");
+        assert!(digest.matches_any(b"data", &approved));
+        assert!(!digest.matches_any(b"other data", &approved));
+    }
+    let digest = SelfAddressing::SHA2_256.derive(b"data");
+    assert!(digest.verify_binding(b"data"));
+    assert!(!digest.matches_any(b"data", &approved));
+    assert!(!digest.matches_any(b"data", &[]));
+}
+
+#[test]
+fn digest_policy() -> Result<(), Error> {
+    let policy = DigestPolicy::new([
+        SelfAddressing::Blake3_256,
+        SelfAddressing::SHA3_256,
+        SelfAddressing::SHA3_512,
+    ]);
+    let digest = SelfAddressing::SHA3_256.derive(b"data");
+    assert!(digest.verify_with_policy(b"data", &policy)?);
+    assert!(!digest.verify_with_policy(b"other data", &policy)?);
+    let sha2 = SelfAddressing::SHA2_256.derive(b"data");
+    assert!(sha2.verify_with_policy(b"data", &policy).is_err());
+    assert!(sha2.verify_with_policy(b"data", &DigestPolicy::default())?);
+
+    let strong = policy.min_strength(256);
+    assert!(digest.verify_with_policy(b"data", &strong).is_err());
+    let digest = SelfAddressing::SHA3_512.derive(b"data");
+    assert!(digest.verify_with_policy(b"data", &strong)?);
+    Ok(())
+}
+
+#[test]
+fn derive_from_icp() -> Result<(), Error> {
+    use crate::message::InceptionBuilder;
+    use crate::version::SerializationKind;
+
+    let key = "DKxy2sgzfplyr-tgwIxS19f2OchFHtLwPWD3v4oYimBx".parse()?;
+    let icp = InceptionBuilder::new().keys(vec![key]).build()?;
+    let prefix = icp.serder.get("i").and_then(|i| i.as_str()).unwrap();
+    let derived =
+        SelfAddressingPrefix::derive_from_icp(SelfAddressing::Blake3_256, icp.serder.raw())?;
+    assert_eq!(derived.to_str(), prefix);
+    assert_eq!(derived, icp.said()?);
+
+    // whatever the prefix and SAID fields hold is dummied
+    let mut ked = icp.serder.ked().clone();
+    ked.insert("i".into(), "#".repeat(44).into());
+    ked.insert("d".into(), "".into());
+    let dummied = Serder::from_ked(ked, SerializationKind::JSON)?;
+    assert_eq!(
+        SelfAddressingPrefix::derive_from_icp(SelfAddressing::Blake3_256, dummied.raw())?,
+        derived
+    );
+    assert_ne!(
+        SelfAddressingPrefix::derive_from_icp(SelfAddressing::SHA3_256, dummied.raw())?,
+        derived
+    );
+
+    let mut ked = icp.serder.ked().clone();
+    ked.insert("t".into(), "rot".into());
+    let rot = Serder::from_ked(ked, SerializationKind::JSON)?;
+    assert!(SelfAddressingPrefix::derive_from_icp(SelfAddressing::Blake3_256, rot.raw()).is_err());
+    Ok(())
+}
+
+#[test]
+fn verify_keyed() -> Result<(), Error> {
+    let (key, data) = (b"0123456789abcdef", b"abcdefghijklmnopqrstuvwxyz0123456789");
+    let keyed = KeyedSelfAddressing::new(SelfAddressing::Blake2S256)?.derive(key, data)?;
+    // parsed back, nothing tells the digest is keyed
+    let parsed = SelfAddressingPrefix::from_str(&keyed.to_str())?;
+    assert!(!parsed.verify_binding(data));
+    assert!(parsed.verify_keyed(key, data)?);
+    assert!(!parsed.verify_keyed(b"another key", data)?);
+    assert!(!parsed.verify_keyed(key, b"other data")?);
+    assert!(parsed.verify_keyed(&[0; 33], data).is_err());
+    // plain digests verify with an empty key, other algorithms have no keyed variant
+    assert!(SelfAddressing::Blake2B256
+        .derive(data)
+        .verify_keyed(b"", data)?);
+    assert!(SelfAddressing::Blake3_256
+        .derive(data)
+        .verify_keyed(key, data)
+        .is_err());
+    Ok(())
+}
@@ -1,25 +1,35 @@
-use super::Prefix;
+use super::{check_derivative_len, Prefix};
 use crate::{
+    codex,
     derivation::{self_signing::SelfSigning, DerivationCode},
     error::Error,
 };
-use base64::decode_config;
 use core::str::FromStr;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::hash::{Hash, Hasher};
+use subtle::ConstantTimeEq;
 
 // TODO should this be renamed to self-signing identifier?
 // TODO #2 handle the condition from the whitepaper where
 //   "If instead of a content digest (hash), a signature of the inception data is used in the the
 //    prefix then no attached signature is required."
 /// A [SelfSigningPrefix] must have an attached signature for verification.
-#[derive(Debug, PartialEq, Clone, Hash)]
+#[derive(Debug, Clone)]
 pub struct SelfSigningPrefix {
     pub derivation: SelfSigning,
     pub signature: Vec<u8>,
 }
 
 impl SelfSigningPrefix {
-    pub fn new(code: SelfSigning, signature: Vec<u8>) -> Self {
+    /// Fails when the signature is not as long as `code` requires.
+    pub fn try_new(code: SelfSigning, signature: Vec<u8>) -> Result<Self, Error> {
+        check_derivative_len(&code, &signature)?;
+        Ok(Self::new_unchecked(code, signature))
+    }
+
+    /// Takes the signature as it is, the qb64 of a signature of the wrong length is not valid
+    /// CESR.
+    pub fn new_unchecked(code: SelfSigning, signature: Vec<u8>) -> Self {
         Self {
             derivation: code,
             signature,
@@ -27,6 +37,22 @@ impl SelfSigningPrefix {
     }
 }
 
+/// Compares the signatures in constant time.
+impl PartialEq for SelfSigningPrefix {
+    fn eq(&self, other: &Self) -> bool {
+        self.derivation == other.derivation && bool::from(self.signature.ct_eq(&other.signature))
+    }
+}
+
+impl Eq for SelfSigningPrefix {}
+
+impl Hash for SelfSigningPrefix {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.derivation.hash(state);
+        self.signature.hash(state);
+    }
+}
+
 impl FromStr for SelfSigningPrefix {
     type Err = Error;
 
@@ -34,15 +60,15 @@ impl FromStr for SelfSigningPrefix {
         let code = SelfSigning::from_str(s)?;
 
         if s.len() == code.prefix_b64_len() {
-            Ok(Self::new(
+            Ok(Self::new_unchecked(
                 code,
-                decode_config(&s[code.code_len()..code.prefix_b64_len()], base64::URL_SAFE)?,
+                codex::decode(&s[code.code_len()..], code.lead_size())?,
             ))
         } else {
-            Err(Error::SemanticError(format!(
-                "Incorrect Prefix Length: {}",
-                s
-            )))
+            Err(Error::WrongLength {
+                expected: code.prefix_b64_len(),
+                got: s.len(),
+            })
         }
     }
 }
@@ -54,6 +80,66 @@ impl Prefix for SelfSigningPrefix {
     fn derivation_code(&self) -> String {
         self.derivation.to_str()
     }
+    fn lead_size(&self) -> usize {
+        self.derivation.lead_size()
+    }
+}
+
+impl From<ed25519_dalek::Signature> for SelfSigningPrefix {
+    fn from(signature: ed25519_dalek::Signature) -> Self {
+        Self::new_unchecked(SelfSigning::Ed25519Sha512, signature.to_bytes().to_vec())
+    }
+}
+
+impl From<k256::ecdsa::Signature> for SelfSigningPrefix {
+    fn from(signature: k256::ecdsa::Signature) -> Self {
+        Self::new_unchecked(
+            SelfSigning::ECDSAsecp256k1Sha256,
+            signature.as_ref().to_vec(),
+        )
+    }
+}
+
+impl From<p256::ecdsa::Signature> for SelfSigningPrefix {
+    fn from(signature: p256::ecdsa::Signature) -> Self {
+        Self::new_unchecked(
+            SelfSigning::ECDSAsecp256r1Sha256,
+            signature.as_ref().to_vec(),
+        )
+    }
+}
+
+impl TryFrom<&SelfSigningPrefix> for ed25519_dalek::Signature {
+    type Error = Error;
+
+    fn try_from(prefix: &SelfSigningPrefix) -> Result<Self, Self::Error> {
+        match prefix.derivation {
+            SelfSigning::Ed25519Sha512 => Ok(Self::try_from(&prefix.signature[..])?),
+            _ => Err(Error::ImproperPrefixType),
+        }
+    }
+}
+
+impl TryFrom<&SelfSigningPrefix> for k256::ecdsa::Signature {
+    type Error = Error;
+
+    fn try_from(prefix: &SelfSigningPrefix) -> Result<Self, Self::Error> {
+        match prefix.derivation {
+            SelfSigning::ECDSAsecp256k1Sha256 => Ok(Self::try_from(&prefix.signature[..])?),
+            _ => Err(Error::ImproperPrefixType),
+        }
+    }
+}
+
+impl TryFrom<&SelfSigningPrefix> for p256::ecdsa::Signature {
+    type Error = Error;
+
+    fn try_from(prefix: &SelfSigningPrefix) -> Result<Self, Self::Error> {
+        match prefix.derivation {
+            SelfSigning::ECDSAsecp256r1Sha256 => Ok(Self::try_from(&prefix.signature[..])?),
+            _ => Err(Error::ImproperPrefixType),
+        }
+    }
 }
 
 /// Serde compatible Serialize
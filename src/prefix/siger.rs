@@ -0,0 +1,234 @@
+use super::{AttachedSignaturePrefix, BasicPrefix, Prefix, SelfSigningPrefix};
+use crate::{
+    derivation::{attached_signature_code::AttachedSignatureCode, DerivationCode},
+    error::Error,
+    keys::Verfer,
+};
+use core::str::FromStr;
+
+/// An indexed signature (siger) together with the [Verfer] of the key it was made with, once
+/// that key has been looked up in the key list of the signed event by the signature index.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Siger {
+    pub code: AttachedSignatureCode,
+    pub signature: SelfSigningPrefix,
+    pub verfer: Option<Verfer>,
+}
+
+impl Siger {
+    pub fn new(code: AttachedSignatureCode, signature: Vec<u8>) -> Self {
+        AttachedSignaturePrefix::with_code(code, signature).into()
+    }
+
+    pub fn with_verfer(self, verfer: Verfer) -> Self {
+        Self {
+            verfer: Some(verfer),
+            ..self
+        }
+    }
+
+    pub fn index(&self) -> u16 {
+        self.code.index
+    }
+
+    /// Binds the key at the signature index of `keys`, the current key list of the signed event.
+    pub fn resolve(self, keys: &[BasicPrefix]) -> Result<Self, Error> {
+        let key = keys.get(self.index() as usize).ok_or_else(|| {
+            Error::SemanticError(format!(
+                "Signature index {} out of range of {} keys",
+                self.index(),
+                keys.len()
+            ))
+        })?;
+        Ok(self.with_verfer(key.clone().into()))
+    }
+
+    /// Verifies the signature over `data` with the bound verfer.
+    /// Errors when no verfer has been bound yet.
+    pub fn verify(&self, data: &[u8]) -> Result<bool, Error> {
+        let verfer = self.verfer.clone().ok_or_else(|| {
+            Error::SemanticError(format!(
+                "No verfer bound to signature with index {}",
+                self.index()
+            ))
+        })?;
+        BasicPrefix::from(verfer).verify(data, &self.signature)
+    }
+}
+
+impl From<AttachedSignaturePrefix> for Siger {
+    fn from(sig: AttachedSignaturePrefix) -> Self {
+        Self {
+            code: sig.code,
+            signature: sig.signature,
+            verfer: None,
+        }
+    }
+}
+
+impl From<Siger> for AttachedSignaturePrefix {
+    fn from(siger: Siger) -> Self {
+        Self {
+            code: siger.code,
+            signature: siger.signature,
+        }
+    }
+}
+
+/// Parses the indexed signature only, the verfer is left unbound.
+impl FromStr for Siger {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        AttachedSignaturePrefix::from_str(s).map(Self::from)
+    }
+}
+
+impl Prefix for Siger {
+    fn derivative(&self) -> Vec<u8> {
+        self.signature.signature.to_vec()
+    }
+    fn derivation_code(&self) -> String {
+        self.code.to_str()
+    }
+
+    fn normalized(&self) -> Self {
+        Self {
+            code: self.code.normalized(),
+            ..self.clone()
+        }
+    }
+}
+
+/// Sorts indexed signatures by index, signatures with the same index kept in order.
+pub fn sort_sigers(sigers: &mut [Siger]) {
+    sigers.sort_by_key(Siger::index);
+}
+
+/// Checks the indices of `sigers` before they are verified against a key list of `key_count`
+/// keys: every index in range of the keys and no two signatures with the same index. Returns the
+/// sigers sorted by index.
+pub fn check_sigers(sigers: &[Siger], key_count: usize) -> Result<Vec<Siger>, Error> {
+    let mut sorted = sigers.to_vec();
+    sort_sigers(&mut sorted);
+    if let Some(siger) = sorted
+        .last()
+        .filter(|siger| siger.index() as usize >= key_count)
+    {
+        return Err(Error::SemanticError(format!(
+            "Signature index {} out of range of {} keys",
+            siger.index(),
+            key_count
+        )));
+    }
+    if let Some(pair) = sorted
+        .windows(2)
+        .find(|pair| pair[0].index() == pair[1].index())
+    {
+        return Err(Error::SemanticError(format!(
+            "Duplicate signature index {}",
+            pair[0].index()
+        )));
+    }
+    Ok(sorted)
+}
+
+/// Verifies each indexed signature over `data` against the key list `keys`, returning the
+/// sigers with their verfers bound. Fails on the first signature that does not verify.
+pub fn verify_sigers(
+    sigers: &[Siger],
+    keys: &[BasicPrefix],
+    data: &[u8],
+) -> Result<Vec<Siger>, Error> {
+    sigers
+        .iter()
+        .map(|siger| {
+            let siger = siger.clone().resolve(keys)?;
+            if siger.verify(data)? {
+                Ok(siger)
+            } else {
+                Err(Error::FaultySignatureVerification)
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{derivation::self_signing::SelfSigning, keys::Signer, test_utils::signer};
+
+    fn signers() -> Vec<Signer> {
+        (1..=3).map(|i| signer(i, true)).collect()
+    }
+
+    #[test]
+    fn encoding() -> Result<(), Error> {
+        let signer = &signers()[0];
+        let sig = signer.sign(b"event");
+        let siger = Siger::new(
+            AttachedSignatureCode::new(SelfSigning::Ed25519Sha512, 2)?,
+            sig.signature.clone(),
+        );
+        assert_eq!(siger.index(), 2);
+        assert_eq!(&siger.to_str()[..2], "AC");
+        assert_eq!(Siger::from_str(&siger.to_str())?, siger);
+
+        let attached: AttachedSignaturePrefix = siger.clone().into();
+        assert_eq!(attached.to_str(), siger.to_str());
+        assert_eq!(Siger::from(attached), siger);
+        Ok(())
+    }
+
+    #[test]
+    fn sort_and_check() -> Result<(), Error> {
+        let signers = signers();
+        let siger = |i: usize| {
+            Siger::new(
+                AttachedSignatureCode::new(SelfSigning::Ed25519Sha512, i as u16).unwrap(),
+                signers[i].sign(b"event").signature,
+            )
+        };
+        let sigers = vec![siger(2), siger(0), siger(1)];
+        let mut sorted = sigers.clone();
+        sort_sigers(&mut sorted);
+        let indices: Vec<u16> = sorted.iter().map(Siger::index).collect();
+        assert_eq!(indices, [0, 1, 2]);
+
+        assert_eq!(check_sigers(&sigers, 3)?, sorted);
+        assert!(check_sigers(&sigers, 2).is_err());
+        assert!(check_sigers(&[siger(1), siger(0), siger(1)], 3).is_err());
+        assert!(check_sigers(&[], 0)?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn resolve_and_verify() -> Result<(), Error> {
+        let signers = signers();
+        let keys: Vec<_> = signers.iter().map(Signer::basic_prefix).collect();
+        let sigers: Vec<_> = [0, 2]
+            .iter()
+            .map(|&i| {
+                let sig = signers[i].sign(b"event");
+                Siger::new(
+                    AttachedSignatureCode::new(SelfSigning::Ed25519Sha512, i as u16).unwrap(),
+                    sig.signature,
+                )
+            })
+            .collect();
+
+        // unbound
+        assert!(sigers[0].verify(b"event").is_err());
+
+        let verified = verify_sigers(&sigers, &keys, b"event")?;
+        assert_eq!(verified[1].verfer, Some(signers[2].verfer().clone()));
+        assert!(verified[1].verify(b"event")?);
+        assert!(!verified[1].verify(b"other event")?);
+
+        assert!(verify_sigers(&sigers, &keys, b"other event").is_err());
+        assert!(verify_sigers(&sigers, &keys[..2], b"event").is_err());
+        // bound to the wrong key
+        assert!(!sigers[0].clone().resolve(&keys[1..])?.verify(b"event")?);
+        Ok(())
+    }
+}
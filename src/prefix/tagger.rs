@@ -0,0 +1,123 @@
+use super::{split_at_code_len, Prefix};
+use crate::{
+    derivation::{split_at_len, tag::TagCode, DerivationCode},
+    error::Error,
+};
+use core::str::FromStr;
+
+/// A label of 1 to 11 Base64 characters, e.g. a message type or field tag, carried as the soft
+/// part of a [TagCode].
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct Tagger {
+    tag: String,
+}
+
+impl Tagger {
+    pub fn new(tag: &str) -> Result<Self, Error> {
+        TagCode::new(tag.len())?;
+        if !tag
+            .bytes()
+            .all(|c| c.is_ascii_alphanumeric() || c == b'-' || c == b'_')
+        {
+            return Err(Error::SemanticError(format!(
+                "Tags must be Base64: {}",
+                tag
+            )));
+        }
+        Ok(Self {
+            tag: tag.to_string(),
+        })
+    }
+
+    pub fn tag(&self) -> &str {
+        &self.tag
+    }
+
+    pub fn code(&self) -> TagCode {
+        TagCode::new(self.tag.len()).expect("tag validated on creation")
+    }
+
+    /// Parses one primitive from the start of `s`, returning it with the rest of `s`.
+    pub fn from_stream(s: &str) -> Result<(Self, &str), Error> {
+        let code = TagCode::from_str(s)?;
+        let (prefix, rest) = split_at_code_len(s, code)?;
+        let (pad, tag) = split_at_len(&prefix[code.to_str().len()..], code.pad_len())?;
+        if pad.bytes().any(|c| c != b'_') {
+            return Err(Error::DeserializeError(format!(
+                "Invalid tag padding: {}",
+                prefix
+            )));
+        }
+        Ok((Self::new(tag)?, rest))
+    }
+}
+
+/// Parses a single primitive, `s` must hold nothing else.
+impl FromStr for Tagger {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match Self::from_stream(s)? {
+            (tagger, "") => Ok(tagger),
+            (_, rest) => Err(Error::WrongLength {
+                expected: s.len() - rest.len(),
+                got: s.len(),
+            }),
+        }
+    }
+}
+
+impl Prefix for Tagger {
+    fn derivative(&self) -> Vec<u8> {
+        vec![]
+    }
+
+    /// The whole primitive, the tag is part of the code.
+    fn derivation_code(&self) -> String {
+        let code = self.code();
+        [code.to_str(), "_".repeat(code.pad_len()), self.tag.clone()].join("")
+    }
+
+    fn to_str(&self) -> String {
+        self.derivation_code()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tagger() -> Result<(), Error> {
+        for (tag, qb64) in [
+            ("z", "0J_z"),
+            ("-a", "0K-a"),
+            ("icp", "Xicp"),
+            ("abcd", "1AAFabcd"),
+            ("abcde", "0L_abcde"),
+            ("abcdef", "0Mabcdef"),
+            ("abcdefg", "Yabcdefg"),
+            ("abcdefgh", "1AANabcdefgh"),
+            ("abcdefghi", "0N_abcdefghi"),
+            ("abcdefghij", "0Oabcdefghij"),
+            ("abcdefghijk", "Zabcdefghijk"),
+        ] {
+            let tagger = Tagger::new(tag)?;
+            assert_eq!(tagger.to_str(), qb64);
+            assert_eq!(Tagger::from_str(qb64)?, tagger);
+            assert_eq!(Tagger::from_qb2(&tagger.to_qb2()?)?, tagger);
+        }
+
+        let (tagger, rest) = Tagger::from_stream("Xicp-AAB")?;
+        assert_eq!(tagger.tag(), "icp");
+        assert_eq!(rest, "-AAB");
+
+        assert!(Tagger::new("").is_err());
+        assert!(Tagger::new("abcdefghijkl").is_err());
+        assert!(Tagger::new("a.b").is_err());
+        assert!(Tagger::from_str("0JAz").is_err());
+        assert!(Tagger::from_str("Xic").is_err());
+        assert!(Tagger::from_str("Xicp-").is_err());
+        Ok(())
+    }
+}
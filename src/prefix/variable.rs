@@ -0,0 +1,182 @@
+use super::{split_at_code_len, Prefix};
+use crate::{
+    codex::{self, b64},
+    derivation::{
+        variable::{VariableCode, VariableKind},
+        DerivationCode,
+    },
+    error::Error,
+};
+use core::str::FromStr;
+
+/// Material of arbitrary length carried under a variable size code, either opaque bytes or a
+/// Base64 string.
+#[derive(Debug, PartialEq, Clone)]
+pub struct VariablePrefix {
+    code: VariableCode,
+    raw: Vec<u8>,
+}
+
+impl VariablePrefix {
+    /// Opaque bytes.
+    pub fn bytes(raw: Vec<u8>) -> Result<Self, Error> {
+        Ok(Self {
+            code: VariableCode::new(VariableKind::Bytes, raw.len())?,
+            raw,
+        })
+    }
+
+    /// A Base64 string, carried as its Base64 decoding after padding it with leading `A`s to a
+    /// whole number of quadlets. A string of whole quadlets starting with `A` therefore loses its
+    /// first `A` in [VariablePrefix::text].
+    pub fn text(b64: &str) -> Result<Self, Error> {
+        if !b64
+            .bytes()
+            .all(|c| c.is_ascii_alphanumeric() || c == b'-' || c == b'_')
+        {
+            return Err(Error::SemanticError(format!(
+                "Not a Base64 string: {}",
+                b64
+            )));
+        }
+        let tail = b64.len() % 4;
+        let lead = (3 - tail) % 3;
+        let padded = ["A".repeat((4 - tail) % 4), b64.to_string()].join("");
+        let raw = b64::decode(padded)?[lead..].to_vec();
+        Ok(Self {
+            code: VariableCode::new(VariableKind::StrB64, raw.len())?,
+            raw,
+        })
+    }
+
+    pub fn code(&self) -> VariableCode {
+        self.code
+    }
+
+    pub fn raw(&self) -> &[u8] {
+        &self.raw
+    }
+
+    /// The Base64 string of `StrB64` material, `None` for opaque bytes.
+    pub fn to_text(&self) -> Option<String> {
+        if self.code.kind != VariableKind::StrB64 {
+            return None;
+        }
+        let b64 = b64::encode(self.aligned());
+        let strip = match self.code.lead {
+            0 if b64.starts_with('A') => 1,
+            0 => 0,
+            lead => lead + 1,
+        };
+        Some(b64[strip..].to_string())
+    }
+
+    /// Parses one primitive from the start of `s`, returning it with the rest of `s`.
+    pub fn from_stream(s: &str) -> Result<(Self, &str), Error> {
+        let code = VariableCode::from_str(s)?;
+        let (prefix, rest) = split_at_code_len(s, code)?;
+        let raw = codex::decode(&prefix[code.code_len()..], code.lead)?;
+        Ok((Self { code, raw }, rest))
+    }
+
+    /// The material prepended with its lead bytes.
+    fn aligned(&self) -> Vec<u8> {
+        [vec![0; self.code.lead], self.raw.clone()].concat()
+    }
+}
+
+/// Parses a single primitive, `s` must hold nothing else.
+impl FromStr for VariablePrefix {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match Self::from_stream(s)? {
+            (prefix, "") => Ok(prefix),
+            (_, rest) => Err(Error::WrongLength {
+                expected: s.len() - rest.len(),
+                got: s.len(),
+            }),
+        }
+    }
+}
+
+impl Prefix for VariablePrefix {
+    fn derivative(&self) -> Vec<u8> {
+        self.raw.clone()
+    }
+
+    fn derivation_code(&self) -> String {
+        self.code.to_str()
+    }
+
+    fn lead_size(&self) -> usize {
+        self.code.lead
+    }
+
+    /// Unlike fixed size primitives empty material is still prefixed.
+    fn to_str(&self) -> String {
+        codex::encode(&self.derivation_code(), self.lead_size(), &self.raw)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bytes() -> Result<(), Error> {
+        for (raw, qb64) in [
+            (&b""[..], "4BAA"),
+            (b"a", "6BABAABh"),
+            (b"ab", "5BABAGFi"),
+            (b"abc", "4BABYWJj"),
+        ] {
+            let prefix = VariablePrefix::bytes(raw.to_vec())?;
+            assert_eq!(prefix.to_str(), qb64);
+            assert_eq!(VariablePrefix::from_str(qb64)?, prefix);
+            assert_eq!(VariablePrefix::from_qb2(&prefix.to_qb2()?)?, prefix);
+            assert_eq!(prefix.to_text(), None);
+        }
+
+        let prefix = VariablePrefix::bytes(vec![7; 3 * 4096 + 2])?;
+        assert_eq!(&prefix.to_str()[..8], "8AABABAB");
+        assert_eq!(VariablePrefix::from_str(&prefix.to_str())?, prefix);
+        Ok(())
+    }
+
+    #[test]
+    fn text() -> Result<(), Error> {
+        for (text, qb64) in [
+            ("", "4AAA"),
+            ("-", "6AABAAA-"),
+            ("-A", "5AABAA-A"),
+            ("-A-", "4AABA-A-"),
+            ("-A-B", "4AAB-A-B"),
+            ("ABC", "4AABAABC"),
+        ] {
+            let prefix = VariablePrefix::text(text)?;
+            assert_eq!(prefix.to_str(), qb64);
+            assert_eq!(VariablePrefix::from_str(qb64)?.to_text(), Some(text.into()));
+        }
+        // whole quadlets starting with A lose it
+        assert_eq!(
+            VariablePrefix::text("ABCD")?.to_text(),
+            Some("BCD".to_string())
+        );
+        assert!(VariablePrefix::text("a=").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn stream() -> Result<(), Error> {
+        let (prefix, rest) = VariablePrefix::from_stream("4BABYWJj-AAB")?;
+        assert_eq!(prefix.raw(), b"abc");
+        assert_eq!(rest, "-AAB");
+
+        assert!(VariablePrefix::from_str("4BABYWJj-AAB").is_err());
+        assert!(VariablePrefix::from_str("4BACYWJj").is_err());
+        // lead bytes must be zero
+        assert!(VariablePrefix::from_str("5BABAWFi").is_err());
+        Ok(())
+    }
+}
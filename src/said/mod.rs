@@ -0,0 +1,404 @@
+use crate::{
+    derivation::{self_addressing::SelfAddressing, DerivationCode},
+    error::Error,
+    prefix::{Prefix, SelfAddressingPrefix},
+    serder::{serialize, sizeify, VERSION_LABEL},
+    version::SerializationKind,
+};
+use core::str::FromStr;
+use serde_json::{Map, Value};
+
+/// Character filling the SAID fields while the SAID is computed.
+pub const DUMMY: char = '#';
+
+/// Computes the SAID of self-addressing data (SAD): the digest of `sad` serialized as `kind`,
+/// with the fields `labels` filled with as many dummy `#` characters as the SAID is long.
+/// The version string of `sad`, if any, is sized for the dummied serialization.
+pub fn compute_said(
+    sad: &Map<String, Value>,
+    labels: &[&str],
+    code: &SelfAddressing,
+    kind: SerializationKind,
+) -> Result<SelfAddressingPrefix, Error> {
+    let mut sad = sad.clone();
+    let dummy = Value::String(DUMMY.to_string().repeat(code.prefix_b64_len()));
+    for label in labels {
+        let field = sad
+            .get_mut(*label)
+            .ok_or_else(|| Error::SemanticError(format!("Missing SAID field: {}", label)))?;
+        *field = dummy.clone();
+    }
+    Ok(code.derive(&serialize_sad(&mut sad, kind)?))
+}
+
+/// Embeds the SAID of `sad` into its `label` field. Returns the SAID together with the
+/// serialization of the SAD holding it.
+pub fn saidify(
+    sad: &Map<String, Value>,
+    label: &str,
+    code: &SelfAddressing,
+    kind: SerializationKind,
+) -> Result<(SelfAddressingPrefix, Vec<u8>), Error> {
+    let said = compute_said(sad, &[label], code, kind)?;
+    let mut sad = sad.clone();
+    sad.insert(label.into(), Value::String(said.to_str()));
+    Ok((said, serialize_sad(&mut sad, kind)?))
+}
+
+/// Checks the SAID in the `label` field of `sad` matches its content, digesting with the code
+/// of the SAID.
+pub fn verify_said(
+    sad: &Map<String, Value>,
+    label: &str,
+    kind: SerializationKind,
+) -> Result<bool, Error> {
+    let said: SelfAddressingPrefix = sad
+        .get(label)
+        .and_then(Value::as_str)
+        .ok_or_else(|| Error::SemanticError(format!("Missing SAID field: {}", label)))?
+        .parse()?;
+    Ok(compute_said(sad, &[label], &said.derivation, kind)? == said)
+}
+
+/// Label of the SAID field of a JSON Schema document.
+pub const SCHEMA_ID_LABEL: &str = "$id";
+
+/// Checks the `$id` field of the JSON Schema document `json_schema` is the SAID of the schema,
+/// as ACDC schemas are identified. The schema is digested serialized as compact JSON, its fields
+/// in document order, with the `$id` field dummied.
+pub fn verify_schema_said(json_schema: &[u8]) -> Result<bool, Error> {
+    let schema: Map<String, Value> = serde_json::from_slice(json_schema)?;
+    verify_said(&schema, SCHEMA_ID_LABEL, SerializationKind::JSON)
+}
+
+/// Path to a SAID field nested in self-addressing data: the labels of the nested blocks leading
+/// to the field followed by the label of the field, e.g. `a.d` for the SAID of the attribute
+/// block of an ACDC.
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct SaidPath(Vec<String>);
+
+impl SaidPath {
+    pub fn new<S: Into<String>>(labels: impl IntoIterator<Item = S>) -> Result<Self, Error> {
+        let labels: Vec<String> = labels.into_iter().map(Into::into).collect();
+        if labels.is_empty() || labels.iter().any(String::is_empty) {
+            return Err(Error::SemanticError(format!(
+                "Invalid SAID path: {:?}",
+                labels
+            )));
+        }
+        Ok(Self(labels))
+    }
+
+    /// Number of blocks the field is nested in.
+    pub fn depth(&self) -> usize {
+        self.0.len() - 1
+    }
+
+    /// Labels of the blocks leading to the field.
+    pub fn blocks(&self) -> &[String] {
+        &self.0[..self.depth()]
+    }
+
+    /// Label of the SAID field.
+    pub fn label(&self) -> &str {
+        &self.0[self.depth()]
+    }
+
+    fn block<'a>(&self, sad: &'a Map<String, Value>) -> Result<&'a Map<String, Value>, Error> {
+        self.blocks().iter().try_fold(sad, |block, label| {
+            block
+                .get(label)
+                .and_then(Value::as_object)
+                .ok_or_else(|| Error::SemanticError(format!("Missing block {} of {}", label, self)))
+        })
+    }
+
+    fn block_mut<'a>(
+        &self,
+        sad: &'a mut Map<String, Value>,
+    ) -> Result<&'a mut Map<String, Value>, Error> {
+        self.blocks().iter().try_fold(sad, |block, label| {
+            block
+                .get_mut(label)
+                .and_then(Value::as_object_mut)
+                .ok_or_else(|| Error::SemanticError(format!("Missing block {} of {}", label, self)))
+        })
+    }
+}
+
+impl core::fmt::Display for SaidPath {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.0.join("."))
+    }
+}
+
+/// Parses a dot separated path, e.g. `a.d`.
+impl FromStr for SaidPath {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::new(s.split('.'))
+    }
+}
+
+/// Embeds the SAIDs of the blocks of `sad` at `paths`, deepest blocks first, so the SAID of an
+/// enclosing block covers the SAIDs of the blocks nested in it. Each block is digested on its
+/// own, serialized as `kind`.
+///
+/// Returns the SAIDs in the order of `paths` together with the serialization of the whole SAD.
+pub fn saidify_paths(
+    sad: &Map<String, Value>,
+    paths: &[SaidPath],
+    code: &SelfAddressing,
+    kind: SerializationKind,
+) -> Result<(Vec<SelfAddressingPrefix>, Vec<u8>), Error> {
+    let mut sad = sad.clone();
+    let saids = embed_saids(&mut sad, paths, code, kind)?;
+    Ok((saids, serialize_sad(&mut sad, kind)?))
+}
+
+/// Embeds the SAIDs of the blocks of `sad` at `paths` in place, see [saidify_paths].
+pub(crate) fn embed_saids(
+    sad: &mut Map<String, Value>,
+    paths: &[SaidPath],
+    code: &SelfAddressing,
+    kind: SerializationKind,
+) -> Result<Vec<SelfAddressingPrefix>, Error> {
+    let mut order: Vec<usize> = (0..paths.len()).collect();
+    order.sort_by_key(|&i| core::cmp::Reverse(paths[i].depth()));
+
+    let mut saids = vec![None; paths.len()];
+    for i in order {
+        let block = paths[i].block_mut(sad)?;
+        let said = compute_said(block, &[paths[i].label()], code, kind)?;
+        block.insert(paths[i].label().into(), Value::String(said.to_str()));
+        saids[i] = Some(said);
+    }
+    Ok(saids.into_iter().flatten().collect())
+}
+
+/// Checks the SAIDs of the blocks of `sad` at `paths` match their content.
+pub fn verify_said_paths(
+    sad: &Map<String, Value>,
+    paths: &[SaidPath],
+    kind: SerializationKind,
+) -> Result<bool, Error> {
+    for path in paths {
+        if !verify_said(path.block(sad)?, path.label(), kind)? {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+/// Serializes a SAD, sizing its version string first if it has one.
+fn serialize_sad(sad: &mut Map<String, Value>, kind: SerializationKind) -> Result<Vec<u8>, Error> {
+    if sad.contains_key(VERSION_LABEL) {
+        Ok(sizeify(sad, kind)?.1)
+    } else {
+        serialize(sad, kind)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn sad(value: Value) -> Map<String, Value> {
+        match value {
+            Value::Object(sad) => sad,
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn saidify_sad() -> Result<(), Error> {
+        let sad = sad(json!({"d": "", "first": "Sue", "last": "Smith", "role": "Founder"}));
+        let (said, raw) = saidify(
+            &sad,
+            "d",
+            &SelfAddressing::Blake3_256,
+            SerializationKind::JSON,
+        )?;
+
+        let dummied = format!(
+            r#"{{"d":"{}","first":"Sue","last":"Smith","role":"Founder"}}"#,
+            "#".repeat(44)
+        );
+        assert_eq!(said, SelfAddressing::Blake3_256.derive(dummied.as_bytes()));
+        assert_eq!(
+            raw,
+            format!(
+                r#"{{"d":"{}","first":"Sue","last":"Smith","role":"Founder"}}"#,
+                said.to_str()
+            )
+            .as_bytes()
+        );
+
+        let saidified: Map<String, Value> = serde_json::from_slice(&raw)?;
+        assert!(verify_said(&saidified, "d", SerializationKind::JSON)?);
+        let mut tampered = saidified.clone();
+        tampered.insert("role".into(), "Employee".into());
+        assert!(!verify_said(&tampered, "d", SerializationKind::JSON)?);
+
+        assert!(saidify(
+            &sad,
+            "x",
+            &SelfAddressing::Blake3_256,
+            SerializationKind::JSON
+        )
+        .is_err());
+        assert!(verify_said(&sad, "d", SerializationKind::JSON).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn said_codes() -> Result<(), Error> {
+        let sad = sad(json!({"d": "", "a": 1}));
+        for code in [SelfAddressing::Blake3_256, SelfAddressing::SHA3_512] {
+            let (said, raw) = saidify(&sad, "d", &code, SerializationKind::CBOR)?;
+            assert_eq!(said.derivation, code);
+            let saidified: Map<String, Value> = serde_cbor::from_slice(&raw)?;
+            assert!(verify_said(&saidified, "d", SerializationKind::CBOR)?);
+            assert!(!verify_said(&saidified, "d", SerializationKind::JSON)?);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn versioned_sad() -> Result<(), Error> {
+        let sad = sad(json!({"v": "ACDC10JSON000000_", "d": "", "i": "EAAA"}));
+        let (said, raw) = saidify(
+            &sad,
+            "d",
+            &SelfAddressing::Blake3_256,
+            SerializationKind::JSON,
+        )?;
+        let saidified: Map<String, Value> = serde_json::from_slice(&raw)?;
+        assert_eq!(
+            saidified["v"],
+            format!("ACDC10JSON{:06x}_", raw.len()).as_str()
+        );
+        assert_eq!(saidified["d"], said.to_str().as_str());
+        assert!(verify_said(&saidified, "d", SerializationKind::JSON)?);
+        Ok(())
+    }
+
+    #[test]
+    fn nested_paths() -> Result<(), Error> {
+        let acdc = sad(json!({
+            "v": "ACDC10JSON000000_",
+            "d": "",
+            "i": "EAAA",
+            "a": {"d": "", "dt": "2020-08-22T17:50:09.988921+00:00", "LEI": "254900OPPU84GM83MG36"},
+            "r": {"d": "", "usage": "Disclaimer"}
+        }));
+        let paths: Vec<SaidPath> = ["d", "a.d", "r.d"]
+            .iter()
+            .map(|p| p.parse())
+            .collect::<Result<_, _>>()?;
+        assert_eq!(paths[1].depth(), 1);
+        assert_eq!(paths[1].blocks(), ["a"]);
+        assert_eq!(paths[1].label(), "d");
+        assert_eq!(paths[1].to_string(), "a.d");
+
+        let (saids, raw) = saidify_paths(
+            &acdc,
+            &paths,
+            &SelfAddressing::Blake3_256,
+            SerializationKind::JSON,
+        )?;
+        let saidified: Map<String, Value> = serde_json::from_slice(&raw)?;
+        assert_eq!(saidified["d"], saids[0].to_str().as_str());
+        assert_eq!(saidified["a"]["d"], saids[1].to_str().as_str());
+        assert_eq!(saidified["r"]["d"], saids[2].to_str().as_str());
+
+        // nested blocks are saidified on their own, before the enclosing block
+        let (attributes, _) = saidify(
+            acdc["a"].as_object().unwrap(),
+            "d",
+            &SelfAddressing::Blake3_256,
+            SerializationKind::JSON,
+        )?;
+        assert_eq!(attributes, saids[1]);
+        assert!(verify_said(&saidified, "d", SerializationKind::JSON)?);
+        assert!(verify_said_paths(
+            &saidified,
+            &paths,
+            SerializationKind::JSON
+        )?);
+
+        // the order of the paths does not matter
+        let reversed: Vec<SaidPath> = paths.iter().rev().cloned().collect();
+        let (_, raw_reversed) = saidify_paths(
+            &acdc,
+            &reversed,
+            &SelfAddressing::Blake3_256,
+            SerializationKind::JSON,
+        )?;
+        assert_eq!(raw, raw_reversed);
+
+        let mut tampered = saidified.clone();
+        tampered["a"]["LEI"] = "5493001KJTIIGC8Y1R17".into();
+        assert!(!verify_said_paths(
+            &tampered,
+            &paths,
+            SerializationKind::JSON
+        )?);
+        Ok(())
+    }
+
+    #[test]
+    fn schema_said() -> Result<(), Error> {
+        let schema = sad(json!({
+            "$id": "",
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "title": "Legal Entity vLEI Credential",
+            "type": "object",
+            "properties": {"LEI": {"type": "string", "format": "ISO 17442"}},
+            "required": ["LEI"]
+        }));
+        let (_, raw) = saidify(
+            &schema,
+            SCHEMA_ID_LABEL,
+            &SelfAddressing::Blake3_256,
+            SerializationKind::JSON,
+        )?;
+        assert!(verify_schema_said(&raw)?);
+
+        // whitespace does not matter, the order of the fields does
+        let pretty = serde_json::to_vec_pretty(&serde_json::from_slice::<Value>(&raw)?)?;
+        assert!(verify_schema_said(&pretty)?);
+        let mut reordered = sad(json!({"title": "Legal Entity vLEI Credential"}));
+        reordered.extend(serde_json::from_slice::<Map<String, Value>>(&raw)?);
+        assert!(!verify_schema_said(&serde_json::to_vec(&reordered)?)?);
+
+        let tampered = String::from_utf8(raw.clone())
+            .unwrap()
+            .replace("ISO 17442", "ISO 17443");
+        assert!(!verify_schema_said(tampered.as_bytes())?);
+        assert!(verify_schema_said(&raw[1..]).is_err());
+        let unsaidified = serde_json::to_vec(&schema)?;
+        assert!(verify_schema_said(&unsaidified).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_paths() {
+        assert!(SaidPath::from_str("").is_err());
+        assert!(SaidPath::from_str("a..d").is_err());
+        assert!(SaidPath::new(Vec::<String>::new()).is_err());
+
+        let sad = sad(json!({"d": "", "a": "flat"}));
+        let paths = [SaidPath::from_str("a.d").unwrap()];
+        assert!(saidify_paths(
+            &sad,
+            &paths,
+            &SelfAddressing::Blake3_256,
+            SerializationKind::JSON
+        )
+        .is_err());
+        assert!(verify_said_paths(&sad, &paths, SerializationKind::JSON).is_err());
+    }
+}
@@ -0,0 +1,339 @@
+use crate::{error::Error, version::SerializationKind};
+use serde_json::{Map, Number, Value};
+
+/// Serializes the field map `sad` as `kind`, byte for byte as KERIpy does, so SAIDs and
+/// signatures computed by either agree. Fields keep their insertion order.
+pub fn serialize(sad: &Map<String, Value>, kind: SerializationKind) -> Result<Vec<u8>, Error> {
+    match kind {
+        SerializationKind::JSON => Ok(to_json(sad)),
+        SerializationKind::CBOR => Ok(to_cbor(sad)),
+        SerializationKind::MGPK => Ok(to_mgpk(sad)),
+        _ => Err(Error::SemanticError(format!(
+            "Unsupported serialization kind: {}",
+            kind.to_str()
+        ))),
+    }
+}
+
+/// Compact JSON as `json.dumps(sad, separators=(",", ":"), ensure_ascii=False)` writes it: no
+/// whitespace, UTF-8 left unescaped and floats as Python prints them.
+pub fn to_json(sad: &Map<String, Value>) -> Vec<u8> {
+    let mut out = vec![];
+    json_map(sad, &mut out);
+    out
+}
+
+/// CBOR as `cbor2.dumps` writes it: definite lengths, the shortest heads and floats in double
+/// precision.
+pub fn to_cbor(sad: &Map<String, Value>) -> Vec<u8> {
+    let mut out = vec![];
+    cbor_map(sad, &mut out);
+    out
+}
+
+/// MessagePack as `msgpack.dumps` writes it: the smallest formats, strings as `str` and floats in
+/// double precision.
+pub fn to_mgpk(sad: &Map<String, Value>) -> Vec<u8> {
+    let mut out = vec![];
+    mgpk_map(sad, &mut out);
+    out
+}
+
+fn json_map(map: &Map<String, Value>, out: &mut Vec<u8>) {
+    out.push(b'{');
+    for (i, (label, value)) in map.iter().enumerate() {
+        if i > 0 {
+            out.push(b',');
+        }
+        json_str(label, out);
+        out.push(b':');
+        json_value(value, out);
+    }
+    out.push(b'}');
+}
+
+fn json_value(value: &Value, out: &mut Vec<u8>) {
+    match value {
+        Value::Null => out.extend_from_slice(b"null"),
+        Value::Bool(true) => out.extend_from_slice(b"true"),
+        Value::Bool(false) => out.extend_from_slice(b"false"),
+        Value::Number(number) => out.extend_from_slice(json_number(number).as_bytes()),
+        Value::String(s) => json_str(s, out),
+        Value::Array(values) => {
+            out.push(b'[');
+            for (i, value) in values.iter().enumerate() {
+                if i > 0 {
+                    out.push(b',');
+                }
+                json_value(value, out);
+            }
+            out.push(b']');
+        }
+        Value::Object(map) => json_map(map, out),
+    }
+}
+
+fn json_str(s: &str, out: &mut Vec<u8>) {
+    out.push(b'"');
+    for c in s.chars() {
+        match c {
+            '"' => out.extend_from_slice(b"\\\""),
+            '\\' => out.extend_from_slice(b"\\\\"),
+            '\n' => out.extend_from_slice(b"\\n"),
+            '\r' => out.extend_from_slice(b"\\r"),
+            '\t' => out.extend_from_slice(b"\\t"),
+            '\u{8}' => out.extend_from_slice(b"\\b"),
+            '\u{c}' => out.extend_from_slice(b"\\f"),
+            c if (c as u32) < 0x20 => {
+                out.extend_from_slice(format!("\\u{:04x}", c as u32).as_bytes())
+            }
+            c => out.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes()),
+        }
+    }
+    out.push(b'"');
+}
+
+/// Integers as they are, floats as Python's `repr`: the shortest digits which round trip,
+/// positional for exponents from -4 to 15 and scientific otherwise, e.g. `1e-05` or `1.5e+16`.
+fn json_number(number: &Number) -> String {
+    let float = match number.as_f64() {
+        Some(float) if number.is_f64() => float,
+        _ => return number.to_string(),
+    };
+    let scientific = format!("{:e}", float);
+    let (mantissa, exponent) = scientific.split_once('e').unwrap_or((&scientific, "0"));
+    let exponent: i32 = exponent.parse().unwrap_or_default();
+    if (-4..16).contains(&exponent) {
+        let positional = float.to_string();
+        match positional.contains('.') {
+            true => positional,
+            false => positional + ".0",
+        }
+    } else {
+        let sign = if exponent < 0 { '-' } else { '+' };
+        format!("{}e{}{:02}", mantissa, sign, exponent.abs())
+    }
+}
+
+fn cbor_head(major: u8, len: u64, out: &mut Vec<u8>) {
+    let major = major << 5;
+    match len {
+        0..=23 => out.push(major | len as u8),
+        24..=0xff => out.extend_from_slice(&[major | 24, len as u8]),
+        0x100..=0xffff => {
+            out.push(major | 25);
+            out.extend_from_slice(&(len as u16).to_be_bytes());
+        }
+        0x1_0000..=0xffff_ffff => {
+            out.push(major | 26);
+            out.extend_from_slice(&(len as u32).to_be_bytes());
+        }
+        _ => {
+            out.push(major | 27);
+            out.extend_from_slice(&len.to_be_bytes());
+        }
+    }
+}
+
+fn cbor_map(map: &Map<String, Value>, out: &mut Vec<u8>) {
+    cbor_head(5, map.len() as u64, out);
+    for (label, value) in map {
+        cbor_head(3, label.len() as u64, out);
+        out.extend_from_slice(label.as_bytes());
+        cbor_value(value, out);
+    }
+}
+
+fn cbor_value(value: &Value, out: &mut Vec<u8>) {
+    match value {
+        Value::Null => out.push(0xf6),
+        Value::Bool(false) => out.push(0xf4),
+        Value::Bool(true) => out.push(0xf5),
+        Value::Number(number) => match (number.as_u64(), number.as_i64()) {
+            (Some(uint), _) => cbor_head(0, uint, out),
+            (None, Some(int)) => cbor_head(1, !int as u64, out),
+            _ => {
+                out.push(0xfb);
+                out.extend_from_slice(&number.as_f64().unwrap_or_default().to_be_bytes());
+            }
+        },
+        Value::String(s) => {
+            cbor_head(3, s.len() as u64, out);
+            out.extend_from_slice(s.as_bytes());
+        }
+        Value::Array(values) => {
+            cbor_head(4, values.len() as u64, out);
+            values.iter().for_each(|value| cbor_value(value, out));
+        }
+        Value::Object(map) => cbor_map(map, out),
+    }
+}
+
+/// Writes the marker of a collection or string of `len` items, `fix` being the marker of its
+/// fixed format holding up to `fix_max` items and `sized` those of its 8, 16 and 32 bit formats.
+fn mgpk_len(len: usize, fix: u8, fix_max: usize, sized: [Option<u8>; 3], out: &mut Vec<u8>) {
+    match (len, sized) {
+        (len, _) if len <= fix_max => out.push(fix | len as u8),
+        (len, [Some(marker), _, _]) if len <= 0xff => out.extend_from_slice(&[marker, len as u8]),
+        (len, [_, Some(marker), _]) if len <= 0xffff => {
+            out.push(marker);
+            out.extend_from_slice(&(len as u16).to_be_bytes());
+        }
+        (len, [_, _, Some(marker)]) => {
+            out.push(marker);
+            out.extend_from_slice(&(len as u32).to_be_bytes());
+        }
+        _ => unreachable!(),
+    }
+}
+
+fn mgpk_str(s: &str, out: &mut Vec<u8>) {
+    mgpk_len(s.len(), 0xa0, 31, [Some(0xd9), Some(0xda), Some(0xdb)], out);
+    out.extend_from_slice(s.as_bytes());
+}
+
+fn mgpk_map(map: &Map<String, Value>, out: &mut Vec<u8>) {
+    mgpk_len(map.len(), 0x80, 15, [None, Some(0xde), Some(0xdf)], out);
+    for (label, value) in map {
+        mgpk_str(label, out);
+        mgpk_value(value, out);
+    }
+}
+
+fn mgpk_value(value: &Value, out: &mut Vec<u8>) {
+    match value {
+        Value::Null => out.push(0xc0),
+        Value::Bool(false) => out.push(0xc2),
+        Value::Bool(true) => out.push(0xc3),
+        Value::Number(number) => match (number.as_u64(), number.as_i64()) {
+            (Some(uint), _) => mgpk_uint(uint, out),
+            (None, Some(int)) => mgpk_int(int, out),
+            _ => {
+                out.push(0xcb);
+                out.extend_from_slice(&number.as_f64().unwrap_or_default().to_be_bytes());
+            }
+        },
+        Value::String(s) => mgpk_str(s, out),
+        Value::Array(values) => {
+            mgpk_len(values.len(), 0x90, 15, [None, Some(0xdc), Some(0xdd)], out);
+            values.iter().for_each(|value| mgpk_value(value, out));
+        }
+        Value::Object(map) => mgpk_map(map, out),
+    }
+}
+
+fn mgpk_uint(uint: u64, out: &mut Vec<u8>) {
+    match uint {
+        0..=0x7f => out.push(uint as u8),
+        0x80..=0xff => out.extend_from_slice(&[0xcc, uint as u8]),
+        0x100..=0xffff => {
+            out.push(0xcd);
+            out.extend_from_slice(&(uint as u16).to_be_bytes());
+        }
+        0x1_0000..=0xffff_ffff => {
+            out.push(0xce);
+            out.extend_from_slice(&(uint as u32).to_be_bytes());
+        }
+        _ => {
+            out.push(0xcf);
+            out.extend_from_slice(&uint.to_be_bytes());
+        }
+    }
+}
+
+/// Negative integers, the only ones given to it.
+fn mgpk_int(int: i64, out: &mut Vec<u8>) {
+    match int {
+        -32..=-1 => out.push(int as u8),
+        -0x80..=-33 => out.extend_from_slice(&[0xd0, int as u8]),
+        -0x8000..=-0x81 => {
+            out.push(0xd1);
+            out.extend_from_slice(&(int as i16).to_be_bytes());
+        }
+        -0x8000_0000..=-0x8001 => {
+            out.push(0xd2);
+            out.extend_from_slice(&(int as i32).to_be_bytes());
+        }
+        _ => {
+            out.push(0xd3);
+            out.extend_from_slice(&int.to_be_bytes());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn map(value: Value) -> Map<String, Value> {
+        match value {
+            Value::Object(sad) => sad,
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn json() {
+        let sad = map(json!({
+            "v": "KERI10JSON000000_",
+            "s": "0",
+            "n": [1, -2, 1.5, 0.0001, 0.00001, 1e16, 123456789012345.6],
+            "q": "\"é\"\n\u{1}\u{7f}",
+            "e": {},
+            "x": [null, true, false]
+        }));
+        assert_eq!(
+            String::from_utf8(to_json(&sad)).unwrap(),
+            concat!(
+                r#"{"v":"KERI10JSON000000_","s":"0","#,
+                r#""n":[1,-2,1.5,0.0001,1e-05,1e+16,123456789012345.6],"#,
+                "\"q\":\"\\\"é\\\"\\n\\u0001\u{7f}\",",
+                r#""e":{},"x":[null,true,false]}"#
+            )
+        );
+        let parsed: Map<String, Value> = serde_json::from_slice(&to_json(&sad)).unwrap();
+        assert_eq!(parsed, sad);
+    }
+
+    #[test]
+    fn cbor() {
+        let sad = map(json!({"t": "icp", "s": 24, "n": -1, "f": 1.5, "a": [null, true]}));
+        assert_eq!(
+            to_cbor(&sad),
+            [
+                vec![0xa5, 0x61, b't', 0x63, b'i', b'c', b'p'],
+                vec![0x61, b's', 0x18, 24, 0x61, b'n', 0x20],
+                vec![0x61, b'f', 0xfb, 0x3f, 0xf8, 0, 0, 0, 0, 0, 0],
+                vec![0x61, b'a', 0x82, 0xf6, 0xf5],
+            ]
+            .concat()
+        );
+        let long = map(json!({"l": "x".repeat(300)}));
+        assert_eq!(to_cbor(&long)[3..6], [0x79, 0x01, 0x2c]);
+        let parsed: Map<String, Value> = serde_cbor::from_slice(&to_cbor(&sad)).unwrap();
+        assert_eq!(parsed, sad);
+    }
+
+    #[test]
+    fn mgpk() {
+        let sad = map(json!({"t": "icp", "s": 200, "n": -33, "f": 1.5, "a": [null, false]}));
+        assert_eq!(
+            to_mgpk(&sad),
+            [
+                vec![0x85, 0xa1, b't', 0xa3, b'i', b'c', b'p'],
+                vec![0xa1, b's', 0xcc, 200, 0xa1, b'n', 0xd0, 0xdf],
+                vec![0xa1, b'f', 0xcb, 0x3f, 0xf8, 0, 0, 0, 0, 0, 0],
+                vec![0xa1, b'a', 0x92, 0xc0, 0xc2],
+            ]
+            .concat()
+        );
+        let long = map(json!({"l": "x".repeat(40), "i": -100000}));
+        let raw = to_mgpk(&long);
+        assert_eq!(raw[3..5], [0xd9, 40]);
+        assert_eq!(raw[raw.len() - 5..], [0xd2, 0xff, 0xfe, 0x79, 0x60]);
+        let parsed: Map<String, Value> = rmp_serde::from_slice(&raw).unwrap();
+        assert_eq!(parsed, long);
+        assert!(serialize(&sad, SerializationKind::CESR).is_err());
+    }
+}
@@ -0,0 +1,321 @@
+use crate::{
+    derivation::self_addressing::SelfAddressing,
+    error::Error,
+    prefix::SelfAddressingPrefix,
+    said, ser,
+    version::{SerializationKind, VersionString},
+};
+use core::str::FromStr;
+use serde_json::{Map, Value};
+
+/// Label of the version string field.
+pub(crate) const VERSION_LABEL: &str = "v";
+
+/// Label of the SAID field.
+const SAID_LABEL: &str = "d";
+
+/// Label of the identifier prefix field, which is also a SAID for self-addressing identifiers.
+const PREFIX_LABEL: &str = "i";
+
+/// A self-framing serialized message together with its deserialized fields.
+///
+/// The version string, the value of the first field `v`, tells the serialization kind and the
+/// size of the message, so a message can be cut out of a stream without knowing its schema.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Serder {
+    raw: Vec<u8>,
+    version: VersionString,
+    ked: Map<String, Value>,
+}
+
+impl Serder {
+    /// Parses the message at the start of `stream`, which may continue past the message.
+    pub fn from_raw(stream: &[u8]) -> Result<Self, Error> {
+        let version = VersionString::find(stream)?;
+        let raw = stream.get(..version.size).ok_or_else(|| {
+            Error::DeserializeError(format!(
+                "Message of {} bytes truncated to {} bytes",
+                version.size,
+                stream.len()
+            ))
+        })?;
+        let ked = deserialize(raw, version.kind)?;
+        if ked.get(VERSION_LABEL).and_then(Value::as_str) != Some(&version.to_str()?) {
+            return Err(Error::DeserializeError(
+                "Version string is not the first field".into(),
+            ));
+        }
+        Ok(Self {
+            raw: raw.to_vec(),
+            version,
+            ked,
+        })
+    }
+
+    /// Serializes `ked` as `kind`, writing the serialization kind and size into its version
+    /// string. The protocol and version are taken from the version string already in `ked`.
+    pub fn from_ked(mut ked: Map<String, Value>, kind: SerializationKind) -> Result<Self, Error> {
+        let (version, raw) = sizeify(&mut ked, kind)?;
+        Ok(Self { raw, version, ked })
+    }
+
+    /// The serialized message.
+    pub fn raw(&self) -> &[u8] {
+        &self.raw
+    }
+
+    pub fn version(&self) -> &VersionString {
+        &self.version
+    }
+
+    pub fn kind(&self) -> SerializationKind {
+        self.version.kind
+    }
+
+    /// The fields of the message in serialization order.
+    pub fn ked(&self) -> &Map<String, Value> {
+        &self.ked
+    }
+
+    pub fn get(&self, label: &str) -> Option<&Value> {
+        self.ked.get(label)
+    }
+
+    /// The message type, e.g. `icp`.
+    pub fn ilk(&self) -> Option<&str> {
+        self.get("t").and_then(Value::as_str)
+    }
+
+    /// The sequence number, a hex string in the message.
+    pub fn sn(&self) -> Result<u64, Error> {
+        let sn = self
+            .get("s")
+            .and_then(Value::as_str)
+            .ok_or_else(|| Error::SemanticError("Missing sequence number field".into()))?;
+        Ok(u64::from_str_radix(sn, 16)?)
+    }
+
+    /// The SAID of the message, the value of its `d` field.
+    pub fn said(&self) -> Result<SelfAddressingPrefix, Error> {
+        self.get(SAID_LABEL)
+            .and_then(Value::as_str)
+            .ok_or_else(|| Error::SemanticError("Missing SAID field".into()))?
+            .parse()
+    }
+
+    /// Computes the SAID of the message with the digest `code`, see [said::compute_said]. The
+    /// identifier prefix is dummied as well when it equals the SAID, as for self-addressing
+    /// identifiers.
+    pub fn compute_said(&self, code: &SelfAddressing) -> Result<SelfAddressingPrefix, Error> {
+        let said = self.get(SAID_LABEL).and_then(Value::as_str);
+        let prefix = self.get(PREFIX_LABEL).and_then(Value::as_str);
+        let labels: &[&str] = match (said, prefix) {
+            (Some(said), Some(prefix)) if said == prefix => &[SAID_LABEL, PREFIX_LABEL],
+            _ => &[SAID_LABEL],
+        };
+        said::compute_said(&self.ked, labels, code, self.kind())
+    }
+
+    /// Checks the SAID of the message matches its content.
+    pub fn verify_said(&self) -> Result<bool, Error> {
+        let said = self.said()?;
+        Ok(self.compute_said(&said.derivation)? == said)
+    }
+}
+
+impl FromStr for Serder {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_raw(s.as_bytes())
+    }
+}
+
+/// Writes the serialization kind and size of `ked` into its version string and serializes it.
+pub(crate) fn sizeify(
+    ked: &mut Map<String, Value>,
+    kind: SerializationKind,
+) -> Result<(VersionString, Vec<u8>), Error> {
+    let mut version = ked
+        .get(VERSION_LABEL)
+        .and_then(Value::as_str)
+        .ok_or_else(|| Error::SemanticError("Missing version string field".into()))?
+        .parse::<VersionString>()?;
+    version.kind = kind;
+    // the size field has a fixed width so sizing the message does not change its size
+    ked.insert(VERSION_LABEL.into(), Value::String(version.to_str()?));
+    version.size = serialize(ked, kind)?.len();
+    ked.insert(VERSION_LABEL.into(), Value::String(version.to_str()?));
+    Ok((version, serialize(ked, kind)?))
+}
+
+pub(crate) fn serialize(
+    ked: &Map<String, Value>,
+    kind: SerializationKind,
+) -> Result<Vec<u8>, Error> {
+    ser::serialize(ked, kind)
+}
+
+pub(crate) fn deserialize(
+    raw: &[u8],
+    kind: SerializationKind,
+) -> Result<Map<String, Value>, Error> {
+    match kind {
+        SerializationKind::JSON => Ok(serde_json::from_slice(raw)?),
+        SerializationKind::CBOR => Ok(serde_cbor::from_slice(raw)?),
+        SerializationKind::MGPK => Ok(rmp_serde::from_slice(raw)?),
+        _ => Err(Error::DeserializeError(format!(
+            "Unsupported serialization kind: {}",
+            kind.to_str()
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prefix::Prefix;
+    use serde_json::json;
+
+    fn ked() -> Map<String, Value> {
+        match json!({
+            "v": "KERI10JSON000000_",
+            "t": "ixn",
+            "d": "",
+            "i": "DErocgXD2RGSyvn3MObcx59jeOsEQhv2TqHirVkzrp0Q",
+            "s": "a",
+            "a": []
+        }) {
+            Value::Object(ked) => ked,
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn from_ked() -> Result<(), Error> {
+        let serder = Serder::from_ked(ked(), SerializationKind::JSON)?;
+        assert_eq!(serder.version().size, serder.raw().len());
+        assert_eq!(
+            serder.raw(),
+            format!(
+                r#"{{"v":"KERI10JSON{:06x}_","t":"ixn","d":"","i":"DErocgXD2RGSyvn3MObcx59jeOsEQhv2TqHirVkzrp0Q","s":"a","a":[]}}"#,
+                serder.raw().len()
+            )
+            .as_bytes()
+        );
+        assert_eq!(serder.ilk(), Some("ixn"));
+        assert_eq!(serder.sn()?, 10);
+        assert!(serder.get("x").is_none());
+
+        let mut ked = ked();
+        ked.remove("v");
+        assert!(Serder::from_ked(ked, SerializationKind::JSON).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn from_raw() -> Result<(), Error> {
+        let serder = Serder::from_ked(ked(), SerializationKind::JSON)?;
+        let stream = [serder.raw(), b"-AAB"].concat();
+        let parsed = Serder::from_raw(&stream)?;
+        assert_eq!(parsed, serder);
+
+        assert!(Serder::from_raw(&serder.raw()[..20]).is_err());
+        // version string not in the first field
+        assert!(Serder::from_str(r#"{"t":"ixn","v":"KERI10JSON000022_"}"#).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn said() -> Result<(), Error> {
+        let serder = Serder::from_ked(ked(), SerializationKind::JSON)?;
+        assert!(serder.said().is_err());
+
+        let said = serder.compute_said(&SelfAddressing::Blake3_256)?;
+        let mut ked = ked();
+        ked.insert("d".into(), Value::String(said.to_str()));
+        let serder = Serder::from_ked(ked, SerializationKind::JSON)?;
+        assert_eq!(serder.said()?, said);
+        assert!(serder.verify_said()?);
+
+        let mut ked = serder.ked().clone();
+        ked.insert("s".into(), Value::String("b".into()));
+        assert!(!Serder::from_ked(ked, SerializationKind::JSON)?.verify_said()?);
+        Ok(())
+    }
+
+    #[test]
+    fn self_addressing_prefix() -> Result<(), Error> {
+        let mut ked = ked();
+        ked.insert("t".into(), Value::String("icp".into()));
+        let serder = Serder::from_ked(ked.clone(), SerializationKind::JSON)?;
+        // the prefix is dummied too once it equals the SAID
+        ked.insert("i".into(), Value::String("".into()));
+        let said = Serder::from_ked(ked.clone(), SerializationKind::JSON)?
+            .compute_said(&SelfAddressing::Blake3_256)?;
+        ked.insert("d".into(), Value::String(said.to_str()));
+        ked.insert("i".into(), Value::String(said.to_str()));
+        let serder_sa = Serder::from_ked(ked, SerializationKind::JSON)?;
+        assert!(serder_sa.verify_said()?);
+        assert_ne!(serder.raw(), serder_sa.raw());
+        Ok(())
+    }
+
+    #[test]
+    fn cbor() -> Result<(), Error> {
+        let serder = Serder::from_ked(ked(), SerializationKind::CBOR)?;
+        assert_eq!(serder.version().to_str()?, "KERI10CBOR000055_");
+        assert_eq!(serder.raw().len(), 0x55);
+        // map of 6 entries, then the version string as the value of `v`
+        assert_eq!(&serder.raw()[..4], &[0xa6, 0x61, b'v', 0x71]);
+        assert_eq!(Serder::from_raw(serder.raw())?, serder);
+
+        let said = serder.compute_said(&SelfAddressing::Blake3_256)?;
+        assert_ne!(
+            said,
+            Serder::from_ked(ked(), SerializationKind::JSON)?
+                .compute_said(&SelfAddressing::Blake3_256)?
+        );
+        let mut ked = ked();
+        ked.insert("d".into(), Value::String(said.to_str()));
+        let serder = Serder::from_ked(ked, SerializationKind::CBOR)?;
+        assert!(serder.verify_said()?);
+        Ok(())
+    }
+
+    #[test]
+    fn mgpk() -> Result<(), Error> {
+        use crate::parser::{sniff, Cold};
+
+        let serder = Serder::from_ked(ked(), SerializationKind::MGPK)?;
+        assert_eq!(serder.version().to_str()?, "KERI10MGPK000055_");
+        // fixed map of 6 entries, then the version string as the value of `v`
+        assert_eq!(&serder.raw()[..4], &[0x86, 0xa1, b'v', 0xb1]);
+        assert_eq!(sniff(serder.raw())?, Cold::MGPK1);
+        assert_eq!(Serder::from_raw(serder.raw())?, serder);
+
+        let said = serder.compute_said(&SelfAddressing::Blake3_256)?;
+        let mut ked = ked();
+        ked.insert("d".into(), Value::String(said.to_str()));
+        let serder = Serder::from_ked(ked.clone(), SerializationKind::MGPK)?;
+        assert!(serder.verify_said()?);
+        assert!(!Serder::from_ked(ked, SerializationKind::CBOR)?.verify_said()?);
+        Ok(())
+    }
+
+    #[test]
+    fn mgpk_big_map() -> Result<(), Error> {
+        use crate::parser::{sniff, Cold};
+
+        let mut ked = ked();
+        for i in 0..10 {
+            ked.insert(format!("x{}", i), Value::String("".into()));
+        }
+        let serder = Serder::from_ked(ked, SerializationKind::MGPK)?;
+        // map16 of 16 entries
+        assert_eq!(&serder.raw()[..3], &[0xde, 0x00, 0x10]);
+        assert_eq!(sniff(serder.raw())?, Cold::MGPK2);
+        assert_eq!(Serder::from_raw(serder.raw())?, serder);
+        Ok(())
+    }
+}
@@ -0,0 +1,485 @@
+//! [proptest] strategies generating random spec valid primitives, counters and attachment groups,
+//! for every supported code. Each type implements [Arbitrary], so `any::<BasicPrefix>()` gives
+//! basic prefixes under all the basic codes.
+
+#[cfg(test)]
+use crate::keys::{PrivateKey, Signer};
+use crate::{
+    attachments::{
+        Attachments, AttachmentsBuilder, EventSeal, FirstSeenReplayCouple, SourceSeal,
+        TransIdxSigGroup, TransReceiptQuadruple,
+    },
+    derivation::{
+        attached_signature_code::{AttachedSignatureCode, Indexer},
+        basic::Basic,
+        counter::{Counter, CounterCode},
+        number::NumberCode,
+        self_addressing::SelfAddressing,
+        self_signing::SelfSigning,
+        DerivationCode,
+    },
+    keys::PublicKey,
+    prefix::{
+        AttachedSignaturePrefix, BasicPrefix, Cigar, CipherPrefix, Dater, IdentifierPrefix, Number,
+        SadPath, SeedPrefix, SelfAddressingPrefix, SelfSigningPrefix, Seqner, Siger, Tagger,
+        VariablePrefix,
+    },
+};
+use chrono::{FixedOffset, TimeZone};
+use proptest::{
+    arbitrary::{any, Arbitrary},
+    collection::vec,
+    option,
+    prelude::*,
+    sample::select,
+    strategy::BoxedStrategy,
+};
+
+/// Random raw bytes of the length the fixed size `code` carries.
+pub fn raw(code: &impl DerivationCode) -> impl Strategy<Value = Vec<u8>> {
+    vec(
+        any::<u8>(),
+        code.derivative_b64_len() * 3 / 4 - code.lead_size(),
+    )
+}
+
+macro_rules! arbitrary {
+    ($type:ty, $strategy:expr) => {
+        impl Arbitrary for $type {
+            type Parameters = ();
+            type Strategy = BoxedStrategy<Self>;
+
+            fn arbitrary_with(_: ()) -> Self::Strategy {
+                $strategy.boxed()
+            }
+        }
+    };
+}
+
+arbitrary!(
+    Basic,
+    select(vec![
+        Basic::ECDSAsecp256k1NT,
+        Basic::ECDSAsecp256k1,
+        Basic::Ed25519NT,
+        Basic::Ed25519,
+        Basic::Ed448NT,
+        Basic::Ed448,
+        Basic::X25519,
+        Basic::X448,
+        Basic::ECDSAsecp256r1NT,
+        Basic::ECDSAsecp256r1,
+        Basic::MLDSA44NT,
+        Basic::MLDSA44,
+        Basic::MLDSA65NT,
+        Basic::MLDSA65,
+        Basic::MLDSA87NT,
+        Basic::MLDSA87,
+    ])
+);
+
+arbitrary!(
+    SelfAddressing,
+    select(vec![
+        SelfAddressing::Blake3_256,
+        SelfAddressing::Blake2B256,
+        SelfAddressing::Blake2S256,
+        SelfAddressing::SHA3_256,
+        SelfAddressing::SHA2_256,
+        SelfAddressing::Blake3_512,
+        SelfAddressing::SHA3_512,
+        SelfAddressing::Blake2B512,
+        SelfAddressing::SHA2_512,
+    ])
+);
+
+arbitrary!(
+    SelfSigning,
+    select(vec![
+        SelfSigning::Ed25519Sha512,
+        SelfSigning::ECDSAsecp256k1Sha256,
+        SelfSigning::Ed448,
+        SelfSigning::ECDSAsecp256r1Sha256,
+        SelfSigning::MLDSA44,
+        SelfSigning::MLDSA65,
+        SelfSigning::MLDSA87,
+    ])
+);
+
+arbitrary!(
+    Indexer,
+    select(vec![
+        Indexer::Ed25519Sig,
+        Indexer::Ed25519CrtSig,
+        Indexer::ECDSAsecp256k1Sig,
+        Indexer::ECDSAsecp256k1CrtSig,
+        Indexer::Ed448Sig,
+        Indexer::Ed448CrtSig,
+        Indexer::Ed25519BigSig,
+        Indexer::Ed25519BigCrtSig,
+        Indexer::ECDSAsecp256k1BigSig,
+        Indexer::ECDSAsecp256k1BigCrtSig,
+        Indexer::Ed448BigSig,
+        Indexer::Ed448BigCrtSig,
+        Indexer::ECDSAsecp256r1Sig,
+        Indexer::ECDSAsecp256r1CrtSig,
+        Indexer::ECDSAsecp256r1BigSig,
+        Indexer::ECDSAsecp256r1BigCrtSig,
+    ])
+);
+
+arbitrary!(
+    AttachedSignatureCode,
+    (any::<Indexer>(), any::<u16>(), any::<u16>()).prop_map(|(code, index, ondex)| {
+        let fit = |num: u16, max: u64| (num as u64 % (max.min(u16::MAX as u64) + 1)) as u16;
+        let index = fit(index, code.max_index());
+        let ondex = match (code.is_current_only(), code.other_size()) {
+            (true, _) => None,
+            (false, 0) => Some(index),
+            (false, _) => Some(fit(ondex, code.max_ondex())),
+        };
+        AttachedSignatureCode::with_indexer(code, index, ondex).expect("index and ondex fit")
+    })
+);
+
+arbitrary!(
+    CounterCode,
+    select(vec![
+        CounterCode::ControllerIdxSigs,
+        CounterCode::WitnessIdxSigs,
+        CounterCode::NonTransReceiptCouples,
+        CounterCode::TransReceiptQuadruples,
+        CounterCode::FirstSeenReplayCouples,
+        CounterCode::TransIdxSigGroups,
+        CounterCode::SealSourceCouples,
+        CounterCode::TransLastIdxSigGroups,
+        CounterCode::SealSourceTriples,
+        CounterCode::SadPathSig,
+        CounterCode::SadPathSigGroup,
+        CounterCode::PathedMaterialQuadlets,
+        CounterCode::MessageDataGroups,
+        CounterCode::AttachedMaterialQuadlets,
+        CounterCode::MessageDataMaterialQuadlets,
+        CounterCode::CombinedMaterialQuadlets,
+        CounterCode::MaterialGroups,
+        CounterCode::MaterialQuadlets,
+        CounterCode::BigAttachedMaterialQuadlets,
+        CounterCode::BigMessageDataMaterialQuadlets,
+        CounterCode::BigCombinedMaterialQuadlets,
+        CounterCode::BigMaterialGroups,
+        CounterCode::BigMaterialQuadlets,
+    ])
+);
+
+arbitrary!(
+    Counter,
+    (any::<CounterCode>(), any::<u32>()).prop_map(|(code, count)| {
+        Counter::new(code, count % code.max_count().saturating_add(1)).expect("count fits")
+    })
+);
+
+arbitrary!(NumberCode, select(NumberCode::ALL.to_vec()));
+
+arbitrary!(
+    BasicPrefix,
+    any::<Basic>().prop_flat_map(|code| {
+        raw(&code).prop_map(move |key| BasicPrefix::new_unchecked(code, PublicKey::new(key)))
+    })
+);
+
+arbitrary!(
+    SelfAddressingPrefix,
+    any::<SelfAddressing>().prop_flat_map(|code| {
+        raw(&code).prop_map(move |digest| SelfAddressingPrefix::new_unchecked(code, digest))
+    })
+);
+
+arbitrary!(
+    SelfSigningPrefix,
+    any::<SelfSigning>().prop_flat_map(|code| {
+        raw(&code).prop_map(move |signature| SelfSigningPrefix::new_unchecked(code, signature))
+    })
+);
+
+arbitrary!(
+    IdentifierPrefix,
+    prop_oneof![
+        any::<BasicPrefix>().prop_map(IdentifierPrefix::Basic),
+        any::<SelfAddressingPrefix>().prop_map(IdentifierPrefix::SelfAddressing),
+        any::<SelfSigningPrefix>().prop_map(IdentifierPrefix::SelfSigning),
+    ]
+);
+
+arbitrary!(
+    SeedPrefix,
+    prop_oneof![
+        vec(any::<u8>(), 16).prop_map(SeedPrefix::RandomSeed128),
+        vec(any::<u8>(), 32).prop_map(SeedPrefix::RandomSeed256Ed25519),
+        vec(any::<u8>(), 32).prop_map(SeedPrefix::RandomSeed256ECDSAsecp256k1),
+        vec(any::<u8>(), 57).prop_map(SeedPrefix::RandomSeed448),
+        vec(any::<u8>(), 32).prop_map(SeedPrefix::RandomSeed256X25519),
+        vec(any::<u8>(), 32).prop_map(SeedPrefix::RandomSeed256ECDSAsecp256r1),
+        vec(any::<u8>(), 32).prop_map(SeedPrefix::RandomSeed256),
+    ]
+);
+
+arbitrary!(
+    CipherPrefix,
+    prop_oneof![
+        vec(any::<u8>(), 92).prop_map(CipherPrefix::X25519Seed),
+        vec(any::<u8>(), 72).prop_map(CipherPrefix::X25519Salt),
+    ]
+);
+
+arbitrary!(
+    AttachedSignaturePrefix,
+    any::<AttachedSignatureCode>().prop_flat_map(|code| {
+        raw(&code.code.signature())
+            .prop_map(move |signature| AttachedSignaturePrefix::with_code(code, signature))
+    })
+);
+
+arbitrary!(
+    Siger,
+    any::<AttachedSignaturePrefix>().prop_map(Siger::from)
+);
+
+arbitrary!(
+    Cigar,
+    (any::<BasicPrefix>(), any::<SelfSigningPrefix>())
+        .prop_map(|(verfer, signature)| Cigar::new(verfer, signature))
+);
+
+arbitrary!(
+    Number,
+    (any::<NumberCode>(), any::<u128>()).prop_map(|(code, num)| {
+        let num = match code.raw_len() {
+            len if len >= 16 => num,
+            len => num % (1 << (8 * len)),
+        };
+        Number::with_code(code, num).expect("number fits")
+    })
+);
+
+arbitrary!(Seqner, any::<u128>().prop_map(Seqner::new));
+
+arbitrary!(
+    Tagger,
+    "[A-Za-z0-9_-]{1,11}".prop_map(|tag| Tagger::new(&tag).expect("Base64 tag"))
+);
+
+arbitrary!(
+    SadPath,
+    vec("[A-Za-z0-9_]{1,8}", 0..4).prop_map(|segments| SadPath::new(segments).expect("segments"))
+);
+
+arbitrary!(
+    VariablePrefix,
+    prop_oneof![
+        vec(any::<u8>(), 0..200).prop_map(|raw| VariablePrefix::bytes(raw).expect("size fits")),
+        "[B-Za-z0-9_-][A-Za-z0-9_-]{0,199}"
+            .prop_map(|b64| VariablePrefix::text(&b64).expect("Base64 text")),
+    ]
+);
+
+// datetimes from year 0000 to 9999 under offsets of whole minutes
+arbitrary!(
+    Dater,
+    (
+        -62_167_219_200i64..253_402_300_800,
+        0u32..1_000_000,
+        -14 * 60i32..=14 * 60
+    )
+        .prop_filter_map("four digit years", |(secs, micros, offset)| {
+            FixedOffset::east_opt(offset * 60)
+                .expect("offset within a day")
+                .timestamp_opt(secs, micros * 1000)
+                .single()
+                .expect("datetime in range")
+                .try_into()
+                .ok()
+        })
+);
+
+arbitrary!(
+    TransReceiptQuadruple,
+    (
+        any::<IdentifierPrefix>(),
+        any::<u64>(),
+        any::<SelfAddressingPrefix>(),
+        any::<Siger>()
+    )
+        .prop_map(|(prefix, sn, digest, signature)| TransReceiptQuadruple {
+            prefix,
+            sn,
+            digest,
+            signature,
+        })
+);
+
+arbitrary!(
+    TransIdxSigGroup,
+    (
+        any::<IdentifierPrefix>(),
+        any::<u64>(),
+        any::<SelfAddressingPrefix>(),
+        vec(any::<Siger>(), 1..4)
+    )
+        .prop_map(|(prefix, sn, digest, signatures)| TransIdxSigGroup {
+            prefix,
+            sn,
+            digest,
+            signatures,
+        })
+);
+
+arbitrary!(
+    SourceSeal,
+    (any::<u64>(), any::<SelfAddressingPrefix>()).prop_map(|(sn, said)| SourceSeal { sn, said })
+);
+
+arbitrary!(
+    EventSeal,
+    (
+        any::<IdentifierPrefix>(),
+        any::<u64>(),
+        any::<SelfAddressingPrefix>()
+    )
+        .prop_map(|(prefix, sn, said)| EventSeal { prefix, sn, said })
+);
+
+arbitrary!(
+    FirstSeenReplayCouple,
+    (any::<u64>(), any::<Dater>()).prop_map(|(first_seen, datetime)| FirstSeenReplayCouple {
+        first_seen,
+        datetime
+    })
+);
+
+/// Attachment groups without pathed material, the members of a pathed material group.
+fn flat_attachments() -> impl Strategy<Value = Attachments> {
+    (
+        vec(any::<Siger>(), 0..3),
+        // witnesses sign under current only codes
+        vec(
+            any::<Siger>().prop_map(|siger| Siger {
+                code: siger.code.to_current_only(),
+                ..siger
+            }),
+            0..3,
+        ),
+        vec(any::<Cigar>(), 0..3),
+        vec(any::<TransReceiptQuadruple>(), 0..2),
+        vec(any::<TransIdxSigGroup>(), 0..2),
+        vec(any::<SourceSeal>(), 0..3),
+        vec(any::<EventSeal>(), 0..2),
+        vec(any::<FirstSeenReplayCouple>(), 0..3),
+    )
+        .prop_map(
+            |(controller, witness, couples, quadruples, groups, seals, triples, replays)| {
+                Attachments {
+                    controller_signatures: controller,
+                    witness_signatures: witness,
+                    receipt_couples: couples,
+                    trans_receipt_quadruples: quadruples,
+                    trans_idx_sig_groups: groups,
+                    seal_source_couples: seals,
+                    seal_source_triples: triples,
+                    first_seen_replay_couples: replays,
+                    pathed_material: vec![],
+                }
+            },
+        )
+}
+
+arbitrary!(
+    Attachments,
+    (
+        flat_attachments(),
+        option::of(vec((any::<SadPath>(), flat_attachments()), 1..3))
+    )
+        .prop_map(|(attachments, pathed_material)| Attachments {
+            pathed_material: pathed_material.unwrap_or_default(),
+            ..attachments
+        })
+        // a few ML-DSA primitives outgrow the count of a pathed material group
+        .prop_filter("pathed material too big", |attachments| {
+            AttachmentsBuilder::from(attachments.clone())
+                .build()
+                .is_ok()
+        })
+);
+
+/// An Ed25519 signer with a seed of 32 `seed` bytes, the signer of the unit tests.
+#[cfg(test)]
+pub(crate) fn signer(seed: u8, transferable: bool) -> Signer {
+    Signer::new(
+        SelfSigning::Ed25519Sha512,
+        PrivateKey::new(vec![seed; 32]),
+        transferable,
+    )
+    .expect("32 byte Ed25519 seed")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prefix::Prefix;
+    use core::{fmt::Debug, str::FromStr};
+
+    fn round_trip<P: Prefix + PartialEq + Debug>(prefix: P) -> Result<(), TestCaseError> {
+        let qb64 = prefix.to_str();
+        let parsed = P::from_str(&qb64).map_err(|e| TestCaseError::fail(e.to_string()))?;
+        prop_assert_eq!(&parsed, &prefix, "{}", qb64);
+        let parsed =
+            P::from_qb64b(&prefix.to_qb64b()).map_err(|e| TestCaseError::fail(e.to_string()))?;
+        prop_assert_eq!(parsed, prefix, "{}", qb64);
+        Ok(())
+    }
+
+    proptest! {
+        #[test]
+        fn primitives(
+            basic in any::<BasicPrefix>(),
+            digest in any::<SelfAddressingPrefix>(),
+            signature in any::<SelfSigningPrefix>(),
+            identifier in any::<IdentifierPrefix>(),
+            seed in any::<SeedPrefix>(),
+            cipher in any::<CipherPrefix>(),
+            indexed in any::<AttachedSignaturePrefix>(),
+        ) {
+            round_trip(basic)?;
+            round_trip(digest)?;
+            round_trip(signature)?;
+            round_trip(identifier)?;
+            round_trip(seed)?;
+            round_trip(cipher)?;
+            round_trip(indexed)?;
+        }
+
+        #[test]
+        fn material(
+            number in any::<Number>(),
+            seqner in any::<Seqner>(),
+            tagger in any::<Tagger>(),
+            path in any::<SadPath>(),
+            variable in any::<VariablePrefix>(),
+            dater in any::<Dater>(),
+            counter in any::<Counter>(),
+        ) {
+            round_trip(number)?;
+            round_trip(seqner)?;
+            round_trip(tagger)?;
+            round_trip(path)?;
+            round_trip(variable)?;
+            round_trip(dater)?;
+            prop_assert_eq!(Counter::from_str(&counter.to_str()).unwrap(), counter);
+        }
+
+        #[test]
+        fn attachments(attachments in any::<Attachments>()) {
+            let stream = AttachmentsBuilder::from(attachments.clone()).build().unwrap();
+            prop_assert_eq!(Attachments::from_str(&stream).unwrap(), attachments);
+        }
+    }
+}
@@ -0,0 +1,341 @@
+use crate::{error::Error, prefix::AttachedSignaturePrefix};
+use core::{fmt, str::FromStr};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// A weight of a weighted threshold, a fraction between 0 and 1 like `1/2`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct Weight {
+    num: u64,
+    den: u64,
+}
+
+impl Weight {
+    pub fn new(num: u64, den: u64) -> Result<Self, Error> {
+        if den == 0 || num > den {
+            return Err(Error::SemanticError(format!(
+                "Invalid weight: {}/{}",
+                num, den
+            )));
+        }
+        let gcd = gcd(num, den);
+        Ok(Self {
+            num: num / gcd,
+            den: den / gcd,
+        })
+    }
+
+    pub fn numerator(&self) -> u64 {
+        self.num
+    }
+
+    pub fn denominator(&self) -> u64 {
+        self.den
+    }
+}
+
+impl FromStr for Weight {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || Error::DeserializeError(format!("Invalid weight: {}", s));
+        let (num, den) = s.split_once('/').unwrap_or((s, "1"));
+        let parse = |n: &str| match n.bytes().all(|b| b.is_ascii_digit()) {
+            true => n.parse::<u64>().map_err(|_| invalid()),
+            false => Err(invalid()),
+        };
+        Self::new(parse(num)?, parse(den)?).map_err(|_| invalid())
+    }
+}
+
+impl fmt::Display for Weight {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.den {
+            1 => write!(f, "{}", self.num),
+            den => write!(f, "{}/{}", self.num, den),
+        }
+    }
+}
+
+/// Signing threshold of a key list, the `kt` and `nt` fields of establishment events.
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub enum SignatureThreshold {
+    /// At least this many keys must sign, serialized as a hex string.
+    Simple(u64),
+    /// The weights of the keys, in clauses which must all be satisfied. A clause is satisfied
+    /// when the weights of the keys that signed add up to at least 1. Key indices run on from one
+    /// clause into the next.
+    Weighted(Vec<Vec<Weight>>),
+}
+
+impl SignatureThreshold {
+    /// Weighted threshold of a single clause.
+    pub fn weighted(weights: Vec<Weight>) -> Result<Self, Error> {
+        Self::clauses(vec![weights])
+    }
+
+    /// Weighted threshold of several clauses, each of which must be satisfiable on its own.
+    pub fn clauses(clauses: Vec<Vec<Weight>>) -> Result<Self, Error> {
+        if clauses.is_empty() {
+            return Err(Error::SemanticError("Empty weighted threshold".into()));
+        }
+        for clause in &clauses {
+            let all: Vec<usize> = (0..clause.len()).collect();
+            if !clause_satisfied(clause, 0, &all)? {
+                return Err(Error::SemanticError(format!(
+                    "Unsatisfiable threshold clause: {}",
+                    clause_to_strings(clause).join(",")
+                )));
+            }
+        }
+        Ok(Self::Weighted(clauses))
+    }
+
+    /// Number of keys a weighted threshold weighs, `None` for simple thresholds.
+    pub fn key_count(&self) -> Option<usize> {
+        match self {
+            Self::Simple(_) => None,
+            Self::Weighted(clauses) => Some(clauses.iter().map(Vec::len).sum()),
+        }
+    }
+
+    /// Whether signatures of the keys at `indices` satisfy the threshold. Repeated indices count
+    /// once.
+    pub fn satisfied(&self, indices: &[u16]) -> bool {
+        let mut indices: Vec<usize> = indices.iter().map(|i| *i as usize).collect();
+        indices.sort_unstable();
+        indices.dedup();
+        match self {
+            Self::Simple(threshold) => indices.len() as u64 >= *threshold,
+            Self::Weighted(clauses) => {
+                let mut start = 0;
+                clauses.iter().all(|clause| {
+                    let satisfied = clause_satisfied(clause, start, &indices).unwrap_or(false);
+                    start += clause.len();
+                    satisfied
+                })
+            }
+        }
+    }
+
+    /// Whether the indexed signatures satisfy the threshold, the signatures are assumed to be
+    /// verified.
+    pub fn satisfied_by(&self, signatures: &[AttachedSignaturePrefix]) -> bool {
+        let indices: Vec<u16> = signatures.iter().map(|sig| sig.code.index).collect();
+        self.satisfied(&indices)
+    }
+}
+
+/// Whether the weights of `clause` at the `indices` from `start` on add up to at least 1. Fails if
+/// the common denominator of the clause does not fit 64 bits.
+fn clause_satisfied(clause: &[Weight], start: usize, indices: &[usize]) -> Result<bool, Error> {
+    let lcm = clause.iter().try_fold(1u64, |lcm, weight| {
+        (lcm / gcd(lcm, weight.den))
+            .checked_mul(weight.den)
+            .ok_or_else(|| Error::SemanticError("Threshold weights too fine".into()))
+    })?;
+    let sum: u128 = indices
+        .iter()
+        .filter_map(|i| clause.get(i.checked_sub(start)?))
+        .map(|weight| weight.num as u128 * (lcm / weight.den) as u128)
+        .sum();
+    Ok(sum >= lcm as u128)
+}
+
+fn gcd(a: u64, b: u64) -> u64 {
+    match b {
+        0 => a.max(1),
+        b => gcd(b, a % b),
+    }
+}
+
+fn clause_to_strings(clause: &[Weight]) -> Vec<String> {
+    clause.iter().map(Weight::to_string).collect()
+}
+
+/// Parses a hex simple threshold like `2`, or a weighted one in its JSON form like
+/// `["1/2","1/2","1/2"]`.
+impl FromStr for SignatureThreshold {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.starts_with('[') {
+            return serde_json::from_str(s)
+                .map_err(|_| Error::DeserializeError(format!("Invalid threshold: {}", s)));
+        }
+        match !s.is_empty() && s.bytes().all(|b| b.is_ascii_hexdigit()) {
+            true => u64::from_str_radix(s, 16)
+                .map(Self::Simple)
+                .map_err(|_| Error::DeserializeError(format!("Invalid threshold: {}", s))),
+            false => Err(Error::DeserializeError(format!("Invalid threshold: {}", s))),
+        }
+    }
+}
+
+impl fmt::Display for SignatureThreshold {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Simple(threshold) => write!(f, "{:x}", threshold),
+            Self::Weighted(_) => write!(
+                f,
+                "{}",
+                serde_json::to_string(self).map_err(|_| fmt::Error)?
+            ),
+        }
+    }
+}
+
+/// Serializes simple thresholds as hex strings, and weighted thresholds of a single clause as a
+/// flat list.
+impl Serialize for SignatureThreshold {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            Self::Simple(threshold) => serializer.serialize_str(&format!("{:x}", threshold)),
+            Self::Weighted(clauses) if clauses.len() == 1 => {
+                clause_to_strings(&clauses[0]).serialize(serializer)
+            }
+            Self::Weighted(clauses) => clauses
+                .iter()
+                .map(|clause| clause_to_strings(clause))
+                .collect::<Vec<_>>()
+                .serialize(serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for SignatureThreshold {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Repr {
+            Int(u64),
+            Hex(String),
+            Clause(Vec<String>),
+            Clauses(Vec<Vec<String>>),
+        }
+
+        let weights = |clause: Vec<String>| -> Result<Vec<Weight>, Error> {
+            clause.iter().map(|w| w.parse()).collect()
+        };
+        match Repr::deserialize(deserializer)? {
+            Repr::Int(threshold) => Ok(Self::Simple(threshold)),
+            Repr::Hex(hex) if !hex.starts_with('[') => hex.parse(),
+            Repr::Hex(hex) => Err(Error::DeserializeError(format!(
+                "Invalid threshold: {}",
+                hex
+            ))),
+            Repr::Clause(clause) => weights(clause).and_then(Self::weighted),
+            Repr::Clauses(clauses) => clauses
+                .into_iter()
+                .map(weights)
+                .collect::<Result<_, _>>()
+                .and_then(Self::clauses),
+        }
+        .map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn simple() -> Result<(), Error> {
+        let threshold: SignatureThreshold = "2".parse()?;
+        assert_eq!(threshold, SignatureThreshold::Simple(2));
+        assert!(!threshold.satisfied(&[0]));
+        assert!(!threshold.satisfied(&[1, 1]));
+        assert!(threshold.satisfied(&[0, 2]));
+        assert_eq!(threshold.key_count(), None);
+
+        let threshold: SignatureThreshold = "a".parse()?;
+        assert_eq!(threshold, SignatureThreshold::Simple(10));
+        assert_eq!(serde_json::to_string(&threshold)?, r#""a""#);
+        assert_eq!(
+            serde_json::from_str::<SignatureThreshold>("3")?,
+            "3".parse()?
+        );
+        assert!("".parse::<SignatureThreshold>().is_err());
+        assert!("g".parse::<SignatureThreshold>().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn weighted() -> Result<(), Error> {
+        // taken from KERIPY: tests/core/test_weighted_threshold.py
+        let threshold: SignatureThreshold = r#"["1/2","1/2","1/2"]"#.parse()?;
+        assert_eq!(threshold.key_count(), Some(3));
+        assert!(!threshold.satisfied(&[]));
+        assert!(!threshold.satisfied(&[1]));
+        assert!(!threshold.satisfied(&[1, 1]));
+        assert!(threshold.satisfied(&[0, 2]));
+        assert!(threshold.satisfied(&[0, 1, 2]));
+        // keys beyond the threshold weigh nothing
+        assert!(!threshold.satisfied(&[0, 3]));
+
+        let threshold: SignatureThreshold =
+            r#"[["1/2","1/2","1/4","1/4","1/4"],["1","1"]]"#.parse()?;
+        assert_eq!(threshold.key_count(), Some(7));
+        assert!(threshold.satisfied(&[0, 2, 4, 5]));
+        assert!(!threshold.satisfied(&[2, 3, 4, 6]));
+        assert!(threshold.satisfied(&[0, 1, 6]));
+        assert!(!threshold.satisfied(&[0, 1]));
+        assert!(!threshold.satisfied(&[5, 6]));
+        assert_eq!(
+            threshold.to_string(),
+            r#"[["1/2","1/2","1/4","1/4","1/4"],["1","1"]]"#
+        );
+        assert_eq!(
+            serde_json::from_str::<SignatureThreshold>(&serde_json::to_string(&threshold)?)?,
+            threshold
+        );
+        assert_eq!(
+            serde_json::to_string(&SignatureThreshold::weighted(vec![
+                "2/4".parse()?,
+                "1/2".parse()?
+            ])?)?,
+            r#"["1/2","1/2"]"#
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn invalid() {
+        for threshold in [
+            r#"["1/3","1/3"]"#,
+            r#"["3/2"]"#,
+            r#"["1/0","1"]"#,
+            r#"["1/2","x"]"#,
+            r#"["-1","1"]"#,
+            r#"[["1"],[]]"#,
+            "[]",
+            r#"[["1"],["1/2"]]"#,
+        ] {
+            assert!(
+                threshold.parse::<SignatureThreshold>().is_err(),
+                "{}",
+                threshold
+            );
+        }
+        assert!(serde_json::from_str::<SignatureThreshold>(r#""[1]""#).is_err());
+    }
+
+    #[test]
+    fn signatures() -> Result<(), Error> {
+        use crate::derivation::self_signing::SelfSigning;
+
+        let sig = |index| {
+            AttachedSignaturePrefix::new(SelfSigning::Ed25519Sha512, vec![0; 64], index).unwrap()
+        };
+        let threshold: SignatureThreshold = r#"["1/2","1/4","1/4"]"#.parse()?;
+        assert!(threshold.satisfied_by(&[sig(0), sig(1), sig(2)]));
+        assert!(!threshold.satisfied_by(&[sig(1), sig(0)]));
+        assert!(SignatureThreshold::Simple(1).satisfied_by(&[sig(5)]));
+        Ok(())
+    }
+}
@@ -0,0 +1,171 @@
+use crate::{
+    error::Error,
+    prefix::{AttachedSignaturePrefix, BasicPrefix, OpaqueMatter, Prefix, SelfSigningPrefix},
+    said,
+    version::SerializationKind,
+};
+use core::str::FromStr;
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+/// A named test vector of a shared conformance suite, as a JSON object with a `name` and a
+/// `type` telling what else it holds.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct TestVector {
+    pub name: String,
+    #[serde(flatten)]
+    pub vector: Vector,
+}
+
+/// What a test vector checks.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum Vector {
+    /// A primitive of the master code table, its raw material and binary domain form in hex
+    /// when given.
+    Primitive {
+        qb64: String,
+        #[serde(default)]
+        raw: Option<String>,
+        #[serde(default)]
+        qb2: Option<String>,
+    },
+    /// Self-addressing data whose `label` field, `d` unless given, is its SAID in compact JSON.
+    Said {
+        sad: Map<String, Value>,
+        #[serde(default = "said_label")]
+        label: String,
+    },
+    /// A signature, indexed or not, of the UTF-8 `message` by the basic prefix `key`.
+    Signature {
+        key: String,
+        message: String,
+        signature: String,
+    },
+}
+
+fn said_label() -> String {
+    "d".into()
+}
+
+/// Parses a test vector file, a JSON array of test vectors.
+pub fn load(json: &str) -> Result<Vec<TestVector>, Error> {
+    Ok(serde_json::from_str(json)?)
+}
+
+/// Checks every vector of a test vector file, failing on the first disagreement. Returns how
+/// many vectors were checked.
+pub fn check_all(json: &str) -> Result<usize, Error> {
+    let vectors = load(json)?;
+    vectors.iter().try_for_each(TestVector::check)?;
+    Ok(vectors.len())
+}
+
+impl TestVector {
+    /// Checks this crate agrees with the vector: primitives round trip through both domains
+    /// unchanged, SAIDs match their data and signatures verify.
+    pub fn check(&self) -> Result<(), Error> {
+        let agrees = match &self.vector {
+            Vector::Primitive { qb64, raw, qb2 } => {
+                let matter = OpaqueMatter::from_str(qb64)?;
+                let binary = matter.to_qb2()?;
+                matter.to_str() == *qb64
+                    && OpaqueMatter::from_qb2(&binary)? == matter
+                    && raw
+                        .as_deref()
+                        .map_or(Ok(true), |raw| unhex(raw).map(|raw| raw == matter.raw))?
+                    && qb2
+                        .as_deref()
+                        .map_or(Ok(true), |qb2| unhex(qb2).map(|qb2| qb2 == binary))?
+            }
+            Vector::Said { sad, label } => said::verify_said(sad, label, SerializationKind::JSON)?,
+            Vector::Signature {
+                key,
+                message,
+                signature,
+            } => {
+                let signature = match SelfSigningPrefix::from_str(signature) {
+                    Ok(signature) => signature,
+                    Err(_) => AttachedSignaturePrefix::from_str(signature)?.signature,
+                };
+                BasicPrefix::from_str(key)?.verify(message.as_bytes(), &signature)?
+            }
+        };
+        if agrees {
+            Ok(())
+        } else {
+            Err(Error::SemanticError(format!(
+                "Disagreement with test vector {}",
+                self.name
+            )))
+        }
+    }
+}
+
+fn unhex(hex: &str) -> Result<Vec<u8>, Error> {
+    if !hex.len().is_multiple_of(2) {
+        return Err(Error::DeserializeError(format!("Odd length hex: {}", hex)));
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| {
+            hex.get(i..i + 2)
+                .and_then(|byte| u8::from_str_radix(byte, 16).ok())
+                .ok_or_else(|| Error::DeserializeError(format!("Invalid hex: {}", hex)))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{derivation::self_addressing::SelfAddressing, test_utils::signer};
+    use serde_json::json;
+
+    #[test]
+    fn conformance() -> Result<(), Error> {
+        let signer = signer(1, true);
+        let signature = signer.sign(b"abc");
+        let mut sad = json!({"d": "", "name": "vector"})
+            .as_object()
+            .unwrap()
+            .clone();
+        let (said, _) = said::saidify(
+            &sad,
+            "d",
+            &SelfAddressing::Blake3_256,
+            SerializationKind::JSON,
+        )?;
+        sad.insert("d".into(), said.to_str().into());
+        let file = json!([
+            {"name": "number", "type": "primitive", "qb64": "MAAB", "raw": "0001", "qb2": "300001"},
+            {"name": "digest", "type": "primitive", "qb64": said.to_str()},
+            {"name": "said", "type": "said", "sad": sad},
+            {
+                "name": "signature",
+                "type": "signature",
+                "key": signer.basic_prefix().to_str(),
+                "message": "abc",
+                "signature": signature.to_str()
+            }
+        ])
+        .to_string();
+        assert_eq!(check_all(&file)?, 4);
+
+        let vectors = load(&file)?;
+        let mut wrong = vectors[0].clone();
+        wrong.vector = Vector::Primitive {
+            qb64: "MAAB".into(),
+            raw: Some("0002".into()),
+            qb2: None,
+        };
+        assert!(wrong.check().is_err());
+        let mut wrong = vectors[3].clone();
+        if let Vector::Signature { message, .. } = &mut wrong.vector {
+            *message = "abd".into();
+        }
+        assert!(wrong.check().is_err());
+        assert!(load(r#"[{"name": "unknown", "type": "cipher"}]"#).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,344 @@
+use crate::{
+    derivation::{b64_to_u64, u64_to_b64},
+    error::Error,
+};
+use core::str::FromStr;
+
+/// Maximum offset of the version string from the start of a serialized message.
+/// Room for the opening of a map and the label of the version field, e.g. `{"v":"`.
+pub const MAX_VERSION_OFFSET: usize = 12;
+
+/// Length of a version 1 version string, e.g. `KERI10JSON0000fd_`.
+pub const VERSION_1_LEN: usize = 17;
+
+/// Length of a version 2 version string, e.g. `KERICAACAAJSONAAD9.`.
+pub const VERSION_2_LEN: usize = 19;
+
+/// Protocols carried in self-framing messages.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum Protocol {
+    KERI,
+    ACDC,
+}
+
+impl Protocol {
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            Self::KERI => "KERI",
+            Self::ACDC => "ACDC",
+        }
+    }
+}
+
+impl FromStr for Protocol {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "KERI" => Ok(Self::KERI),
+            "ACDC" => Ok(Self::ACDC),
+            _ => Err(Error::DeserializeError(format!("Unknown protocol: {}", s))),
+        }
+    }
+}
+
+/// Serialization kinds of self-framing messages.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum SerializationKind {
+    JSON,
+    CBOR,
+    MGPK,
+    CESR,
+}
+
+impl SerializationKind {
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            Self::JSON => "JSON",
+            Self::CBOR => "CBOR",
+            Self::MGPK => "MGPK",
+            Self::CESR => "CESR",
+        }
+    }
+}
+
+impl FromStr for SerializationKind {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "JSON" => Ok(Self::JSON),
+            "CBOR" => Ok(Self::CBOR),
+            "MGPK" => Ok(Self::MGPK),
+            "CESR" => Ok(Self::CESR),
+            _ => Err(Error::DeserializeError(format!(
+                "Unknown serialization kind: {}",
+                s
+            ))),
+        }
+    }
+}
+
+/// A major and minor version number.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, PartialOrd, Ord)]
+pub struct Version {
+    pub major: u8,
+    pub minor: u16,
+}
+
+impl Version {
+    pub fn new(major: u8, minor: u16) -> Self {
+        Self { major, minor }
+    }
+}
+
+/// The version string of a self-framing message, which tells its protocol, protocol version,
+/// serialization kind and size in bytes.
+///
+/// Two forms are supported:
+/// * version 1, `PPPPvvKKKKssssss_`: protocol, major and minor version as hex digits,
+///   serialization kind and size as six hex digits, e.g. `KERI10JSON0000fd_`.
+/// * version 2, `PPPPVvvGggKKKKssss.`: protocol, major and minor protocol version, major and minor
+///   genus version, serialization kind and size as four Base64 digits, e.g. `KERICAACAAJSONAAD9.`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct VersionString {
+    pub protocol: Protocol,
+    pub version: Version,
+    /// Version of the CESR code tables, only carried by version 2 version strings.
+    pub genus_version: Option<Version>,
+    pub kind: SerializationKind,
+    pub size: usize,
+}
+
+impl VersionString {
+    /// Version 1 version string.
+    pub fn new(protocol: Protocol, version: Version, kind: SerializationKind, size: usize) -> Self {
+        Self {
+            protocol,
+            version,
+            genus_version: None,
+            kind,
+            size,
+        }
+    }
+
+    /// Version 2 version string.
+    pub fn with_genus(
+        protocol: Protocol,
+        version: Version,
+        genus_version: Version,
+        kind: SerializationKind,
+        size: usize,
+    ) -> Self {
+        Self {
+            genus_version: Some(genus_version),
+            ..Self::new(protocol, version, kind, size)
+        }
+    }
+
+    /// Length of the version string in characters.
+    pub fn span(&self) -> usize {
+        match self.genus_version {
+            None => VERSION_1_LEN,
+            Some(_) => VERSION_2_LEN,
+        }
+    }
+
+    /// Emits the version string, failing when a number does not fit its field.
+    pub fn to_str(&self) -> Result<String, Error> {
+        let too_big = |what: &str| Error::SemanticError(format!("{} too big: {:?}", what, self));
+        match self.genus_version {
+            None => {
+                if self.version.major > 0xf || self.version.minor > 0xf {
+                    return Err(too_big("Version"));
+                }
+                if self.size > 0xff_ffff {
+                    return Err(too_big("Size"));
+                }
+                Ok(format!(
+                    "{}{:x}{:x}{}{:06x}_",
+                    self.protocol.to_str(),
+                    self.version.major,
+                    self.version.minor,
+                    self.kind.to_str(),
+                    self.size
+                ))
+            }
+            Some(genus) => {
+                let fits = |v: &Version| v.major < 64 && v.minor < 64 * 64;
+                if !fits(&self.version) || !fits(&genus) {
+                    return Err(too_big("Version"));
+                }
+                if self.size >= 1 << 24 {
+                    return Err(too_big("Size"));
+                }
+                let b64 = |v: &Version| {
+                    [u64_to_b64(v.major as u64, 1), u64_to_b64(v.minor as u64, 2)].join("")
+                };
+                Ok(format!(
+                    "{}{}{}{}{}.",
+                    self.protocol.to_str(),
+                    b64(&self.version),
+                    b64(&genus),
+                    self.kind.to_str(),
+                    u64_to_b64(self.size as u64, 4)
+                ))
+            }
+        }
+    }
+
+    /// Looks up the version string within the start of a serialized message, where it is
+    /// expected to be the value of the first field.
+    pub fn find(message: &[u8]) -> Result<Self, Error> {
+        (0..=MAX_VERSION_OFFSET)
+            .flat_map(|offset| {
+                [VERSION_1_LEN, VERSION_2_LEN]
+                    .into_iter()
+                    .filter_map(move |len| message.get(offset..offset + len))
+            })
+            .find_map(|vs| std::str::from_utf8(vs).ok()?.parse().ok())
+            .ok_or_else(|| Error::DeserializeError("Missing version string".into()))
+    }
+}
+
+/// Parses a version 1 or version 2 version string, `s` must hold nothing else.
+impl FromStr for VersionString {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || Error::DeserializeError(format!("Invalid version string: {}", s));
+        if !s.is_ascii() {
+            return Err(invalid());
+        }
+        let protocol = Protocol::from_str(s.get(..4).ok_or_else(invalid)?)?;
+        match s.len() {
+            VERSION_1_LEN if s.ends_with('_') => {
+                let hex = |r: core::ops::Range<usize>| -> Result<usize, Error> {
+                    let digits = &s[r];
+                    if !digits
+                        .bytes()
+                        .all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b))
+                    {
+                        return Err(invalid());
+                    }
+                    Ok(usize::from_str_radix(digits, 16)?)
+                };
+                Ok(Self::new(
+                    protocol,
+                    Version::new(hex(4..5)? as u8, hex(5..6)? as u16),
+                    SerializationKind::from_str(&s[6..10])?,
+                    hex(10..16)?,
+                ))
+            }
+            VERSION_2_LEN if s.ends_with('.') => {
+                let b64 = |r: core::ops::Range<usize>| b64_to_u64(&s[r]);
+                Ok(Self::with_genus(
+                    protocol,
+                    Version::new(b64(4..5)? as u8, b64(5..7)? as u16),
+                    Version::new(b64(7..8)? as u8, b64(8..10)? as u16),
+                    SerializationKind::from_str(&s[10..14])?,
+                    b64(14..18)? as usize,
+                ))
+            }
+            _ => Err(invalid()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn version_1() -> Result<(), Error> {
+        let vs = VersionString::from_str("KERI10JSON0000fd_")?;
+        assert_eq!(
+            vs,
+            VersionString::new(
+                Protocol::KERI,
+                Version::new(1, 0),
+                SerializationKind::JSON,
+                0xfd
+            )
+        );
+        assert_eq!(vs.to_str()?, "KERI10JSON0000fd_");
+        assert_eq!(vs.span(), VERSION_1_LEN);
+
+        let vs = VersionString::from_str("ACDC10CBOR00012a_")?;
+        assert_eq!(vs.protocol, Protocol::ACDC);
+        assert_eq!(vs.kind, SerializationKind::CBOR);
+        assert_eq!(vs.size, 0x12a);
+        Ok(())
+    }
+
+    #[test]
+    fn version_2() -> Result<(), Error> {
+        let vs = VersionString::from_str("KERICAACAAJSONAAD9.")?;
+        assert_eq!(
+            vs,
+            VersionString::with_genus(
+                Protocol::KERI,
+                Version::new(2, 0),
+                Version::new(2, 0),
+                SerializationKind::JSON,
+                0xfd
+            )
+        );
+        assert_eq!(vs.to_str()?, "KERICAACAAJSONAAD9.");
+        assert_eq!(vs.span(), VERSION_2_LEN);
+
+        let vs = VersionString::from_str("KERIBAACAAMGPKAABA.")?;
+        assert_eq!(vs.version, Version::new(1, 0));
+        assert_eq!(vs.kind, SerializationKind::MGPK);
+        assert_eq!(vs.size, 64);
+        Ok(())
+    }
+
+    #[test]
+    fn invalid() {
+        for vs in [
+            "",
+            "KERI10JSON0000fd",
+            "KERI10JSON0000FD_",
+            "KERI10JSON0000fd.",
+            "KERX10JSON0000fd_",
+            "KERI10JSNN0000fd_",
+            "KERI1gJSON0000fd_",
+            "KERICAACAAJSONAAD9_",
+            "KERICAACAAJSONAA=9.",
+            "KERICAACAAJSONAAD9.\u{e9}",
+        ] {
+            assert!(VersionString::from_str(vs).is_err(), "{}", vs);
+        }
+
+        let vs = VersionString::new(
+            Protocol::KERI,
+            Version::new(16, 0),
+            SerializationKind::JSON,
+            0,
+        );
+        assert!(vs.to_str().is_err());
+        let vs = VersionString::new(
+            Protocol::KERI,
+            Version::new(1, 0),
+            SerializationKind::JSON,
+            1 << 24,
+        );
+        assert!(vs.to_str().is_err());
+    }
+
+    #[test]
+    fn find() -> Result<(), Error> {
+        let vs = VersionString::find(br#"{"v":"KERI10JSON00002e_","t":"ixn"}"#)?;
+        assert_eq!(vs.size, 0x2e);
+        let vs = VersionString::find(br#"{"v":"KERICAACAAJSONAAAu.","t":"ixn"}"#)?;
+        assert_eq!(vs.size, 0x2e);
+        // MessagePack fixmap with the version string as first value
+        let vs = VersionString::find(b"\x83\xa1v\xb1KERI10MGPK00002e_")?;
+        assert_eq!(vs.kind, SerializationKind::MGPK);
+
+        assert!(VersionString::find(br#"{"t":"ixn"}"#).is_err());
+        assert!(VersionString::find(br#"{"padding":"xxxxx","v":"KERI10JSON000023_"}"#).is_err());
+        Ok(())
+    }
+}
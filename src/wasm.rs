@@ -0,0 +1,92 @@
+use crate::{
+    derivation::self_addressing::SelfAddressing,
+    error::Error,
+    keys::Signer,
+    prefix::{
+        BasicPrefix, IdentifierPrefix, Prefix, SeedPrefix, SelfAddressingPrefix, SelfSigningPrefix,
+    },
+};
+use core::str::FromStr;
+use wasm_bindgen::prelude::*;
+
+fn js(error: Error) -> JsError {
+    JsError::new(&error.to_string())
+}
+
+/// Digests `data` into a self-addressing prefix under the digest `code`, e.g. `E` for Blake3-256.
+#[wasm_bindgen]
+pub fn digest(code: &str, data: &[u8]) -> Result<String, JsError> {
+    Ok(SelfAddressing::from_str(code)
+        .map_err(js)?
+        .derive(data)
+        .to_str())
+}
+
+/// Whether `digest` is the self-addressing prefix of `data`.
+#[wasm_bindgen(js_name = verifyDigest)]
+pub fn verify_digest(digest: &str, data: &[u8]) -> Result<bool, JsError> {
+    Ok(SelfAddressingPrefix::from_str(digest)
+        .map_err(js)?
+        .verify_binding(data))
+}
+
+/// The derivation code and raw bytes of a qb64 primitive.
+#[wasm_bindgen]
+pub struct ParsedPrefix {
+    code: String,
+    raw: Vec<u8>,
+}
+
+#[wasm_bindgen]
+impl ParsedPrefix {
+    #[wasm_bindgen(getter)]
+    pub fn code(&self) -> String {
+        self.code.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn raw(&self) -> Vec<u8> {
+        self.raw.clone()
+    }
+}
+
+/// Parses an identifier prefix or a signature from its text domain encoding.
+#[wasm_bindgen(js_name = parsePrefix)]
+pub fn parse_prefix(qb64: &str) -> Result<ParsedPrefix, JsError> {
+    let (code, raw) = match IdentifierPrefix::from_str(qb64) {
+        Ok(prefix) => (prefix.derivation_code(), prefix.derivative()),
+        Err(_) => {
+            let signature = SelfSigningPrefix::from_str(qb64).map_err(js)?;
+            (signature.derivation_code(), signature.derivative())
+        }
+    };
+    Ok(ParsedPrefix { code, raw })
+}
+
+/// The public key of the signer of `seed`, as a basic prefix.
+#[wasm_bindgen(js_name = publicKey)]
+pub fn public_key(seed: &str, transferable: bool) -> Result<String, JsError> {
+    let seed = SeedPrefix::from_str(seed).map_err(js)?;
+    Ok(Signer::from_seed(&seed, transferable)
+        .map_err(js)?
+        .basic_prefix()
+        .to_str())
+}
+
+/// Signs `data` with the private key encoded in `seed`, returning the qb64 signature.
+#[wasm_bindgen]
+pub fn sign(seed: &str, data: &[u8]) -> Result<String, JsError> {
+    let seed = SeedPrefix::from_str(seed).map_err(js)?;
+    Ok(Signer::from_seed(&seed, true)
+        .map_err(js)?
+        .sign(data)
+        .to_str())
+}
+
+/// Verifies the qb64 `signature` over `data` against the basic prefix `key`.
+#[wasm_bindgen]
+pub fn verify(key: &str, data: &[u8], signature: &str) -> Result<bool, JsError> {
+    let key = BasicPrefix::from_str(key).map_err(js)?;
+    let signature = SelfSigningPrefix::from_str(signature).map_err(js)?;
+    key.verify(data, &signature).map_err(js)
+}